@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
+use crate::{
+  pagination::PageDirection, recorder::Exchange, response::BodyFormat, spec_patch::SpecField, update_check::Release,
+  webhook::Delivery,
+};
+
 type Command = String;
 type Args = Option<String>;
 
@@ -12,6 +17,7 @@ pub enum Action {
   Suspend,
   Resume,
   Quit,
+  ConfirmedQuit,
   Refresh,
   Error(String),
   Help,
@@ -29,20 +35,122 @@ pub enum Action {
   Go,
   Back,
   ToggleFullScreen,
+  CollapsePane,
+  ExpandPane,
+  FocusResponse(String),
   StatusLine(String),
   TimedStatusLine(String, u64),
   FocusFooter(Command, Args),
   FooterResult(Command, Args),
   Noop,
   NewCall(Option<String>),
+  NewAdHocCall(String, String),
   HangUp(Option<String>),
+  NavigateBack,
+  NavigateForward,
+  SetBookmark(u32),
+  JumpToBookmark(u32),
+  RecordMacro,
+  SaveMacro(char, Vec<crossterm::event::KeyEvent>),
   Dial,
   History,
-  CloseHistory,
+  RemoveDraft(String),
+  ClearHistory,
+  ConfirmedClearHistory,
+  Recent,
+  ClosePopup,
+  Confirm(String, Box<Action>),
+  Confirmed(Box<Action>),
+  ConfirmedDial,
+  ListenWebhook(String),
+  SendSample(String),
+  WebhookDelivery(String, Delivery),
+  ToggleDryRun,
+  ToggleIdempotencyKey,
+  DryRunOutput(Option<String>),
+  Paginate(PageDirection, bool),
+  FollowLocation,
+  SetPathParameters(Vec<(String, String)>),
+  SetParameter(String, String),
+  ImportParameter(String, String, String),
+  ImportBody(Option<String>, String),
+  AddAssertion(String),
+  ClearAssertions,
+  EditParameterValue(String, String),
+  SaveParameterValue(String),
+  ToggleMark,
+  BulkInvoke,
+  ConfirmedBulkInvoke,
+  Smoke,
+  ConfirmedSmoke,
+  TrustCertificate(String, String, Option<String>),
+  PinCertificate(String, String, String),
+  Poll(u64, Option<(String, String)>),
+  ConfirmedPoll(u64, Option<(String, String)>),
+  StopPoll,
+  PollProgress(Option<String>),
   AddQuery(String),
   RemoveQuery(String),
   AddHeader(String),
   RemoveHeader(String),
+  CycleEncoding,
   OpenRequestPayload(String),
-  SaveResponsePayload(String),
+  SaveRequestExample,
+  WriteRequestExample(String, String, String),
+  PickFile(String),
+  SaveResponsePayload(String, bool),
+  SaveResponseSelection(String),
+  DiffResponsePayload(String),
+  SplitResponsePayload,
+  InterpretResponseAs(Option<BodyFormat>),
+  PipeResponse(Option<String>),
+  PipedBody(String, usize, String),
+  PinResponse,
+  UnpinResponse,
+  ToggleTimestamps,
+  ToggleHumanize,
+  ToggleRaw,
+  WatchSpec(Option<u64>),
+  SpecFetched(String),
+  ReloadSpec,
+  ExportProposedSchema(String),
+  Record(Option<String>),
+  Recorded(Exchange),
+  SaveRecording(String),
+  MergeRecording,
+  ImportPostman(String),
+  ImportHttp(String),
+  ExportHttp(String),
+  ExportPostman(String),
+  ExportInsomnia(String),
+  CopySchemaPointer,
+  CopySchemaName,
+  ToggleWrap,
+  ScrollLeft,
+  ScrollRight,
+  CopyToClipboard(String),
+  OpenInBrowser(String),
+  OpenLink,
+  ShowMessages,
+  ShowVariables,
+  LoadEnvFile(String),
+  ShowChangelog,
+  ShowNotes,
+  SetBaseUrl(String),
+  ShowProfiles,
+  SwitchProfile(Option<String>),
+  ShowAuth(String),
+  SetCredential(String, Option<String>),
+  SaveNotes(String),
+  Annotate(String, Option<String>),
+  ShowStatusInfo(u16, Vec<(String, String)>),
+  UpdatesFound(Vec<Release>),
+  WarmUp(String),
+  ServerWarmedUp(String, Result<u64, String>),
+  Capture(String, String),
+  EditSpec(SpecField),
+  PatchSpec(String, SpecField, String),
+  PromptSecret(String),
+  StoreSecret(String, String),
+  DeleteSecret(String),
 }
@@ -1,74 +1,264 @@
-use std::collections::HashMap;
+use std::{
+  collections::{HashMap, VecDeque},
+  path::PathBuf,
+  time::Duration,
+};
 
-use color_eyre::eyre::Result;
-use crossterm::event::KeyEvent;
+use color_eyre::eyre::{bail, ContextCompat, Result, WrapErr};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use openapi_31::v31::{ObjectOrRef, Openapi};
 use ratatui::{
   layout::{Constraint, Layout},
   prelude::Rect,
 };
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
+use tokio::{sync::mpsc, task::JoinHandle};
 
 use crate::{
   action::Action,
-  config::Config,
+  assertions,
+  config::{Config, ImageProtocol},
+  control::{ControlRequest, ControlServer},
+  executor::{self, Dispatch, Executor},
+  history_store::{Drafts, HISTORY_FILE},
+  http_file, import, insomnia,
+  known_hosts::{KnownHosts, KNOWN_HOSTS_FILE},
+  media_type,
+  navigation::{Location, Navigation},
   pages::{home::Home, phone::Phone, Page},
-  panes::{footer::FooterPane, header::HeaderPane, history::HistoryPane, Pane},
-  request::Request,
+  pagination,
+  panes::{
+    auth::AuthPane,
+    bulk_summary::{BulkSummaryPane, InvokeResult},
+    changelog::ChangelogPane,
+    confirm::ConfirmPane,
+    file_picker::FilePickerPane,
+    footer::FooterPane,
+    header::HeaderPane,
+    history::HistoryPane,
+    messages::MessagesPane,
+    notes::NotesPane,
+    profiles::ProfilesPane,
+    recent::RecentPane,
+    secret::SecretPane,
+    spec_editor::SpecEditorPane,
+    status_info::StatusInfoPane,
+    value_editor::ValueEditorPane,
+    variables::VariablesPane,
+    Pane,
+  },
+  postman,
+  rate_limit::RateLimit,
+  recorder::{self, Exchange},
+  request::{self, Request},
   response::Response,
-  state::{InputMode, OperationItemType, State},
-  tui,
+  secrets, spec_diff,
+  spec_patch::{self, SpecField},
+  state::{InputMode, OperationItem, OperationItemType, State},
+  storage, tui,
+  tunnel::{self, Tunnel},
+  update_check,
+  utils::get_data_dir,
+  warm_up,
+  warm_up::ServerHealth,
+  webhook,
 };
 
+const SMOKE_CONCURRENCY: usize = 4;
+
+/// Bulk invoke or smoke test running in the background, its summary is shown once the last response arrived.
+pub struct Batch {
+  title: String,
+  total: usize,
+  results: Vec<InvokeResult>,
+  completed_rx: mpsc::UnboundedReceiver<(OperationItem, Result<Response>, Duration)>,
+}
+
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Mode {
   #[default]
   Home,
+  /// Request page of an operation.
+  Request,
+}
+
+/// Key waiting for the register that follows it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MacroKey {
+  Record,
+  Replay,
 }
 
 pub struct App {
   pub config: Config,
   pub pages: Vec<Box<dyn Page>>,
   pub history: HashMap<String, Box<dyn Page>>,
+  pub navigation: Navigation,
   pub active_page: usize,
   pub footer: FooterPane,
   pub header: HeaderPane,
   pub popup: Option<Box<dyn Pane>>,
   pub should_quit: bool,
   pub should_suspend: bool,
-  pub mode: Mode,
   pub last_tick_key_events: Vec<KeyEvent>,
   pub state: State,
+  pub terminal_title: String,
+  pub terminal_focused: bool,
+  pub pending_bookmark_key: Option<char>,
+  /// `RecordMacro` or `@` waiting for the register that follows it.
+  pub pending_macro_key: Option<MacroKey>,
+  /// Keys of a macro being replayed, handled before new terminal events.
+  pub replaying_keys: VecDeque<KeyEvent>,
+  pub last_macro: Option<char>,
+  /// Whether the last frame had a popup, its hole in a graphics protocol image is only filled by a full redraw.
+  pub popup_drawn: bool,
+  pub webhook_listener: Option<JoinHandle<()>>,
+  pub spec_watcher: Option<JoinHandle<()>>,
+  pub recorder: Option<JoinHandle<()>>,
+  /// Target of the recording proxy and the calls recorded so far.
+  pub recording: Option<(String, Vec<Exchange>)>,
+  /// Recorded calls already in a saved draft spec or merged into the spec.
+  pub kept_recorded: usize,
+  /// Newer version of the spec found by the watcher with its text, applied with `Action::ReloadSpec`.
+  pub pending_spec: Option<(Openapi, String)>,
+  pub executor: Executor,
+  pub batch: Option<Batch>,
+  pub startup_call: Option<String>,
+  pub control: Option<ControlServer>,
+  /// SSH forwards of the `tunnels` config, kept open while the app runs.
+  pub tunnels: Vec<Tunnel>,
 }
 
 impl App {
-  pub async fn new(input: String) -> Result<Self> {
-    let state = State::from_input(input).await?;
-    let home = Home::new()?;
+  pub async fn new(input: String, base_url: Option<String>) -> Result<Self> {
+    let mut state = State::from_input(input).await?;
+    if let Some(base_url) = base_url {
+      reqwest::Url::parse(base_url.as_str()).wrap_err_with(|| format!("invalid --base-url {base_url}"))?;
+      state.base_url = Some(base_url);
+    }
     let config = Config::new()?;
-    let mode = Mode::Home;
+    let home = Home::new(&config.layout)?;
+    let mut tunnels = vec![];
+    let connect_timeout = Duration::from_secs(config.client.connect_timeout_secs);
+    let opened = futures::future::join_all(
+      config.tunnels.iter().map(|tunnel_config| tunnel::open(tunnel_config, connect_timeout)),
+    )
+    .await;
+    for (tunnel_config, opened) in config.tunnels.iter().zip(opened) {
+      match opened {
+        Ok(tunnel) => {
+          let message =
+            format!("{} tunneled to {} through {}", tunnel.hosts.join(", "), tunnel.target, tunnel_config.ssh);
+          state.push_message(message.as_str());
+          tunnels.push(tunnel);
+        },
+        Err(error) => {
+          log::error!("{error}");
+          state.push_message(format!("can't open the tunnel to {}: {error}", tunnel_config.target).as_str());
+        },
+      }
+    }
+    if config.client.trust_on_first_use {
+      state.known_hosts = Some(KnownHosts::load(get_data_dir().join(KNOWN_HOSTS_FILE)));
+    }
+    state.default_headers = config.client.standing_headers();
+    state.profiles = config.profiles.clone();
+    if state.profile().is_none() {
+      state.active_profile = None;
+    }
+    state.client = config
+      .client
+      .for_profile(state.profile())
+      .build(&tunnel::routes(&tunnels), state.known_hosts.as_ref().map(KnownHosts::pins))?;
+    let executor = Executor::new(state.client.clone(), config.dial.queue_follow_up);
 
     Ok(Self {
       pages: vec![Box::new(home)],
       history: HashMap::default(),
+      navigation: Navigation::default(),
       active_page: 0,
       footer: FooterPane::new(),
       header: HeaderPane::new(),
       popup: None,
       should_quit: false,
       should_suspend: false,
-      config,
-      mode,
       last_tick_key_events: Vec::new(),
       state,
+      terminal_title: String::default(),
+      terminal_focused: true,
+      pending_bookmark_key: None,
+      pending_macro_key: None,
+      replaying_keys: VecDeque::default(),
+      last_macro: None,
+      popup_drawn: false,
+      webhook_listener: None,
+      spec_watcher: None,
+      recorder: None,
+      recording: None,
+      kept_recorded: 0,
+      pending_spec: None,
+      executor,
+      batch: None,
+      startup_call: None,
+      control: None,
+      tunnels,
+      config,
     })
   }
 
+  /// Applies the `--operation`, `--server`, `--header`, `--env-file`, `--call` and `--control-socket` flags.
+  pub fn startup(
+    &mut self,
+    operation_id: Option<String>,
+    server: Option<String>,
+    headers: Vec<(String, String)>,
+    env_files: Vec<String>,
+    call: bool,
+    control_socket: Option<PathBuf>,
+  ) -> Result<()> {
+    if let Some(path) = control_socket {
+      self.control = Some(ControlServer::bind(path.as_path())?);
+    }
+    if let Some(index) = server.as_deref().and_then(|server| server.parse::<usize>().ok()) {
+      let servers = self.state.server_urls(&None);
+      if index >= servers.len() {
+        let valid = servers.iter().enumerate().map(|(index, url)| format!("{index}: {url}")).collect::<Vec<_>>();
+        match valid.is_empty() {
+          true => bail!("--server {index} is out of range, the spec has no servers"),
+          false => bail!("--server {index} is out of range, valid indexes are {}", valid.join(", ")),
+        }
+      }
+    }
+    if server.is_some() {
+      self.state.preferred_server = server;
+    }
+    self.state.session_headers = headers;
+    for env_file in env_files {
+      self.state.load_env_file(env_file.as_str()).wrap_err_with(|| format!("can't load {env_file}"))?;
+    }
+    if let Some(operation_id) = operation_id {
+      // the restored filter and tag may hide the operation
+      self.state.active_filter = String::default();
+      self.state.active_tag_name = None;
+      self.state.webhooks_only = false;
+      let index = self
+        .state
+        .visible_operations()
+        .position(|operation_item| operation_item.operation.operation_id.as_ref() == Some(&operation_id));
+      self.state.active_operation_index = index.context(format!("operation {operation_id} not found"))?;
+      self.startup_call = call.then_some(operation_id);
+    }
+    Ok(())
+  }
+
   pub async fn run(&mut self) -> Result<()> {
     let (action_tx, mut action_rx) = mpsc::unbounded_channel::<Action>();
     let (request_tx, mut request_rx) = mpsc::unbounded_channel::<Request>();
 
-    let mut tui = tui::Tui::new()?;
+    let mut tui = tui::Tui::new()?
+      .frame_rate(self.config.display.frame_rate())
+      .tick_rate(self.config.display.tick_rate())
+      .paste(true);
     tui.enter()?;
 
     for page in self.pages.iter_mut() {
@@ -86,25 +276,65 @@ impl App {
 
     self.header.init(&self.state)?;
     self.footer.init(&self.state)?;
+    self.restore_history(&action_tx, &request_tx)?;
+
+    action_tx.send(Action::Update)?;
+    if self.config.layout.filter_on_startup && self.location() == Location::Home {
+      action_tx.send(Action::FocusFooter("/".into(), Some(self.state.active_filter.clone())))?;
+    }
+    if self.state.has_unresolved_servers() {
+      action_tx.send(Action::TimedStatusLine(
+        "servers of the spec are relative, resolve them with :base <url> or --base-url".into(),
+        10,
+      ))?;
+    }
+    if self.config.dial.warm_up {
+      if let Some(base_url) = self.state.default_server_urls(&None).first() {
+        action_tx.send(Action::WarmUp(base_url.clone()))?;
+      }
+    }
+    if self.config.update.check {
+      let client = self.state.client.clone();
+      let action_tx = action_tx.clone();
+      tokio::spawn(async move {
+        match update_check::newer_releases(&client).await {
+          Ok(releases) if !releases.is_empty() => {
+            action_tx.send(Action::UpdatesFound(releases)).ok();
+          },
+          Ok(_) => {},
+          Err(error) => log::error!("can't check for updates: {error}"),
+        }
+      });
+    }
+    if let Some(operation_id) = self.startup_call.take() {
+      action_tx.send(Action::NewCall(Some(operation_id)))?;
+    }
 
     loop {
-      if let Some(e) = tui.next().await {
-        let mut stop_event_propagation = self
-          .popup
-          .as_mut()
-          .and_then(|pane| pane.handle_events(e.clone(), &mut self.state).ok())
-          .map(|response| match response {
-            Some(tui::EventResponse::Continue(action)) => {
-              action_tx.send(action).ok();
-              false
-            },
-            Some(tui::EventResponse::Stop(action)) => {
-              action_tx.send(action).ok();
-              true
-            },
-            _ => false,
-          })
-          .unwrap_or(false);
+      let event = match self.replaying_keys.pop_front() {
+        Some(key) => Some(tui::Event::Key(key)),
+        None => tui.next().await,
+      };
+      if let Some(e) = event {
+        let mut stop_event_propagation =
+          self.handle_macro_keys(&e, &action_tx)? || self.handle_bookmark_keys(&e, &action_tx)?;
+        stop_event_propagation = stop_event_propagation
+          || self
+            .popup
+            .as_mut()
+            .and_then(|pane| pane.handle_events(e.clone(), &mut self.state).ok())
+            .map(|response| match response {
+              Some(tui::EventResponse::Continue(action)) => {
+                action_tx.send(action).ok();
+                false
+              },
+              Some(tui::EventResponse::Stop(action)) => {
+                action_tx.send(action).ok();
+                true
+              },
+              _ => false,
+            })
+            .unwrap_or(false);
         stop_event_propagation = stop_event_propagation
           || self
             .pages
@@ -146,8 +376,10 @@ impl App {
             tui::Event::Tick => action_tx.send(Action::Tick)?,
             tui::Event::Render => action_tx.send(Action::Render)?,
             tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
+            tui::Event::FocusGained => self.terminal_focused = true,
+            tui::Event::FocusLost => self.terminal_focused = false,
             tui::Event::Key(key) => {
-              if let Some(keymap) = self.config.keybindings.get(&self.mode) {
+              if let Some(keymap) = self.config.keybindings.get(&self.mode()) {
                 if let Some(action) = keymap.get(&vec![key]) {
                   action_tx.send(action.clone())?;
                 } else {
@@ -165,6 +397,10 @@ impl App {
             _ => {},
           }
         }
+        // with a low frame rate, redraw right after input instead of waiting for the next frame.
+        if self.config.display.reduced_motion && matches!(e, tui::Event::Key(_) | tui::Event::Paste(_)) {
+          action_tx.send(Action::Render)?;
+        }
       }
 
       while let Ok(action) = action_rx.try_recv() {
@@ -175,7 +411,16 @@ impl App {
           Action::Tick => {
             self.last_tick_key_events.drain(..);
           },
-          Action::Quit if self.state.input_mode == InputMode::Normal => self.should_quit = true,
+          Action::Quit if self.state.input_mode == InputMode::Normal => {
+            let losses = self.quit_losses();
+            if losses.is_empty() {
+              self.should_quit = true;
+            } else {
+              let message = format!("Quit anyway? This would be lost:\n{}", losses.join("\n"));
+              action_tx.send(Action::Confirm(message, Box::new(Action::ConfirmedQuit)))?;
+            }
+          },
+          Action::ConfirmedQuit => self.should_quit = true,
           Action::Suspend => self.should_suspend = true,
           Action::Resume => self.should_suspend = false,
           Action::Resize(w, h) => {
@@ -187,6 +432,14 @@ impl App {
             })?;
           },
           Action::Render => {
+            // cells under an image are skipped, a closed popup would stay on them.
+            if self.popup_drawn
+              && self.popup.is_none()
+              && self.config.display.image_protocol() != ImageProtocol::HalfBlocks
+            {
+              tui.clear()?;
+            }
+            self.popup_drawn = self.popup.is_some();
             tui.draw(|f| {
               self.draw(f).unwrap_or_else(|err| {
                 action_tx.send(Action::Error(format!("Failed to draw: {:?}", err))).unwrap();
@@ -194,36 +447,65 @@ impl App {
             })?;
           },
           Action::NewCall(ref operation_id) => {
-            if let Some(operation_item) = self.state.get_operation(operation_id.clone()) {
-              if let OperationItemType::Path = operation_item.r#type {
-                if let Some(page) = operation_item
-                  .operation
-                  .operation_id
-                  .clone()
-                  .and_then(|operation_id| self.history.remove(&operation_id))
-                {
-                  self.pages[0].unfocus()?;
-                  self.pages.insert(0, page);
-                  self.pages[0].focus()?;
-                } else if let Ok(mut page) = Phone::new(operation_item.clone(), request_tx.clone(), &self.state) {
-                  self.pages[0].unfocus()?;
-                  page.init(&self.state)?;
-                  page.register_action_handler(action_tx.clone())?;
-                  self.pages.insert(0, Box::new(page));
-                  self.pages[0].focus()?;
-                }
-              }
+            let location = self.location();
+            self.open_call(operation_id.clone(), &action_tx, &request_tx)?;
+            if self.location() != location {
+              self.navigation.visit(location);
             }
-            action_tx.send(Action::CloseHistory).unwrap();
+            action_tx.send(Action::ClosePopup).unwrap();
+          },
+          Action::NewAdHocCall(ref method, ref path) => {
+            let operation_id = self.state.add_ad_hoc_operation(method, path);
+            action_tx.send(Action::NewCall(operation_id))?;
           },
           Action::HangUp(ref operation_id) => {
             if self.pages.len() > 1 {
-              self.pages[0].unfocus()?;
-              let page = self.pages.remove(0);
-              self.pages[0].focus()?;
-              if let Some(operation_id) = operation_id {
-                self.history.insert(operation_id.clone(), page);
+              self.navigation.visit(self.location());
+              self.hang_up(operation_id.clone())?;
+            }
+          },
+          Action::RecordMacro => self.record_macro(&action_tx)?,
+          Action::SaveMacro(register, ref keys) => {
+            let status_line = format!("recorded {} keys into @{register}", keys.len());
+            self.state.set_macro(register, keys.clone());
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::SetBookmark(slot) => {
+            let operation_id = match self.pages.first().and_then(|page| page.operation_id()) {
+              Some(operation_id) => Some(operation_id),
+              None => {
+                self.state.active_operation().and_then(|operation_item| operation_item.operation.operation_id.clone())
+              },
+            };
+            let status_line = match operation_id {
+              Some(operation_id) => {
+                self.state.set_bookmark(slot, operation_id.clone());
+                format!("bookmark {slot} set to {}", self.operation_label(operation_id.as_str()))
+              },
+              None => String::from("no operation to bookmark"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::JumpToBookmark(slot) => match self.state.bookmarks.get(&slot).cloned() {
+            Some(operation_id) => {
+              let location = self.location();
+              self.go_to(Location::Call(operation_id), &action_tx, &request_tx)?;
+              if self.location() != location {
+                self.navigation.visit(location);
               }
+            },
+            None => {
+              action_tx.send(Action::TimedStatusLine(format!("bookmark {slot} is not set, set it with m{slot}"), 3))?
+            },
+          },
+          Action::NavigateBack if self.state.input_mode == InputMode::Normal => {
+            if let Some(location) = self.navigation.back(self.location()) {
+              self.go_to(location, &action_tx, &request_tx)?;
+            }
+          },
+          Action::NavigateForward if self.state.input_mode == InputMode::Normal => {
+            if let Some(location) = self.navigation.forward(self.location()) {
+              self.go_to(location, &action_tx, &request_tx)?;
             }
           },
           Action::History => {
@@ -231,15 +513,526 @@ impl App {
               .state
               .openapi_operations
               .iter()
-              .filter(|operation_item| {
-                let op_id = operation_item.operation.operation_id.clone();
-                self.history.keys().any(|operation_id| op_id.eq(&Some(operation_id.clone())))
+              .filter_map(|operation_item| {
+                let page = self.history.get(operation_item.operation.operation_id.as_ref()?)?;
+                Some((operation_item, page.is_dirty()))
               })
               .collect::<Vec<_>>();
-            let history_popup = HistoryPane::new(operation_ids);
+            let mut history_popup = HistoryPane::new(operation_ids, &self.state);
+            history_popup.register_config_handler(self.config.clone())?;
             self.popup = Some(Box::new(history_popup));
           },
-          Action::CloseHistory => {
+          Action::RemoveDraft(ref operation_id) => {
+            self.history.remove(operation_id);
+          },
+          Action::ClearHistory => {
+            let dirty = self.history.values().filter(|page| page.is_dirty()).count();
+            if dirty > 0 {
+              action_tx.send(Action::Confirm(
+                format!("Clear history with {dirty} drafts holding unsaved edits?"),
+                Box::new(Action::ConfirmedClearHistory),
+              ))?;
+            } else {
+              action_tx.send(Action::ConfirmedClearHistory)?;
+            }
+          },
+          Action::ConfirmedClearHistory => {
+            let drafts = self.history.len();
+            self.history.clear();
+            self.save_history(&Drafts::default());
+            action_tx.send(Action::TimedStatusLine(format!("{drafts} drafts removed from history"), 3))?;
+          },
+          Action::Recent if self.state.input_mode == InputMode::Normal => {
+            let current = self.pages.first().and_then(|page| page.operation_id());
+            let operation_items = self
+              .state
+              .recent_operations
+              .iter()
+              .filter(|operation_id| current.as_ref() != Some(*operation_id))
+              .filter_map(|operation_id| self.state.get_operation(Some(operation_id.clone())))
+              .collect::<Vec<_>>();
+            let mut recent_popup = RecentPane::new(operation_items);
+            recent_popup.register_config_handler(self.config.clone())?;
+            self.popup = Some(Box::new(recent_popup));
+          },
+          Action::ShowMessages => {
+            self.popup = Some(Box::new(MessagesPane::default()));
+          },
+          Action::Annotate(ref operation_id, ref annotation) => {
+            self.state.set_annotation(operation_id, annotation.clone());
+            let operation = self.operation_label(operation_id);
+            let status_line = match annotation {
+              Some(annotation) => format!("{operation} annotated with {annotation}"),
+              None => format!("annotation of {operation} removed"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::SetBaseUrl(ref base_url) => {
+            let status_line = match reqwest::Url::parse(base_url) {
+              Ok(_) => {
+                self.state.base_url = Some(base_url.clone());
+                self.state.save_settings();
+                if let Some(home) = self.pages.last_mut() {
+                  home.init(&self.state)?;
+                }
+                let request_pages = self.pages.iter_mut().rev().skip(1).chain(self.history.values_mut());
+                for page in request_pages {
+                  if let Some(action) = page.update(action.clone(), &mut self.state)? {
+                    action_tx.send(action)?;
+                  }
+                }
+                format!("relative servers resolved against {base_url}")
+              },
+              Err(error) => format!("can't use {base_url} as base url: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::ShowProfiles => {
+            self.popup = Some(Box::new(ProfilesPane::new(&self.state)));
+          },
+          Action::SwitchProfile(ref name) => {
+            let client = self
+              .config
+              .client
+              .for_profile(name.as_ref().and_then(|name| self.state.profiles.get(name)))
+              .build(&tunnel::routes(&self.tunnels), self.state.known_hosts.as_ref().map(KnownHosts::pins));
+            let status_line = match (name, client) {
+              (Some(name), _) if !self.state.profiles.contains_key(name) => {
+                format!("no profile named {name} in the config")
+              },
+              (_, Err(error)) => format!("can't build the client of the profile: {error}"),
+              (_, Ok(client)) => {
+                self.executor.set_client(client.clone());
+                self.state.client = client;
+                self.state.active_profile.clone_from(name);
+                self.state.save_settings();
+                self.popup = None;
+                // parked request pages keep their servers and headers otherwise, the active page gets it below.
+                let active_page = self.active_page;
+                let inactive_pages =
+                  self.pages.iter_mut().enumerate().filter(|(index, _)| *index != active_page).map(|(_, page)| page);
+                for page in inactive_pages.chain(self.history.values_mut()) {
+                  if let Some(action) = page.update(action.clone(), &mut self.state)? {
+                    action_tx.send(action)?;
+                  }
+                }
+                match name {
+                  Some(name) => format!("switched to profile {name}"),
+                  None => String::from("profile turned off"),
+                }
+              },
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::ShowAuth(ref operation_id) => {
+            self.popup = Some(Box::new(AuthPane::new(operation_id, &self.state)));
+          },
+          Action::SetCredential(ref name, ref credential) => {
+            let status_line = match credential {
+              Some(credential) => {
+                self.state.credentials.insert(name.clone(), credential.clone());
+                format!("credential of {name} set for this session")
+              },
+              None => {
+                self.state.credentials.remove(name);
+                format!("credential of {name} cleared")
+              },
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::ShowNotes => {
+            self.popup = Some(Box::new(NotesPane::default()));
+          },
+          Action::SaveNotes(ref notes) => {
+            self.state.set_notes(notes.clone());
+            action_tx.send(Action::TimedStatusLine("notes saved".into(), 3))?;
+          },
+          Action::ShowVariables => {
+            self.popup = Some(Box::new(VariablesPane::default()));
+          },
+          Action::LoadEnvFile(ref filepath) => {
+            let status_line = match self.state.load_env_file(filepath) {
+              Ok(count) => format!("{count} variables loaded from {filepath}"),
+              Err(error) => format!("can't load {filepath}: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::UpdatesFound(ref releases) => {
+            self.state.newer_releases = releases.clone();
+          },
+          Action::ShowChangelog => {
+            if self.state.newer_releases.is_empty() {
+              action_tx.send(Action::TimedStatusLine("no newer release found".into(), 3))?;
+            } else {
+              self.popup = Some(Box::new(ChangelogPane::default()));
+            }
+          },
+          Action::ShowStatusInfo(status, ref documented) => {
+            self.popup = Some(Box::new(StatusInfoPane::new(status, documented.clone())));
+          },
+          Action::CopyToClipboard(ref text) => {
+            let status_line = match tui::copy_to_clipboard(text) {
+              Ok(_) => format!("copied {text}"),
+              Err(error) => format!("can't copy to clipboard: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::OpenInBrowser(ref url) => {
+            let status_line = match tui::open_in_browser(url) {
+              Ok(_) => format!("opened {url}"),
+              Err(error) => format!("can't open {url}: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::EditSpec(ref field) => match self.spec_field_value(field) {
+            Ok((operation_id, value)) => {
+              self.popup = Some(Box::new(SpecEditorPane::new(operation_id, field.clone(), value)));
+            },
+            Err(error) => action_tx.send(Action::TimedStatusLine(format!("can't edit {field}: {error}"), 5))?,
+          },
+          Action::PatchSpec(ref operation_id, ref field, ref value) => {
+            let status_line = match self.patch_spec(operation_id, field, value) {
+              Ok(_) => {
+                self.popup = None;
+                format!("{field} saved to {}", self.state.openapi_input_source)
+              },
+              Err(error) => format!("can't save {field}: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 5))?;
+          },
+          Action::EditParameterValue(ref name, ref value) => {
+            self.popup = Some(Box::new(ValueEditorPane::new(name.clone(), value.clone())));
+          },
+          Action::SaveParameterValue(_) => {
+            self.popup = None;
+          },
+          Action::PipedBody(..) => {
+            // the pipe may finish after its page went to the background or under a popup, the active page gets it
+            // below otherwise.
+            let popup_open = self.popup.is_some();
+            let background_pages = self
+              .pages
+              .iter_mut()
+              .enumerate()
+              .filter(|(index, _)| popup_open || *index != self.active_page)
+              .map(|(_, page)| page)
+              .chain(self.history.values_mut());
+            for page in background_pages {
+              if let Some(action) = page.update(action.clone(), &mut self.state)? {
+                action_tx.send(action)?;
+              }
+            }
+          },
+          Action::WarmUp(ref base_url)
+            if !matches!(
+              self.state.server_health.get(base_url),
+              Some(ServerHealth::Resolving | ServerHealth::Ready(_))
+            ) =>
+          {
+            self.state.server_health.insert(base_url.clone(), ServerHealth::Resolving);
+            let base_url = base_url.clone();
+            let (client, action_tx) = (self.state.client.clone(), action_tx.clone());
+            tokio::spawn(async move {
+              let result = warm_up::warm_up(&client, base_url.as_str())
+                .await
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .map_err(|error| error.to_string());
+              action_tx.send(Action::ServerWarmedUp(base_url, result)).ok();
+            });
+          },
+          Action::ServerWarmedUp(ref base_url, ref result) => {
+            let health = match result {
+              Ok(elapsed) => ServerHealth::Ready(Duration::from_millis(*elapsed)),
+              Err(error) => {
+                action_tx.send(Action::TimedStatusLine(format!("{base_url} is not reachable: {error}"), 5))?;
+                ServerHealth::Failed(error.clone())
+              },
+            };
+            self.state.server_health.insert(base_url.clone(), health);
+          },
+          Action::ListenWebhook(ref operation_id) => {
+            if let Some(handle) = self.webhook_listener.take() {
+              handle.abort();
+            }
+            let status_line = match self.state.webhook_listener.take() {
+              Some((listening_operation_id, _)) if listening_operation_id.eq(operation_id) => {
+                String::from("webhook listener stopped")
+              },
+              _ => {
+                let listen_address = self.config.webhook.listen_address.clone();
+                match webhook::listen(listen_address.as_str(), operation_id.clone(), action_tx.clone()).await {
+                  Ok((address, handle)) => {
+                    self.webhook_listener = Some(handle);
+                    self.state.webhook_listener = Some((operation_id.clone(), address.to_string()));
+                    format!(
+                      "listening for {} calls on http://{address}, send again to stop",
+                      self.operation_label(operation_id)
+                    )
+                  },
+                  Err(error) => format!("can't listen on {listen_address}: {error}"),
+                }
+              },
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 5))?;
+          },
+          Action::WebhookDelivery(ref operation_id, ref delivery) => {
+            let status_line = format!("{} {} received, {} bytes", delivery.method, delivery.path, delivery.body.len());
+            self.state.responses.insert(operation_id.clone(), Response::from_delivery(delivery));
+            action_tx.send(Action::TimedStatusLine(status_line, 5))?;
+          },
+          Action::WriteRequestExample(ref operation_id, ref content_type, ref body) => {
+            let status_line = match self.write_request_example(operation_id, content_type, body) {
+              Ok(_) => format!("{content_type} example saved to {}", self.state.openapi_input_source),
+              Err(error) => format!("can't save example: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 5))?;
+          },
+          Action::PromptSecret(ref name) => {
+            self.popup = Some(Box::new(SecretPane::new(name.clone())));
+          },
+          Action::StoreSecret(ref name, ref value) => {
+            self.popup = None;
+            let status_line = match secrets::store(name, value) {
+              Ok(_) => format!("secret stored, reference it with {}{name}", secrets::SECRET_SCHEME),
+              Err(error) => format!("can't store secret: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 5))?;
+          },
+          Action::DeleteSecret(ref name) => {
+            let status_line = match secrets::delete(name) {
+              Ok(_) => format!("secret {name} removed"),
+              Err(error) => format!("can't remove secret: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 5))?;
+          },
+          Action::Confirm(ref message, ref action) => {
+            self.popup = Some(Box::new(ConfirmPane::new("Confirm".into(), message.clone(), *action.clone())));
+          },
+          Action::WatchSpec(interval) => {
+            if let Some(handle) = self.spec_watcher.take() {
+              handle.abort();
+            }
+            let status_line = match (interval, reqwest::Url::parse(self.state.openapi_input_source.as_str())) {
+              (None, _) => String::from("spec watch stopped"),
+              (Some(interval), Ok(url)) if url.scheme().starts_with("http") => {
+                self.spec_watcher = Some(self.watch_spec(url, Duration::from_millis(interval), &action_tx));
+                format!("watching the spec every {:?}", Duration::from_millis(interval))
+              },
+              (Some(_), _) => String::from("only specs loaded from a url can be watched"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::SpecFetched(ref text) => match serde_yaml::from_str::<Openapi>(text) {
+            Ok(spec) => {
+              let changes = spec_diff::diff(&self.state.openapi_spec, &spec);
+              if !changes.is_empty() {
+                self.pending_spec = Some((spec, text.clone()));
+                let message = format!("{changes}\n\nreload the spec? request pages will be closed");
+                match self.popup {
+                  None => action_tx.send(Action::Confirm(message, Box::new(Action::ReloadSpec)))?,
+                  Some(_) => action_tx.send(Action::TimedStatusLine("spec changed, :reload to load it".into(), 5))?,
+                }
+              }
+            },
+            Err(error) => log::error!("can't parse the watched spec: {error}"),
+          },
+          Action::ReloadSpec => {
+            let status_line = match self.pending_spec.take() {
+              Some((spec, text)) => {
+                self.reload_spec(spec, Some(text.as_str()))?;
+                action_tx.send(Action::Update)?;
+                String::from("spec reloaded")
+              },
+              None => String::from("no spec changes to reload"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::Record(ref target) => {
+            if let Some(handle) = self.recorder.take() {
+              handle.abort();
+            }
+            let status_line = match target.as_ref().map(|target| reqwest::Url::parse(target)) {
+              None => {
+                let recorded = self.recording.as_ref().map_or(0, |(_, exchanges)| exchanges.len());
+                format!("recording stopped, {recorded} calls recorded, :record save or :record merge them")
+              },
+              Some(Ok(url)) => {
+                let listen_address = self.config.record.listen_address.clone();
+                let client = self.state.client.clone();
+                match recorder::listen(listen_address.as_str(), url.clone(), client, action_tx.clone()).await {
+                  Ok((address, handle)) => {
+                    self.recorder = Some(handle);
+                    self.recording = Some((url.to_string(), vec![]));
+                    self.kept_recorded = 0;
+                    format!("recording calls to http://{address}, forwarded to {url}, :record stop to stop")
+                  },
+                  Err(error) => format!("can't listen on {listen_address}: {error}"),
+                }
+              },
+              Some(Err(error)) => format!("invalid record target: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 5))?;
+          },
+          Action::Recorded(ref exchange) => {
+            if let Some((_, exchanges)) = self.recording.as_mut() {
+              exchanges.push(exchange.clone());
+              let status_line = format!(
+                "recorded {} {} → {}, {} calls",
+                exchange.method.to_uppercase(),
+                exchange.path,
+                exchange.status,
+                exchanges.len()
+              );
+              action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+            }
+          },
+          Action::SaveRecording(ref filepath) => {
+            let status_line = match &self.recording {
+              Some((target, exchanges)) => {
+                match serde_yaml::to_string(&recorder::draft_spec(exchanges, target))
+                  .map_err(color_eyre::Report::from)
+                  .and_then(|draft| std::fs::write(filepath, draft).map_err(color_eyre::Report::from))
+                {
+                  Ok(_) => {
+                    self.kept_recorded = exchanges.len();
+                    format!("draft spec of {} calls saved to {filepath}", exchanges.len())
+                  },
+                  Err(error) => format!("can't save the draft spec: {error}"),
+                }
+              },
+              None => String::from("nothing is recorded, start with :record <target-url>"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::MergeRecording => {
+            let status_line = match &self.recording {
+              Some((_, exchanges)) => match recorder::merge(&self.state.openapi_spec, exchanges) {
+                Ok(spec) => {
+                  self.kept_recorded = exchanges.len();
+                  self.reload_spec(spec, None)?;
+                  action_tx.send(Action::Update)?;
+                  String::from("recorded operations merged into the spec, the spec file is left untouched")
+                },
+                Err(error) => format!("can't merge the recorded operations: {error}"),
+              },
+              None => String::from("nothing is recorded, start with :record <target-url>"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::ImportPostman(ref filepath) | Action::ImportHttp(ref filepath) => {
+            let parse = if matches!(action, Action::ImportPostman(_)) { postman::parse } else { http_file::parse };
+            let status_line =
+              match std::fs::read_to_string(filepath).map_err(color_eyre::Report::from).and_then(|text| parse(&text)) {
+                Ok(collection) => self.import_drafts(collection, &action_tx, &request_tx)?,
+                Err(error) => format!("can't import {filepath}: {error}"),
+              };
+            action_tx.send(Action::TimedStatusLine(status_line, 5))?;
+          },
+          Action::ExportPostman(ref filepath) | Action::ExportInsomnia(ref filepath) => {
+            let requests = self.draft_requests();
+            let name = self.state.openapi_spec.info.title.clone();
+            let collection = match action {
+              Action::ExportPostman(_) => postman::export(name.as_str(), &requests, &self.state.variables),
+              _ => insomnia::export(name.as_str(), &requests, &self.state.variables),
+            };
+            let status_line = match serde_json::to_string_pretty(&collection)
+              .map_err(color_eyre::Report::from)
+              .and_then(|text| std::fs::write(filepath, text).map_err(color_eyre::Report::from))
+            {
+              Ok(_) => format!(
+                "{} requests and {} variables exported to {filepath}",
+                requests.len(),
+                self.state.variables.len()
+              ),
+              Err(error) => format!("can't export the collection: {error}"),
+            };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::PickFile(ref command) => {
+            self.popup = Some(Box::new(FilePickerPane::new(command.clone())));
+          },
+          Action::Confirmed(ref action) => {
+            self.popup = None;
+            action_tx.send(*action.clone())?;
+          },
+          Action::BulkInvoke => {
+            let requests = self.bulk_requests(&request_tx);
+            let warnings =
+              self.rate_limit_warnings(&requests.iter().map(|(operation_item, _)| operation_item).collect::<Vec<_>>());
+            if requests.is_empty() {
+              action_tx.send(Action::TimedStatusLine("no operation is marked, mark them with space".into(), 3))?;
+            } else if !warnings.is_empty()
+              || requests.iter().any(|(_, request)| {
+                request.as_ref().is_ok_and(|request| {
+                  self.config.confirm.requires_confirmation(request.method().as_str(), request.url().as_str())
+                })
+              })
+            {
+              let question = format!("Invoke {} marked operations?", requests.len());
+              action_tx.send(Action::Confirm(
+                warnings.into_iter().chain(std::iter::once(question)).collect::<Vec<_>>().join("\n\n"),
+                Box::new(Action::ConfirmedBulkInvoke),
+              ))?;
+            } else {
+              self.bulk_invoke(&action_tx, &request_tx)?;
+            }
+          },
+          Action::ConfirmedBulkInvoke => {
+            self.bulk_invoke(&action_tx, &request_tx)?;
+          },
+          Action::Smoke => {
+            let operation_items = self
+              .state
+              .openapi_operations
+              .iter()
+              .filter(|operation_item| self.is_smoke_operation(operation_item))
+              .collect::<Vec<_>>();
+            let warnings = self.rate_limit_warnings(&operation_items);
+            if warnings.is_empty() {
+              self.smoke(&action_tx, &request_tx)?;
+            } else {
+              action_tx.send(Action::Confirm(
+                format!("{}\n\nRun the smoke test anyway?", warnings.join("\n\n")),
+                Box::new(Action::ConfirmedSmoke),
+              ))?;
+            }
+          },
+          Action::TrustCertificate(ref host, ref fingerprint, ref operation_id) => {
+            let status_line =
+              match self.state.known_hosts.as_mut().map(|known_hosts| known_hosts.trust(host, fingerprint)) {
+                Some(Ok(())) => match operation_id {
+                  Some(operation_id) => {
+                    action_tx.send(Action::NewCall(Some(operation_id.clone())))?;
+                    action_tx.send(Action::Dial)?;
+                    format!(
+                      "trusting the new certificate of {host}, sending {} again",
+                      self.operation_label(operation_id)
+                    )
+                  },
+                  None => format!("trusting the new certificate of {host}"),
+                },
+                Some(Err(error)) => format!("can't save the certificate of {host}: {error}"),
+                None => String::from("trust on first use isn't enabled"),
+              };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::PinCertificate(ref host, ref fingerprint, ref operation_id) => {
+            let status_line =
+              match self.state.known_hosts.as_mut().map(|known_hosts| known_hosts.pin(host, fingerprint)) {
+                Some(Ok(())) => {
+                  action_tx.send(Action::NewCall(Some(operation_id.clone())))?;
+                  action_tx.send(Action::Dial)?;
+                  format!("certificate of {host} pinned, sending {} again", self.operation_label(operation_id))
+                },
+                Some(Err(error)) => format!("can't save the certificate of {host}: {error}"),
+                None => String::from("trust on first use isn't enabled"),
+              };
+            action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+          },
+          Action::ConfirmedSmoke => {
+            self.smoke(&action_tx, &request_tx)?;
+          },
+          Action::ClosePopup => {
             if self.popup.is_some() {
               self.popup = None;
             }
@@ -266,26 +1059,57 @@ impl App {
       }
 
       while let Ok(request) = request_rx.try_recv() {
-        if let Ok(response) = reqwest::Client::new().execute(request.request).await {
-          self.state.responses.insert(
-            request.operation_id,
-            Response {
-              status: response.status(),
-              version: response.version(),
-              headers: response.headers().clone(),
-              content_length: response.content_length(),
-              body: response.text().await?.clone(),
-            },
-          );
+        let operation = self.operation_label(request.operation_id.as_str());
+        match self.executor.dispatch(request) {
+          Dispatch::Sent => {},
+          Dispatch::AlreadyInFlight => {
+            action_tx.send(Action::TimedStatusLine(format!("{operation} is already in flight"), 3))?;
+          },
+          Dispatch::Queued => action_tx.send(Action::TimedStatusLine(
+            format!("{operation} is already in flight, it will be sent once more when it finishes"),
+            3,
+          ))?,
         }
       }
 
+      while let Some(completed) = self.executor.try_completed() {
+        let operation_id = completed.operation_id.clone();
+        let elapsed = completed.started_at.elapsed();
+        let result =
+          completed.result.map(|response| self.store_response(operation_id.as_str(), completed.append, response));
+        match &result {
+          Ok(_) if self.config.dial.focus_response => action_tx.send(Action::FocusResponse(operation_id.clone()))?,
+          Ok(_) => {},
+          Err(error) => {
+            log::error!("{error}");
+            self.offer_trust(operation_id.as_str(), error);
+          },
+        }
+        if self.config.notify.enabled && !self.terminal_focused && elapsed.as_secs() >= self.config.notify.after_secs {
+          self.notify_finished(operation_id.as_str(), result, elapsed)?;
+        }
+      }
+      self.state.in_flight = self.executor.in_flight().cloned().collect();
+      self.receive_batch(&action_tx)?;
+
+      while let Some(request) = self.control.as_mut().and_then(ControlServer::try_request) {
+        let result = self.handle_control(&request, &action_tx);
+        request.reply(result);
+      }
+
+      self.update_terminal_title()?;
+
       if self.should_suspend {
         tui.suspend()?;
         action_tx.send(Action::Resume)?;
-        tui = tui::Tui::new()?;
+        tui = tui::Tui::new()?
+          .frame_rate(self.config.display.frame_rate())
+          .tick_rate(self.config.display.tick_rate())
+          .paste(true);
         tui.enter()?;
       } else if self.should_quit {
+        self.state.save_settings();
+        self.save_history(&self.drafts());
         tui.stop()?;
         break;
       }
@@ -294,6 +1118,869 @@ impl App {
     Ok(())
   }
 
+  /// Handles the `m1`..`m9` and `'1`..`'9` bookmark sequences on the home page, returns whether the key was
+  /// consumed. Request pages and popups get `m` and `'` as typed.
+  fn handle_bookmark_keys(&mut self, event: &tui::Event, action_tx: &mpsc::UnboundedSender<Action>) -> Result<bool> {
+    let tui::Event::Key(key) = event else {
+      return Ok(false);
+    };
+    if self.popup.is_some()
+      || self.mode() != Mode::Home
+      || self.state.input_mode != InputMode::Normal
+      || !key.modifiers.difference(KeyModifiers::SHIFT).is_empty()
+    {
+      self.pending_bookmark_key = None;
+      return Ok(false);
+    }
+    if let Some(prefix) = self.pending_bookmark_key.take() {
+      if let KeyCode::Char(slot @ '1'..='9') = key.code {
+        let slot = slot.to_digit(10).unwrap_or_default();
+        action_tx.send(if prefix == 'm' { Action::SetBookmark(slot) } else { Action::JumpToBookmark(slot) })?;
+        return Ok(true);
+      }
+    }
+    match key.code {
+      KeyCode::Char(prefix @ ('m' | '\'')) => {
+        self.pending_bookmark_key = Some(prefix);
+        if prefix == '\'' {
+          let jump_list = self
+            .state
+            .bookmarks
+            .iter()
+            .map(|(slot, operation_id)| format!("[{slot} {}]", self.operation_label(operation_id)))
+            .collect::<Vec<_>>();
+          let status_line =
+            if jump_list.is_empty() { String::from("no bookmarks, set them with m1..m9") } else { jump_list.join(" ") };
+          action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+        }
+        Ok(true)
+      },
+      _ => Ok(false),
+    }
+  }
+
+  /// Handles `q<register>` to start and `q` to stop recording a macro and `@<register>` to replay one, `@@` replays
+  /// the last one. A `q` followed by anything else, or by nothing for a second, quits. Other keys are recorded while a
+  /// macro is being recorded, including the keys of replayed macros.
+  /// Waits for the register to record into, or stops the recording and saves it.
+  fn record_macro(&mut self, action_tx: &mpsc::UnboundedSender<Action>) -> Result<()> {
+    match self.state.recording_macro.take() {
+      Some((register, mut keys)) => {
+        // the keys bound to stop recording were recorded as well.
+        let stop_keys = self
+          .config
+          .keybindings
+          .get(&self.mode())
+          .into_iter()
+          .flatten()
+          .filter(|(stop_keys, action)| **action == Action::RecordMacro && keys.ends_with(stop_keys))
+          .map(|(stop_keys, _)| stop_keys.len())
+          .max()
+          .unwrap_or_default();
+        keys.truncate(keys.len() - stop_keys);
+        action_tx.send(Action::SaveMacro(register, keys))?;
+      },
+      None => {
+        self.pending_macro_key = Some(MacroKey::Record);
+        action_tx.send(Action::TimedStatusLine(String::from("record a macro into register a-z"), 3))?;
+      },
+    }
+    Ok(())
+  }
+
+  fn handle_macro_keys(&mut self, event: &tui::Event, action_tx: &mpsc::UnboundedSender<Action>) -> Result<bool> {
+    let tui::Event::Key(key) = event else {
+      return Ok(false);
+    };
+    let is_command_key = self.popup.is_none()
+      && self.state.input_mode == InputMode::Normal
+      && key.modifiers.difference(KeyModifiers::SHIFT).is_empty();
+    if let Some(prefix) = self.pending_macro_key.take() {
+      let register = match key.code {
+        KeyCode::Char('@') if prefix == MacroKey::Replay => self.last_macro,
+        KeyCode::Char(register @ 'a'..='z') => Some(register),
+        _ => None,
+      };
+      if prefix == MacroKey::Record && (register.is_none() || !is_command_key) {
+        action_tx.send(Action::TimedStatusLine(String::from("macro not recorded, registers are a-z"), 3))?;
+        return Ok(true);
+      }
+      if let Some(register) = register.filter(|_| is_command_key) {
+        if prefix == MacroKey::Record {
+          self.state.recording_macro = Some((register, vec![]));
+        } else {
+          match self.state.macros.get(&register) {
+            Some(keys) => self.replaying_keys.extend(keys.iter().copied()),
+            None => action_tx.send(Action::TimedStatusLine(format!("no macro recorded in @{register}"), 3))?,
+          }
+          self.last_macro = Some(register);
+        }
+        return Ok(true);
+      }
+    }
+    if is_command_key && key.code == KeyCode::Char('@') {
+      self.pending_macro_key = Some(MacroKey::Replay);
+      return Ok(true);
+    }
+    if let Some((_, keys)) = &mut self.state.recording_macro {
+      keys.push(*key);
+    }
+    Ok(false)
+  }
+
+  /// Operation of the current page and the value of one of its documentation fields.
+  fn spec_field_value(&self, field: &SpecField) -> Result<(String, String)> {
+    let operation_item = match self.pages.first().and_then(|page| page.operation_id()) {
+      Some(operation_id) => self.state.get_operation(Some(operation_id)),
+      None => self.state.active_operation(),
+    }
+    .context("no operation is selected")?;
+    let operation = &operation_item.operation;
+    let value = match field {
+      SpecField::Summary => operation.summary.clone(),
+      SpecField::Description => operation.description.clone(),
+      SpecField::ParameterDescription(name) => {
+        let parameter = operation
+          .parameters
+          .iter()
+          .flatten()
+          .find_map(|parameter| match parameter {
+            ObjectOrRef::Object(parameter) if parameter.name.eq(name) => Some(parameter),
+            _ => None,
+          })
+          .context(format!("parameter {name} is not defined inline"))?;
+        parameter.description.clone()
+      },
+    };
+    Ok((operation.operation_id.clone().context("operation has no id")?, value.unwrap_or_default()))
+  }
+
+  fn local_spec_source(&self) -> Result<String> {
+    let input_source = self.state.openapi_input_source.clone();
+    if reqwest::Url::parse(input_source.as_str()).is_ok_and(|url| url.scheme().starts_with("http")) {
+      bail!("only local spec files can be edited");
+    }
+    Ok(input_source)
+  }
+
+  /// Edits a documentation field of an operation in the spec file, keeping the previous file as `.bak`.
+  fn patch_spec(&mut self, operation_id: &str, field: &SpecField, value: &str) -> Result<()> {
+    let input_source = self.local_spec_source()?;
+    let operation_item = self
+      .state
+      .openapi_operations
+      .iter_mut()
+      .find(|operation_item| operation_item.operation.operation_id.as_deref() == Some(operation_id))
+      .context("operation not found")?;
+    let source = std::fs::read_to_string(&input_source)?;
+    let patched = spec_patch::patch(&source, &operation_item.path, &operation_item.method, field, value)?;
+    spec_patch::validate(&patched)?;
+    std::fs::copy(&input_source, format!("{input_source}.bak"))?;
+    std::fs::write(&input_source, patched)?;
+
+    let value = (!value.is_empty()).then(|| value.to_string());
+    let operation = &mut operation_item.operation;
+    match field {
+      SpecField::Summary => operation.summary = value,
+      SpecField::Description => operation.description = value,
+      SpecField::ParameterDescription(name) => {
+        for parameter in operation.parameters.iter_mut().flatten() {
+          if let ObjectOrRef::Object(parameter) = parameter {
+            if parameter.name.eq(name) {
+              parameter.description.clone_from(&value);
+            }
+          }
+        }
+      },
+    }
+    Ok(())
+  }
+
+  /// Saves a request body as the example of a media type in the spec file, keeping the previous file as `.bak`.
+  fn write_request_example(&mut self, operation_id: &str, content_type: &str, body: &str) -> Result<()> {
+    let input_source = self.local_spec_source()?;
+    let example = match serde_json::from_str::<serde_json::Value>(body) {
+      Ok(example) if media_type::is_json(content_type) => example,
+      _ => serde_json::Value::String(body.to_string()),
+    };
+    let operation_item = self
+      .state
+      .openapi_operations
+      .iter_mut()
+      .find(|operation_item| operation_item.operation.operation_id.as_deref() == Some(operation_id))
+      .context("operation not found")?;
+    let source = std::fs::read_to_string(&input_source)?;
+    let patched =
+      spec_patch::patch_example(&source, &operation_item.path, &operation_item.method, content_type, &example)?;
+    spec_patch::validate(&patched)?;
+    std::fs::copy(&input_source, format!("{input_source}.bak"))?;
+    std::fs::write(&input_source, patched)?;
+
+    if let Some(ObjectOrRef::Object(request_body)) = operation_item.operation.request_body.as_mut() {
+      if let Some(media_type) = request_body.content.get_mut(content_type) {
+        media_type.example = Some(example);
+      }
+    }
+    Ok(())
+  }
+
+  /// Handles a call of the control socket, actions are sent through `action_tx` so they run like key presses.
+  fn handle_control(
+    &self,
+    request: &ControlRequest,
+    action_tx: &mpsc::UnboundedSender<Action>,
+  ) -> std::result::Result<serde_json::Value, String> {
+    let current_operation_id = match self.location() {
+      Location::Call(operation_id) => Some(operation_id),
+      Location::Home => None,
+    };
+    let send =
+      |action: Action| action_tx.send(action).map(|_| serde_json::Value::Bool(true)).map_err(|e| e.to_string());
+    match request.method.as_str() {
+      "list_operations" => Ok(
+        self
+          .state
+          .openapi_operations
+          .iter()
+          .chain(self.state.ad_hoc_operations.iter())
+          .map(|operation_item| {
+            serde_json::json!({
+              "operation_id": operation_item.operation.operation_id,
+              "method": operation_item.method,
+              "path": operation_item.path,
+              "summary": operation_item.operation.summary,
+            })
+          })
+          .collect(),
+      ),
+      "select_operation" => {
+        let operation_id = request.param("operation_id")?;
+        self.state.get_operation(Some(operation_id.clone())).ok_or(format!("operation {operation_id} not found"))?;
+        send(Action::NewCall(Some(operation_id)))
+      },
+      "set_parameter" => {
+        current_operation_id.ok_or("no operation selected")?;
+        let name = request.param("name")?;
+        if !self.pages.first().is_some_and(|page| page.has_parameter(name.as_str())) {
+          return Err(format!("no parameter named {name}"));
+        }
+        send(Action::SetParameter(name, request.param("value")?))
+      },
+      "dial" => {
+        current_operation_id.ok_or("no operation selected")?;
+        send(Action::Dial)
+      },
+      "get_response" => {
+        let operation_id =
+          request.param("operation_id").ok().or(current_operation_id).ok_or("no operation selected")?;
+        Ok(self.state.responses.get(&operation_id).map_or(serde_json::Value::Null, |response| {
+          serde_json::json!({
+            "status": response.status.as_u16(),
+            "headers": response
+              .headers
+              .iter()
+              .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).to_string()))
+              .collect::<Vec<_>>(),
+            "body": response.body,
+            "age_ms": response.received_at.elapsed().as_millis() as u64,
+          })
+        }))
+      },
+      method => Err(format!("unknown method {method}")),
+    }
+  }
+
+  fn operation_label(&self, operation_id: &str) -> String {
+    self
+      .state
+      .get_operation(Some(operation_id.to_string()))
+      .map(|operation_item| format!("{} {}", operation_item.method, operation_item.path))
+      .unwrap_or(operation_id.to_string())
+  }
+
+  fn location(&self) -> Location {
+    match self.pages.first().and_then(|page| page.operation_id()) {
+      Some(operation_id) => Location::Call(operation_id),
+      None => Location::Home,
+    }
+  }
+
+  /// Opens the phone page of an operation, restoring its draft from history when there is one.
+  fn open_call(
+    &mut self,
+    operation_id: Option<String>,
+    action_tx: &mpsc::UnboundedSender<Action>,
+    request_tx: &mpsc::UnboundedSender<Request>,
+  ) -> Result<()> {
+    if let Some(operation_item) = self.state.get_operation(operation_id) {
+      if let Some(page) =
+        operation_item.operation.operation_id.clone().and_then(|operation_id| self.history.remove(&operation_id))
+      {
+        self.pages[0].unfocus()?;
+        self.pages.insert(0, page);
+        self.pages[0].focus()?;
+      } else if let Ok(mut page) = Phone::new(operation_item.clone(), request_tx.clone(), &self.state) {
+        self.pages[0].unfocus()?;
+        page.init(&self.state)?;
+        page.register_action_handler(action_tx.clone())?;
+        page.register_config_handler(self.config.clone())?;
+        self.pages.insert(0, Box::new(page));
+        self.pages[0].focus()?;
+      }
+    }
+    if let Location::Call(operation_id) = self.location() {
+      self.state.remember_operation(operation_id.as_str());
+    }
+    Ok(())
+  }
+
+  /// Keymap mode of the active page.
+  fn mode(&self) -> Mode {
+    match self.pages.get(self.active_page).and_then(|page| page.operation_id()) {
+      Some(_) => Mode::Request,
+      None => Mode::Home,
+    }
+  }
+
+  /// Requests of the open pages and of the drafts in history, named after their operation. Drafts that can't be
+  /// built are left out.
+  fn draft_requests(&self) -> Vec<(String, reqwest::Request)> {
+    let open = self.pages.iter().filter_map(|page| Some((page.operation_id()?, page)));
+    let drafts = self.history.iter().map(|(operation_id, page)| (operation_id.clone(), page));
+    let mut requests = open
+      .chain(drafts)
+      .filter_map(|(operation_id, page)| Some((operation_id, page.request()?.ok()?)))
+      .collect::<Vec<_>>();
+    requests.sort_by(|(a, _), (b, _)| a.cmp(b));
+    requests.dedup_by(|(a, _), (b, _)| a == b);
+    requests
+  }
+
+  /// Request page of an operation filled in with `(location, name, value)` parameters and a body with its content
+  /// type, for drafts that weren't typed in this session.
+  fn draft_page(
+    &mut self,
+    operation_item: OperationItem,
+    parameters: impl IntoIterator<Item = (String, String, String)>,
+    body: Option<(Option<String>, String)>,
+    action_tx: &mpsc::UnboundedSender<Action>,
+    request_tx: &mpsc::UnboundedSender<Request>,
+  ) -> Result<Phone> {
+    let mut page = Phone::new(operation_item, request_tx.clone(), &self.state)?;
+    page.init(&self.state)?;
+    page.register_action_handler(action_tx.clone())?;
+    page.register_config_handler(self.config.clone())?;
+    for (location, name, value) in parameters {
+      page.update(Action::ImportParameter(location, name, value), &mut self.state)?;
+    }
+    if let Some((content_type, body)) = body {
+      page.update(Action::ImportBody(content_type, body), &mut self.state)?;
+    }
+    Ok(page)
+  }
+
+  /// Drafts of the open request pages and of history, with their last response.
+  fn drafts(&self) -> Drafts {
+    self
+      .history
+      .iter()
+      .filter_map(|(operation_id, page)| Some((operation_id.clone(), page.draft(&self.state)?)))
+      .chain(self.pages.iter().filter_map(|page| Some((page.operation_id()?, page.draft(&self.state)?))))
+      .filter(|(_, draft)| !draft.is_empty())
+      .collect()
+  }
+
+  /// What quitting now would lose: requests still in flight, recorded calls neither saved nor merged and edits of
+  /// ad-hoc requests, which aren't reopened like the drafts of spec operations.
+  fn quit_losses(&self) -> Vec<String> {
+    let mut losses = vec![];
+    let mut in_flight =
+      self.executor.in_flight().map(|operation_id| self.operation_label(operation_id)).collect::<Vec<_>>();
+    if !in_flight.is_empty() {
+      in_flight.sort();
+      losses.push(format!("· {} requests in flight: {}", in_flight.len(), in_flight.join(", ")));
+    }
+    if let Some(batch) = &self.batch {
+      losses.push(format!("· {} running, {} of {} responses received", batch.title, batch.results.len(), batch.total));
+    }
+    let recorded = self.recording.as_ref().map_or(0, |(_, exchanges)| exchanges.len());
+    if recorded > self.kept_recorded {
+      losses
+        .push(format!("· {} recorded calls, :record save or :record merge keep them", recorded - self.kept_recorded));
+    }
+    let mut ad_hoc = self
+      .history
+      .values()
+      .chain(self.pages.iter())
+      .filter(|page| page.is_dirty())
+      .filter_map(|page| page.operation_id())
+      .filter(|operation_id| {
+        self.state.ad_hoc_operations.iter().any(|item| item.operation.operation_id.as_ref() == Some(operation_id))
+      })
+      .map(|operation_id| self.operation_label(operation_id.as_str()))
+      .collect::<Vec<_>>();
+    if !ad_hoc.is_empty() {
+      ad_hoc.sort();
+      ad_hoc.dedup();
+      losses.push(format!("· edits of {} ad-hoc requests: {}", ad_hoc.len(), ad_hoc.join(", ")));
+    }
+    losses
+  }
+
+  fn save_history(&self, drafts: &Drafts) {
+    if let Err(error) = storage::save(self.state.openapi_input_source.as_str(), HISTORY_FILE, drafts) {
+      log::error!("can't save history: {error}");
+    }
+  }
+
+  /// Reopens the drafts of the previous session into history, with their responses.
+  fn restore_history(
+    &mut self,
+    action_tx: &mpsc::UnboundedSender<Action>,
+    request_tx: &mpsc::UnboundedSender<Request>,
+  ) -> Result<()> {
+    let drafts: Drafts = storage::load(self.state.openapi_input_source.as_str(), HISTORY_FILE);
+    for (operation_id, draft) in drafts {
+      let Some(operation_item) = self.state.get_operation(Some(operation_id.clone())).cloned() else {
+        continue;
+      };
+      if let Some(response) = &draft.response {
+        self.state.responses.insert(operation_id.clone(), response.to_response());
+      }
+      let body = draft.body.map(|body| (draft.content_type, body));
+      let page = self.draft_page(operation_item, draft.parameters, body, action_tx, request_tx)?;
+      self.history.insert(operation_id, Box::new(page));
+    }
+    Ok(())
+  }
+
+  /// Turns the requests of an imported collection into drafts of the operations they call, opened with the values of
+  /// the collection, and adds the collection variables to the captured ones. Later requests to the same operation
+  /// replace earlier drafts.
+  fn import_drafts(
+    &mut self,
+    collection: import::Collection,
+    action_tx: &mpsc::UnboundedSender<Action>,
+    request_tx: &mpsc::UnboundedSender<Request>,
+  ) -> Result<String> {
+    let mut drafts = 0;
+    let mut unmatched = vec![];
+    for request in &collection.requests {
+      let Some((index, path_parameters)) = import::find_operation(request, &self.state.openapi_operations) else {
+        unmatched.push(format!("{} {}", request.method, request.name));
+        continue;
+      };
+      let operation_item = self.state.openapi_operations[index].clone();
+      let operation_id = operation_item.operation.operation_id.clone().unwrap_or_default();
+      let parameters = path_parameters
+        .into_iter()
+        .map(|(name, value)| (String::from("path"), name, value))
+        .chain(request.query.iter().map(|(name, value)| (String::from("query"), name.clone(), value.clone())))
+        .chain(
+          request
+            .headers
+            .iter()
+            .filter(|(name, _)| !name.eq_ignore_ascii_case("content-type"))
+            .map(|(name, value)| (String::from("header"), name.clone(), value.clone())),
+        );
+      let body = request.body.clone().map(|body| (request.content_type().map(String::from), body));
+      let page = self.draft_page(operation_item, parameters, body, action_tx, request_tx)?;
+      self.history.insert(operation_id, Box::new(page));
+      drafts += 1;
+    }
+    let variables = collection.variables.len();
+    for (name, _) in &collection.variables {
+      self.state.variable_sources.remove(name);
+    }
+    self.state.variables.extend(collection.variables);
+    let mut status_line = format!("{drafts} drafts and {variables} variables imported, find the drafts in :history");
+    if !unmatched.is_empty() {
+      status_line.push_str(format!(", no operation for {}", unmatched.join(", ")).as_str());
+    }
+    Ok(status_line)
+  }
+
+  /// Fetches the spec url every interval and sends its text when it differs from the previous fetch.
+  fn watch_spec(
+    &self,
+    url: reqwest::Url,
+    interval: Duration,
+    action_tx: &mpsc::UnboundedSender<Action>,
+  ) -> JoinHandle<()> {
+    let client = self.state.client.clone();
+    let action_tx = action_tx.clone();
+    tokio::spawn(async move {
+      let mut last_text = None;
+      loop {
+        tokio::time::sleep(interval).await;
+        let response = match client.get(url.clone()).build() {
+          Ok(request) => client.execute(request).await,
+          Err(error) => Err(error),
+        };
+        let text = match response.and_then(|response| response.error_for_status()) {
+          Ok(response) => response.text().await,
+          Err(error) => Err(error),
+        };
+        match text {
+          Ok(text) if last_text.as_ref() != Some(&text) => {
+            last_text = Some(text.clone());
+            if action_tx.send(Action::SpecFetched(text)).is_err() {
+              break;
+            }
+          },
+          Ok(_) => {},
+          Err(error) => log::error!("can't fetch the watched spec: {error}"),
+        }
+      }
+    })
+  }
+
+  /// Loads a newer version of the spec, request pages built from the old one are closed.
+  fn reload_spec(&mut self, spec: Openapi, text: Option<&str>) -> Result<()> {
+    self.state.replace_spec(spec, text);
+    self.history.clear();
+    while self.pages.len() > 1 {
+      self.pages.remove(0);
+    }
+    self.pages[0].init(&self.state)?;
+    self.pages[0].focus()?;
+    Ok(())
+  }
+
+  fn hang_up(&mut self, operation_id: Option<String>) -> Result<()> {
+    self.pages[0].unfocus()?;
+    let page = self.pages.remove(0);
+    self.pages[0].focus()?;
+    if let Some(operation_id) = operation_id {
+      self.history.insert(operation_id, page);
+    }
+    Ok(())
+  }
+
+  fn go_to(
+    &mut self,
+    location: Location,
+    action_tx: &mpsc::UnboundedSender<Action>,
+    request_tx: &mpsc::UnboundedSender<Request>,
+  ) -> Result<()> {
+    match location {
+      Location::Home => {
+        while self.pages.len() > 1 {
+          let operation_id = self.pages[0].operation_id();
+          self.hang_up(operation_id)?;
+        }
+      },
+      Location::Call(operation_id) => {
+        if let Some(index) = self.pages.iter().position(|page| page.operation_id().as_ref() == Some(&operation_id)) {
+          self.pages[0].unfocus()?;
+          let page = self.pages.remove(index);
+          self.pages.insert(0, page);
+          self.pages[0].focus()?;
+        } else {
+          self.open_call(Some(operation_id), action_tx, request_tx)?;
+        }
+      },
+    }
+    if let Location::Call(operation_id) = self.location() {
+      self.state.remember_operation(operation_id.as_str());
+    }
+    Ok(())
+  }
+
+  fn update_terminal_title(&mut self) -> Result<()> {
+    let operation_item = match self.pages.get(self.active_page).and_then(|page| page.operation_id()) {
+      Some(operation_id) => self.state.get_operation(Some(operation_id)),
+      None => self.state.active_operation(),
+    };
+    let mut title = format!("{} — {}", env!("CARGO_PKG_NAME"), self.state.openapi_spec.info.title);
+    if let Some(operation_item) = operation_item {
+      title = format!("{title} — {} {}", operation_item.method, operation_item.path);
+    }
+    if title != self.terminal_title {
+      tui::set_title(title.as_str())?;
+      self.terminal_title = title;
+    }
+    Ok(())
+  }
+
+  fn notify_finished(
+    &self,
+    operation_id: &str,
+    result: Result<reqwest::StatusCode>,
+    elapsed: std::time::Duration,
+  ) -> Result<()> {
+    let operation = self.operation_label(operation_id);
+    let outcome = match result {
+      Ok(status) => status.to_string(),
+      Err(error) => format!("failed, {error}"),
+    };
+    tui::notify(env!("CARGO_PKG_NAME"), format!("{operation} {outcome} in {}s", elapsed.as_secs()).as_str())
+  }
+
+  fn store_response(&mut self, operation_id: &str, append: bool, mut response: Response) -> reqwest::StatusCode {
+    if let Some(previous) = self.state.responses.get(operation_id).filter(|_| append) {
+      response.body = pagination::append_page(&previous.body, &response.body);
+      response.content_length = None;
+    }
+    let status = response.status;
+    self.state.record_call(operation_id);
+    self.state.set_response(operation_id, response);
+    let stored = self.state.responses.get(operation_id);
+    if let Some((certificate, previous)) =
+      stored.and_then(|response| response.certificate.as_ref().zip(response.certificate_changed.as_ref()))
+    {
+      let message = format!(
+        "The certificate of {} changed since it was first trusted.\n\nremembered {previous}\npresented  {}\n\nTrust the new certificate? (Y/n)",
+        certificate.host, certificate.fingerprint
+      );
+      let action = Action::TrustCertificate(certificate.host.clone(), certificate.fingerprint.clone(), None);
+      self.popup = Some(Box::new(ConfirmPane::new("Certificate changed".into(), message, action)));
+    }
+    status
+  }
+
+  /// Asks to trust the certificate the handshake of a failed call refused, pinning one that didn't validate or
+  /// replacing the one remembered for its host, and sends the call again once it's trusted.
+  fn offer_trust(&mut self, operation_id: &str, error: &color_eyre::Report) {
+    let Some(rejected) = self.state.known_hosts.as_ref().and_then(|known_hosts| known_hosts.pins().take_rejected())
+    else {
+      return;
+    };
+    let url = error.chain().find_map(|error| error.downcast_ref::<reqwest::Error>()).and_then(reqwest::Error::url);
+    let host = url
+      .and_then(|url| Some(format!("{}:{}", url.host_str()?, url.port_or_known_default()?)))
+      .unwrap_or_else(|| format!("{}:443", rejected.host));
+    let (title, message, action) = match rejected.remembered {
+      Some(remembered) => (
+        "Certificate changed",
+        format!(
+          "The certificate of {host} changed since it was first trusted, the connection was refused.\n\nremembered {remembered}\npresented  {}\n\nTrust the new certificate and send again? (Y/n)",
+          rejected.fingerprint
+        ),
+        Action::TrustCertificate(host, rejected.fingerprint, Some(operation_id.to_string())),
+      ),
+      None => (
+        "Certificate not trusted",
+        format!(
+          "The certificate of {host} doesn't validate against the system CAs.\n\npresented {}\n\nPin it, accept it from now on and send again? (Y/n)",
+          rejected.fingerprint
+        ),
+        Action::PinCertificate(host, rejected.fingerprint, operation_id.to_string()),
+      ),
+    };
+    self.popup = Some(Box::new(ConfirmPane::new(title.into(), message, action)));
+  }
+
+  /// Requests of the marked operations in spec order, built from their drafts when they have one.
+  fn bulk_requests(
+    &self,
+    request_tx: &mpsc::UnboundedSender<Request>,
+  ) -> Vec<(OperationItem, Result<reqwest::Request>)> {
+    self
+      .state
+      .openapi_operations
+      .iter()
+      .filter(|operation_item| {
+        matches!(operation_item.r#type, OperationItemType::Path) && self.state.is_marked(operation_item)
+      })
+      .map(|operation_item| {
+        let draft = operation_item
+          .operation
+          .operation_id
+          .as_ref()
+          .and_then(|operation_id| self.history.get(operation_id))
+          .and_then(|page| page.request());
+        let request = draft.unwrap_or_else(|| {
+          let mut page = Phone::new(operation_item.clone(), request_tx.clone(), &self.state)?;
+          page.init(&self.state)?;
+          page.request().context("can't build request")?
+        });
+        (operation_item.clone(), request)
+      })
+      .collect()
+  }
+
+  /// Assertions of an operation failing on its last response, with what was found instead.
+  fn failed_assertions(&self, operation_id: &str) -> Vec<String> {
+    let (Some(assertions), Some(response)) =
+      (self.state.assertions.get(operation_id), self.state.responses.get(operation_id))
+    else {
+      return vec![];
+    };
+    assertions::evaluate(assertions, response.status, response.body.as_str())
+      .into_iter()
+      .filter_map(|(text, result)| result.err().map(|error| format!("{text}: {error}")))
+      .collect()
+  }
+
+  /// Request of an operation with what its page sends it with besides its parameters and body.
+  fn prepare(&self, operation_item: &OperationItem, mut request: reqwest::Request) -> Result<reqwest::Request> {
+    let operation_id = operation_item.operation.operation_id.clone().unwrap_or_default();
+    request::prepare(
+      &mut request,
+      &operation_item.operation,
+      &operation_id,
+      &self.state,
+      true,
+      self.config.trace.traceparent,
+    )?;
+    Ok(request)
+  }
+
+  /// Sends the requests of the marked operations one after the other in the background.
+  fn bulk_invoke(
+    &mut self,
+    action_tx: &mpsc::UnboundedSender<Action>,
+    request_tx: &mpsc::UnboundedSender<Request>,
+  ) -> Result<()> {
+    let requests = self
+      .bulk_requests(request_tx)
+      .into_iter()
+      .map(|(operation_item, request)| {
+        let request = request.and_then(|request| self.prepare(&operation_item, request));
+        (operation_item, request)
+      })
+      .collect();
+    self.start_batch("Bulk Invoke", requests, 1, action_tx)
+  }
+
+  /// Sends a batch of requests in the background, unless one is still running.
+  fn start_batch(
+    &mut self,
+    title: &str,
+    requests: Vec<(OperationItem, Result<reqwest::Request>)>,
+    concurrency: usize,
+    action_tx: &mpsc::UnboundedSender<Action>,
+  ) -> Result<()> {
+    if let Some(batch) = &self.batch {
+      action_tx.send(Action::TimedStatusLine(format!("{} is still running", batch.title), 3))?;
+      return Ok(());
+    }
+    let total = requests.len();
+    action_tx.send(Action::TimedStatusLine(format!("{title} of {total} operations started"), 3))?;
+    let completed_rx = executor::spawn_batch(self.state.client.clone(), requests, concurrency);
+    self.batch = Some(Batch { title: title.to_string(), total, results: vec![], completed_rx });
+    Ok(())
+  }
+
+  /// Stores the responses of the running batch that arrived, reporting the progress in the status line, and shows
+  /// its summary after the last one.
+  fn receive_batch(&mut self, action_tx: &mpsc::UnboundedSender<Action>) -> Result<()> {
+    let Some(mut batch) = self.batch.take() else {
+      return Ok(());
+    };
+    loop {
+      let (operation_item, response, elapsed) = match batch.completed_rx.try_recv() {
+        Ok(completed) => completed,
+        Err(mpsc::error::TryRecvError::Empty) => {
+          self.batch = Some(batch);
+          return Ok(());
+        },
+        Err(mpsc::error::TryRecvError::Disconnected) => break,
+      };
+      let operation_id = operation_item.operation.operation_id.clone().unwrap_or_default();
+      let status = response.map(|response| self.store_response(operation_id.as_str(), false, response));
+      let failed_assertions = match status {
+        Ok(_) => self.failed_assertions(operation_id.as_str()),
+        Err(_) => vec![],
+      };
+      let outcome = match &status {
+        Ok(status) => status.to_string(),
+        Err(error) => format!("failed, {error}"),
+      };
+      let status_line = format!(
+        "{} {}/{}: {} {outcome}",
+        batch.title,
+        batch.results.len() + 1,
+        batch.total,
+        self.operation_label(operation_id.as_str())
+      );
+      action_tx.send(Action::TimedStatusLine(status_line, 3))?;
+      batch.results.push(InvokeResult {
+        failed_assertions,
+        operation_id,
+        method: operation_item.method,
+        path: operation_item.path,
+        status: status.map_err(|error| error.to_string()),
+        elapsed,
+      });
+    }
+    let mut summary = BulkSummaryPane::new(batch.title, batch.results);
+    summary.register_config_handler(self.config.clone())?;
+    self.popup = Some(Box::new(summary));
+    Ok(())
+  }
+
+  /// Operations the smoke test calls, the GETs that need no input.
+  fn is_smoke_operation(&self, operation_item: &OperationItem) -> bool {
+    matches!(operation_item.r#type, OperationItemType::Path)
+      && operation_item.method.eq_ignore_ascii_case("GET")
+      && !operation_item.requires_input(&self.state.openapi_spec)
+  }
+
+  /// Why sending a request to each of the operations may go over the rate limits, from the limits the spec declares
+  /// for the whole API and for the operations, and the quota the latest response reported.
+  fn rate_limit_warnings(&self, operation_items: &[&OperationItem]) -> Vec<String> {
+    let count = operation_items.len() as u64;
+    let mut warnings = vec![];
+    let declared = &self.state.declared_rate_limits;
+    if let Some(limit) = declared.api.filter(|limit| count > limit.limit) {
+      warnings.push(format!("{count} requests go over the limit of {limit} the spec declares"));
+    }
+    let mut operation_limits: Vec<(RateLimit, u64)> = vec![];
+    for limit in operation_items.iter().filter_map(|item| declared.operation(&item.method, &item.path)) {
+      match operation_limits.iter_mut().find(|(other, _)| *other == limit) {
+        Some((_, count)) => *count += 1,
+        None => operation_limits.push((limit, 1)),
+      }
+    }
+    for (limit, count) in operation_limits.into_iter().filter(|(limit, count)| *count > limit.limit) {
+      warnings.push(format!("{count} requests go over the limit of {limit} the spec declares for their operations"));
+    }
+    let latest = self
+      .state
+      .responses
+      .values()
+      .filter_map(|response| Some((response.received_at, response.quota?)))
+      .max_by_key(|(received_at, _)| *received_at);
+    if let Some((received_at, quota)) = latest {
+      let reset_in = quota.reset_in(received_at.elapsed());
+      // a quota whose reset has passed is full again
+      let current = quota.reset.is_none() || reset_in.is_some();
+      if let Some(remaining) = quota.remaining.filter(|remaining| current && count > *remaining) {
+        warnings.push(match reset_in {
+          Some(reset_in) => {
+            format!("{count} requests go over the {remaining} left of the quota, it resets in {}s", reset_in.as_secs())
+          },
+          None => format!("{count} requests go over the {remaining} left of the quota"),
+        });
+      }
+    }
+    warnings
+  }
+
+  /// Sends every GET operation that can be called without any input, i.e. all of its required parameters have
+  /// defaults, and reports their statuses.
+  fn smoke(
+    &mut self,
+    action_tx: &mpsc::UnboundedSender<Action>,
+    request_tx: &mpsc::UnboundedSender<Request>,
+  ) -> Result<()> {
+    let requests = self
+      .state
+      .openapi_operations
+      .iter()
+      .filter(|operation_item| self.is_smoke_operation(operation_item))
+      .filter_map(|operation_item| {
+        let mut page = Phone::new(operation_item.clone(), request_tx.clone(), &self.state).ok()?;
+        page.init(&self.state).ok()?;
+        let request = page.request()?.ok()?;
+        Some((operation_item.clone(), self.prepare(operation_item, request)))
+      })
+      .collect();
+    self.start_batch("Smoke Test", requests, SMOKE_CONCURRENCY, action_tx)
+  }
+
   fn draw(&mut self, frame: &mut tui::Frame<'_>) -> Result<()> {
     let vertical_layout =
       Layout::vertical(vec![Constraint::Max(1), Constraint::Fill(1), Constraint::Max(1)]).split(frame.area());
@@ -312,6 +1999,103 @@ impl App {
       popup.draw(frame, popup_layout[1], &self.state)?;
     }
     self.footer.draw(frame, vertical_layout[2], &self.state)?;
+
+    // screen readers read box drawing characters aloud, keep only the text.
+    if self.config.display.screen_reader {
+      for cell in frame.buffer_mut().content.iter_mut() {
+        if cell.symbol().chars().next().is_some_and(|c| ('\u{2500}'..='\u{257f}').contains(&c)) {
+          cell.set_symbol(" ");
+        }
+      }
+    }
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_macro_record_and_replay() {
+    let spec = std::env::temp_dir().join(format!("openapi-tui-macro-{}.yaml", std::process::id()));
+    std::fs::write(&spec, "openapi: 3.1.0\ninfo: {title: pets, version: '1'}\npaths: {}\n").unwrap();
+    let mut app = App::new(spec.display().to_string(), None).await.unwrap();
+    std::fs::remove_file(&spec).unwrap();
+    let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+    let key = |c: char| tui::Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+
+    app.record_macro(&action_tx).unwrap();
+    assert!(matches!(action_rx.try_recv(), Ok(Action::TimedStatusLine(..))));
+    let handled = ['a', 'j', 'k'].map(|c| app.handle_macro_keys(&key(c), &action_tx).unwrap());
+    assert_eq!(handled, [true, false, false]);
+    let stop = tui::Event::Key(KeyEvent::new(KeyCode::Char('Q'), KeyModifiers::SHIFT));
+    assert!(!app.handle_macro_keys(&stop, &action_tx).unwrap());
+    app.record_macro(&action_tx).unwrap();
+    let Ok(Action::SaveMacro(register, keys)) = action_rx.try_recv() else {
+      panic!("macro not saved");
+    };
+    assert_eq!(register, 'a');
+    app.state.macros.insert(register, keys);
+
+    assert!(app.handle_macro_keys(&key('@'), &action_tx).unwrap());
+    assert!(app.handle_macro_keys(&key('a'), &action_tx).unwrap());
+    let replayed = app.replaying_keys.iter().map(|key| key.code).collect::<Vec<_>>();
+    assert_eq!(replayed, [KeyCode::Char('j'), KeyCode::Char('k')]);
+
+    assert!(!app.handle_macro_keys(&key('q'), &action_tx).unwrap());
+    assert!(action_rx.try_recv().is_err());
+  }
+
+  #[tokio::test]
+  async fn test_bookmark_keys_on_home_only() {
+    let spec = std::env::temp_dir().join(format!("openapi-tui-bookmark-{}.yaml", std::process::id()));
+    let text = "openapi: 3.1.0\ninfo: {title: pets, version: '1'}\npaths: {/pets: {get: {operationId: listPets}}}\n";
+    std::fs::write(&spec, text).unwrap();
+    let mut app = App::new(spec.display().to_string(), None).await.unwrap();
+    std::fs::remove_file(&spec).unwrap();
+    let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+    let (request_tx, _request_rx) = mpsc::unbounded_channel();
+    let key = |c: char| tui::Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+
+    assert!(app.handle_bookmark_keys(&key('m'), &action_tx).unwrap());
+    assert!(app.handle_bookmark_keys(&key('1'), &action_tx).unwrap());
+    assert_eq!(action_rx.try_recv().ok(), Some(Action::SetBookmark(1)));
+
+    let operation_item = app.state.openapi_operations[0].clone();
+    app.pages.insert(0, Box::new(Phone::new(operation_item, request_tx, &app.state).unwrap()));
+    app.active_page = 0;
+    assert!(!app.handle_bookmark_keys(&key('m'), &action_tx).unwrap());
+    assert!(!app.handle_bookmark_keys(&key('\''), &action_tx).unwrap());
+    assert!(action_rx.try_recv().is_err());
+  }
+
+  #[tokio::test]
+  async fn test_poll_checks_method() {
+    let spec = std::env::temp_dir().join(format!("openapi-tui-poll-{}.yaml", std::process::id()));
+    let text = "openapi: 3.1.0\ninfo: {title: pets, version: '1'}\npaths: {/pets: {post: {operationId: addPet}, delete: {operationId: deletePets}}}\n";
+    std::fs::write(&spec, text).unwrap();
+    let mut app = App::new(spec.display().to_string(), None).await.unwrap();
+    std::fs::remove_file(&spec).unwrap();
+    let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+    let (request_tx, _request_rx) = mpsc::unbounded_channel();
+    let mut poll = |operation_id: &str| {
+      let operation_item = app.state.get_operation(Some(operation_id.to_string())).unwrap();
+      let mut page = Phone::new(operation_item.clone(), request_tx.clone(), &app.state).unwrap();
+      page.register_action_handler(action_tx.clone()).unwrap();
+      page.update(Action::Poll(1000, None), &mut app.state).unwrap();
+      action_rx.try_recv().ok()
+    };
+
+    let Some(Action::TimedStatusLine(status_line, _)) = poll("addPet") else {
+      panic!("polling a post isn't refused");
+    };
+    assert_eq!(status_line, "POST isn't idempotent, polling would repeat its side effects");
+    let Some(Action::Confirm(_, action)) = poll("deletePets") else {
+      panic!("polling a delete isn't confirmed");
+    };
+    assert_eq!(*action, Action::ConfirmedPoll(1000, None));
+  }
+}
@@ -0,0 +1,205 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde_json::Value;
+
+use crate::jsonpath;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+  Eq,
+  Ne,
+  Gt,
+  Ge,
+  Lt,
+  Le,
+}
+
+impl FromStr for Operator {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "==" | "=" => Ok(Operator::Eq),
+      "!=" => Ok(Operator::Ne),
+      ">" => Ok(Operator::Gt),
+      ">=" => Ok(Operator::Ge),
+      "<" => Ok(Operator::Lt),
+      "<=" => Ok(Operator::Le),
+      _ => Err(format!("unknown operator {s}")),
+    }
+  }
+}
+
+impl Operator {
+  fn holds(&self, actual: &Value, expected: &Value) -> Result<bool, String> {
+    if let (Some(actual), Some(expected)) = (actual.as_f64(), expected.as_f64()) {
+      return Ok(match self {
+        Operator::Eq => actual == expected,
+        Operator::Ne => actual != expected,
+        Operator::Gt => actual > expected,
+        Operator::Ge => actual >= expected,
+        Operator::Lt => actual < expected,
+        Operator::Le => actual <= expected,
+      });
+    }
+    match self {
+      Operator::Eq => Ok(actual == expected),
+      Operator::Ne => Ok(actual != expected),
+      _ => Err(format!("{} is not a number", jsonpath::display(actual))),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Check {
+  Exists,
+  Compare { length: bool, operator: Operator, expected: Value },
+}
+
+/// A check of a response, e.g. `status 2xx`, `jsonpath $.items length > 0` or `jsonpath $.name == rex`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assertion {
+  text: String,
+  kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+  /// Status code, or its class like `2xx`.
+  Status(String),
+  JsonPath {
+    path: String,
+    check: Check,
+  },
+}
+
+impl FromStr for Assertion {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let parts = s.split_whitespace().collect::<Vec<_>>();
+    let kind = match parts.as_slice() {
+      ["status", status] if status.len() == 3 && status.chars().all(|c| c.is_ascii_digit() || c == 'x') => {
+        Kind::Status(status.to_string())
+      },
+      ["jsonpath", path, rest @ ..] => {
+        jsonpath::parse(path).ok_or_else(|| format!("invalid jsonpath {path}"))?;
+        let (length, rest) = match rest {
+          ["length", rest @ ..] => (true, rest),
+          rest => (false, rest),
+        };
+        let check = match rest {
+          ["exists"] if !length => Check::Exists,
+          [operator, expected @ ..] if !expected.is_empty() => {
+            let expected = expected.join(" ");
+            let expected = serde_json::from_str(expected.as_str()).unwrap_or(Value::String(expected));
+            Check::Compare { length, operator: operator.parse()?, expected }
+          },
+          _ => return Err(String::from("expected exists or <operator> <value> after the jsonpath")),
+        };
+        Kind::JsonPath { path: path.to_string(), check }
+      },
+      _ => return Err(String::from("expected status <code> or jsonpath <path> [length] <operator> <value>")),
+    };
+    Ok(Self { text: parts.join(" "), kind })
+  }
+}
+
+impl Display for Assertion {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.text)
+  }
+}
+
+impl Assertion {
+  /// Checks a response with its body parsed as JSON, failing with what was found instead.
+  pub fn check(&self, status: reqwest::StatusCode, body: Option<&Value>) -> Result<(), String> {
+    match &self.kind {
+      Kind::Status(expected) => {
+        let matches = expected.chars().zip(status.as_str().chars()).all(|(e, a)| e == 'x' || e == a);
+        if matches {
+          Ok(())
+        } else {
+          Err(format!("got {}", status.as_str()))
+        }
+      },
+      Kind::JsonPath { path, check } => {
+        let body = body.ok_or("body is not JSON")?;
+        let actual = jsonpath::select(body, path);
+        match (check, actual) {
+          (Check::Exists, Some(_)) => Ok(()),
+          (_, None) => Err(String::from("missing")),
+          (Check::Compare { length, operator, expected }, Some(actual)) => {
+            let actual = match (length, actual) {
+              (false, actual) => actual.clone(),
+              (true, Value::Array(items)) => Value::from(items.len()),
+              (true, Value::Object(fields)) => Value::from(fields.len()),
+              (true, Value::String(text)) => Value::from(text.chars().count()),
+              (true, actual) => return Err(format!("{} has no length", jsonpath::display(actual))),
+            };
+            if operator.holds(&actual, expected)? {
+              Ok(())
+            } else {
+              Err(format!("got {}", jsonpath::display(&actual)))
+            }
+          },
+        }
+      },
+    }
+  }
+}
+
+/// Each assertion with whether it passed or what was found instead.
+pub type AssertionResults = Vec<(String, Result<(), String>)>;
+
+/// Results of the assertions on a response, assertions that don't parse fail with the parse error.
+pub fn evaluate(assertions: &[String], status: reqwest::StatusCode, body: &str) -> AssertionResults {
+  let body = serde_json::from_str::<Value>(body).ok();
+  assertions
+    .iter()
+    .map(|text| {
+      let result = text.parse::<Assertion>().and_then(|assertion| assertion.check(status, body.as_ref()));
+      (text.clone(), result)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use reqwest::StatusCode;
+
+  use super::*;
+
+  #[test]
+  fn test_evaluate() {
+    let body = r#"{"items": [1, 2], "name": "rex", "total": 2}"#;
+    let assertions = [
+      "status 2xx",
+      "status  404",
+      "jsonpath $.items length > 0",
+      "jsonpath $.name == rex",
+      "jsonpath $.total >= 3",
+      "jsonpath $.next exists",
+      "jsonpath $.name > 1",
+      "header content-type",
+    ]
+    .map(String::from);
+    let results = evaluate(&assertions, StatusCode::OK, body);
+    assert_eq!(
+      results.iter().map(|(_, result)| result.clone()).collect::<Vec<_>>(),
+      [
+        Ok(()),
+        Err(String::from("got 200")),
+        Ok(()),
+        Ok(()),
+        Err(String::from("got 2")),
+        Err(String::from("missing")),
+        Err(String::from("rex is not a number")),
+        Err(String::from("expected status <code> or jsonpath <path> [length] <operator> <value>")),
+      ]
+    );
+    assert_eq!("status  404".parse::<Assertion>().unwrap().to_string(), "status 404");
+    assert!(evaluate(&assertions[2..3], StatusCode::OK, "<xml/>")[0].1.is_err());
+  }
+}
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use color_eyre::eyre::Result;
+use openapi_31::v31::{Openapi, Operation};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION, COOKIE};
+use serde_json::Value;
+
+use crate::{request, secrets, variables};
+
+/// Characters encoded in the name of an api key sent in the query, the unreserved ones are kept.
+const QUERY_NAME: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/// Part of the request an api key is sent in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyLocation {
+  Header,
+  Query,
+  Cookie,
+}
+
+/// How a security scheme of `components.securitySchemes` is sent, the ones openapi-tui can't send are kept to be
+/// listed anyway.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scheme {
+  ApiKey {
+    name: String,
+    location: KeyLocation,
+  },
+  Bearer {
+    format: Option<String>,
+  },
+  Basic,
+  /// Names of the flows, the access token is pasted in and sent as a bearer token.
+  OAuth2 {
+    flows: Vec<String>,
+  },
+  OpenIdConnect,
+  Unsupported(String),
+}
+
+impl Scheme {
+  fn from_value(value: &Value) -> Self {
+    let text = |key: &str| value[key].as_str().map(String::from);
+    match (value["type"].as_str().unwrap_or_default(), text("scheme").map(|scheme| scheme.to_lowercase())) {
+      ("apiKey", _) => {
+        let location = match value["in"].as_str() {
+          Some("query") => KeyLocation::Query,
+          Some("cookie") => KeyLocation::Cookie,
+          _ => KeyLocation::Header,
+        };
+        Self::ApiKey { name: text("name").unwrap_or_default(), location }
+      },
+      ("http", Some(scheme)) if scheme == "bearer" => Self::Bearer { format: text("bearerFormat") },
+      ("http", Some(scheme)) if scheme == "basic" => Self::Basic,
+      ("http", scheme) => Self::Unsupported(format!("http {}", scheme.unwrap_or_default())),
+      ("oauth2", _) => {
+        Self::OAuth2 { flows: value["flows"].as_object().into_iter().flatten().map(|(flow, _)| flow.clone()).collect() }
+      },
+      ("openIdConnect", _) => Self::OpenIdConnect,
+      (r#type, _) => Self::Unsupported(r#type.to_string()),
+    }
+  }
+
+  /// What the scheme sends and what to fill in for it, e.g. `api key in header X-API-Key`.
+  pub fn summary(&self) -> String {
+    match self {
+      Self::ApiKey { name, location } => {
+        let location = match location {
+          KeyLocation::Header => "header",
+          KeyLocation::Query => "query",
+          KeyLocation::Cookie => "cookie",
+        };
+        format!("api key in {location} {name}")
+      },
+      Self::Bearer { format: Some(format) } => format!("bearer token ({format})"),
+      Self::Bearer { format: None } => String::from("bearer token"),
+      Self::Basic => String::from("basic, user:password"),
+      Self::OAuth2 { flows } if flows.is_empty() => String::from("oauth2, access token"),
+      Self::OAuth2 { flows } => format!("oauth2 {}, access token", flows.join(", ")),
+      Self::OpenIdConnect => String::from("openIdConnect, access token"),
+      Self::Unsupported(r#type) => format!("{type}, not supported"),
+    }
+  }
+
+  fn is_supported(&self) -> bool {
+    !matches!(self, Self::Unsupported(_))
+  }
+
+  /// Where the credential goes, its name and the value sent.
+  fn injection(&self, credential: &str) -> Option<(KeyLocation, String, String)> {
+    match self {
+      Self::ApiKey { name, location } => Some((*location, name.clone(), credential.to_string())),
+      Self::Bearer { .. } | Self::OAuth2 { .. } | Self::OpenIdConnect => {
+        Some((KeyLocation::Header, AUTHORIZATION.to_string(), format!("Bearer {credential}")))
+      },
+      Self::Basic => {
+        Some((KeyLocation::Header, AUTHORIZATION.to_string(), format!("Basic {}", BASE64_STANDARD.encode(credential))))
+      },
+      Self::Unsupported(_) => None,
+    }
+  }
+}
+
+/// Security schemes of the components of a spec by name, read from its text since the parsed spec leaves out how
+/// they are sent.
+#[derive(Debug, Default)]
+pub struct SecuritySchemes {
+  schemes: BTreeMap<String, Scheme>,
+}
+
+impl SecuritySchemes {
+  /// Reads the schemes from the text of a spec, in json or yaml.
+  pub fn from_spec(text: &str) -> Self {
+    let Ok(spec) = serde_yaml::from_str::<Value>(text) else {
+      return Self::default();
+    };
+    let schemes = spec["components"]["securitySchemes"]
+      .as_object()
+      .into_iter()
+      .flatten()
+      .map(|(name, value)| (name.clone(), Scheme::from_value(value)))
+      .collect();
+    Self { schemes }
+  }
+
+  pub fn get(&self, name: &str) -> Option<&Scheme> {
+    self.schemes.get(name)
+  }
+
+  /// Schemes of the first alternative of the requirements that has credentials for all of them, with the
+  /// credentials. Nothing when no alternative is covered, an alternative without schemes makes authentication
+  /// optional.
+  pub fn covered<'a>(
+    &'a self,
+    requirements: &[Vec<String>],
+    credentials: &'a BTreeMap<String, String>,
+  ) -> Vec<(&'a Scheme, &'a str)> {
+    requirements
+      .iter()
+      .filter(|schemes| !schemes.is_empty())
+      .map(|schemes| {
+        schemes
+          .iter()
+          .map(|name| {
+            let scheme = self.schemes.get(name).filter(|scheme| scheme.is_supported())?;
+            Some((scheme, credentials.get(name)?.as_str()))
+          })
+          .collect::<Option<Vec<_>>>()
+      })
+      .find_map(|covered| covered)
+      .unwrap_or_default()
+  }
+}
+
+/// Security requirements of an operation as alternatives of the schemes required together, the ones of the spec
+/// when the operation declares none. `None` when neither declares any.
+pub fn requirements(operation: &Operation, openapi_spec: &Openapi) -> Option<Vec<Vec<String>>> {
+  match &operation.security {
+    Some(requirements) => Some(requirements.iter().map(|requirement| requirement.keys().cloned().collect()).collect()),
+    None => openapi_spec.security.as_ref().and_then(|security| security.as_array()).map(|requirements| {
+      requirements
+        .iter()
+        .map(|requirement| requirement.as_object().map(|schemes| schemes.keys().cloned().collect()).unwrap_or_default())
+        .collect()
+    }),
+  }
+}
+
+/// Security requirements as alternatives separated by `|`, with the schemes required together joined by `+`.
+pub fn describe(requirements: &[Vec<String>]) -> String {
+  if requirements.is_empty() {
+    return String::from("none");
+  }
+  requirements
+    .iter()
+    .map(|schemes| if schemes.is_empty() { String::from("none") } else { schemes.join("+") })
+    .collect::<Vec<_>>()
+    .join(" | ")
+}
+
+/// Adds the credentials of covered schemes to a request, leaving alone the headers, query parameters and cookies it
+/// already sets. `secret://` references are read from the keyring and `{{name}}` placeholders substituted first,
+/// except for api keys in the query which keep the placeholder of their secret until the request is sent.
+pub fn apply(
+  request: &mut reqwest::Request,
+  covered: &[(&Scheme, &str)],
+  variables: &BTreeMap<String, String>,
+) -> Result<()> {
+  for (scheme, credential) in covered {
+    let query_secret =
+      matches!(scheme, Scheme::ApiKey { location: KeyLocation::Query, .. }) && secrets::is_reference(credential);
+    let credential = match query_secret {
+      true => secrets::placeholder(credential),
+      false => variables::substitute(secrets::resolve(credential)?.as_str(), variables),
+    };
+    let Some((location, name, value)) = scheme.injection(credential.as_str()) else {
+      continue;
+    };
+    match location {
+      KeyLocation::Header => {
+        let name = HeaderName::from_bytes(name.as_bytes())?;
+        if !request.headers().contains_key(&name) {
+          let mut value = HeaderValue::from_str(request::encode_header_value(value.as_str()).as_ref())?;
+          value.set_sensitive(true);
+          request.headers_mut().insert(name, value);
+        }
+      },
+      KeyLocation::Query => {
+        if request.url().query_pairs().any(|(key, _)| key == name.as_str()) {
+          continue;
+        }
+        if query_secret {
+          let pair = format!("{}={value}", utf8_percent_encode(name.as_str(), QUERY_NAME));
+          let query = match request.url().query().filter(|query| !query.is_empty()) {
+            Some(query) => format!("{query}&{pair}"),
+            None => pair,
+          };
+          request.url_mut().set_query(Some(query.as_str()));
+        } else {
+          request.url_mut().query_pairs_mut().append_pair(name.as_str(), value.as_str());
+        }
+      },
+      KeyLocation::Cookie => {
+        let cookies = request.headers().get(COOKIE).and_then(|cookies| cookies.to_str().ok()).unwrap_or_default();
+        if !cookies.split(';').any(|cookie| cookie.trim().split('=').next() == Some(name.as_str())) {
+          let cookies = match cookies.is_empty() {
+            true => format!("{name}={value}"),
+            false => format!("{cookies}; {name}={value}"),
+          };
+          let mut value = HeaderValue::from_str(request::encode_header_value(cookies.as_str()).as_ref())?;
+          value.set_sensitive(true);
+          request.headers_mut().insert(COOKIE, value);
+        }
+      },
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  const SPEC: &str = r#"
+components:
+  securitySchemes:
+    api_key: {type: apiKey, name: X-API-Key, in: header}
+    session: {type: apiKey, name: sid, in: cookie}
+    jwt: {type: http, scheme: Bearer, bearerFormat: JWT}
+    basic: {type: http, scheme: basic}
+    petstore_auth:
+      type: oauth2
+      flows: {clientCredentials: {tokenUrl: 'https://example.com/token', scopes: {}}}
+    mtls: {type: mutualTLS}
+"#;
+
+  #[test]
+  fn test_security_schemes() {
+    let schemes = SecuritySchemes::from_spec(SPEC);
+    assert_eq!(schemes.get("api_key").unwrap().summary(), "api key in header X-API-Key");
+    assert_eq!(schemes.get("jwt").unwrap().summary(), "bearer token (JWT)");
+    assert_eq!(schemes.get("petstore_auth").unwrap().summary(), "oauth2 clientCredentials, access token");
+    assert_eq!(schemes.get("mtls").unwrap().summary(), "mutualTLS, not supported");
+
+    let requirements = vec![vec![], vec![String::from("mtls")], vec![String::from("jwt"), String::from("session")]];
+    assert_eq!(describe(&requirements), "none | mtls | jwt+session");
+    let mut credentials = BTreeMap::from([(String::from("mtls"), String::from("cert"))]);
+    assert!(schemes.covered(&requirements, &credentials).is_empty());
+    credentials.insert(String::from("jwt"), String::from("{{token}}"));
+    assert!(schemes.covered(&requirements, &credentials).is_empty());
+    credentials.insert(String::from("session"), String::from("abc"));
+    assert_eq!(schemes.covered(&requirements, &credentials).len(), 2);
+  }
+
+  #[test]
+  fn test_apply() {
+    let schemes = SecuritySchemes::from_spec(SPEC);
+    let query_key = Scheme::ApiKey { name: String::from("api_key"), location: KeyLocation::Query };
+    let covered = [
+      (schemes.get("basic").unwrap(), "{{user}}:secret"),
+      (schemes.get("session").unwrap(), "abc"),
+      (&query_key, "k1"),
+    ];
+    let variables = BTreeMap::from([(String::from("user"), String::from("hamed"))]);
+    let mut request =
+      reqwest::Client::new().get("https://example.com/pets?limit=1").header(COOKIE, "theme=dark").build().unwrap();
+    apply(&mut request, &covered, &variables).unwrap();
+    assert_eq!(request.headers()[AUTHORIZATION], "Basic aGFtZWQ6c2VjcmV0");
+    assert_eq!(request.headers()[COOKIE], "theme=dark; sid=abc");
+    assert_eq!(request.url().as_str(), "https://example.com/pets?limit=1&api_key=k1");
+
+    let mut request = reqwest::Client::new().get("https://example.com/pets").bearer_auth("typed").build().unwrap();
+    apply(&mut request, &[(schemes.get("jwt").unwrap(), "from-auth-pane")], &variables).unwrap();
+    assert_eq!(request.headers()[AUTHORIZATION], "Bearer typed");
+
+    let mut request = reqwest::Client::new().get("https://example.com/pets?limit=1").build().unwrap();
+    apply(&mut request, &[(&query_key, "secret://api-key")], &variables).unwrap();
+    assert_eq!(request.url().as_str(), "https://example.com/pets?limit=1&api_key={{secret://api-key}}");
+  }
+}
@@ -1,15 +1,89 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use clap_complete::Shell;
 
 use crate::utils::version;
 
 #[derive(Parser, Debug)]
-#[command(author, version = version(), about)]
+#[command(author, version = version(), about, subcommand_negates_reqs = true)]
 pub struct Cli {
   #[arg(
     short,
     long,
     value_name = "PATH",
+    required = true,
     help = "Input file or url, in json or yaml format with openapi specification"
   )]
-  pub input: String,
+  pub input: Option<String>,
+
+  #[arg(long, value_name = "OPERATION_ID", help = "Select this operation on startup")]
+  pub operation: Option<String>,
+
+  #[arg(long, value_name = "URL|INDEX", help = "Server to use first, a url or the index of one of the spec servers")]
+  pub server: Option<String>,
+
+  #[arg(
+    long,
+    value_name = "URL",
+    help = "Url relative servers of a spec file are resolved against, e.g. http://localhost:8080"
+  )]
+  pub base_url: Option<String>,
+
+  #[arg(long, requires = "operation", help = "Open the request page of the selected operation on startup")]
+  pub call: bool,
+
+  #[arg(
+    long = "header",
+    value_name = "'NAME: VALUE'",
+    value_parser = parse_header,
+    help = "Header added to every request of the session, can be repeated"
+  )]
+  pub headers: Vec<(String, String)>,
+
+  #[arg(
+    long = "env-file",
+    value_name = "PATH",
+    help = "Dotenv file of variables for {{name}} placeholders, can be repeated, later files override earlier ones"
+  )]
+  pub env_files: Vec<String>,
+
+  #[arg(long, value_name = "PATH", help = "Unix socket where other tools can drive the app with JSON-RPC calls")]
+  pub control_socket: Option<PathBuf>,
+
+  #[command(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Print the completion script of a shell, e.g. `openapi-tui completions zsh > _openapi-tui`
+  Completions { shell: Shell },
+  /// Print the man page in roff, e.g. `openapi-tui man > openapi-tui.1`
+  Man,
+}
+
+fn parse_header(header: &str) -> Result<(String, String), String> {
+  let (name, value) = header.split_once(':').ok_or_else(|| format!("expected 'NAME: VALUE', got '{header}'"))?;
+  let name = name.trim();
+  reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|_| format!("invalid header name '{name}'"))?;
+  Ok((name.to_string(), value.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::CommandFactory;
+
+  use super::*;
+
+  #[test]
+  fn test_man_page() {
+    let mut page = vec![];
+    clap_mangen::Man::new(Cli::command()).render(&mut page).unwrap();
+    let page = String::from_utf8(page).unwrap();
+    assert!(page.starts_with(".ie \\n(.g .ds Aq \\(aq"));
+    assert!(page.contains(".TH openapi-tui 1"));
+    assert!(page.contains("\\fB\\-\\-header\\fR"));
+    assert!(page.contains("openapi\\-tui\\-completions(1)"));
+  }
 }
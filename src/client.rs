@@ -0,0 +1,94 @@
+use reqwest::{
+  header::{HeaderValue, HOST},
+  IntoUrl, Method, Request, RequestBuilder, Response,
+};
+
+/// Client of the requests of the app, the ones to the host and port of an open tunnel are dialed at its local end.
+#[derive(Clone, Debug, Default)]
+pub struct Client {
+  direct: reqwest::Client,
+  /// Client per tunneled host and port, resolving the host to the local end of the tunnel.
+  tunneled: Vec<(String, u16, reqwest::Client)>,
+}
+
+impl Client {
+  pub fn new(direct: reqwest::Client, tunneled: Vec<(String, u16, reqwest::Client)>) -> Self {
+    Self { direct, tunneled }
+  }
+
+  pub fn request(&self, method: Method, url: impl IntoUrl) -> RequestBuilder {
+    self.direct.request(method, url)
+  }
+
+  pub fn get(&self, url: impl IntoUrl) -> RequestBuilder {
+    self.direct.get(url)
+  }
+
+  /// Client dialing the host and port of a url, the one of its tunnel when there is one.
+  fn route(&self, url: &reqwest::Url) -> Option<&reqwest::Client> {
+    let (host, port) = (url.host_str()?, url.port_or_known_default()?);
+    self
+      .tunneled
+      .iter()
+      .find(|(tunneled_host, tunneled_port, _)| tunneled_host == host && *tunneled_port == port)
+      .map(|(_, _, client)| client)
+  }
+
+  /// Sends a request, through the tunnel of its host and port when there is one. A resolved address keeps its port
+  /// only for urls without one, so the port of a tunneled url moves to the `Host` header.
+  pub async fn execute(&self, mut request: Request) -> reqwest::Result<Response> {
+    let Some(client) = self.route(request.url()) else {
+      return self.direct.execute(request).await;
+    };
+    let url = request.url().clone();
+    if let Some((host, port)) = url.host_str().zip(url.port()) {
+      if let Ok(value) = HeaderValue::from_str(format!("{host}:{port}").as_str()) {
+        request.headers_mut().entry(HOST).or_insert(value);
+      }
+      request.url_mut().set_port(None).ok();
+    }
+    client.execute(request).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::{Ipv4Addr, SocketAddr};
+
+  use pretty_assertions::assert_eq;
+  use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+  };
+
+  use super::*;
+
+  /// Local server answering one request with the `Host` header it received.
+  async fn echo_host() -> SocketAddr {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      let mut buffer = vec![0; 4096];
+      let read = stream.read(&mut buffer).await.unwrap();
+      let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+      let host = request.lines().find_map(|line| line.strip_prefix("host: ")).unwrap_or_default().to_string();
+      let reply = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{host}", host.len());
+      stream.write_all(reply.as_bytes()).await.unwrap();
+    });
+    address
+  }
+
+  #[tokio::test]
+  async fn test_tunneled_port() {
+    let tunnel = echo_host().await;
+    let tunneled = reqwest::Client::builder().resolve("api.internal", tunnel).build().unwrap();
+    let client = Client::new(reqwest::Client::new(), vec![(String::from("api.internal"), 8443, tunneled)]);
+    let request = client.get("http://api.internal:8443/pets").build().unwrap();
+    let response = client.execute(request).await.unwrap();
+    assert_eq!(response.text().await.unwrap(), "api.internal:8443");
+
+    let request = client.get("http://api.internal:9443/pets").build().unwrap();
+    assert!(client.route(request.url()).is_none());
+  }
+}
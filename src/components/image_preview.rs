@@ -0,0 +1,202 @@
+use std::{io::Cursor, time::Instant};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use icy_sixel::{DiffusionMethod, MethodForLargest, MethodForRep, PixelFormat, Quality};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat};
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{config::ImageProtocol, response::Response};
+
+/// Cell size in pixels assumed when the terminal doesn't report its size in pixels.
+const CELL_SIZE: (u32, u32) = (10, 20);
+
+/// Renders `image/*` responses with the sixel or iTerm2 graphics protocol, or with half-block characters, two pixels
+/// per cell, in every other terminal. Falls back to metadata when the payload can't be decoded.
+#[derive(Default)]
+pub struct ImagePreview {
+  received_at: Option<Instant>,
+  image: Option<DynamicImage>,
+  error: Option<String>,
+  content_type: String,
+  size: usize,
+  rendered: Option<Rendered>,
+  rendered_for: Option<(Rect, ImageProtocol)>,
+}
+
+enum Rendered {
+  Lines(Vec<Line<'static>>),
+  /// Escape sequence drawing the whole image from the top left cell of its area.
+  Sequence(String),
+}
+
+impl ImagePreview {
+  pub fn is_image(response: &Response) -> bool {
+    response
+      .headers
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .is_some_and(|content_type| content_type.trim().to_ascii_lowercase().starts_with("image/"))
+  }
+
+  pub fn set(&mut self, response: &Response) {
+    if self.received_at == Some(response.received_at) {
+      return;
+    }
+    self.received_at = Some(response.received_at);
+    self.content_type = response
+      .headers
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .unwrap_or_default()
+      .to_string();
+    self.size = response.bytes.len();
+    self.rendered = None;
+    self.rendered_for = None;
+    match image::load_from_memory(&response.bytes) {
+      Ok(image) => {
+        self.image = Some(image);
+        self.error = None;
+      },
+      Err(error) => {
+        self.image = None;
+        self.error = Some(error.to_string());
+      },
+    }
+  }
+
+  fn metadata(&self) -> Line<'static> {
+    let mut spans = vec![Span::styled(self.content_type.clone(), Style::default().add_modifier(Modifier::BOLD))];
+    if let Some(image) = &self.image {
+      let (width, height) = image.dimensions();
+      spans.push(Span::raw(format!(" {} {width}x{height}", symbols::DOT)));
+    }
+    spans.push(Span::raw(format!(" {} {}", symbols::DOT, humansize::format_size(self.size, humansize::DECIMAL))));
+    if let Some(error) = &self.error {
+      spans.push(Span::styled(format!(" {} can't decode image: {error}", symbols::DOT), Style::default().dim()));
+    }
+    Line::from(spans)
+  }
+
+  fn render_lines(image: &DynamicImage, area: Rect) -> Vec<Line<'static>> {
+    if area.width == 0 || area.height == 0 {
+      return vec![];
+    }
+    let preview = image.resize(area.width.into(), u32::from(area.height) * 2, FilterType::Triangle).to_rgb8();
+    let (width, height) = preview.dimensions();
+    (0..height)
+      .step_by(2)
+      .map(|y| {
+        Line::from(
+          (0..width)
+            .map(|x| {
+              let top = preview.get_pixel(x, y);
+              let style = Style::default().fg(Color::Rgb(top[0], top[1], top[2]));
+              if y + 1 < height {
+                let bottom = preview.get_pixel(x, y + 1);
+                Span::styled("▀", style.bg(Color::Rgb(bottom[0], bottom[1], bottom[2])))
+              } else {
+                Span::styled("▀", style)
+              }
+            })
+            .collect::<Vec<_>>(),
+        )
+      })
+      .collect()
+  }
+
+  fn cell_size() -> (u32, u32) {
+    crossterm::terminal::window_size()
+      .ok()
+      .filter(|size| size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0)
+      .map(|size| (u32::from(size.width / size.columns), u32::from(size.height / size.rows)))
+      .unwrap_or(CELL_SIZE)
+  }
+
+  fn render_sequence(image: &DynamicImage, area: Rect, protocol: ImageProtocol) -> Option<String> {
+    if area.width == 0 || area.height == 0 {
+      return None;
+    }
+    let (cell_width, cell_height) = Self::cell_size();
+    let width = u32::from(area.width) * cell_width;
+    let height = u32::from(area.height) * cell_height;
+    match protocol {
+      ImageProtocol::Sixel => {
+        // sixels are drawn in bands of six pixels, a partial band would spill into the next row.
+        let preview = image.resize(width, height - height % 6, FilterType::Triangle).to_rgb8();
+        let (width, height) = preview.dimensions();
+        let sixel = icy_sixel::sixel_string(
+          preview.as_raw(),
+          i32::try_from(width).ok()?,
+          i32::try_from(height).ok()?,
+          PixelFormat::RGB888,
+          DiffusionMethod::Stucki,
+          MethodForLargest::Auto,
+          MethodForRep::Auto,
+          Quality::HIGH,
+        );
+        sixel.map_err(|error| log::error!("can't encode sixel: {error}")).ok()
+      },
+      ImageProtocol::Iterm2 => {
+        let mut png = Cursor::new(vec![]);
+        let preview = image.resize(width, height, FilterType::Triangle);
+        preview.write_to(&mut png, ImageFormat::Png).map_err(|error| log::error!("can't encode png: {error}")).ok()?;
+        let png = png.into_inner();
+        Some(format!(
+          "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1;doNotMoveCursor=1:{}\x07",
+          png.len(),
+          area.width,
+          area.height,
+          BASE64_STANDARD.encode(&png),
+        ))
+      },
+      ImageProtocol::Auto | ImageProtocol::HalfBlocks => None,
+    }
+  }
+
+  pub fn render_widget(&mut self, frame: &mut Frame<'_>, area: Rect, protocol: ImageProtocol) {
+    let [metadata_area, image_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+    frame.render_widget(Paragraph::new(self.metadata()), metadata_area);
+    let Some(image) = &self.image else {
+      return;
+    };
+    if self.rendered_for != Some((image_area, protocol)) {
+      let rendered = match Self::render_sequence(image, image_area, protocol) {
+        Some(sequence) => Rendered::Sequence(sequence),
+        None => Rendered::Lines(Self::render_lines(image, image_area)),
+      };
+      self.rendered = Some(rendered);
+      self.rendered_for = Some((image_area, protocol));
+    }
+    match &self.rendered {
+      Some(Rendered::Lines(lines)) => frame.render_widget(Paragraph::new(lines.clone()), image_area),
+      Some(Rendered::Sequence(sequence)) => {
+        // the terminal draws the image over the whole area, its other cells are left alone.
+        let buffer = frame.buffer_mut();
+        for position in image_area.positions() {
+          buffer[position].set_skip(true);
+        }
+        buffer[image_area.as_position()].set_skip(false).set_symbol(sequence);
+      },
+      None => {},
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_render_sequence() {
+    let image = DynamicImage::new_rgb8(40, 40);
+    let area = Rect::new(0, 0, 4, 2);
+    let sixel = ImagePreview::render_sequence(&image, area, ImageProtocol::Sixel).unwrap();
+    assert!(sixel.starts_with("\x1bP"), "{sixel:?}");
+    let iterm2 = ImagePreview::render_sequence(&image, area, ImageProtocol::Iterm2).unwrap();
+    assert!(iterm2.starts_with("\x1b]1337;File=inline=1;"), "{iterm2:?}");
+    assert!(iterm2.contains(";width=4;height=2;"));
+    assert_eq!(ImagePreview::render_sequence(&image, area, ImageProtocol::HalfBlocks), None);
+  }
+}
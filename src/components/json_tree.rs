@@ -0,0 +1,199 @@
+use std::collections::HashSet;
+
+use ratatui::{prelude::*, widgets::*};
+use serde_json::Value;
+
+use crate::jsonpath::{self, Segment};
+
+/// A visible node of the tree, objects and arrays can be folded.
+pub struct Node<'a> {
+  pub path: Vec<Segment>,
+  pub key: Option<Segment>,
+  pub value: &'a Value,
+  pub collapsed: bool,
+}
+
+/// Interactive view of a JSON document where objects and arrays can be folded and unfolded.
+#[derive(Default)]
+pub struct JsonTree {
+  root: Value,
+  collapsed: HashSet<Vec<Segment>>,
+  /// Paths of the visible nodes in order, rebuilt when the document or the folding changes.
+  visible: Vec<Vec<Segment>>,
+  selected: usize,
+  offset: usize,
+}
+
+impl JsonTree {
+  pub fn set(&mut self, root: Value) {
+    self.root = root;
+    self.collapsed = HashSet::default();
+    self.selected = 0;
+    self.offset = 0;
+    self.refresh();
+  }
+
+  fn refresh(&mut self) {
+    let mut visible = vec![];
+    self.push_paths(&mut visible, vec![], &self.root);
+    self.visible = visible;
+  }
+
+  fn push_paths(&self, paths: &mut Vec<Vec<Segment>>, path: Vec<Segment>, value: &Value) {
+    let collapsed = self.collapsed.contains(&path);
+    paths.push(path.clone());
+    if collapsed {
+      return;
+    }
+    let children: Vec<(Segment, &Value)> = match value {
+      Value::Object(object) => object.iter().map(|(key, value)| (Segment::Key(key.clone()), value)).collect(),
+      Value::Array(array) => array.iter().enumerate().map(|(index, value)| (Segment::Index(index), value)).collect(),
+      _ => vec![],
+    };
+    for (segment, value) in children {
+      let mut child_path = path.clone();
+      child_path.push(segment);
+      self.push_paths(paths, child_path, value);
+    }
+  }
+
+  /// Number of visible nodes.
+  pub fn node_count(&self) -> usize {
+    self.visible.len()
+  }
+
+  /// Visible node at an index, counted from the root.
+  pub fn node(&self, index: usize) -> Option<Node<'_>> {
+    let path = self.visible.get(index)?;
+    let value = path.iter().try_fold(&self.root, |value, segment| match segment {
+      Segment::Key(key) => value.get(key),
+      Segment::Index(index) => value.get(index),
+    })?;
+    Some(Node { path: path.clone(), key: path.last().cloned(), value, collapsed: self.collapsed.contains(path) })
+  }
+
+  pub fn down(&mut self) {
+    self.selected = (self.selected + 1).min(self.node_count().saturating_sub(1));
+  }
+
+  pub fn up(&mut self) {
+    self.selected = self.selected.saturating_sub(1);
+  }
+
+  /// Folds or unfolds the selected object or array.
+  pub fn toggle(&mut self) {
+    let Some(path) = self
+      .node(self.selected)
+      .filter(|node| matches!(node.value, Value::Object(_) | Value::Array(_)))
+      .map(|node| node.path)
+    else {
+      return;
+    };
+    if !self.collapsed.remove(&path) {
+      self.collapsed.insert(path);
+    }
+    self.refresh();
+  }
+
+  pub fn selected_path(&self) -> Option<Vec<Segment>> {
+    self.node(self.selected).map(|node| node.path)
+  }
+
+  /// Selected value, strings without quotes and objects or arrays pretty printed.
+  pub fn selected_value(&self) -> Option<String> {
+    self.node(self.selected).map(|node| match node.value {
+      Value::Object(_) | Value::Array(_) => serde_json::to_string_pretty(node.value).unwrap_or_default(),
+      value => jsonpath::display(value),
+    })
+  }
+
+  /// Selected value as a pretty printed JSON document, strings keep their quotes.
+  pub fn selected_json(&self) -> Option<String> {
+    self.node(self.selected).and_then(|node| serde_json::to_string_pretty(node.value).ok())
+  }
+
+  pub fn render(&mut self, frame: &mut Frame<'_>, area: Rect) {
+    self.selected = self.selected.min(self.node_count().saturating_sub(1));
+    let height = area.height as usize;
+    if self.selected < self.offset {
+      self.offset = self.selected;
+    } else if height > 0 && self.selected >= self.offset + height {
+      self.offset = self.selected + 1 - height;
+    }
+    let lines = (self.offset..self.node_count().min(self.offset + height))
+      .filter_map(|index| self.node(index).map(|node| (index, node)))
+      .map(|(index, node)| {
+        let mut line = Line::from(Self::spans(&node));
+        if index == self.selected {
+          line = line.style(Style::default().add_modifier(Modifier::REVERSED));
+        }
+        line
+      })
+      .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lines), area);
+  }
+
+  fn spans<'a>(node: &Node<'a>) -> Vec<Span<'a>> {
+    let mut spans = vec![Span::raw("  ".repeat(node.path.len().saturating_sub(1)))];
+    let marker = match (node.value, node.collapsed) {
+      (Value::Object(_) | Value::Array(_), true) => "▸ ",
+      (Value::Object(_) | Value::Array(_), false) => "▾ ",
+      _ => "  ",
+    };
+    spans.push(Span::styled(marker, Style::default().dim()));
+    match &node.key {
+      Some(Segment::Key(key)) => {
+        spans.push(Span::styled(key.clone(), Style::default().fg(Color::LightBlue)));
+        spans.push(Span::raw(": "));
+      },
+      Some(Segment::Index(index)) => spans.push(Span::styled(format!("[{index}] "), Style::default().dim())),
+      None => {},
+    }
+    let (summary, color) = match node.value {
+      Value::Object(object) => (format!("{{…}} {} keys", object.len()), Color::default()),
+      Value::Array(array) => (format!("[…] {} items", array.len()), Color::default()),
+      Value::String(value) => (format!("{value:?}"), Color::LightGreen),
+      Value::Number(value) => (value.to_string(), Color::LightYellow),
+      Value::Bool(value) => (value.to_string(), Color::LightMagenta),
+      Value::Null => (String::from("null"), Color::DarkGray),
+    };
+    let style = match node.value {
+      Value::Object(_) | Value::Array(_) => Style::default().dim(),
+      _ => Style::default().fg(color),
+    };
+    spans.push(Span::styled(summary, style));
+    spans
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_fold_and_select() {
+    let mut tree = JsonTree::default();
+    tree.set(json!({"pets": [{"name": "rex"}, {"name": "tom"}], "total": 2}));
+    assert_eq!(tree.node_count(), 7);
+
+    tree.down();
+    tree.toggle();
+    assert_eq!(tree.node_count(), 3);
+    assert_eq!(tree.selected_path(), Some(vec![Segment::Key("pets".into())]));
+
+    tree.toggle();
+    tree.down();
+    tree.down();
+    assert_eq!(
+      tree.selected_path(),
+      Some(vec![Segment::Key("pets".into()), Segment::Index(0), Segment::Key("name".into())])
+    );
+    assert_eq!(tree.selected_value(), Some(String::from("rex")));
+    assert_eq!(tree.selected_json(), Some(String::from("\"rex\"")));
+    tree.up();
+    assert_eq!(tree.selected_json(), Some(String::from("{\n  \"name\": \"rex\"\n}")));
+  }
+}
@@ -0,0 +1,237 @@
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::prelude::*;
+
+/// A table being rendered, cells are kept as plain text so columns can be aligned.
+#[derive(Default)]
+struct Table {
+  rows: Vec<Vec<String>>,
+  header_rows: usize,
+}
+
+#[derive(Default)]
+struct Renderer {
+  lines: Vec<Line<'static>>,
+  spans: Vec<Span<'static>>,
+  styles: Vec<Style>,
+  /// Next number of each nested list, `None` for bullet lists.
+  lists: Vec<Option<u64>>,
+  table: Option<Table>,
+  code_block: bool,
+}
+
+impl Renderer {
+  fn style(&self) -> Style {
+    self.styles.last().copied().unwrap_or_default()
+  }
+
+  fn push_style(&mut self, style: Style) {
+    self.styles.push(self.style().patch(style));
+  }
+
+  fn flush(&mut self) {
+    if !self.spans.is_empty() {
+      self.lines.push(Line::from(std::mem::take(&mut self.spans)));
+    }
+  }
+
+  /// Separates blocks with an empty line, except inside lists.
+  fn end_block(&mut self) {
+    self.flush();
+    if self.lists.is_empty() && self.lines.last().is_some_and(|line| line.width() > 0) {
+      self.lines.push(Line::default());
+    }
+  }
+
+  fn text(&mut self, text: &str, style: Style) {
+    if let Some(cell) = self.table.as_mut().and_then(|table| table.rows.last_mut()).and_then(|row| row.last_mut()) {
+      cell.push_str(text);
+      return;
+    }
+    let mut parts = text.split('\n').peekable();
+    while let Some(part) = parts.next() {
+      if !part.is_empty() {
+        self.spans.push(Span::styled(part.to_string(), style));
+      }
+      if parts.peek().is_some() {
+        self.flush();
+      }
+    }
+  }
+
+  fn event(&mut self, event: Event<'_>) {
+    match event {
+      Event::Start(Tag::Heading { level, .. }) => {
+        let style = match level {
+          HeadingLevel::H1 => Style::default().bold().underlined(),
+          _ => Style::default().bold(),
+        };
+        self.push_style(style);
+      },
+      Event::End(TagEnd::Heading(_)) => {
+        self.styles.pop();
+        self.end_block();
+      },
+      Event::End(TagEnd::Paragraph) => self.end_block(),
+      Event::Start(Tag::Emphasis) => self.push_style(Style::default().italic()),
+      Event::Start(Tag::Strong) => self.push_style(Style::default().bold()),
+      Event::Start(Tag::Strikethrough) => self.push_style(Style::default().crossed_out()),
+      Event::Start(Tag::Link { .. }) => self.push_style(Style::default().underlined()),
+      Event::Start(Tag::BlockQuote(_)) => self.push_style(Style::default().dim().italic()),
+      Event::End(TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link) => {
+        self.styles.pop();
+      },
+      Event::End(TagEnd::BlockQuote(_)) => {
+        self.styles.pop();
+        self.end_block();
+      },
+      Event::Start(Tag::CodeBlock(_)) => {
+        self.flush();
+        self.code_block = true;
+        self.push_style(Style::default().fg(Color::LightYellow));
+      },
+      Event::End(TagEnd::CodeBlock) => {
+        self.code_block = false;
+        self.styles.pop();
+        self.end_block();
+      },
+      Event::Start(Tag::List(start)) => {
+        self.flush();
+        self.lists.push(start);
+      },
+      Event::End(TagEnd::List(_)) => {
+        self.lists.pop();
+        self.end_block();
+      },
+      Event::Start(Tag::Item) => {
+        self.flush();
+        let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+        let marker = match self.lists.last_mut() {
+          Some(Some(number)) => {
+            *number += 1;
+            format!("{indent}{}. ", *number - 1)
+          },
+          _ => format!("{indent}• "),
+        };
+        self.spans.push(Span::styled(marker, Style::default().dim()));
+      },
+      Event::End(TagEnd::Item) => self.flush(),
+      Event::Start(Tag::Table(_)) => {
+        self.flush();
+        self.table = Some(Table::default());
+      },
+      Event::Start(Tag::TableHead | Tag::TableRow) => {
+        if let Some(table) = self.table.as_mut() {
+          table.rows.push(vec![]);
+        }
+      },
+      Event::End(TagEnd::TableHead) => {
+        if let Some(table) = self.table.as_mut() {
+          table.header_rows = table.rows.len();
+        }
+      },
+      Event::Start(Tag::TableCell) => {
+        if let Some(row) = self.table.as_mut().and_then(|table| table.rows.last_mut()) {
+          row.push(String::default());
+        }
+      },
+      Event::End(TagEnd::Table) => {
+        if let Some(table) = self.table.take() {
+          self.lines.extend(table_lines(&table));
+        }
+        self.end_block();
+      },
+      Event::Text(text) if self.code_block => {
+        let style = self.style();
+        for line in text.lines() {
+          self.lines.push(Line::styled(format!("  {line}"), style));
+        }
+      },
+      Event::Text(text) => self.text(&text, self.style()),
+      Event::Code(code) => self.text(&code, self.style().patch(Style::default().fg(Color::LightYellow))),
+      Event::SoftBreak => self.text(" ", self.style()),
+      Event::HardBreak => self.flush(),
+      Event::Rule => {
+        self.flush();
+        self.lines.push(Line::styled("─".repeat(20), Style::default().dim()));
+      },
+      _ => {},
+    }
+  }
+}
+
+/// Rows of a table with aligned columns and a line under the header.
+fn table_lines(table: &Table) -> Vec<Line<'static>> {
+  let columns = table.rows.iter().map(Vec::len).max().unwrap_or_default();
+  let widths = (0..columns)
+    .map(|column| {
+      table.rows.iter().filter_map(|row| row.get(column)).map(|cell| Span::raw(cell.as_str()).width()).max()
+    })
+    .map(Option::unwrap_or_default)
+    .collect::<Vec<_>>();
+  let mut lines = vec![];
+  for (index, row) in table.rows.iter().enumerate() {
+    let style = if index < table.header_rows { Style::default().bold() } else { Style::default() };
+    let mut spans = vec![];
+    for (column, width) in widths.iter().enumerate() {
+      if column > 0 {
+        spans.push(Span::styled(" │ ", Style::default().dim()));
+      }
+      let cell = row.get(column).map(String::as_str).unwrap_or_default();
+      let padding = width.saturating_sub(Span::raw(cell).width());
+      spans.push(Span::styled(format!("{cell}{}", " ".repeat(padding)), style));
+    }
+    lines.push(Line::from(spans));
+    if index + 1 == table.header_rows {
+      let rule = widths.iter().map(|width| "─".repeat(*width)).collect::<Vec<_>>().join("─┼─");
+      lines.push(Line::styled(rule, Style::default().dim()));
+    }
+  }
+  lines
+}
+
+/// Renders CommonMark text, like the descriptions of a spec, with emphasis, code spans, lists and simple tables.
+pub fn render(text: &str) -> Vec<Line<'static>> {
+  let mut renderer = Renderer::default();
+  for event in Parser::new_ext(text, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH) {
+    renderer.event(event);
+  }
+  renderer.flush();
+  while renderer.lines.last().is_some_and(|line| line.width() == 0) {
+    renderer.lines.pop();
+  }
+  renderer.lines
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn plain(lines: &[Line<'_>]) -> Vec<String> {
+    lines.iter().map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect()).collect()
+  }
+
+  #[test]
+  fn test_render() {
+    let lines = render(
+      "Returns *all* pets\nfrom `store`.\n\n1. first\n2. second\n   - nested\n\n| Code | Meaning |\n|---|---|\n| 200 | ok |\n",
+    );
+    assert_eq!(
+      plain(&lines),
+      [
+        "Returns all pets from store.",
+        "",
+        "1. first",
+        "2. second",
+        "  • nested",
+        "",
+        "Code │ Meaning",
+        "─────┼────────",
+        "200  │ ok     ",
+      ]
+    );
+    assert_eq!(lines[0].spans[1], Span::styled("all", Style::default().italic()));
+    assert_eq!(lines[0].spans[5], Span::styled("store", Style::default().fg(Color::LightYellow)));
+  }
+}
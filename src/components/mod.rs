@@ -1 +1,4 @@
+pub mod image_preview;
+pub mod json_tree;
+pub mod markdown;
 pub mod schema_viewer;
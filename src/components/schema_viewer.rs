@@ -7,11 +7,16 @@ use syntect::{easy::HighlightLines, highlighting::ThemeSet, parsing::SyntaxSet,
 use crate::state::State;
 
 const SYNTAX_THEME: &str = "Solarized (dark)";
+const SCROLL_COLUMNS: usize = 8;
 
 pub struct SchemaViewer {
   components: HashMap<String, serde_json::Value>,
+  root: serde_json::Value,
   styles: Vec<Vec<(Style, String)>>,
+  yaml_lines: Vec<String>,
   line_offset: usize,
+  column_offset: usize,
+  wrap: bool,
 
   name_history: Vec<String>,
   line_offset_history: Vec<usize>,
@@ -24,8 +29,12 @@ impl Default for SchemaViewer {
   fn default() -> Self {
     Self {
       components: HashMap::default(),
+      root: serde_json::Value::Null,
       styles: Vec::default(),
+      yaml_lines: Vec::default(),
       line_offset: 0,
+      column_offset: 0,
+      wrap: false,
       name_history: Vec::default(),
       line_offset_history: Vec::default(),
       highlighter_syntax_set: SyntaxSet::load_defaults_newlines(),
@@ -46,6 +55,7 @@ impl SchemaViewer {
 
   pub fn clear(&mut self) {
     self.line_offset = 0;
+    self.column_offset = 0;
     self.name_history = vec![];
     self.line_offset_history = vec![];
     self.styles = vec![];
@@ -53,40 +63,60 @@ impl SchemaViewer {
 
   pub fn set(&mut self, schema: serde_json::Value) -> Result<()> {
     self.line_offset = 0;
+    self.column_offset = 0;
     self.name_history = vec![];
     self.line_offset_history = vec![];
+    self.root = schema.clone();
     self.set_styles(schema)?;
     self.go()
   }
 
+  /// Follows the `$ref` or `$dynamicRef` of the selected line. References into components, their `$defs` and
+  /// `$defs` of the schema being viewed are navigable, dynamic references are resolved by their `$dynamicAnchor`.
   pub fn go(&mut self) -> Result<()> {
-    if let Some(line_styles) = self.styles.get(self.line_offset) {
-      let line: Vec<String> = line_styles
-        .iter()
-        .filter_map(|item| {
-          if item.1.eq("$ref") || item.1.starts_with("#/components/schemas/") {
-            return Some(item.1.clone());
-          }
-          None
-        })
-        .collect();
-      if line.len() != 2 {
-        return Ok(());
-      }
-      if !line[0].eq("$ref") || !line[1].starts_with("#/components/schemas/") {
-        return Ok(());
-      }
+    let Some((keyword, reference)) = self.yaml_lines.get(self.line_offset).and_then(|line| reference(line)) else {
+      return Ok(());
+    };
+    let current = self.name_history.last().cloned();
+    let target = match (keyword.as_str(), reference.strip_prefix('#')) {
+      ("$ref", Some(pointer)) if pointer.starts_with("/components/schemas/") => {
+        Some(pointer.trim_start_matches("/components/schemas/").to_string())
+      },
+      // other local references are resolved against the component, or the inline schema, being viewed
+      ("$ref", Some(pointer)) if pointer.starts_with('/') => Some(match current.as_ref() {
+        Some(current) if !current.starts_with('#') => {
+          format!("{}{pointer}", current.split('/').next().unwrap_or_default())
+        },
+        _ => format!("#{pointer}"),
+      }),
+      ("$dynamicRef", Some(anchor)) => {
+        let (base, document) = match current.as_ref() {
+          Some(current) if !current.starts_with('#') => {
+            let component = current.split('/').next().unwrap_or_default().to_string();
+            (component.clone(), self.components.get(unescape_pointer_segment(component.as_str()).as_str()))
+          },
+          _ => ("#".to_string(), Some(&self.root)),
+        };
+        document.and_then(|document| find_anchor(document, anchor)).map(|pointer| format!("{base}{pointer}")).or_else(
+          || {
+            self.components.iter().find_map(|(name, schema)| {
+              find_anchor(schema, anchor).map(|pointer| format!("{}{pointer}", escape_pointer_segment(name)))
+            })
+          },
+        )
+      },
+      _ => None,
+    };
+    let Some(target) = target.filter(|target| self.resolve(target).is_some()) else {
+      return Ok(());
+    };
 
-      let (_, schema_name) = line[1].split_at(21);
-
-      self.line_offset_history.push(self.line_offset);
-      self.line_offset = 0;
-      self.name_history.push(schema_name.to_string());
+    self.line_offset_history.push(self.line_offset);
+    self.line_offset = 0;
+    self.column_offset = 0;
+    self.name_history.push(target.clone());
 
-      self.set_styles_by_name(schema_name.to_string())
-    } else {
-      Ok(())
-    }
+    self.set_styles_by_name(target)
   }
 
   pub fn back(&mut self, schema: serde_json::Value) -> Result<()> {
@@ -116,20 +146,72 @@ impl SchemaViewer {
     self.line_offset = self.line_offset.saturating_sub(1);
   }
 
+  /// Wraps long lines instead of clipping them, horizontal scrolling is reset.
+  pub fn toggle_wrap(&mut self) -> bool {
+    self.wrap = !self.wrap;
+    self.column_offset = 0;
+    self.wrap
+  }
+
+  pub fn scroll_left(&mut self) {
+    self.column_offset = self.column_offset.saturating_sub(SCROLL_COLUMNS);
+  }
+
+  pub fn scroll_right(&mut self) {
+    let longest = self.yaml_lines.iter().map(|line| line.chars().count()).max().unwrap_or_default();
+    if !self.wrap {
+      self.column_offset = self.column_offset.saturating_add(SCROLL_COLUMNS).min(longest.saturating_sub(1));
+    }
+  }
+
   pub fn schema_path(&self) -> Vec<String> {
     self.name_history.clone()
   }
 
+  /// Name of the referenced schema being viewed, if navigated into one. Schemas under `$defs` are named by their key.
+  pub fn schema_name(&self) -> Option<String> {
+    self.name_history.last().and_then(|target| target.rsplit('/').next()).map(unescape_pointer_segment)
+  }
+
+  /// JSON Pointer of the selected line in the referenced schema being viewed, like
+  /// `#/components/schemas/Pet/properties/name`.
+  pub fn schema_pointer(&self) -> Option<String> {
+    let target = self.name_history.last().filter(|target| !target.starts_with('#'))?;
+    let mut pointer = format!("#/components/schemas/{target}");
+    for segment in yaml_line_path(&self.yaml_lines, self.line_offset) {
+      pointer.push('/');
+      pointer.push_str(escape_pointer_segment(segment.as_str()).as_str());
+    }
+    Some(pointer)
+  }
+
   pub fn render_widget(&self, frame: &mut Frame<'_>, area: Rect) {
+    let to_line = |items: &[(Style, String)]| {
+      Line::from(items.iter().map(|item| Span::styled(item.1.clone(), item.0.bg(Color::Reset))).collect::<Vec<_>>())
+    };
     let lines = self.styles.iter().map(|items| {
-      return Line::from(
-        items
-          .iter()
-          .map(|item| {
-            return Span::styled(&item.1, item.0.bg(Color::Reset));
+      // the line number gutter stays in place while the rest is wrapped or scrolled
+      let Some((gutter, content)) = items.split_first() else {
+        return ListItem::new(Line::default());
+      };
+      if self.wrap {
+        // one column is taken by the highlight symbol
+        let width = (area.width as usize).saturating_sub(gutter.1.chars().count() + 1).max(1);
+        let blank = (gutter.0, " ".repeat(gutter.1.chars().count()));
+        let lines = wrap_columns(content, width)
+          .into_iter()
+          .enumerate()
+          .map(|(index, mut segments)| {
+            segments.insert(0, if index == 0 { gutter.clone() } else { blank.clone() });
+            to_line(&segments)
           })
-          .collect::<Vec<_>>(),
-      );
+          .collect::<Vec<_>>();
+        ListItem::new(lines)
+      } else {
+        let mut segments = skip_columns(content, self.column_offset);
+        segments.insert(0, gutter.clone());
+        ListItem::new(to_line(&segments))
+      }
     });
     let mut list_state = ListState::default().with_selected(Some(self.line_offset));
 
@@ -143,6 +225,7 @@ impl SchemaViewer {
   fn set_styles(&mut self, schema: serde_json::Value) -> Result<()> {
     self.styles = vec![];
     let yaml_schema = serde_yaml::to_string(&schema)?;
+    self.yaml_lines = yaml_schema.lines().map(String::from).collect();
     let mut highlighter = HighlightLines::new(
       self.highlighter_syntax_set.find_syntax_by_extension("yaml").expect("yaml syntax highlighter not found"),
       &self.highlighter_theme_set.themes[SYNTAX_THEME],
@@ -158,21 +241,297 @@ impl SchemaViewer {
               .unwrap_or_default()
               .underline_color(Color::Reset)
               .bg(Color::Reset),
-            segment.1.to_string(),
+            segment.1.trim_end_matches('\n').to_string(),
           )
         })
         .collect();
       line_styles.insert(0, (Style::default().dim(), format!(" {:<3} ", line_num + 1)));
+      line_styles.extend(property_markers(&schema, &yaml_line_path(&self.yaml_lines, line_num)));
       self.styles.push(line_styles);
     }
     Ok(())
   }
 
+  /// Schema of a navigation target, either `Name/json/pointer` into a component or `#/json/pointer` into the inline
+  /// schema.
+  fn resolve(&self, target: &str) -> Option<&serde_json::Value> {
+    match target.strip_prefix('#') {
+      Some(pointer) => self.root.pointer(pointer),
+      None => {
+        let (name, pointer) =
+          target.split_once('/').map_or((target, String::default()), |(name, pointer)| (name, format!("/{pointer}")));
+        self.components.get(unescape_pointer_segment(name).as_str())?.pointer(pointer.as_str())
+      },
+    }
+  }
+
   fn set_styles_by_name(&mut self, schema_name: String) -> Result<()> {
-    if let Some(schema) = self.components.get(schema_name.as_str()) {
+    if let Some(schema) = self.resolve(schema_name.as_str()) {
       self.set_styles(schema.clone())
     } else {
       Ok(())
     }
   }
 }
+
+/// Required or optional and nullable markers for the line declaring a property, `path` being the keys leading to
+/// the line. Whether it's required comes from the `required` list of the parent schema.
+fn property_markers(schema: &serde_json::Value, path: &[String]) -> Vec<(Style, String)> {
+  let [parent @ .., properties, name] = path else {
+    return vec![];
+  };
+  let parent_pointer = parent.iter().map(|segment| format!("/{}", escape_pointer_segment(segment))).collect::<String>();
+  let Some(parent) = schema.pointer(parent_pointer.as_str()).filter(|_| properties == "properties") else {
+    return vec![];
+  };
+  let Some(property) = parent.get("properties").and_then(|properties| properties.get(name)) else {
+    return vec![];
+  };
+  let required = parent
+    .get("required")
+    .and_then(|required| required.as_array())
+    .is_some_and(|required| required.iter().any(|item| item.as_str() == Some(name.as_str())));
+  let mut markers = vec![match required {
+    true => (Style::default().fg(Color::LightRed), String::from(" [required]")),
+    false => (Style::default().dim(), String::from(" [optional]")),
+  }];
+  if is_nullable(property) {
+    markers.push((Style::default().fg(Color::Yellow), String::from(" [nullable]")));
+  }
+  markers
+}
+
+/// Whether a schema allows null, by `nullable: true` of OpenAPI 3.0, a `null` type or a nullable `oneOf`/`anyOf`
+/// variant.
+fn is_nullable(schema: &serde_json::Value) -> bool {
+  let null_type = match schema.get("type") {
+    Some(serde_json::Value::String(r#type)) => r#type == "null",
+    Some(serde_json::Value::Array(types)) => types.iter().any(|r#type| r#type.as_str() == Some("null")),
+    _ => false,
+  };
+  null_type
+    || schema.get("nullable").and_then(|nullable| nullable.as_bool()).unwrap_or(false)
+    || ["oneOf", "anyOf"].iter().any(|keyword| {
+      schema
+        .get(*keyword)
+        .and_then(|variants| variants.as_array())
+        .is_some_and(|variants| variants.iter().any(is_nullable))
+    })
+}
+
+/// Styled segments without their first `columns` characters.
+fn skip_columns(segments: &[(Style, String)], columns: usize) -> Vec<(Style, String)> {
+  let mut remaining = columns;
+  segments
+    .iter()
+    .filter_map(|(style, text)| {
+      let len = text.chars().count();
+      if remaining >= len {
+        remaining -= len;
+        return None;
+      }
+      let text = text.chars().skip(remaining).collect::<String>();
+      remaining = 0;
+      Some((*style, text))
+    })
+    .collect()
+}
+
+/// Styled segments split into rows of at most `width` characters, each piece keeping the style of its segment.
+fn wrap_columns(segments: &[(Style, String)], width: usize) -> Vec<Vec<(Style, String)>> {
+  let mut rows = vec![vec![]];
+  let mut row_len = 0;
+  for (style, text) in segments {
+    let mut piece = String::default();
+    for char in text.chars().filter(|char| *char != '\n') {
+      if row_len == width {
+        if !piece.is_empty() {
+          rows.last_mut().expect("rows is never empty").push((*style, std::mem::take(&mut piece)));
+        }
+        rows.push(vec![]);
+        row_len = 0;
+      }
+      piece.push(char);
+      row_len += 1;
+    }
+    if !piece.is_empty() {
+      rows.last_mut().expect("rows is never empty").push((*style, piece));
+    }
+  }
+  rows
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+  segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_pointer_segment(segment: &str) -> String {
+  segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// Keyword and value of a `$ref` or `$dynamicRef` yaml line.
+fn reference(line: &str) -> Option<(String, String)> {
+  let content = line.trim_start();
+  let content = content.strip_prefix("- ").unwrap_or(content);
+  let keyword = yaml_key(content).filter(|key| key == "$ref" || key == "$dynamicRef")?;
+  let value = content.split_once(": ")?.1.trim().trim_matches(['\'', '"']);
+  Some((keyword, value.to_string()))
+}
+
+/// JSON Pointer of the subschema declaring a `$dynamicAnchor` or `$anchor`.
+fn find_anchor(schema: &serde_json::Value, anchor: &str) -> Option<String> {
+  match schema {
+    serde_json::Value::Object(map) => {
+      let declared = ["$dynamicAnchor", "$anchor"]
+        .iter()
+        .any(|keyword| map.get(*keyword).and_then(|value| value.as_str()) == Some(anchor));
+      if declared {
+        return Some(String::default());
+      }
+      map.iter().find_map(|(key, value)| {
+        find_anchor(value, anchor).map(|pointer| format!("/{}{pointer}", escape_pointer_segment(key)))
+      })
+    },
+    serde_json::Value::Array(items) => items
+      .iter()
+      .enumerate()
+      .find_map(|(index, value)| find_anchor(value, anchor).map(|pointer| format!("/{index}{pointer}"))),
+    _ => None,
+  }
+}
+
+fn yaml_key(content: &str) -> Option<String> {
+  let (key, rest) = match content.chars().next()? {
+    quote @ ('\'' | '"') => {
+      let end = content[1..].find(quote)? + 1;
+      (&content[1..end], &content[end + 1..])
+    },
+    _ => {
+      let end = content.find(": ").or_else(|| content.strip_suffix(':').map(str::len))?;
+      (&content[..end], &content[end..])
+    },
+  };
+  rest.starts_with(':').then(|| key.to_string())
+}
+
+/// Keys and sequence indexes leading to a line of a block style YAML document, as written by `serde_yaml`.
+fn yaml_line_path(lines: &[String], line_offset: usize) -> Vec<String> {
+  // (indent, segment, whether the segment is a sequence index)
+  let mut stack: Vec<(usize, String, bool)> = vec![];
+  for line in lines.iter().take(line_offset.saturating_add(1)) {
+    let content = line.trim_start();
+    if content.is_empty() {
+      continue;
+    }
+    let indent = line.len() - content.len();
+    if let Some(item) = content.strip_prefix("- ").or(if content == "-" { Some("") } else { None }) {
+      stack.retain(|(entry_indent, ..)| *entry_indent <= indent);
+      let index = match stack.last() {
+        Some((entry_indent, segment, true)) if *entry_indent == indent => {
+          let index = segment.parse::<usize>().unwrap_or_default() + 1;
+          stack.pop();
+          index
+        },
+        _ => 0,
+      };
+      stack.push((indent, index.to_string(), true));
+      if let Some(key) = yaml_key(item) {
+        stack.push((indent + 2, key, false));
+      }
+    } else if let Some(key) = yaml_key(content) {
+      stack.retain(|(entry_indent, ..)| *entry_indent < indent);
+      stack.push((indent, key, false));
+    }
+  }
+  stack.into_iter().map(|(_, segment, _)| segment).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_go_to_references() {
+    let mut viewer = SchemaViewer {
+      components: HashMap::from([(
+        "Tree".to_string(),
+        serde_json::json!({
+          "$dynamicAnchor": "node",
+          "properties": {"children": {"items": {"$dynamicRef": "#node"}}, "leaf": {"$ref": "#/$defs/Leaf"}},
+          "$defs": {"Leaf": {"type": "string"}},
+        }),
+      )]),
+      ..SchemaViewer::default()
+    };
+    viewer.set(serde_json::json!({"$ref": "#/components/schemas/Tree"})).unwrap();
+    assert_eq!(viewer.schema_name(), Some("Tree".to_string()));
+
+    viewer.line_offset = viewer.yaml_lines.iter().position(|line| line.contains("$ref")).unwrap();
+    viewer.go().unwrap();
+    assert_eq!(viewer.schema_path(), vec!["Tree", "Tree/$defs/Leaf"]);
+    assert_eq!(viewer.schema_name(), Some("Leaf".to_string()));
+    assert_eq!(viewer.schema_pointer(), Some("#/components/schemas/Tree/$defs/Leaf/type".to_string()));
+
+    viewer.back(serde_json::Value::Null).unwrap();
+    viewer.line_offset = viewer.yaml_lines.iter().position(|line| line.contains("$dynamicRef")).unwrap();
+    viewer.go().unwrap();
+    assert_eq!(viewer.schema_name(), Some("Tree".to_string()));
+    assert_eq!(viewer.schema_path().len(), 2);
+  }
+
+  #[test]
+  fn test_property_markers() {
+    let mut viewer = SchemaViewer::default();
+    viewer
+      .set(serde_json::json!({
+        "required": ["id"],
+        "properties": {
+          "id": {"type": "integer"},
+          "name": {"type": ["string", "null"]},
+          "tag": {"oneOf": [{"type": "string"}, {"type": "null"}]},
+        },
+      }))
+      .unwrap();
+    let markers = |key: &str| {
+      let index = viewer.yaml_lines.iter().position(|line| line.trim_start().starts_with(key)).unwrap();
+      viewer.styles[index]
+        .iter()
+        .filter(|(_, text)| text.starts_with(" ["))
+        .map(|(_, text)| text.trim())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(markers("id:"), ["[required]"]);
+    assert_eq!(markers("name:"), ["[optional]", "[nullable]"]);
+    assert_eq!(markers("tag:"), ["[optional]", "[nullable]"]);
+    assert!(markers("type: integer").is_empty());
+  }
+
+  #[test]
+  fn test_wrap_and_scroll_columns() {
+    let key = Style::default().fg(Color::Blue);
+    let segments = vec![(key, String::from("description")), (Style::default(), String::from(": a long line"))];
+    assert_eq!(skip_columns(&segments, 13), vec![(Style::default(), String::from("a long line"))]);
+    assert_eq!(skip_columns(&segments, 4), vec![(key, String::from("ription")), segments[1].clone()]);
+    assert_eq!(
+      wrap_columns(&segments, 10),
+      vec![
+        vec![(key, String::from("descriptio"))],
+        vec![(key, String::from("n")), (Style::default(), String::from(": a long "))],
+        vec![(Style::default(), String::from("line"))],
+      ]
+    );
+  }
+
+  #[test]
+  fn test_yaml_line_path() {
+    let yaml = "type: object\nproperties:\n  tags:\n    type: array\n    items:\n    - a/b\n    - name: x\n      type: string\nrequired:\n- tags\n";
+    let lines = yaml.lines().map(String::from).collect::<Vec<_>>();
+    assert_eq!(yaml_line_path(&lines, 0), vec!["type"]);
+    assert_eq!(yaml_line_path(&lines, 3), vec!["properties", "tags", "type"]);
+    assert_eq!(yaml_line_path(&lines, 5), vec!["properties", "tags", "items", "0"]);
+    assert_eq!(yaml_line_path(&lines, 7), vec!["properties", "tags", "items", "1", "type"]);
+    assert_eq!(yaml_line_path(&lines, 9), vec!["required", "0"]);
+  }
+}
@@ -1,4 +1,10 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+  collections::{BTreeMap, HashMap},
+  net::SocketAddr,
+  path::PathBuf,
+  sync::Arc,
+  time::Duration,
+};
 
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -6,7 +12,13 @@ use derive_deref::{Deref, DerefMut};
 use ratatui::style::{Color, Modifier, Style};
 use serde::{de::Deserializer, Deserialize};
 
-use crate::{action::Action, app::Mode};
+use crate::{
+  action::Action,
+  app::Mode,
+  client::Client,
+  profiles::Profile,
+  tls::{self, Pins},
+};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
@@ -18,6 +30,485 @@ pub struct AppConfig {
   pub _config_dir: PathBuf,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ConfirmConfig {
+  pub methods: Vec<String>,
+  pub servers: Vec<String>,
+}
+
+impl Default for ConfirmConfig {
+  fn default() -> Self {
+    Self { methods: vec!["DELETE".into()], servers: vec![] }
+  }
+}
+
+impl ConfirmConfig {
+  pub fn requires_confirmation(&self, method: &str, server_url: &str) -> bool {
+    let server_url = server_url.to_lowercase();
+    self.methods.iter().any(|item| item.eq_ignore_ascii_case(method))
+      && (self.servers.is_empty() || self.servers.iter().any(|pattern| server_url.contains(&pattern.to_lowercase())))
+  }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DiffConfig {
+  #[serde(default)]
+  pub ignore: Vec<String>,
+}
+
+impl Default for DiffConfig {
+  fn default() -> Self {
+    Self { ignore: ["createdAt", "updatedAt", "created_at", "updated_at", "timestamp"].map(String::from).to_vec() }
+  }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+  pub enabled: bool,
+  pub after_secs: u64,
+}
+
+impl Default for NotifyConfig {
+  fn default() -> Self {
+    Self { enabled: true, after_secs: 3 }
+  }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct DialConfig {
+  /// Sends a request once more after the one in flight finishes, when it's dialed again meanwhile.
+  pub queue_follow_up: bool,
+  /// Asks before sending a request whose body doesn't match its content type, length or method.
+  pub consistency_checks: bool,
+  /// Sends a `HEAD` request to the selected server in the background, flagging unreachable ones.
+  pub warm_up: bool,
+  /// Focuses and expands the response pane when a response arrives, `b` goes back to the pane focused before.
+  pub focus_response: bool,
+}
+
+impl Default for DialConfig {
+  fn default() -> Self {
+    Self { queue_follow_up: false, consistency_checks: true, warm_up: false, focus_response: false }
+  }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ClientConfig {
+  /// Seconds a whole request may take, `0` waits forever.
+  pub timeout_secs: u64,
+  pub connect_timeout_secs: u64,
+  /// Seconds an idle pooled connection is kept open for reuse.
+  pub pool_idle_timeout_secs: u64,
+  pub user_agent: String,
+  /// Proxy all requests go through, e.g. `http://proxy.internal:3128`, the `HTTPS_PROXY` and `HTTP_PROXY`
+  /// environment variables otherwise.
+  pub proxy: Option<String>,
+  /// Accepts self-signed or otherwise invalid certificates, for local and staging servers.
+  pub accept_invalid_certs: bool,
+  /// Remembers the certificate fingerprint of each host on first use and warns when it changes, and offers to pin the
+  /// certificate of a host that doesn't validate against the system CAs, accepting it from then on.
+  pub trust_on_first_use: bool,
+  /// Headers added to every request, like `Accept-Language`, shown in the header tab where they can be changed.
+  pub default_headers: BTreeMap<String, String>,
+}
+
+impl Default for ClientConfig {
+  fn default() -> Self {
+    Self {
+      timeout_secs: 0,
+      connect_timeout_secs: 10,
+      pool_idle_timeout_secs: 90,
+      user_agent: format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+      proxy: None,
+      accept_invalid_certs: false,
+      trust_on_first_use: false,
+      default_headers: BTreeMap::default(),
+    }
+  }
+}
+
+impl ClientConfig {
+  /// Client shared by all requests so connections and TLS sessions are reused, dialing the host and port of each of
+  /// `routes` at its address, e.g. the local end of a tunnel. Certificates of `pins` are accepted although they
+  /// don't validate. Redirects are followed by the executor to record them in the timeline.
+  pub fn build(&self, routes: &[(String, u16, SocketAddr)], pins: Option<Arc<Pins>>) -> Result<Client> {
+    let tls = match pins.filter(|_| !self.accept_invalid_certs) {
+      Some(pins) => Some(tls::client_config(pins)?),
+      None => None,
+    };
+    let tunneled = routes
+      .iter()
+      .map(|(host, port, address)| {
+        Ok((host.clone(), *port, self.builder(tls.clone())?.resolve(host, *address).build()?))
+      })
+      .collect::<Result<Vec<_>>>()?;
+    Ok(Client::new(self.builder(tls)?.build()?, tunneled))
+  }
+
+  /// Settings with the ones a profile changes.
+  pub fn for_profile(&self, profile: Option<&Profile>) -> ClientConfig {
+    let mut client = self.clone();
+    if let Some(overrides) = profile.map(|profile| &profile.client) {
+      client.timeout_secs = overrides.timeout_secs.unwrap_or(client.timeout_secs);
+      client.connect_timeout_secs = overrides.connect_timeout_secs.unwrap_or(client.connect_timeout_secs);
+      client.proxy = overrides.proxy.clone().or(client.proxy);
+      client.accept_invalid_certs = overrides.accept_invalid_certs.unwrap_or(client.accept_invalid_certs);
+    }
+    client
+  }
+
+  fn builder(&self, tls: Option<rustls::ClientConfig>) -> Result<reqwest::ClientBuilder> {
+    let mut builder = reqwest::Client::builder()
+      .redirect(reqwest::redirect::Policy::none())
+      .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+      .pool_idle_timeout(Duration::from_secs(self.pool_idle_timeout_secs))
+      .user_agent(self.user_agent.as_str())
+      .tls_info(true);
+    builder = match tls {
+      Some(tls) => builder.use_preconfigured_tls(tls),
+      None => builder.danger_accept_invalid_certs(self.accept_invalid_certs),
+    };
+    if self.timeout_secs > 0 {
+      builder = builder.timeout(Duration::from_secs(self.timeout_secs));
+    }
+    if let Some(proxy) = &self.proxy {
+      builder = builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+    }
+    Ok(builder)
+  }
+
+  /// Standing headers of the header tab, the `User-Agent` sent by the client first unless it's set in
+  /// `default_headers`.
+  pub fn standing_headers(&self) -> Vec<(String, String)> {
+    let mut headers = vec![];
+    if !self.default_headers.keys().any(|name| name.eq_ignore_ascii_case("user-agent")) {
+      headers.push((String::from("User-Agent"), self.user_agent.clone()));
+    }
+    headers.extend(self.default_headers.iter().map(|(name, value)| (name.clone(), value.clone())));
+    headers
+  }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ResponseConfig {
+  /// Shows ISO-8601 timestamps of pretty printed responses in the local timezone and relative to now.
+  pub annotate_timestamps: bool,
+  /// Shows large numbers with thousands separators and byte sizes, e.g. of `sizeBytes` fields, in a readable form.
+  pub humanize_numbers: bool,
+  /// Shell commands by name that `:pipe <name>` sends the response body to on stdin, showing their output instead.
+  pub pipes: BTreeMap<String, String>,
+}
+
+/// What drafts keep in the history file of a spec.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+  /// Keeps the last response of each draft, whose body can hold tokens or personal data.
+  pub keep_responses: bool,
+  /// Responses with larger bodies are kept for the session only.
+  pub max_response_bytes: usize,
+}
+
+impl Default for HistoryConfig {
+  fn default() -> Self {
+    Self { keep_responses: true, max_response_bytes: 1024 * 1024 }
+  }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct UpdateConfig {
+  /// Looks for newer releases on startup, at most once a day.
+  pub check: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TraceConfig {
+  /// Sends a fresh W3C `traceparent` header with every request that doesn't carry one.
+  pub traceparent: bool,
+  /// Url of a trace in Jaeger or Tempo, `{trace_id}` is replaced with the trace id of the response.
+  pub link_template: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+  /// Address of the local listener receiving webhook calls in test mode.
+  pub listen_address: String,
+}
+
+impl Default for WebhookConfig {
+  fn default() -> Self {
+    Self { listen_address: String::from("127.0.0.1:8787") }
+  }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct RecordConfig {
+  /// Address of the local proxy recording the calls forwarded to the target of `:record`.
+  pub listen_address: String,
+}
+
+impl Default for RecordConfig {
+  fn default() -> Self {
+    Self { listen_address: String::from("127.0.0.1:8788") }
+  }
+}
+
+/// An SSH local port forward opened at startup, for APIs only reachable through a bastion.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TunnelConfig {
+  /// Hosts whose requests are dialed through the tunnel, the host of `target` when empty.
+  #[serde(default)]
+  pub hosts: Vec<String>,
+  /// Bastion to forward through, as `user@host` or a host of the ssh config.
+  pub ssh: String,
+  /// Identity file, the ssh config or agent decides when it's missing.
+  #[serde(default)]
+  pub key: Option<String>,
+  /// `host:port` the bastion connects to.
+  pub target: String,
+  /// Local end of the forward, a free port when `0`.
+  #[serde(default)]
+  pub local_port: u16,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+  pub reduced_motion: bool,
+  /// Linear layout for screen readers: only the focused pane is drawn, box drawing is blanked out and focus changes
+  /// are announced in the footer.
+  pub screen_reader: bool,
+  pub frame_rate: f64,
+  pub tick_rate: f64,
+  /// Terminal graphics protocol image responses are drawn with.
+  pub image_protocol: ImageProtocol,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImageProtocol {
+  /// Picked from the environment of the terminal, half-blocks when it isn't known to support a protocol.
+  #[default]
+  Auto,
+  Sixel,
+  Iterm2,
+  /// Two pixels per cell drawn with `▀`, works in every terminal.
+  HalfBlocks,
+}
+
+/// Startup layout of the main page, for skipping the keys pressed on every start.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+  /// Pane focused on startup, `apis`, `tags`, `address`, `request` or `response`.
+  pub focus: Option<String>,
+  /// Pane shown full screen on startup.
+  pub fullscreen: Option<String>,
+  /// Keeps the tags pane one line high while it's not focused.
+  pub collapse_tags: bool,
+  /// Opens the `/` filter on startup.
+  pub filter_on_startup: bool,
+}
+
+impl Default for DisplayConfig {
+  fn default() -> Self {
+    Self {
+      reduced_motion: false,
+      screen_reader: false,
+      frame_rate: 60.0,
+      tick_rate: 4.0,
+      image_protocol: ImageProtocol::default(),
+    }
+  }
+}
+
+impl DisplayConfig {
+  const REDUCED_FRAME_RATE: f64 = 2.0;
+  const REDUCED_TICK_RATE: f64 = 1.0;
+
+  /// Frames rendered per second, capped when reduced motion is enabled.
+  pub fn frame_rate(&self) -> f64 {
+    let frame_rate = self.frame_rate.max(0.1);
+    if self.reduced_motion {
+      frame_rate.min(Self::REDUCED_FRAME_RATE)
+    } else {
+      frame_rate
+    }
+  }
+
+  pub fn tick_rate(&self) -> f64 {
+    let tick_rate = self.tick_rate.max(0.1);
+    if self.reduced_motion {
+      tick_rate.min(Self::REDUCED_TICK_RATE)
+    } else {
+      tick_rate
+    }
+  }
+
+  /// Graphics protocol of image responses with `auto` resolved. tmux would need passthrough and the kitty protocol
+  /// draws above the text where it outlives the preview, both get half-blocks.
+  pub fn image_protocol(&self) -> ImageProtocol {
+    if self.image_protocol != ImageProtocol::Auto {
+      return self.image_protocol;
+    }
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    if std::env::var("TMUX").is_ok() {
+      ImageProtocol::HalfBlocks
+    } else if matches!(var("TERM_PROGRAM").as_str(), "iTerm.app" | "WezTerm" | "mintty")
+      || var("LC_TERMINAL") == "iTerm2"
+    {
+      ImageProtocol::Iterm2
+    } else if ["foot", "mlterm", "contour", "sixel"].iter().any(|term| var("TERM").contains(term))
+      || std::env::var("KONSOLE_VERSION").is_ok()
+      || std::env::var("WT_SESSION").is_ok()
+    {
+      ImageProtocol::Sixel
+    } else {
+      ImageProtocol::HalfBlocks
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SplitConfig {
+  #[serde(default)]
+  pub command: Option<String>,
+}
+
+impl SplitConfig {
+  /// Command used to show a file in a new terminal split, `{file}` is replaced with the file path. Falls back to
+  /// tmux or wezterm when running inside them.
+  pub fn command(&self) -> Option<String> {
+    self.command.clone().or_else(|| {
+      if std::env::var("TMUX").is_ok() {
+        Some("tmux split-window -h less -R {file}".into())
+      } else if std::env::var("WEZTERM_PANE").is_ok() {
+        Some("wezterm cli split-pane --right -- less -R {file}".into())
+      } else {
+        None
+      }
+    })
+  }
+}
+
+/// Colors used for HTTP methods and status classes. `symbols` drops the colors and prefixes status codes with a
+/// symbol of their class instead.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+  #[default]
+  Default,
+  ColorBlind,
+  HighContrast,
+  Symbols,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StatusClass {
+  Informational,
+  Success,
+  Redirection,
+  ClientError,
+  ServerError,
+  Failure,
+}
+
+impl StatusClass {
+  fn from_status(status: &str) -> Self {
+    match status.chars().next() {
+      _ if status.starts_with("default") => Self::Success,
+      Some('1') => Self::Informational,
+      Some('2') => Self::Success,
+      Some('3') => Self::Redirection,
+      Some('4') => Self::ClientError,
+      Some('5') => Self::ServerError,
+      _ => Self::Failure,
+    }
+  }
+}
+
+impl Palette {
+  pub fn method_style(&self, method: &str) -> Style {
+    match self {
+      Self::Default => Style::default().fg(match method {
+        "GET" => Color::LightCyan,
+        "POST" => Color::LightBlue,
+        "PUT" => Color::LightYellow,
+        "DELETE" => Color::LightRed,
+        _ => Color::Gray,
+      }),
+      // Okabe-Ito colors, distinguishable with the common forms of color blindness.
+      Self::ColorBlind => Style::default().fg(match method {
+        "GET" => Color::Rgb(86, 180, 233),
+        "POST" => Color::Rgb(0, 114, 178),
+        "PUT" => Color::Rgb(230, 159, 0),
+        "PATCH" => Color::Rgb(240, 228, 66),
+        "DELETE" => Color::Rgb(213, 94, 0),
+        _ => Color::Gray,
+      }),
+      Self::HighContrast => Style::default().add_modifier(Modifier::BOLD).fg(match method {
+        "GET" => Color::White,
+        "POST" => Color::LightCyan,
+        "PUT" | "PATCH" => Color::LightYellow,
+        "DELETE" => Color::LightMagenta,
+        _ => Color::White,
+      }),
+      Self::Symbols => Style::default().add_modifier(Modifier::BOLD),
+    }
+  }
+
+  /// Style of a status code, `default` responses of the spec count as success.
+  pub fn status_style(&self, status: &str) -> Style {
+    let class = StatusClass::from_status(status);
+    match self {
+      Self::Default => Style::default().fg(match class {
+        StatusClass::Informational => Color::Gray,
+        StatusClass::Success => Color::LightCyan,
+        StatusClass::Redirection => Color::LightBlue,
+        StatusClass::ClientError => Color::LightYellow,
+        StatusClass::ServerError | StatusClass::Failure => Color::LightRed,
+      }),
+      Self::ColorBlind => Style::default().fg(match class {
+        StatusClass::Informational => Color::Gray,
+        StatusClass::Success => Color::Rgb(0, 158, 115),
+        StatusClass::Redirection => Color::Rgb(86, 180, 233),
+        StatusClass::ClientError => Color::Rgb(230, 159, 0),
+        StatusClass::ServerError | StatusClass::Failure => Color::Rgb(213, 94, 0),
+      }),
+      Self::HighContrast => Style::default().add_modifier(Modifier::BOLD).fg(match class {
+        StatusClass::Informational | StatusClass::Success => Color::White,
+        StatusClass::Redirection => Color::LightCyan,
+        StatusClass::ClientError => Color::LightYellow,
+        StatusClass::ServerError | StatusClass::Failure => Color::LightMagenta,
+      }),
+      Self::Symbols => Style::default(),
+    }
+  }
+
+  /// Prefix put before a status code, only the `symbols` palette has one.
+  pub fn status_symbol(&self, status: &str) -> &'static str {
+    if *self != Self::Symbols {
+      return "";
+    }
+    match StatusClass::from_status(status) {
+      StatusClass::Informational => "i ",
+      StatusClass::Success => "✓ ",
+      StatusClass::Redirection => "→ ",
+      StatusClass::ClientError => "! ",
+      StatusClass::ServerError | StatusClass::Failure => "✗ ",
+    }
+  }
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Config {
   #[serde(default, flatten)]
@@ -26,6 +517,40 @@ pub struct Config {
   pub keybindings: KeyBindings,
   #[serde(default)]
   pub styles: Styles,
+  #[serde(default)]
+  pub palette: Palette,
+  #[serde(default)]
+  pub confirm: ConfirmConfig,
+  #[serde(default)]
+  pub diff: DiffConfig,
+  #[serde(default)]
+  pub notify: NotifyConfig,
+  #[serde(default)]
+  pub split: SplitConfig,
+  #[serde(default)]
+  pub display: DisplayConfig,
+  #[serde(default)]
+  pub layout: LayoutConfig,
+  #[serde(default)]
+  pub webhook: WebhookConfig,
+  #[serde(default)]
+  pub dial: DialConfig,
+  #[serde(default)]
+  pub client: ClientConfig,
+  #[serde(default)]
+  pub trace: TraceConfig,
+  #[serde(default)]
+  pub update: UpdateConfig,
+  #[serde(default)]
+  pub response: ResponseConfig,
+  #[serde(default)]
+  pub history: HistoryConfig,
+  #[serde(default)]
+  pub record: RecordConfig,
+  #[serde(default)]
+  pub tunnels: Vec<TunnelConfig>,
+  #[serde(default)]
+  pub profiles: BTreeMap<String, Profile>,
 }
 
 impl Config {
@@ -387,6 +912,15 @@ mod tests {
 
   use super::*;
 
+  #[test]
+  fn test_palette_status_symbol() {
+    assert_eq!(Palette::Symbols.status_symbol("204"), "✓ ");
+    assert_eq!(Palette::Symbols.status_symbol("default"), "✓ ");
+    assert_eq!(Palette::Symbols.status_symbol("404"), "! ");
+    assert_eq!(Palette::Symbols.status_symbol("connection refused"), "✗ ");
+    assert_eq!(Palette::Default.status_symbol("500"), "");
+  }
+
   #[test]
   fn test_parse_style_default() {
     let style = parse_style("");
@@ -441,9 +975,46 @@ mod tests {
       c.keybindings.get(&Mode::Home).unwrap().get(&parse_key_sequence("<q>").unwrap_or_default()).unwrap(),
       &Action::Quit
     );
+    let tab = parse_key_sequence("<Tab>").unwrap_or_default();
+    assert_eq!(c.keybindings.get(&Mode::Home).unwrap().get(&tab), Some(&Action::NavigateForward));
+    assert_eq!(c.keybindings.get(&Mode::Request).unwrap().get(&tab), None);
     Ok(())
   }
 
+  #[test]
+  fn test_confirm_config() {
+    let confirm = ConfirmConfig::default();
+    assert!(confirm.requires_confirmation("delete", "https://api.example.com"));
+    assert!(!confirm.requires_confirmation("GET", "https://api.example.com"));
+
+    let confirm = ConfirmConfig { methods: vec!["DELETE".into(), "PUT".into()], servers: vec!["Prod".into()] };
+    assert!(confirm.requires_confirmation("PUT", "https://api.prod.example.com"));
+    assert!(!confirm.requires_confirmation("PUT", "https://api.staging.example.com"));
+
+    let confirm = serde_json::from_str::<ConfirmConfig>(r#"{"servers": ["prod"]}"#).unwrap();
+    assert!(confirm.requires_confirmation("DELETE", "https://api.prod.example.com"));
+  }
+
+  #[test]
+  fn test_client_for_profile() {
+    let client = ClientConfig { timeout_secs: 5, ..ClientConfig::default() };
+    assert_eq!(client.for_profile(None).timeout_secs, 5);
+
+    let profile = serde_json::from_str::<Profile>(
+      r#"{"client": {"connect_timeout_secs": 2, "proxy": "http://proxy:3128", "accept_invalid_certs": true}}"#,
+    )
+    .unwrap();
+    let overridden = client.for_profile(Some(&profile));
+    assert_eq!(overridden.timeout_secs, 5);
+    assert_eq!(overridden.connect_timeout_secs, 2);
+    assert_eq!(overridden.proxy.as_deref(), Some("http://proxy:3128"));
+    assert!(overridden.accept_invalid_certs);
+    assert!(overridden.build(&[], None).is_ok());
+
+    let profile = serde_json::from_str::<Profile>(r#"{"client": {"proxy": "not a url"}}"#).unwrap();
+    assert!(client.for_profile(Some(&profile)).build(&[], None).is_err());
+  }
+
   #[test]
   fn test_simple_keys() {
     assert_eq!(parse_key_event("a").unwrap(), KeyEvent::new(KeyCode::Char('a'), KeyModifiers::empty()));
@@ -0,0 +1,204 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+  sync::{mpsc, oneshot},
+  task::JoinHandle,
+};
+
+const PARSE_ERROR: i64 = -32700;
+const SERVER_ERROR: i64 = -32000;
+
+/// A JSON-RPC call read from the control socket, answered once the app has handled it.
+pub struct ControlRequest {
+  pub method: String,
+  pub params: Value,
+  reply_tx: oneshot::Sender<Result<Value, String>>,
+}
+
+impl ControlRequest {
+  pub fn reply(self, result: Result<Value, String>) {
+    self.reply_tx.send(result).ok();
+  }
+
+  /// String parameter of the call, e.g. `{"params": {"name": "petId"}}`.
+  pub fn param(&self, name: &str) -> Result<String, String> {
+    match self.params.get(name) {
+      Some(Value::String(value)) => Ok(value.clone()),
+      Some(value) if !value.is_null() => Ok(value.to_string()),
+      _ => Err(format!("missing parameter {name}")),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct Call {
+  #[serde(default)]
+  id: Value,
+  method: String,
+  #[serde(default)]
+  params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct Reply {
+  jsonrpc: &'static str,
+  id: Value,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  result: Option<Value>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  error: Option<Value>,
+}
+
+impl Reply {
+  fn new(id: Value, result: Result<Value, (i64, String)>) -> Self {
+    match result {
+      Ok(result) => Self { jsonrpc: "2.0", id, result: Some(result), error: None },
+      Err((code, message)) => {
+        Self { jsonrpc: "2.0", id, result: None, error: Some(json!({"code": code, "message": message})) }
+      },
+    }
+  }
+}
+
+/// Unix socket where external tools, e.g. an editor plugin, drive the app with newline delimited JSON-RPC calls.
+pub struct ControlServer {
+  path: PathBuf,
+  request_rx: mpsc::UnboundedReceiver<ControlRequest>,
+  handle: JoinHandle<()>,
+}
+
+impl ControlServer {
+  #[cfg(unix)]
+  pub fn bind(path: &Path) -> Result<Self> {
+    remove_stale_socket(path)?;
+    let listener = tokio::net::UnixListener::bind(path)
+      .map_err(|error| eyre!("can't listen on control socket {}: {error}", path.display()))?;
+    let (request_tx, request_rx) = mpsc::unbounded_channel();
+    let handle = tokio::spawn(async move {
+      while let Ok((stream, _)) = listener.accept().await {
+        let (reader, writer) = stream.into_split();
+        tokio::spawn(serve(reader, writer, request_tx.clone()));
+      }
+    });
+    Ok(Self { path: path.to_path_buf(), request_rx, handle })
+  }
+
+  #[cfg(not(unix))]
+  pub fn bind(_path: &Path) -> Result<Self> {
+    Err(eyre!("control sockets are only supported on unix"))
+  }
+
+  /// Next call waiting to be handled, if any.
+  pub fn try_request(&mut self) -> Option<ControlRequest> {
+    self.request_rx.try_recv().ok()
+  }
+}
+
+/// Removes a socket file left behind by an app that didn't shut down, failing when another app still listens on it.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> Result<()> {
+  if !path.exists() {
+    return Ok(());
+  }
+  if std::os::unix::net::UnixStream::connect(path).is_ok() {
+    return Err(eyre!("control socket {} is in use by another instance", path.display()));
+  }
+  std::fs::remove_file(path).map_err(|error| eyre!("can't remove stale control socket {}: {error}", path.display()))
+}
+
+impl Drop for ControlServer {
+  fn drop(&mut self) {
+    self.handle.abort();
+    std::fs::remove_file(&self.path).ok();
+  }
+}
+
+/// Answers the calls of one connection in order, until it is closed.
+async fn serve<R, W>(reader: R, mut writer: W, request_tx: mpsc::UnboundedSender<ControlRequest>)
+where
+  R: tokio::io::AsyncRead + Unpin,
+  W: tokio::io::AsyncWrite + Unpin,
+{
+  use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+  let mut lines = BufReader::new(reader).lines();
+  while let Ok(Some(line)) = lines.next_line().await {
+    if line.trim().is_empty() {
+      continue;
+    }
+    let reply = match serde_json::from_str::<Call>(line.as_str()) {
+      Ok(call) => {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let request = ControlRequest { method: call.method, params: call.params, reply_tx };
+        let result = match request_tx.send(request) {
+          Ok(_) => reply_rx.await.unwrap_or_else(|_| Err(String::from("app is shutting down"))),
+          Err(_) => Err(String::from("app is shutting down")),
+        };
+        Reply::new(call.id, result.map_err(|message| (SERVER_ERROR, message)))
+      },
+      Err(error) => Reply::new(Value::Null, Err((PARSE_ERROR, error.to_string()))),
+    };
+    let Ok(mut reply) = serde_json::to_vec(&reply) else {
+      continue;
+    };
+    reply.push(b'\n');
+    if writer.write_all(&reply).await.is_err() {
+      break;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_serve() {
+    let (client, server) = tokio::io::duplex(1024);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    let (request_tx, mut request_rx) = mpsc::unbounded_channel();
+    tokio::spawn(serve(server_reader, server_writer, request_tx));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+    client_writer
+      .write_all(b"{\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"set_parameter\", \"params\": {\"name\": \"petId\", \"value\": 42}}\n")
+      .await
+      .unwrap();
+    let request = request_rx.recv().await.unwrap();
+    assert_eq!(request.method, "set_parameter");
+    assert_eq!(request.param("name"), Ok(String::from("petId")));
+    assert_eq!(request.param("value"), Ok(String::from("42")));
+    assert_eq!(request.param("missing"), Err(String::from("missing parameter missing")));
+    request.reply(Ok(json!(true)));
+    assert_eq!(lines.next_line().await.unwrap().unwrap(), r#"{"jsonrpc":"2.0","id":1,"result":true}"#);
+
+    client_writer.write_all(b"{\"id\": 2, \"method\": \"dial\"}\n").await.unwrap();
+    request_rx.recv().await.unwrap().reply(Err(String::from("no call page open")));
+    assert_eq!(
+      lines.next_line().await.unwrap().unwrap(),
+      r#"{"jsonrpc":"2.0","id":2,"error":{"code":-32000,"message":"no call page open"}}"#
+    );
+
+    client_writer.write_all(b"not json\n").await.unwrap();
+    assert!(lines.next_line().await.unwrap().unwrap().contains("-32700"));
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_bind_stale_socket() {
+    let path = std::env::temp_dir().join(format!("openapi-tui-control-{}.sock", std::process::id()));
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+    let server = ControlServer::bind(&path).unwrap();
+    assert!(ControlServer::bind(&path).is_err());
+    drop(server);
+    assert!(!path.exists());
+  }
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+
+use openapi_31::v31::{self, Openapi, Operation};
+
+use crate::media_type;
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+/// Variant of a polymorphic schema picked by its discriminator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+  pub property_name: String,
+  pub value: String,
+  pub name: String,
+  pub schema: serde_json::Value,
+}
+
+/// Response documented for a status, falling back to the `2XX` like ranges and `default`.
+fn documented_response(operation: &Operation, openapi_spec: &Openapi, status: &str) -> Option<v31::Response> {
+  let range = format!("{}XX", &status[..1.min(status.len())]);
+  let response = [status, range.as_str(), "default"]
+    .into_iter()
+    .find_map(|key| operation.responses.as_ref()?.get(key)?.resolve(openapi_spec).ok());
+  response
+}
+
+/// Whether a response with this status and content type is documented, a content type is only expected to be
+/// documented when the response documents any content.
+pub fn is_documented(operation: &Operation, openapi_spec: &Openapi, status: &str, content_type: Option<&str>) -> bool {
+  let Some(response) = documented_response(operation, openapi_spec, status) else {
+    return false;
+  };
+  match (response.content, content_type) {
+    (Some(content), Some(content_type)) => media_type::find(&content, content_type).is_some(),
+    (None, Some(_)) => false,
+    (_, None) => true,
+  }
+}
+
+/// Schema documented for a response status and content type, falling back to the `2XX` like ranges and `default`.
+pub fn response_schema(
+  operation: &Operation,
+  openapi_spec: &Openapi,
+  status: &str,
+  content_type: Option<&str>,
+) -> Option<serde_json::Value> {
+  let response = documented_response(operation, openapi_spec, status)?;
+  let content = response.content?;
+  let media_type = content_type
+    .and_then(|content_type| media_type::find(&content, content_type))
+    .or_else(|| content.values().next())?;
+  media_type.schema.clone()
+}
+
+/// Picks the variant of a schema with a `discriminator` from the discriminator property of a payload, through the
+/// explicit `mapping`, the `oneOf`/`anyOf` references named like the value or a component with that name. Arrays
+/// are matched by their first item.
+pub fn variant(
+  schema: &serde_json::Value,
+  payload: &serde_json::Value,
+  components: &BTreeMap<String, serde_json::Value>,
+) -> Option<Variant> {
+  let schema = resolve(schema, components)?;
+  if let (Some(items), serde_json::Value::Array(payload)) = (schema.get("items"), payload) {
+    return variant(items, payload.first()?, components);
+  }
+  let discriminator = schema.get("discriminator")?;
+  let property_name = discriminator.get("propertyName")?.as_str()?;
+  let value = match payload.get(property_name)? {
+    serde_json::Value::String(value) => value.clone(),
+    value => value.to_string(),
+  };
+
+  let reference = discriminator
+    .get("mapping")
+    .and_then(|mapping| mapping.get(value.as_str()))
+    .and_then(|reference| reference.as_str())
+    .map(|reference| if reference.starts_with('#') { reference.to_string() } else { schema_ref(reference) })
+    .or_else(|| {
+      ["oneOf", "anyOf"]
+        .iter()
+        .filter_map(|keyword| schema.get(*keyword)?.as_array())
+        .flatten()
+        .filter_map(|candidate| candidate.get("$ref")?.as_str())
+        .find(|reference| reference.rsplit('/').next() == Some(value.as_str()))
+        .map(String::from)
+    })
+    .unwrap_or_else(|| schema_ref(value.as_str()));
+
+  let name = reference.trim_start_matches(SCHEMA_REF_PREFIX).to_string();
+  let schema = components.get(name.as_str())?.clone();
+  Some(Variant { property_name: property_name.to_string(), value, name, schema })
+}
+
+fn schema_ref(name: &str) -> String {
+  format!("{SCHEMA_REF_PREFIX}{name}")
+}
+
+/// Schema a `$ref` to a component schema points to, other schemas as they are.
+pub fn resolve<'a>(
+  schema: &'a serde_json::Value,
+  components: &'a BTreeMap<String, serde_json::Value>,
+) -> Option<&'a serde_json::Value> {
+  match schema.get("$ref").and_then(|reference| reference.as_str()) {
+    Some(reference) => components.get(reference.strip_prefix(SCHEMA_REF_PREFIX)?),
+    None => Some(schema),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_variant() {
+    let components = BTreeMap::from([
+      (
+        "Pet".to_string(),
+        json!({
+          "oneOf": [{"$ref": "#/components/schemas/Cat"}, {"$ref": "#/components/schemas/Dog"}],
+          "discriminator": {"propertyName": "petType", "mapping": {"kitten": "Cat"}},
+        }),
+      ),
+      ("Cat".to_string(), json!({"properties": {"meow": {"type": "boolean"}}})),
+      ("Dog".to_string(), json!({"properties": {"bark": {"type": "boolean"}}})),
+    ]);
+    let schema = json!({"$ref": "#/components/schemas/Pet"});
+
+    let dog = variant(&schema, &json!({"petType": "Dog", "bark": true}), &components).unwrap();
+    assert_eq!(dog.name, "Dog");
+    assert_eq!(dog.value, "Dog");
+
+    let cat = variant(&json!({"items": schema}), &json!([{"petType": "kitten"}]), &components).unwrap();
+    assert_eq!(cat.name, "Cat");
+    assert_eq!(cat.property_name, "petType");
+
+    assert_eq!(variant(&schema, &json!({"petType": "Fish"}), &components), None);
+    assert_eq!(variant(&schema, &json!({"name": "rex"}), &components), None);
+  }
+}
@@ -0,0 +1,89 @@
+use color_eyre::eyre::{bail, Result};
+
+/// Value of a line after its `=`, unquoted. Single quoted values are taken as they are, double quoted ones have their
+/// `\n`, `\"` and `\\` escapes replaced, and unquoted ones end at a ` #` comment.
+fn value(raw: &str) -> Option<String> {
+  let raw = raw.trim();
+  if let Some(quoted) = raw.strip_prefix('\'') {
+    return quoted.split_once('\'').map(|(value, _)| value.to_string());
+  }
+  if let Some(quoted) = raw.strip_prefix('"') {
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+      match c {
+        '"' => return Some(value),
+        '\\' => match chars.next()? {
+          'n' => value.push('\n'),
+          't' => value.push('\t'),
+          escaped => value.push(escaped),
+        },
+        c => value.push(c),
+      }
+    }
+    return None;
+  }
+  let value = raw.split_once(" #").map_or(raw, |(value, _)| value);
+  Some(value.trim_end().to_string())
+}
+
+/// `KEY=VALUE` pairs of a dotenv file, skipping blank lines, `#` comments and `export` prefixes.
+pub fn parse(text: &str) -> Result<Vec<(String, String)>> {
+  let mut variables = vec![];
+  for (index, line) in text.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let Some((name, raw_value)) = line.split_once('=') else {
+      bail!("line {} has no =", index + 1);
+    };
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+      bail!("line {} has an invalid name {name:?}", index + 1);
+    }
+    let Some(value) = value(raw_value) else {
+      bail!("line {} has an unterminated quote", index + 1);
+    };
+    variables.push((name.to_string(), value));
+  }
+  Ok(variables)
+}
+
+/// Reads the `KEY=VALUE` pairs of a dotenv file.
+pub fn load(filepath: &str) -> Result<Vec<(String, String)>> {
+  parse(std::fs::read_to_string(filepath)?.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_parse() {
+    let variables = parse(
+      r#"
+# api access
+export BASE_URL=https://api.example.com # production
+TOKEN = 'abc #123'
+GREETING="hello\n\"world\""
+EMPTY=
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+      variables,
+      [
+        (String::from("BASE_URL"), String::from("https://api.example.com")),
+        (String::from("TOKEN"), String::from("abc #123")),
+        (String::from("GREETING"), String::from("hello\n\"world\"")),
+        (String::from("EMPTY"), String::new()),
+      ]
+    );
+    assert_eq!(parse("A=1\nB").unwrap_err().to_string(), "line 2 has no =");
+    assert_eq!(parse("A=\"1").unwrap_err().to_string(), "line 1 has an unterminated quote");
+  }
+}
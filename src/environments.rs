@@ -0,0 +1,63 @@
+/// Environments recognized in server descriptions and urls, with the words naming them.
+const ENVIRONMENTS: [(&str, &[&str]); 6] = [
+  ("production", &["production", "prod", "live"]),
+  ("staging", &["staging", "stage", "preprod", "uat"]),
+  ("sandbox", &["sandbox"]),
+  ("test", &["test", "testing", "qa"]),
+  ("development", &["development", "dev"]),
+  ("local", &["local", "localhost"]),
+];
+
+fn find(text: &str) -> Option<&'static str> {
+  let words = text.split(|c: char| !c.is_ascii_alphanumeric()).map(str::to_lowercase).collect::<Vec<_>>();
+  ENVIRONMENTS
+    .iter()
+    .find(|(_, names)| words.iter().any(|word| names.contains(&word.as_str())))
+    .map(|(environment, _)| *environment)
+}
+
+/// Environment of a server named by its description or else its url, e.g. `Staging (EU)` or
+/// `https://api.staging.example.com`.
+pub fn environment(url: &str, description: Option<&str>) -> Option<&'static str> {
+  description.and_then(find).or_else(|| find(url))
+}
+
+/// Servers with the ones of the same environment next to each other, in the order each environment first appears so
+/// the first server stays first.
+pub fn group(urls: Vec<String>, environment: impl Fn(&str) -> Option<&'static str>) -> Vec<String> {
+  let environments = urls.iter().map(|url| environment(url)).collect::<Vec<_>>();
+  let mut grouped = urls.into_iter().enumerate().collect::<Vec<_>>();
+  grouped.sort_by_key(|(index, _)| match environments[*index] {
+    Some(url_environment) => environments.iter().position(|other| *other == Some(url_environment)).unwrap_or(*index),
+    None => *index,
+  });
+  grouped.into_iter().map(|(_, url)| url).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_group() {
+    assert_eq!(environment("https://api.example.com", Some("Staging (EU)")), Some("staging"));
+    assert_eq!(environment("https://api.dev.example.com", Some("Europe")), Some("development"));
+    assert_eq!(environment("https://api.example.com", Some("Europe")), None);
+    assert_eq!(environment("https://devices.example.com", None), None);
+
+    let urls =
+      ["https://eu.example.com", "https://eu.staging.example.com", "https://us.example.com", "http://localhost"]
+        .map(String::from)
+        .to_vec();
+    let environment = |url: &str| {
+      environment(url, url.ends_with("//eu.example.com").then_some("production EU"))
+        .or(url.ends_with("//us.example.com").then_some("production"))
+    };
+    assert_eq!(
+      group(urls, environment),
+      ["https://eu.example.com", "https://us.example.com", "https://eu.staging.example.com", "http://localhost"]
+    );
+  }
+}
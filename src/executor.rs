@@ -0,0 +1,304 @@
+use std::{
+  collections::{HashMap, HashSet},
+  time::{Duration, Instant},
+};
+
+use color_eyre::eyre::{bail, Result};
+use futures::StreamExt;
+use reqwest::{header, Method, StatusCode, Url};
+use tokio::sync::mpsc;
+
+use crate::{
+  client::Client,
+  idempotency,
+  request::{self, Request},
+  response::Response,
+  secrets, trace,
+};
+
+const MAX_REDIRECTS: usize = 10;
+
+/// Outcome of handing a request to the executor.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Dispatch {
+  Sent,
+  AlreadyInFlight,
+  Queued,
+}
+
+pub struct Completed {
+  pub operation_id: String,
+  pub append: bool,
+  pub started_at: Instant,
+  pub result: Result<Response>,
+}
+
+/// Sends requests in the background so the ui stays responsive, with at most one request in flight per operation.
+/// Dialing an operation again while its request is in flight is dropped, or queued as a single follow-up.
+pub struct Executor {
+  client: Client,
+  queue_follow_up: bool,
+  in_flight: HashSet<String>,
+  queued: HashMap<String, Request>,
+  completed_tx: mpsc::UnboundedSender<Completed>,
+  completed_rx: mpsc::UnboundedReceiver<Completed>,
+}
+
+impl Executor {
+  pub fn new(client: Client, queue_follow_up: bool) -> Self {
+    let (completed_tx, completed_rx) = mpsc::unbounded_channel();
+    Self {
+      client,
+      queue_follow_up,
+      in_flight: HashSet::default(),
+      queued: HashMap::default(),
+      completed_tx,
+      completed_rx,
+    }
+  }
+
+  /// Sends the requests dispatched from now on with another client, the ones in flight keep theirs.
+  pub fn set_client(&mut self, client: Client) {
+    self.client = client;
+  }
+
+  pub fn dispatch(&mut self, request: Request) -> Dispatch {
+    if !self.in_flight.contains(&request.operation_id) {
+      self.spawn(request);
+      return Dispatch::Sent;
+    }
+    if self.queue_follow_up {
+      self.queued.insert(request.operation_id.clone(), request);
+      return Dispatch::Queued;
+    }
+    Dispatch::AlreadyInFlight
+  }
+
+  /// Operations with a request in flight, a queued follow-up is sent after it.
+  pub fn in_flight(&self) -> impl Iterator<Item = &String> {
+    self.in_flight.iter()
+  }
+
+  /// Next finished request, sending the queued follow-up of its operation if there is one.
+  pub fn try_completed(&mut self) -> Option<Completed> {
+    let completed = self.completed_rx.try_recv().ok()?;
+    self.in_flight.remove(&completed.operation_id);
+    if let Some(request) = self.queued.remove(&completed.operation_id) {
+      self.spawn(request);
+    }
+    Some(completed)
+  }
+
+  fn spawn(&mut self, request: Request) {
+    let started_at = Instant::now();
+    self.in_flight.insert(request.operation_id.clone());
+    let completed_tx = self.completed_tx.clone();
+    let client = self.client.clone();
+    tokio::spawn(async move {
+      let result = send(&client, request.request).await;
+      completed_tx
+        .send(Completed { operation_id: request.operation_id, append: request.append, started_at, result })
+        .ok();
+    });
+  }
+}
+
+/// Sends a batch of requests in the background, e.g. of a bulk invoke or a smoke test, at most `concurrency` at a
+/// time. Each response arrives in the order of the requests, with the item it was sent for and the time it took, and
+/// the channel closes after the last one. Requests that couldn't be built arrive as their error.
+pub fn spawn_batch<T: Send + 'static>(
+  client: Client,
+  requests: Vec<(T, Result<reqwest::Request>)>,
+  concurrency: usize,
+) -> mpsc::UnboundedReceiver<(T, Result<Response>, Duration)> {
+  let (completed_tx, completed_rx) = mpsc::unbounded_channel();
+  tokio::spawn(async move {
+    let mut responses = futures::stream::iter(requests)
+      .map(|(item, request)| {
+        let client = client.clone();
+        async move {
+          let started_at = Instant::now();
+          let response = match request {
+            Ok(request) => send(&client, request).await,
+            Err(error) => Err(error),
+          };
+          (item, response, started_at.elapsed())
+        }
+      })
+      .buffered(concurrency);
+    while let Some(completed) = responses.next().await {
+      if completed_tx.send(completed).is_err() {
+        break;
+      }
+    }
+  });
+  completed_rx
+}
+
+/// Sends a request with the shared client, following redirects and recording them and when the response arrived
+/// in its timeline.
+pub async fn send(client: &Client, mut request: reqwest::Request) -> Result<Response> {
+  let started_at = Instant::now();
+  let mut timeline = vec![(Duration::ZERO, format!("{} {} sent", request.method(), request::template_url(&request)))];
+  secrets::resolve_url(&mut request)?;
+  let trace_id = request
+    .headers()
+    .get(trace::TRACEPARENT)
+    .and_then(|traceparent| traceparent.to_str().ok())
+    .and_then(trace::trace_id);
+  let idempotency_key = idempotency::key(&request);
+  let mut redirects = 0;
+  let response = loop {
+    let retry = request.try_clone();
+    let url = request.url().clone();
+    let response = client.execute(request).await?;
+    let location = response
+      .headers()
+      .get(header::LOCATION)
+      .and_then(|location| location.to_str().ok())
+      .and_then(|location| url.join(location).ok());
+    let redirected = matches!(
+      response.status(),
+      StatusCode::MOVED_PERMANENTLY
+        | StatusCode::FOUND
+        | StatusCode::SEE_OTHER
+        | StatusCode::TEMPORARY_REDIRECT
+        | StatusCode::PERMANENT_REDIRECT
+    );
+    if redirected && retry.is_none() && location.is_some() {
+      let note = format!("{} not followed, the streamed request body can't be sent again", response.status());
+      timeline.push((started_at.elapsed(), note));
+    }
+    let Some(next) = retry.zip(location).and_then(|(retry, location)| redirect(retry, response.status(), location))
+    else {
+      break response;
+    };
+    redirects += 1;
+    if redirects > MAX_REDIRECTS {
+      bail!("too many redirects");
+    }
+    timeline.push((started_at.elapsed(), format!("{} redirected to {}", response.status(), next.url())));
+    request = next;
+  };
+  timeline.push((started_at.elapsed(), format!("{} headers received", response.status())));
+  let mut response = Response::from_reqwest(response).await?;
+  timeline.push((started_at.elapsed(), format!("body received, {} bytes", response.bytes.len())));
+  response.timeline = timeline;
+  response.trace_id = trace_id;
+  response.idempotency_key = idempotency_key;
+  Ok(response)
+}
+
+/// The request to send next when a response redirects to `location`, like browsers do: 301, 302 and 303 switch to
+/// a GET without a body, 307 and 308 repeat the request. Credentials aren't sent to another host.
+fn redirect(mut request: reqwest::Request, status: StatusCode, location: Url) -> Option<reqwest::Request> {
+  match status {
+    StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND | StatusCode::SEE_OTHER => {
+      if request.method() != Method::HEAD {
+        *request.method_mut() = Method::GET;
+      }
+      *request.body_mut() = None;
+      for name in [header::CONTENT_TYPE, header::CONTENT_LENGTH, header::CONTENT_ENCODING, header::TRANSFER_ENCODING] {
+        request.headers_mut().remove(name);
+      }
+    },
+    StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT => {},
+    _ => return None,
+  }
+  if !same_origin(request.url(), &location) {
+    strip_credentials(&mut request);
+  }
+  *request.url_mut() = location;
+  Some(request)
+}
+
+/// Whether two urls point at the same host and port, credentials are only sent on to the same one.
+pub fn same_origin(url: &Url, other: &Url) -> bool {
+  url.host_str() == other.host_str() && url.port_or_known_default() == other.port_or_known_default()
+}
+
+/// Removes the credentials of a request sent to another host: the authorization and cookie headers and the headers
+/// marked sensitive, like api keys and secrets.
+pub fn strip_credentials(request: &mut reqwest::Request) {
+  for name in [header::AUTHORIZATION, header::COOKIE, header::PROXY_AUTHORIZATION, header::WWW_AUTHENTICATE] {
+    request.headers_mut().remove(name);
+  }
+  let sensitive = request
+    .headers()
+    .iter()
+    .filter(|(_, value)| value.is_sensitive())
+    .map(|(name, _)| name.clone())
+    .collect::<Vec<_>>();
+  for name in sensitive {
+    request.headers_mut().remove(name);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn request(operation_id: &str) -> Request {
+    let request = reqwest::Client::new().get("http://127.0.0.1:9/").build().unwrap();
+    Request { operation_id: operation_id.to_string(), request, append: false }
+  }
+
+  #[tokio::test]
+  async fn test_dispatch() {
+    let mut executor = Executor::new(Client::default(), false);
+    assert_eq!(executor.dispatch(request("a")), Dispatch::Sent);
+    assert_eq!(executor.dispatch(request("a")), Dispatch::AlreadyInFlight);
+    assert_eq!(executor.dispatch(request("b")), Dispatch::Sent);
+    assert_eq!(executor.in_flight().count(), 2);
+
+    let mut executor = Executor::new(Client::default(), true);
+    assert_eq!(executor.dispatch(request("a")), Dispatch::Sent);
+    assert_eq!(executor.dispatch(request("a")), Dispatch::Queued);
+    assert_eq!(executor.dispatch(request("a")), Dispatch::Queued);
+    assert_eq!(executor.queued.len(), 1);
+  }
+
+  #[tokio::test]
+  async fn test_spawn_batch() {
+    let requests = vec![("a", Err(color_eyre::eyre::eyre!("can't build request"))), ("b", Ok(request("b").request))];
+    let mut completed_rx = spawn_batch(Client::default(), requests, 2);
+    let (item, response, _) = completed_rx.recv().await.unwrap();
+    assert_eq!((item, response.err().map(|error| error.to_string())), ("a", Some(String::from("can't build request"))));
+    let (item, response, _) = completed_rx.recv().await.unwrap();
+    assert_eq!(item, "b");
+    assert!(response.is_err());
+    assert!(completed_rx.recv().await.is_none());
+  }
+
+  #[test]
+  fn test_redirect() {
+    let client = reqwest::Client::new();
+    let mut api_key = header::HeaderValue::from_static("k1");
+    api_key.set_sensitive(true);
+    let request = client
+      .post("https://example.com/pets")
+      .bearer_auth("token")
+      .header("x-api-key", api_key)
+      .header("accept", "application/json")
+      .body("{}")
+      .build()
+      .unwrap();
+
+    let location = Url::parse("https://example.com/pets/1").unwrap();
+    let next = redirect(request.try_clone().unwrap(), StatusCode::SEE_OTHER, location.clone()).unwrap();
+    assert_eq!((next.method(), next.url()), (&Method::GET, &location));
+    assert!(next.body().is_none());
+    assert!(next.headers().contains_key(header::AUTHORIZATION));
+
+    let location = Url::parse("https://mirror.example.com/pets").unwrap();
+    let next = redirect(request.try_clone().unwrap(), StatusCode::TEMPORARY_REDIRECT, location).unwrap();
+    assert_eq!(next.method(), Method::POST);
+    assert!(next.body().is_some());
+    assert!(!next.headers().contains_key(header::AUTHORIZATION));
+    assert_eq!(next.headers().keys().collect::<Vec<_>>(), [header::ACCEPT]);
+
+    assert!(redirect(request, StatusCode::NOT_MODIFIED, Url::parse("https://example.com").unwrap()).is_none());
+  }
+}
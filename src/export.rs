@@ -0,0 +1,75 @@
+use openapi_31::v31::Openapi;
+
+use crate::{auth, state::OperationItem};
+
+const COLUMNS: [&str; 5] = ["method", "path", "summary", "deprecated", "auth"];
+
+/// Writes operations as a csv file, or as a markdown table when the file name ends with `.md`.
+pub fn write_operation_list(
+  filepath: &str,
+  operations: &[&OperationItem],
+  openapi_spec: &Openapi,
+) -> std::io::Result<()> {
+  let rows = operations.iter().map(|operation_item| operation_row(operation_item, openapi_spec)).collect::<Vec<_>>();
+  let content =
+    if filepath.ends_with(".md") || filepath.ends_with(".markdown") { markdown_table(&rows) } else { csv(&rows) };
+  std::fs::write(filepath, content)
+}
+
+fn operation_row(operation_item: &OperationItem, openapi_spec: &Openapi) -> [String; 5] {
+  let operation = &operation_item.operation;
+  let security = auth::requirements(operation, openapi_spec);
+  [
+    operation_item.method.to_uppercase(),
+    operation_item.path.clone(),
+    operation.summary.clone().unwrap_or_default(),
+    operation.deprecated.unwrap_or(false).to_string(),
+    security.map(|requirements| auth::describe(&requirements)).unwrap_or_default(),
+  ]
+}
+
+fn csv(rows: &[[String; 5]]) -> String {
+  let escape = |field: &str| {
+    if field.contains([',', '"', '\n']) {
+      format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+      field.to_string()
+    }
+  };
+  std::iter::once(COLUMNS.map(String::from))
+    .chain(rows.iter().cloned())
+    .map(|row| row.iter().map(|field| escape(field)).collect::<Vec<_>>().join(",") + "\n")
+    .collect()
+}
+
+fn markdown_table(rows: &[[String; 5]]) -> String {
+  let mut table = format!("| {} |\n|{}\n", COLUMNS.join(" | "), ":---|".repeat(COLUMNS.len()));
+  for row in rows {
+    let cells = row.iter().map(|cell| cell.replace('|', "\\|").replace('\n', " ")).collect::<Vec<_>>();
+    table.push_str(format!("| {} |\n", cells.join(" | ")).as_str());
+  }
+  table
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_operation_list() {
+    let rows = [
+      ["GET".into(), "/pets".into(), "List pets, all".into(), "false".into(), "none".into()],
+      ["DELETE".into(), "/pets/{id}".into(), "Delete a \"pet\"".into(), "true".into(), "apiKey | oauth+mtls".into()],
+    ];
+    assert_eq!(
+      csv(&rows),
+      "method,path,summary,deprecated,auth\nGET,/pets,\"List pets, all\",false,none\nDELETE,/pets/{id},\"Delete a \"\"pet\"\"\",true,apiKey | oauth+mtls\n"
+    );
+    assert!(
+      markdown_table(&rows).ends_with("| DELETE | /pets/{id} | Delete a \"pet\" | true | apiKey \\| oauth+mtls |\n")
+    );
+    assert_eq!(auth::describe(&[vec![], vec!["apiKey".into()]]), "none | apiKey");
+  }
+}
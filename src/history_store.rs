@@ -0,0 +1,136 @@
+use std::{
+  collections::BTreeMap,
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::{response::Response, secrets};
+
+pub const HISTORY_FILE: &str = "history.json";
+
+/// Headers that carry or hand out credentials, never written to the history file unless they're secret references.
+const CREDENTIAL_HEADERS: [&str; 7] =
+  ["set-cookie", "set-cookie2", "cookie", "authorization", "proxy-authorization", "x-auth-token", "x-api-key"];
+
+/// A request page kept across sessions: the values typed into it and the last response it got. Secret references and
+/// `{{name}}` placeholders are stored as typed, never resolved, and credential headers typed in plain are left out.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Draft {
+  /// Location, name and value of the filled-in parameters.
+  pub parameters: Vec<(String, String, String)>,
+  pub content_type: Option<String>,
+  pub body: Option<String>,
+  pub response: Option<StoredResponse>,
+}
+
+impl Draft {
+  pub fn is_empty(&self) -> bool {
+    self.parameters.is_empty() && self.body.is_none() && self.response.is_none()
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredResponse {
+  pub status: u16,
+  pub headers: Vec<(String, String)>,
+  /// The body with its `Content-Encoding` undone, base64 encoded when it's not utf-8.
+  pub body: String,
+  #[serde(default)]
+  pub base64: bool,
+  /// When the response arrived, in seconds since the unix epoch.
+  #[serde(default)]
+  pub received_at: u64,
+}
+
+impl StoredResponse {
+  /// The body of a response with its headers but the credentials it hands out, `None` for a body larger than
+  /// `max_body_bytes`.
+  pub fn from_response(response: &Response, max_body_bytes: usize) -> Option<Self> {
+    let content = response.content();
+    if content.len() > max_body_bytes {
+      return None;
+    }
+    let headers = response
+      .headers
+      .iter()
+      .filter(|(name, _)| !matches!(name.as_str(), "content-encoding" | "content-length" | "transfer-encoding"))
+      .filter(|(name, value)| !value.is_sensitive() && !CREDENTIAL_HEADERS.contains(&name.as_str()))
+      .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+      .collect();
+    let (body, base64) = match std::str::from_utf8(&content) {
+      Ok(body) => (body.to_string(), false),
+      Err(_) => (BASE64_STANDARD.encode(&content), true),
+    };
+    let received_at = SystemTime::now().checked_sub(response.received_at.elapsed()).unwrap_or_else(SystemTime::now);
+    let received_at = received_at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    Some(Self { status: response.status.as_u16(), headers, body, base64, received_at })
+  }
+
+  pub fn to_response(&self) -> Response {
+    let status = reqwest::StatusCode::from_u16(self.status).unwrap_or(reqwest::StatusCode::OK);
+    let bytes = match self.base64 {
+      true => BASE64_STANDARD.decode(&self.body).unwrap_or_default(),
+      false => self.body.clone().into_bytes(),
+    };
+    let mut response = Response::from_parts(status, &self.headers, bytes);
+    let received_at = UNIX_EPOCH + Duration::from_secs(self.received_at);
+    let age = SystemTime::now().duration_since(received_at).unwrap_or_default();
+    response.received_at = Instant::now().checked_sub(age).unwrap_or(response.received_at);
+    response
+  }
+}
+
+/// Whether a typed parameter can be written to the history file, a credential header only as a secret reference.
+pub fn is_storable(location: &str, name: &str, value: &str) -> bool {
+  !location.eq_ignore_ascii_case("header")
+    || !CREDENTIAL_HEADERS.contains(&name.to_ascii_lowercase().as_str())
+    || secrets::is_reference(value)
+}
+
+/// Drafts by operation id, as saved in the spec data directory.
+pub type Drafts = BTreeMap<String, Draft>;
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_stored_response() {
+    let headers = vec![
+      (String::from("content-type"), String::from("application/json")),
+      (String::from("content-length"), String::from("8")),
+      (String::from("set-cookie"), String::from("sid=abc")),
+    ];
+    let mut response = Response::from_parts(reqwest::StatusCode::CREATED, &headers, br#"{"id":1}"#.to_vec());
+    response.received_at = Instant::now().checked_sub(Duration::from_secs(120)).unwrap();
+    let stored = StoredResponse::from_response(&response, 1024).unwrap();
+    assert_eq!(stored.headers, [(String::from("content-type"), String::from("application/json"))]);
+    assert!(!stored.base64);
+
+    let restored = stored.to_response();
+    assert_eq!(restored.status, reqwest::StatusCode::CREATED);
+    assert_eq!(restored.body, response.body);
+    assert!((119..=122).contains(&restored.received_at.elapsed().as_secs()));
+    assert_eq!(StoredResponse::from_response(&restored, 1024).map(|restored| restored.body), Some(stored.body));
+    assert_eq!(StoredResponse::from_response(&restored, 4), None);
+
+    let png = vec![0x89, b'P', b'N', b'G', 0xff, 0x00];
+    let response = Response::from_parts(reqwest::StatusCode::OK, &[], png.clone());
+    let stored = StoredResponse::from_response(&response, 1024).unwrap();
+    assert!(stored.base64);
+    assert_eq!(stored.to_response().bytes, png);
+  }
+
+  #[test]
+  fn test_is_storable() {
+    assert!(is_storable("query", "api_key", "abc"));
+    assert!(is_storable("header", "X-Request-Id", "abc"));
+    assert!(!is_storable("header", "Authorization", "Bearer abc"));
+    assert!(is_storable("header", "Authorization", "secret://token"));
+  }
+}
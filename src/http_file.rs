@@ -0,0 +1,141 @@
+use color_eyre::eyre::{bail, Result};
+
+use crate::{
+  import::{split_url, Collection, ImportedRequest},
+  request,
+};
+
+const METHODS: [&str; 9] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "TRACE", "CONNECT"];
+
+fn is_comment(line: &str) -> bool {
+  line.starts_with('#') || line.starts_with("//")
+}
+
+/// Request of the lines between two `###` separators, `None` when they hold only comments and variables.
+fn request(separator: &str, lines: &[&str], variables: &mut Vec<(String, String)>) -> Option<ImportedRequest> {
+  let mut name = Some(separator.trim()).filter(|name| !name.is_empty()).map(String::from);
+  let mut lines = lines.iter().map(|line| line.trim_end());
+  let request_line = loop {
+    let line = lines.next()?.trim_start();
+    if let Some(tag) = line.strip_prefix('#').or_else(|| line.strip_prefix("//")).map(str::trim) {
+      if let Some(tag_name) = tag.strip_prefix("@name") {
+        name = Some(tag_name.trim_start_matches([' ', '=']).trim().to_string());
+      }
+    } else if let Some((variable, value)) = line.strip_prefix('@').and_then(|line| line.split_once('=')) {
+      variables.push((variable.trim().to_string(), value.trim().to_string()));
+    } else if !line.is_empty() {
+      break line;
+    }
+  };
+  let (method, url) = match request_line.split_whitespace().collect::<Vec<_>>().as_slice() {
+    [method, url, ..] if METHODS.contains(method) => (method.to_string(), url.to_string()),
+    [url, ..] => (String::from("GET"), url.to_string()),
+    [] => return None,
+  };
+  let mut headers = vec![];
+  for line in lines.by_ref() {
+    if line.trim().is_empty() {
+      break;
+    }
+    if let Some((header, value)) = line.split_once(':').filter(|_| !is_comment(line)) {
+      headers.push((header.trim().to_string(), value.trim().to_string()));
+    }
+  }
+  let body = lines.take_while(|line| !line.starts_with("> {%") && !line.starts_with("<> ")).collect::<Vec<_>>();
+  let body = Some(body.join("\n").trim().to_string()).filter(|body| !body.is_empty());
+  let (segments, query) = split_url(url.as_str());
+  Some(ImportedRequest {
+    name: name.unwrap_or_else(|| format!("{method} {url}")),
+    method,
+    segments,
+    path_variables: vec![],
+    query,
+    headers,
+    body,
+  })
+}
+
+/// Reads the requests of a `.http` file, as written for the VS Code REST Client and the JetBrains HTTP Client, and
+/// its `@name = value` variables.
+pub fn parse(text: &str) -> Result<Collection> {
+  let mut collection = Collection::default();
+  let mut separator = "";
+  let mut lines = vec![];
+  for line in text.lines().chain(std::iter::once("###")) {
+    match line.strip_prefix("###") {
+      Some(next_separator) => {
+        collection.requests.extend(request(separator, &lines, &mut collection.variables));
+        separator = next_separator;
+        lines.clear();
+      },
+      None => lines.push(line),
+    }
+  }
+  if collection.requests.is_empty() {
+    bail!("no requests found");
+  }
+  Ok(collection)
+}
+
+/// A request in the `.http` file format, named after its operation. Secrets are written as `<secret>`.
+pub fn to_http_file(name: &str, request: &reqwest::Request) -> String {
+  let mut lines = vec![format!("### {name}"), format!("{} {}", request.method(), request::template_url(request))];
+  lines.extend(request::header_lines(request).into_iter().map(|(header, value)| format!("{header}: {value}")));
+  if let Some(body) = request::body_text(request) {
+    lines.push(String::new());
+    lines.push(body);
+  }
+  lines.push(String::new());
+  lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_parse_and_write() {
+    let collection = parse(
+      r#"@baseUrl = https://api.example.com/v1
+
+### list pets
+GET {{baseUrl}}/pets?limit=10 HTTP/1.1
+Accept: application/json
+
+###
+# @name addPet
+POST {{baseUrl}}/pets
+Content-Type: application/json
+
+{"name": "rex"}
+
+> {% client.global.set("id", response.body.id); %}
+"#,
+    )
+    .unwrap();
+    assert_eq!(collection.variables, [(String::from("baseUrl"), String::from("https://api.example.com/v1"))]);
+    let [list, add] = collection.requests.as_slice() else { panic!("two requests expected") };
+    assert_eq!(
+      (list.name.as_str(), list.method.as_str(), list.segments.as_slice()),
+      ("list pets", "GET", &[String::from("pets")][..])
+    );
+    assert_eq!(list.headers, [(String::from("Accept"), String::from("application/json"))]);
+    assert_eq!((add.name.as_str(), add.body.as_deref()), ("addPet", Some(r#"{"name": "rex"}"#)));
+    assert!(parse("# nothing here").is_err());
+
+    let request = reqwest::Client::new()
+      .post("https://api.example.com/v1/pets/{{id}}")
+      .header("content-type", "application/json")
+      .body(r#"{"name": "rex"}"#)
+      .build()
+      .unwrap();
+    let text = to_http_file("updatePet", &request);
+    assert_eq!(
+      text,
+      "### updatePet\nPOST https://api.example.com/v1/pets/{{id}}\ncontent-type: application/json\n\n{\"name\": \"rex\"}\n"
+    );
+    assert_eq!(parse(text.as_str()).unwrap().requests[0].segments, ["v1", "pets", "{{id}}"]);
+  }
+}
@@ -0,0 +1,336 @@
+/// What a status code means, where it's defined and why servers usually send it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatusInfo {
+  pub summary: &'static str,
+  pub reference: &'static str,
+  pub causes: &'static str,
+}
+
+const STATUSES: [(u16, StatusInfo); 40] = [
+  (
+    100,
+    info(
+      "The server got the headers and the client should send the body.",
+      "RFC 9110 §15.2.1",
+      "The request was sent with `Expect: 100-continue`.",
+    ),
+  ),
+  (
+    101,
+    info(
+      "The server switches to the protocol asked for in the `Upgrade` header.",
+      "RFC 9110 §15.2.2",
+      "WebSocket or HTTP/2 cleartext upgrades.",
+    ),
+  ),
+  (200, info("The request succeeded.", "RFC 9110 §15.3.1", "")),
+  (
+    201,
+    info(
+      "The request succeeded and created a resource.",
+      "RFC 9110 §15.3.2",
+      "The `Location` header usually points to the new resource.",
+    ),
+  ),
+  (
+    202,
+    info(
+      "The request was accepted but not processed yet.",
+      "RFC 9110 §15.3.3",
+      "Asynchronous jobs, poll the resource the response points to for the outcome.",
+    ),
+  ),
+  (
+    204,
+    info(
+      "The request succeeded and there is no content to send back.",
+      "RFC 9110 §15.3.5",
+      "Deletes and updates that don't return the resource.",
+    ),
+  ),
+  (
+    206,
+    info(
+      "Only the requested ranges of the resource are sent.",
+      "RFC 9110 §15.3.7",
+      "The request had a `Range` header.",
+    ),
+  ),
+  (
+    301,
+    info(
+      "The resource moved to the url in `Location` for good.",
+      "RFC 9110 §15.4.2",
+      "Old paths, http to https redirects or a missing trailing slash.",
+    ),
+  ),
+  (
+    302,
+    info(
+      "The resource is temporarily at the url in `Location`.",
+      "RFC 9110 §15.4.3",
+      "Login redirects or load balancers, clients may change the method to GET.",
+    ),
+  ),
+  (
+    303,
+    info(
+      "The outcome is at the url in `Location`, to be fetched with GET.",
+      "RFC 9110 §15.4.4",
+      "Redirect after a POST.",
+    ),
+  ),
+  (
+    304,
+    info(
+      "The cached copy is still valid.",
+      "RFC 9110 §15.4.5",
+      "The request had `If-None-Match` or `If-Modified-Since` headers.",
+    ),
+  ),
+  (
+    307,
+    info(
+      "The resource is temporarily at the url in `Location`, keep the method and body.",
+      "RFC 9110 §15.4.8",
+      "Maintenance or regional redirects.",
+    ),
+  ),
+  (
+    308,
+    info(
+      "The resource moved to the url in `Location` for good, keep the method and body.",
+      "RFC 9110 §15.4.9",
+      "Old paths or a missing trailing slash.",
+    ),
+  ),
+  (
+    400,
+    info(
+      "The server can't process the request as sent.",
+      "RFC 9110 §15.5.1",
+      "Malformed JSON, missing required fields or parameters of the wrong type.",
+    ),
+  ),
+  (
+    401,
+    info(
+      "The request lacks valid credentials.",
+      "RFC 9110 §15.5.2",
+      "Missing, expired or malformed `Authorization` header, see `WWW-Authenticate`.",
+    ),
+  ),
+  (
+    403,
+    info(
+      "The server understood the request but refuses it.",
+      "RFC 9110 §15.5.4",
+      "Valid credentials without the needed scope or role, or blocked by a firewall.",
+    ),
+  ),
+  (
+    404,
+    info(
+      "The server has nothing at this url.",
+      "RFC 9110 §15.5.5",
+      "Wrong server or base path, a path parameter of a missing resource, or a hidden forbidden resource.",
+    ),
+  ),
+  (
+    405,
+    info(
+      "The resource doesn't support this method.",
+      "RFC 9110 §15.5.6",
+      "The spec is out of date or the path is wrong, `Allow` lists the supported methods.",
+    ),
+  ),
+  (
+    406,
+    info(
+      "No representation matches the `Accept` headers.",
+      "RFC 9110 §15.5.7",
+      "Asking for a content type the operation doesn't produce, cycle it with [a].",
+    ),
+  ),
+  (
+    408,
+    info(
+      "The server gave up waiting for the request.",
+      "RFC 9110 §15.5.9",
+      "Slow uploads or idle kept-alive connections.",
+    ),
+  ),
+  (
+    409,
+    info(
+      "The request conflicts with the state of the resource.",
+      "RFC 9110 §15.5.10",
+      "Duplicate unique keys, or an edit based on an old version.",
+    ),
+  ),
+  (410, info("The resource is gone for good.", "RFC 9110 §15.5.11", "Deleted resources or retired endpoints.")),
+  (411, info("The server needs a `Content-Length` header.", "RFC 9110 §15.5.12", "A body sent without its length.")),
+  (
+    412,
+    info(
+      "A precondition header didn't hold.",
+      "RFC 9110 §15.5.13",
+      "`If-Match` with an old ETag, the resource changed meanwhile.",
+    ),
+  ),
+  (
+    413,
+    info(
+      "The body is larger than the server accepts.",
+      "RFC 9110 §15.5.14",
+      "Uploads over the size limit of the server or a proxy.",
+    ),
+  ),
+  (
+    414,
+    info(
+      "The url is longer than the server accepts.",
+      "RFC 9110 §15.5.15",
+      "Too many or too long query parameters, send them in a body.",
+    ),
+  ),
+  (
+    415,
+    info(
+      "The server doesn't accept the body in this content type.",
+      "RFC 9110 §15.5.16",
+      "A wrong or missing `Content-Type` header, or a content type the operation doesn't consume.",
+    ),
+  ),
+  (
+    416,
+    info(
+      "The requested ranges are outside the resource.",
+      "RFC 9110 §15.5.17",
+      "A `Range` header past the end of the resource.",
+    ),
+  ),
+  (
+    417,
+    info(
+      "The `Expect` header can't be met.",
+      "RFC 9110 §15.5.18",
+      "`Expect: 100-continue` through a proxy that doesn't support it.",
+    ),
+  ),
+  (
+    422,
+    info(
+      "The body is well-formed but its content is invalid.",
+      "RFC 9110 §15.5.21",
+      "Validation errors, like values out of range or fields that contradict each other.",
+    ),
+  ),
+  (
+    428,
+    info(
+      "The server needs a conditional request.",
+      "RFC 6585 §3",
+      "An update without `If-Match`, to avoid lost updates.",
+    ),
+  ),
+  (
+    429,
+    info(
+      "Too many requests were sent in a given time.",
+      "RFC 6585 §4",
+      "Rate limiting, `Retry-After` says when to try again.",
+    ),
+  ),
+  (431, info("The headers are larger than the server accepts.", "RFC 6585 §5", "Large cookies or tokens.")),
+  (451, info("The resource is unavailable for legal reasons.", "RFC 7725 §3", "Censorship or legal demands.")),
+  (
+    500,
+    info(
+      "The server failed while handling the request.",
+      "RFC 9110 §15.6.1",
+      "A bug or an unhandled input on the server, its logs have the details.",
+    ),
+  ),
+  (
+    501,
+    info(
+      "The server doesn't support what the request needs.",
+      "RFC 9110 §15.6.2",
+      "Unimplemented methods or endpoints.",
+    ),
+  ),
+  (
+    502,
+    info(
+      "A gateway got an invalid response from the upstream server.",
+      "RFC 9110 §15.6.3",
+      "The upstream server crashed, restarted or closed the connection.",
+    ),
+  ),
+  (
+    503,
+    info(
+      "The server can't handle the request right now.",
+      "RFC 9110 §15.6.4",
+      "Overload or maintenance, `Retry-After` may say when to try again.",
+    ),
+  ),
+  (
+    504,
+    info(
+      "A gateway timed out waiting for the upstream server.",
+      "RFC 9110 §15.6.5",
+      "Slow upstream servers or long running requests.",
+    ),
+  ),
+  (505, info("The server doesn't support the HTTP version of the request.", "RFC 9110 §15.6.6", "")),
+];
+
+const CLASSES: [StatusInfo; 5] = [
+  info("Informational, the request was received and is being processed.", "RFC 9110 §15.2", ""),
+  info("Successful, the request was received, understood and accepted.", "RFC 9110 §15.3", ""),
+  info("Redirection, the client has to take further action.", "RFC 9110 §15.4", ""),
+  info("Client error, the request can't be fulfilled as sent.", "RFC 9110 §15.5", ""),
+  info("Server error, the server failed to fulfill a valid request.", "RFC 9110 §15.6", ""),
+];
+
+const fn info(summary: &'static str, reference: &'static str, causes: &'static str) -> StatusInfo {
+  StatusInfo { summary, reference, causes }
+}
+
+/// Explanation of a status code, or of its class for codes without their own.
+pub fn info_of(status: u16) -> Option<StatusInfo> {
+  STATUSES
+    .iter()
+    .find(|(code, _)| *code == status)
+    .map(|(_, info)| *info)
+    .or_else(|| CLASSES.get(usize::from(status / 100).checked_sub(1)?).copied())
+}
+
+/// Key of the documented responses covering a status: the status itself, its range like `4XX`, or `default`.
+pub fn covering<'a>(status: u16, keys: &[&'a str]) -> Option<&'a str> {
+  let status = status.to_string();
+  let range = format!("{}XX", &status[..1]);
+  let key = [status.as_str(), range.as_str(), "default"]
+    .into_iter()
+    .find_map(|candidate| keys.iter().find(|key| key.eq_ignore_ascii_case(candidate)).copied());
+  key
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_info_and_covering() {
+    assert_eq!(info_of(404).map(|info| info.reference), Some("RFC 9110 §15.5.5"));
+    assert_eq!(info_of(418).map(|info| info.reference), Some("RFC 9110 §15.5"));
+    assert_eq!(info_of(99), None);
+    assert_eq!(covering(404, &["200", "4xx", "default"]), Some("4xx"));
+    assert_eq!(covering(500, &["200", "default"]), Some("default"));
+    assert_eq!(covering(500, &["200"]), None);
+  }
+}
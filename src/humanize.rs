@@ -0,0 +1,60 @@
+/// Integers with at least this many digits get thousands separators.
+const MIN_DIGITS: usize = 5;
+
+/// Keys of fields holding a size in bytes, matched case-insensitively against the end of the key.
+const SIZE_KEYS: [&str; 5] = ["bytes", "size", "length", "_len", "memory"];
+
+/// Integer part of a number with thousands separators, e.g. `-1234567.5` is `-1,234,567.5`.
+pub fn thousands(number: &str) -> String {
+  let (sign, unsigned) = number.strip_prefix('-').map_or(("", number), |unsigned| ("-", unsigned));
+  let (integer, fraction) = unsigned.find(['.', 'e', 'E']).map_or((unsigned, ""), |index| unsigned.split_at(index));
+  let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+  for (index, digit) in integer.chars().enumerate() {
+    if index > 0 && (integer.len() - index) % 3 == 0 {
+      grouped.push(',');
+    }
+    grouped.push(digit);
+  }
+  format!("{sign}{grouped}{fraction}")
+}
+
+/// Key and number of a `"key": number` line of pretty printed JSON.
+fn number_field(line: &str) -> Option<(&str, &str)> {
+  let (key, value) = line.trim().split_once("\": ")?;
+  let key = key.strip_prefix('"')?;
+  let value = value.trim_end_matches(',');
+  value.parse::<f64>().ok().filter(|number| number.is_finite()).map(|_| (key, value))
+}
+
+/// Number of a line in a readable form: a size for fields named like `sizeBytes` or `content_length`, thousands
+/// separators for other large numbers, `None` when there's nothing to add.
+pub fn annotation(line: &str) -> Option<String> {
+  let (key, value) = number_field(line)?;
+  let key = key.to_lowercase();
+  if let Ok(bytes) = value.parse::<u64>() {
+    if bytes >= 1000 && SIZE_KEYS.iter().any(|size_key| key.ends_with(size_key)) {
+      return Some(humansize::format_size(bytes, humansize::DECIMAL));
+    }
+  }
+  let digits = value.trim_start_matches('-').split(['.', 'e', 'E']).next().unwrap_or_default().len();
+  (digits >= MIN_DIGITS && !value.contains(['e', 'E'])).then(|| thousands(value))
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_annotation() {
+    assert_eq!(thousands("-1234567.25"), "-1,234,567.25");
+    assert_eq!(thousands("123"), "123");
+    assert_eq!(annotation(r#"    "requests": 1234567,"#), Some(String::from("1,234,567")));
+    assert_eq!(annotation(r#"    "fileSizeBytes": 2500000"#), Some(String::from("2.50 MB")));
+    assert_eq!(annotation(r#"    "content_length": 999,"#), None);
+    assert_eq!(annotation(r#"    "id": 42,"#), None);
+    assert_eq!(annotation(r#"    "ratio": 1.5e10,"#), None);
+    assert_eq!(annotation(r#"    "zip": "12345","#), None);
+  }
+}
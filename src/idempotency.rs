@@ -0,0 +1,44 @@
+use crate::variables;
+
+/// Header carrying the key servers use to tell a retried request from a new one, see
+/// https://datatracker.ietf.org/doc/draft-ietf-httpapi-idempotency-key-header/.
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// Adds a fresh UUID `Idempotency-Key` header, unless the request already carries one.
+pub fn apply(request: &mut reqwest::Request) {
+  if !request.headers().contains_key(IDEMPOTENCY_KEY) {
+    if let Ok(value) = reqwest::header::HeaderValue::from_str(variables::uuid().as_str()) {
+      request.headers_mut().insert(IDEMPOTENCY_KEY, value);
+    }
+  }
+}
+
+/// Value of the `Idempotency-Key` header of a request.
+pub fn key(request: &reqwest::Request) -> Option<String> {
+  request.headers().get(IDEMPOTENCY_KEY)?.to_str().ok().map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_apply() {
+    let mut request = reqwest::Client::new().post("https://example.com/payments").build().unwrap();
+    assert_eq!(key(&request), None);
+    apply(&mut request);
+    let first = key(&request).unwrap();
+    assert_eq!(first.len(), 36);
+
+    let mut retried = reqwest::Client::new().post("https://example.com/payments").build().unwrap();
+    apply(&mut retried);
+    assert_ne!(key(&retried), Some(first));
+
+    let mut request =
+      reqwest::Client::new().post("https://example.com/payments").header("Idempotency-Key", "kept").build().unwrap();
+    apply(&mut request);
+    assert_eq!(key(&request).as_deref(), Some("kept"));
+  }
+}
@@ -0,0 +1,107 @@
+use crate::state::{OperationItem, OperationItemType};
+
+/// A request read from a collection of another client, with the parts a request page can hold.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedRequest {
+  pub name: String,
+  pub method: String,
+  /// Path segments after the host, `:name` segments are path variables as Postman writes them.
+  pub segments: Vec<String>,
+  pub path_variables: Vec<(String, String)>,
+  pub query: Vec<(String, String)>,
+  pub headers: Vec<(String, String)>,
+  pub body: Option<String>,
+}
+
+impl ImportedRequest {
+  pub fn content_type(&self) -> Option<&str> {
+    self.headers.iter().find(|(name, _)| name.eq_ignore_ascii_case("content-type")).map(|(_, value)| value.as_str())
+  }
+}
+
+/// Requests of a collection, folders flattened, and its variables.
+#[derive(Debug, Default, PartialEq)]
+pub struct Collection {
+  pub requests: Vec<ImportedRequest>,
+  pub variables: Vec<(String, String)>,
+}
+
+/// Path segments and query of a raw url, the scheme and host, or the `{{baseUrl}}` standing for them, are dropped.
+pub fn split_url(raw: &str) -> (Vec<String>, Vec<(String, String)>) {
+  let (url, query) = raw.split_once('?').unwrap_or((raw, ""));
+  let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+  let path = url.split_once('/').map_or("", |(_, path)| path);
+  let query = query
+    .split('&')
+    .filter(|pair| !pair.is_empty())
+    .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+    .map(|(name, value)| (name.to_string(), value.to_string()))
+    .collect();
+  (path.split('/').filter(|segment| !segment.is_empty()).map(String::from).collect(), query)
+}
+
+/// Path parameters of a spec path when it matches the end of the request path, the segments before it are taken as
+/// the base path of the server. `:name` segments take the value of their path variable, or `{{name}}` without one.
+pub fn match_path(path: &str, request: &ImportedRequest) -> Option<Vec<(String, String)>> {
+  let template = path.split('/').filter(|segment| !segment.is_empty()).collect::<Vec<_>>();
+  let segments = request.segments.get(request.segments.len().checked_sub(template.len())?..)?;
+  let mut parameters = vec![];
+  for (template, segment) in template.iter().zip(segments) {
+    match template.strip_prefix('{').and_then(|name| name.strip_suffix('}')) {
+      Some(name) => {
+        let value = match segment.strip_prefix(':') {
+          Some(variable) => request
+            .path_variables
+            .iter()
+            .find(|(name, value)| name == variable && !value.is_empty())
+            .map_or_else(|| format!("{{{{{variable}}}}}"), |(_, value)| value.clone()),
+          None => segment.clone(),
+        };
+        parameters.push((name.to_string(), value));
+      },
+      None if template == segment => {},
+      None => return None,
+    }
+  }
+  Some(parameters)
+}
+
+/// Index of the operation a request calls and its path parameters, operations with more literal segments win.
+pub fn find_operation(
+  request: &ImportedRequest,
+  operations: &[OperationItem],
+) -> Option<(usize, Vec<(String, String)>)> {
+  operations
+    .iter()
+    .enumerate()
+    .filter(|(_, operation_item)| matches!(operation_item.r#type, OperationItemType::Path))
+    .filter(|(_, operation_item)| operation_item.method.eq_ignore_ascii_case(request.method.as_str()))
+    .filter_map(|(index, operation_item)| {
+      let parameters = match_path(operation_item.path.as_str(), request)?;
+      let segments = operation_item.path.split('/').filter(|segment| !segment.is_empty()).count();
+      Some(((segments - parameters.len(), segments), index, parameters))
+    })
+    .max_by_key(|(score, ..)| *score)
+    .map(|(_, index, parameters)| (index, parameters))
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_match_path() {
+    let (segments, query) = split_url("{{baseUrl}}/pets/:id?verbose");
+    assert_eq!(query, [(String::from("verbose"), String::new())]);
+    let request = ImportedRequest { segments, path_variables: vec![("id".into(), "42".into())], ..Default::default() };
+    assert_eq!(match_path("/pets/{petId}", &request), Some(vec![("petId".into(), "42".into())]));
+    assert_eq!(
+      match_path("/{kind}/{petId}", &request),
+      Some(vec![("kind".into(), "pets".into()), ("petId".into(), "42".into())])
+    );
+    assert_eq!(match_path("/owners/{ownerId}", &request), None);
+    assert_eq!(match_path("/v1/pets/{petId}", &request), None);
+  }
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::request;
+
+const WORKSPACE_ID: &str = "wrk_openapi_tui";
+
+/// Text with `{{name}}` placeholders written the way Insomnia reads environment variables, `{{ _.name }}`.
+fn placeholders(text: &str) -> String {
+  let mut result = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find("{{") {
+    result.push_str(&rest[..start]);
+    let tail = &rest[start + 2..];
+    match tail.find("}}") {
+      Some(end) => {
+        result.push_str(format!("{{{{ _.{} }}}}", tail[..end].trim()).as_str());
+        rest = &tail[end + 2..];
+      },
+      None => {
+        result.push_str("{{");
+        rest = tail;
+      },
+    }
+  }
+  result.push_str(rest);
+  result
+}
+
+/// Insomnia export, format 4, with a workspace of requests named after their operation and the variables as its
+/// base environment. Secrets are written as `<secret>`.
+pub fn export(name: &str, requests: &[(String, reqwest::Request)], variables: &BTreeMap<String, String>) -> Value {
+  let mut resources = vec![
+    json!({"_id": WORKSPACE_ID, "_type": "workspace", "parentId": null, "name": name}),
+    json!({
+      "_id": "env_openapi_tui",
+      "_type": "environment",
+      "parentId": WORKSPACE_ID,
+      "name": "Base Environment",
+      "data": variables,
+    }),
+  ];
+  for (index, (operation_id, request)) in requests.iter().enumerate() {
+    let headers = request::header_lines(request)
+      .into_iter()
+      .map(|(name, value)| json!({"name": name, "value": placeholders(value.as_str())}))
+      .collect::<Vec<_>>();
+    let content_type = request
+      .headers()
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|value| value.to_str().ok())
+      .unwrap_or("text/plain");
+    let body = request::body_text(request)
+      .map(|body| json!({"mimeType": content_type, "text": placeholders(body.as_str())}))
+      .unwrap_or_else(|| json!({}));
+    resources.push(json!({
+      "_id": format!("req_openapi_tui_{index}"),
+      "_type": "request",
+      "parentId": WORKSPACE_ID,
+      "name": operation_id,
+      "method": request.method().as_str(),
+      "url": placeholders(request::template_url(request).as_str()),
+      "headers": headers,
+      "body": body,
+    }));
+  }
+  json!({"_type": "export", "__export_format": 4, "__export_source": "openapi-tui", "resources": resources})
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_export() {
+    assert_eq!(placeholders("{{host}}/pets/{{ id }}?q={{"), "{{ _.host }}/pets/{{ _.id }}?q={{");
+    let request = reqwest::Client::new()
+      .post("https://example.com/pets/{{id}}")
+      .header("content-type", "application/json")
+      .body(r#"{"name": "{{name}}"}"#)
+      .build()
+      .unwrap();
+    let variables = BTreeMap::from([(String::from("id"), String::from("42"))]);
+    let export = export("pets", &[(String::from("addPet"), request)], &variables);
+    let resources = export["resources"].as_array().unwrap();
+    assert_eq!(resources[1]["data"], json!({"id": "42"}));
+    assert_eq!(resources[2]["url"], "https://example.com/pets/{{ _.id }}");
+    assert_eq!(resources[2]["body"], json!({"mimeType": "application/json", "text": r#"{"name": "{{ _.name }}"}"#}));
+  }
+}
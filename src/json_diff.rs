@@ -0,0 +1,84 @@
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+  Added(String, Value),
+  Removed(String, Value),
+  Changed(String, Value, Value),
+}
+
+/// Structural diff of two JSON documents. Entries listed in `ignore`, either by key name anywhere in the document or
+/// by their full path like `$.meta.requestId`, are skipped.
+pub fn diff(expected: &Value, actual: &Value, ignore: &[String]) -> Vec<Change> {
+  let mut changes = vec![];
+  diff_value("$", expected, actual, ignore, &mut changes);
+  changes
+}
+
+fn is_ignored(path: &str, key: &str, ignore: &[String]) -> bool {
+  ignore.iter().any(|item| item.eq(key) || item.eq(path))
+}
+
+fn diff_value(path: &str, expected: &Value, actual: &Value, ignore: &[String], changes: &mut Vec<Change>) {
+  match (expected, actual) {
+    (Value::Object(expected), Value::Object(actual)) => {
+      for (key, expected_value) in expected {
+        let child_path = format!("{path}.{key}");
+        if is_ignored(&child_path, key, ignore) {
+          continue;
+        }
+        match actual.get(key) {
+          Some(actual_value) => diff_value(&child_path, expected_value, actual_value, ignore, changes),
+          None => changes.push(Change::Removed(child_path, expected_value.clone())),
+        }
+      }
+      for (key, actual_value) in actual {
+        let child_path = format!("{path}.{key}");
+        if !expected.contains_key(key) && !is_ignored(&child_path, key, ignore) {
+          changes.push(Change::Added(child_path, actual_value.clone()));
+        }
+      }
+    },
+    (Value::Array(expected), Value::Array(actual)) => {
+      for index in 0..expected.len().max(actual.len()) {
+        let child_path = format!("{path}[{index}]");
+        match (expected.get(index), actual.get(index)) {
+          (Some(expected_value), Some(actual_value)) => {
+            diff_value(&child_path, expected_value, actual_value, ignore, changes)
+          },
+          (Some(expected_value), None) => changes.push(Change::Removed(child_path, expected_value.clone())),
+          (None, Some(actual_value)) => changes.push(Change::Added(child_path, actual_value.clone())),
+          (None, None) => {},
+        }
+      }
+    },
+    (expected, actual) if expected != actual => {
+      changes.push(Change::Changed(path.to_string(), expected.clone(), actual.clone()))
+    },
+    _ => {},
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_diff() {
+    let expected = json!({"id": 1, "name": "rex", "tags": ["a", "b"], "updatedAt": "yesterday", "meta": {"rid": 1}});
+    let actual =
+      json!({"id": 1, "name": "max", "tags": ["a"], "owner": null, "updatedAt": "today", "meta": {"rid": 2}});
+    assert_eq!(
+      diff(&expected, &actual, &["updatedAt".into(), "$.meta.rid".into()]),
+      vec![
+        Change::Changed("$.name".into(), json!("rex"), json!("max")),
+        Change::Removed("$.tags[1]".into(), json!("b")),
+        Change::Added("$.owner".into(), json!(null)),
+      ]
+    );
+    assert_eq!(diff(&expected, &expected, &[]), vec![]);
+  }
+}
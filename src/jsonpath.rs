@@ -0,0 +1,111 @@
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+  Key(String),
+  Index(usize),
+}
+
+/// Parses the dotted subset of JSONPath used across the app, e.g. `$.items[0].name` or `$['odd key']`.
+pub fn parse(path: &str) -> Option<Vec<Segment>> {
+  let mut segments = vec![];
+  let relative_path;
+  let mut rest = match path.trim().strip_prefix('$') {
+    Some(rest) => rest,
+    None => {
+      relative_path = format!(".{}", path.trim());
+      relative_path.as_str()
+    },
+  };
+  while !rest.is_empty() {
+    if let Some(tail) = rest.strip_prefix('.') {
+      let end = tail.find(['.', '[']).unwrap_or(tail.len());
+      if end == 0 {
+        return None;
+      }
+      segments.push(Segment::Key(tail[..end].to_string()));
+      rest = &tail[end..];
+    } else if let Some(tail) = rest.strip_prefix("['") {
+      let end = tail.find("']")?;
+      segments.push(Segment::Key(tail[..end].to_string()));
+      rest = &tail[end + 2..];
+    } else if let Some(tail) = rest.strip_prefix('[') {
+      let end = tail.find(']')?;
+      segments.push(Segment::Index(tail[..end].trim().parse().ok()?));
+      rest = &tail[end + 1..];
+    } else {
+      return None;
+    }
+  }
+  Some(segments)
+}
+
+/// Renders segments as a JSONPath that `parse` reads back, e.g. `$.items[0]['odd key']`.
+pub fn format(segments: &[Segment]) -> String {
+  segments.iter().fold(String::from("$"), |path, segment| match segment {
+    Segment::Key(key)
+      if !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+    {
+      format!("{path}.{key}")
+    },
+    Segment::Key(key) => format!("{path}['{key}']"),
+    Segment::Index(index) => format!("{path}[{index}]"),
+  })
+}
+
+/// Renders segments as a JSON Pointer, e.g. `/items/0/odd~1key`.
+pub fn pointer(segments: &[Segment]) -> String {
+  segments
+    .iter()
+    .map(|segment| match segment {
+      Segment::Key(key) => format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+      Segment::Index(index) => format!("/{index}"),
+    })
+    .collect()
+}
+
+pub fn select<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+  parse(path)?.iter().try_fold(value, |value, segment| match segment {
+    Segment::Key(key) => value.get(key),
+    Segment::Index(index) => value.get(index),
+  })
+}
+
+/// Renders a selected value the way users type it in commands, strings without quotes.
+pub fn display(value: &Value) -> String {
+  match value {
+    Value::String(value) => value.clone(),
+    value => value.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_select() {
+    let value = json!({"job": {"status": "done", "steps": [{"id": 1}, {"id": 2}]}, "odd key": true});
+    assert_eq!(select(&value, "$.job.status"), Some(&json!("done")));
+    assert_eq!(select(&value, "$.job.steps[1].id"), Some(&json!(2)));
+    assert_eq!(select(&value, "$['odd key']"), Some(&json!(true)));
+    assert_eq!(select(&value, "$"), Some(&value));
+    assert_eq!(select(&value, "job.status"), Some(&json!("done")));
+    assert_eq!(select(&value, "$.job.missing"), None);
+    assert_eq!(select(&value, "$.job.steps[x]"), None);
+    assert_eq!(display(select(&value, "$.job.steps[0].id").unwrap()), "1");
+  }
+
+  #[test]
+  fn test_format() {
+    for path in ["$", "$.job.steps[1].id", "$['odd key']", "$.x-request-id"] {
+      assert_eq!(format(&parse(path).unwrap()), path);
+    }
+    assert_eq!(pointer(&parse("$.items[0]['a/b']").unwrap()), "/items/0/a~1b");
+  }
+}
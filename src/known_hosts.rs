@@ -0,0 +1,119 @@
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::tls::{Certificate, Pins};
+
+pub const KNOWN_HOSTS_FILE: &str = "known_hosts.json";
+
+/// What a certificate fingerprint says about its host, compared to the one remembered for it.
+#[derive(Debug, PartialEq)]
+pub enum Trust {
+  /// The host wasn't known, its fingerprint is now remembered.
+  FirstUse,
+  Known,
+  /// The host presented another certificate, with the fingerprint remembered before.
+  Changed(String),
+}
+
+/// Contents of the known hosts file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Stored {
+  hosts: BTreeMap<String, String>,
+  pinned: BTreeMap<String, String>,
+}
+
+/// Certificate fingerprints trusted on first use and the ones pinned for certificates that don't validate, both per
+/// `host:port` and shared by every spec.
+#[derive(Debug, Default)]
+pub struct KnownHosts {
+  path: PathBuf,
+  pins: Arc<Pins>,
+}
+
+impl KnownHosts {
+  /// Reads the fingerprints of a known hosts file, starting empty when it's missing or broken.
+  pub fn load(path: PathBuf) -> Self {
+    let stored: Stored = match std::fs::read_to_string(&path) {
+      Ok(content) => serde_json::from_str(content.as_str()).unwrap_or_else(|error| {
+        log::error!("can't parse {}: {error}", path.display());
+        Stored::default()
+      }),
+      Err(_) => Stored::default(),
+    };
+    Self { path, pins: Arc::new(Pins::new(stored.pinned, stored.hosts)) }
+  }
+
+  fn save(&self) -> Result<()> {
+    if let Some(directory) = self.path.parent() {
+      std::fs::create_dir_all(directory)?;
+    }
+    let stored = Stored { hosts: self.pins.remembered(), pinned: self.pins.pinned() };
+    std::fs::write(&self.path, serde_json::to_string_pretty(&stored)?)?;
+    Ok(())
+  }
+
+  /// Pinned and remembered fingerprints, shared with the verifier of the client.
+  pub fn pins(&self) -> Arc<Pins> {
+    self.pins.clone()
+  }
+
+  /// Accepts the certificate with a fingerprint from a host from now on, although it doesn't validate.
+  pub fn pin(&mut self, host: &str, fingerprint: &str) -> Result<()> {
+    self.pins.pin(host, fingerprint);
+    self.save()
+  }
+
+  /// Compares the fingerprint of a certificate with the one remembered for its host, remembering it on first use.
+  pub fn check(&mut self, certificate: &Certificate) -> Trust {
+    match self.pins.remembered().get(&certificate.host) {
+      Some(fingerprint) if *fingerprint == certificate.fingerprint => Trust::Known,
+      Some(fingerprint) => Trust::Changed(fingerprint.clone()),
+      None => {
+        if let Err(error) = self.trust(certificate.host.as_str(), certificate.fingerprint.as_str()) {
+          log::error!("can't save {}: {error}", self.path.display());
+        }
+        Trust::FirstUse
+      },
+    }
+  }
+
+  /// Remembers the fingerprint of a host, replacing the one it had.
+  pub fn trust(&mut self, host: &str, fingerprint: &str) -> Result<()> {
+    self.pins.remember(host, fingerprint);
+    self.save()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_check() {
+    let path = std::env::temp_dir().join(format!("openapi-tui-known-hosts-{}.json", std::process::id()));
+    let certificate = |fingerprint: &str| Certificate {
+      host: String::from("internal.example.com:443"),
+      fingerprint: fingerprint.to_string(),
+      ..Certificate::default()
+    };
+    let mut known_hosts = KnownHosts::load(path.clone());
+    assert_eq!(known_hosts.check(&certificate("AA:BB")), Trust::FirstUse);
+    assert_eq!(known_hosts.check(&certificate("AA:BB")), Trust::Known);
+
+    let mut known_hosts = KnownHosts::load(path.clone());
+    assert_eq!(known_hosts.check(&certificate("CC:DD")), Trust::Changed(String::from("AA:BB")));
+    known_hosts.trust("internal.example.com:443", "CC:DD").unwrap();
+    assert_eq!(KnownHosts::load(path.clone()).check(&certificate("CC:DD")), Trust::Known);
+
+    known_hosts.pin("internal.example.com:8443", "EE:FF").unwrap();
+    let pins = KnownHosts::load(path.clone()).pins();
+    assert_eq!(pins.pinned(), BTreeMap::from([(String::from("internal.example.com:8443"), String::from("EE:FF"))]));
+    assert_eq!(pins.remembered(), BTreeMap::from([(String::from("internal.example.com:443"), String::from("CC:DD"))]));
+    std::fs::remove_file(path).unwrap();
+  }
+}
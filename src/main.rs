@@ -1,19 +1,59 @@
 pub mod action;
 pub mod app;
+pub mod assertions;
+pub mod auth;
 pub mod cli;
+pub mod client;
 pub mod components;
 pub mod config;
+pub mod control;
+pub mod discriminator;
+pub mod dotenv;
+pub mod environments;
+pub mod executor;
+pub mod export;
+pub mod history_store;
+pub mod http_file;
+pub mod http_status;
+pub mod humanize;
+pub mod idempotency;
+pub mod import;
+pub mod insomnia;
+pub mod json_diff;
+pub mod jsonpath;
+pub mod known_hosts;
+pub mod media_type;
+pub mod navigation;
 pub mod pages;
+pub mod pagination;
 pub mod panes;
+pub mod postman;
+pub mod profiles;
+pub mod rate_limit;
+pub mod recorder;
 pub mod request;
 pub mod response;
+pub mod sample;
+pub mod schema_inference;
+pub mod secrets;
+pub mod spec_diff;
+pub mod spec_patch;
 pub mod state;
+pub mod storage;
+pub mod timestamps;
+pub mod tls;
+pub mod trace;
 pub mod tui;
+pub mod tunnel;
+pub mod update_check;
 pub mod utils;
+pub mod variables;
+pub mod warm_up;
+pub mod webhook;
 
-use clap::Parser;
-use cli::Cli;
-use color_eyre::eyre::Result;
+use clap::{CommandFactory, Parser};
+use cli::{Cli, Command};
+use color_eyre::eyre::{ContextCompat, Result};
 
 use crate::{
   app::App,
@@ -26,7 +66,19 @@ async fn tokio_main() -> Result<()> {
   initialize_panic_handler()?;
 
   let args = Cli::parse();
-  let mut app = App::new(args.input).await?;
+  match args.command {
+    Some(Command::Completions { shell }) => {
+      clap_complete::generate(shell, &mut Cli::command(), env!("CARGO_PKG_NAME"), &mut std::io::stdout());
+      return Ok(());
+    },
+    Some(Command::Man) => {
+      clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout())?;
+      return Ok(());
+    },
+    None => {},
+  }
+  let mut app = App::new(args.input.context("--input is required")?, args.base_url).await?;
+  app.startup(args.operation, args.server, args.headers, args.env_files, args.call, args.control_socket)?;
   app.run().await?;
 
   Ok(())
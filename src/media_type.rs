@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+/// Type and subtype of a media type without its parameters, lowercased, `application/vnd.api+json; version=2` is
+/// `application/vnd.api+json`.
+pub fn essence(media_type: &str) -> String {
+  media_type.split(';').next().unwrap_or_default().trim().to_lowercase()
+}
+
+/// Whether the media type is JSON, `application/json` or a vendored type with a `+json` suffix.
+pub fn is_json(media_type: &str) -> bool {
+  let essence = essence(media_type);
+  essence.ends_with("/json") || essence.ends_with("+json")
+}
+
+/// Whether the media type is XML, `application/xml`, `text/xml` or a vendored type with a `+xml` suffix.
+pub fn is_xml(media_type: &str) -> bool {
+  let essence = essence(media_type);
+  essence.ends_with("/xml") || essence.ends_with("+xml")
+}
+
+/// File extension of a media type, for naming saved bodies.
+pub fn extension(media_type: &str) -> Option<&'static str> {
+  let essence = essence(media_type);
+  let extension = match essence.as_str() {
+    _ if is_json(media_type) => "json",
+    _ if is_xml(media_type) || essence == "image/svg+xml" => "xml",
+    "text/html" => "html",
+    "text/csv" => "csv",
+    "text/plain" => "txt",
+    "text/markdown" => "md",
+    "application/yaml" | "application/x-yaml" | "text/yaml" => "yaml",
+    "application/x-ndjson" | "application/jsonlines" => "jsonl",
+    "application/pdf" => "pdf",
+    "application/zip" => "zip",
+    "application/gzip" => "gz",
+    "application/octet-stream" => "bin",
+    "image/png" => "png",
+    "image/jpeg" => "jpg",
+    "image/gif" => "gif",
+    "image/webp" => "webp",
+    _ => return None,
+  };
+  Some(extension)
+}
+
+/// Media type documented in a content map for a received content type, the exact one first, then the one with the
+/// same essence and then `type/*` or `*/*` ranges.
+pub fn find<'a, V>(content: &'a BTreeMap<String, V>, content_type: &str) -> Option<&'a V> {
+  let essence = essence(content_type);
+  let range = format!("{}/*", essence.split('/').next().unwrap_or_default());
+  content
+    .get(content_type)
+    .or_else(|| content.iter().find(|(key, _)| self::essence(key) == essence).map(|(_, value)| value))
+    .or_else(|| content.iter().find(|(key, _)| self::essence(key) == range).map(|(_, value)| value))
+    .or_else(|| content.get("*/*"))
+}
+
+/// Tab labels of media types, without parameters unless two of them only differ by their parameters.
+pub fn labels(media_types: &[String]) -> Vec<String> {
+  media_types
+    .iter()
+    .map(|media_type| {
+      let essence = essence(media_type);
+      let ambiguous = media_types.iter().filter(|other| self::essence(other) == essence).count() > 1;
+      if ambiguous {
+        media_type.clone()
+      } else {
+        essence
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_media_types() {
+    assert_eq!(essence("Application/Vnd.Api+JSON; version=2"), "application/vnd.api+json");
+    assert!(is_json("application/vnd.api+json; version=2"));
+    assert!(is_json("application/json;charset=utf-8"));
+    assert!(!is_json("application/jsonlines"));
+    assert!(is_xml("application/atom+xml"));
+    assert_eq!(extension("application/problem+json; charset=utf-8"), Some("json"));
+    assert_eq!(extension("image/jpeg"), Some("jpg"));
+    assert_eq!(extension("application/x-unknown"), None);
+
+    let content = BTreeMap::from([
+      ("application/vnd.api+json; version=2".to_string(), 1),
+      ("image/*".to_string(), 2),
+      ("*/*".to_string(), 3),
+    ]);
+    assert_eq!(find(&content, "application/vnd.api+json"), Some(&1));
+    assert_eq!(find(&content, "image/png"), Some(&2));
+    assert_eq!(find(&content, "text/plain"), Some(&3));
+
+    assert_eq!(
+      labels(&["application/vnd.api+json; version=1".into(), "application/vnd.api+json; version=2".into()]),
+      ["application/vnd.api+json; version=1", "application/vnd.api+json; version=2"]
+    );
+    assert_eq!(labels(&["application/json; charset=utf-8".into()]), ["application/json"]);
+  }
+}
@@ -0,0 +1,59 @@
+const MAX_LOCATIONS: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Location {
+  Home,
+  Call(String),
+}
+
+/// Browser like back/forward history over the visited pages.
+#[derive(Default)]
+pub struct Navigation {
+  back: Vec<Location>,
+  forward: Vec<Location>,
+}
+
+impl Navigation {
+  /// Records leaving `from` for a new location, which drops the forward history.
+  pub fn visit(&mut self, from: Location) {
+    if self.back.last() != Some(&from) {
+      self.back.push(from);
+    }
+    if self.back.len() > MAX_LOCATIONS {
+      self.back.remove(0);
+    }
+    self.forward.clear();
+  }
+
+  pub fn back(&mut self, current: Location) -> Option<Location> {
+    let location = self.back.pop()?;
+    self.forward.push(current);
+    Some(location)
+  }
+
+  pub fn forward(&mut self, current: Location) -> Option<Location> {
+    let location = self.forward.pop()?;
+    self.back.push(current);
+    Some(location)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_back_and_forward() {
+    let mut navigation = Navigation::default();
+    navigation.visit(Location::Home);
+    navigation.visit(Location::Call("a".into()));
+    assert_eq!(navigation.back(Location::Call("b".into())), Some(Location::Call("a".into())));
+    assert_eq!(navigation.back(Location::Call("a".into())), Some(Location::Home));
+    assert_eq!(navigation.back(Location::Home), None);
+    assert_eq!(navigation.forward(Location::Home), Some(Location::Call("a".into())));
+    navigation.visit(Location::Call("a".into()));
+    assert_eq!(navigation.forward(Location::Call("c".into())), None);
+  }
+}
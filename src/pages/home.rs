@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::prelude::*;
@@ -5,13 +7,18 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
   action::Action,
-  config::Config,
-  pages::Page,
+  config::{Config, LayoutConfig},
+  export,
+  pages::{env_command, profile_command, secret_command, Page},
   panes::{address::AddressPane, apis::ApisPane, request::RequestPane, response::ResponsePane, tags::TagsPane, Pane},
-  state::{InputMode, State},
+  spec_patch::SpecField,
+  state::{InputMode, OperationItemType, State, AD_HOC_METHODS},
   tui::EventResponse,
+  utils::parse_duration,
 };
 
+const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(30);
+
 #[derive(Default)]
 pub struct Home {
   command_tx: Option<UnboundedSender<Action>>,
@@ -22,22 +29,48 @@ pub struct Home {
 }
 
 impl Home {
-  pub fn new() -> Result<Self> {
+  const PANE_NAMES: [&'static str; 5] = ["APIs", "Tags", "Address", "Request", "Response"];
+
+  pub fn new(layout: &LayoutConfig) -> Result<Self> {
     let focused_border_style = Style::default().fg(Color::LightGreen);
+    let focused_pane_index = layout.focus.as_deref().and_then(Self::pane_index).unwrap_or_default();
 
     Ok(Self {
       command_tx: None,
       config: Config::default(),
       panes: vec![
-        Box::new(ApisPane::new(true, focused_border_style)),
-        Box::new(TagsPane::new(false, focused_border_style)),
-        Box::new(AddressPane::new(false, focused_border_style)),
-        Box::new(RequestPane::new(false, focused_border_style)),
-        Box::new(ResponsePane::new(false, focused_border_style)),
+        Box::new(ApisPane::new(focused_pane_index == 0, focused_border_style)),
+        Box::new(TagsPane::new(focused_pane_index == 1, focused_border_style, layout.collapse_tags)),
+        Box::new(AddressPane::new(focused_pane_index == 2, focused_border_style)),
+        Box::new(RequestPane::new(focused_pane_index == 3, focused_border_style)),
+        Box::new(ResponsePane::new(focused_pane_index == 4, focused_border_style)),
       ],
 
-      focused_pane_index: 0,
-      fullscreen_pane_index: None,
+      focused_pane_index,
+      fullscreen_pane_index: layout.fullscreen.as_deref().and_then(Self::pane_index),
+    })
+  }
+
+  /// Index of a pane by its name, case insensitive.
+  fn pane_index(name: &str) -> Option<usize> {
+    let index = Self::PANE_NAMES.iter().position(|pane_name| pane_name.eq_ignore_ascii_case(name.trim()));
+    if index.is_none() {
+      log::error!("unknown pane {name} in the layout config, expected one of {}", Self::PANE_NAMES.join(", "));
+    }
+    index
+  }
+
+  fn focus_announcement(&self) -> Option<Action> {
+    self.config.display.screen_reader.then(|| {
+      Action::TimedStatusLine(
+        format!(
+          "{} pane focused, {} of {}",
+          Self::PANE_NAMES.get(self.focused_pane_index).unwrap_or(&"unknown"),
+          self.focused_pane_index + 1,
+          self.panes.len()
+        ),
+        3,
+      )
     })
   }
 }
@@ -66,6 +99,9 @@ impl Page for Home {
   }
 
   fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    for pane in self.panes.iter_mut() {
+      pane.register_config_handler(config.clone())?;
+    }
     self.config = config;
     Ok(())
   }
@@ -83,6 +119,7 @@ impl Page for Home {
         if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
           actions.push(pane.update(Action::Focus, state)?);
         }
+        actions.push(self.focus_announcement());
       },
       Action::FocusPrev => {
         let prev_index = self.focused_pane_index.saturating_add(self.panes.len() - 1) % self.panes.len();
@@ -93,12 +130,18 @@ impl Page for Home {
         if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
           actions.push(pane.update(Action::Focus, state)?);
         }
+        actions.push(self.focus_announcement());
       },
       Action::Update => {
         for pane in self.panes.iter_mut() {
           actions.push(pane.update(action.clone(), state)?);
         }
       },
+      Action::SwitchProfile(_) => {
+        for pane in self.panes.iter_mut() {
+          actions.push(pane.update(action.clone(), state)?);
+        }
+      },
       Action::ToggleFullScreen => {
         self.fullscreen_pane_index = self.fullscreen_pane_index.map_or(Some(self.focused_pane_index), |_| None);
       },
@@ -125,8 +168,137 @@ impl Page for Home {
         } else if args.eq("request") || args.eq("r") {
           actions
             .push(Some(Action::NewCall(state.active_operation().and_then(|op| op.operation.operation_id.clone()))));
+        } else if args.eq("changelog") {
+          actions.push(Some(Action::ShowChangelog));
+        } else if let Some(base_url) = args.strip_prefix("base ").map(str::trim).filter(|url| !url.is_empty()) {
+          actions.push(Some(Action::SetBaseUrl(base_url.to_string())));
+        } else if args.eq("base") {
+          let status_line = match &state.base_url {
+            Some(base_url) => format!("relative servers are resolved against {base_url}"),
+            None => String::from("no base url, set one with base <url>"),
+          };
+          actions.push(Some(Action::TimedStatusLine(status_line, 3)));
+        } else if args.eq("notes") {
+          actions.push(Some(Action::ShowNotes));
+        } else if let Some(annotation) =
+          args.strip_prefix("annotate").filter(|rest| rest.is_empty() || rest.starts_with(' ')).map(str::trim)
+        {
+          match state.active_operation().and_then(|operation_item| operation_item.operation.operation_id.clone()) {
+            Some(operation_id) => actions.push(Some(Action::Annotate(
+              operation_id,
+              Some(annotation).filter(|annotation| !annotation.is_empty() && *annotation != "clear").map(String::from),
+            ))),
+            None => actions.push(Some(Action::TimedStatusLine("no operation to annotate".into(), 3))),
+          }
+        } else if let Some(argument) = args.strip_prefix("record ").map(str::trim) {
+          match argument.split_once(' ').map(|(command, path)| (command, path.trim())) {
+            _ if argument.eq("stop") => actions.push(Some(Action::Record(None))),
+            _ if argument.eq("merge") => actions.push(Some(Action::MergeRecording)),
+            _ if argument.eq("save") => actions.push(Some(Action::PickFile(String::from("record save")))),
+            Some(("save", filepath)) => actions.push(Some(Action::SaveRecording(filepath.to_string()))),
+            None if argument.starts_with("http://") || argument.starts_with("https://") => {
+              actions.push(Some(Action::Record(Some(argument.to_string()))))
+            },
+            _ => actions.push(Some(Action::TimedStatusLine(
+              "invalid record args. record <target-url> / record stop / record save <file-name> / record merge".into(),
+              3,
+            ))),
+          }
+        } else if let Some(format) =
+          ["postman", "http"].into_iter().find(|format| args.trim().eq(&format!("import {format}")))
+        {
+          actions.push(Some(Action::PickFile(format!("import {format}"))));
+        } else if let Some(filepath) =
+          args.strip_prefix("import postman ").map(str::trim).filter(|path| !path.is_empty())
+        {
+          actions.push(Some(Action::ImportPostman(filepath.to_string())));
+        } else if let Some(filepath) = args.strip_prefix("import http ").map(str::trim).filter(|path| !path.is_empty())
+        {
+          actions.push(Some(Action::ImportHttp(filepath.to_string())));
+        } else if args.eq("reload") {
+          actions.push(Some(Action::ReloadSpec));
+        } else if args.eq("watch") || args.starts_with("watch ") {
+          let argument = args.strip_prefix("watch").unwrap_or_default().trim();
+          match argument {
+            "stop" => actions.push(Some(Action::WatchSpec(None))),
+            "" => actions.push(Some(Action::WatchSpec(Some(DEFAULT_WATCH_INTERVAL.as_millis() as u64)))),
+            interval => match parse_duration(interval) {
+              Some(interval) => actions.push(Some(Action::WatchSpec(Some(interval.as_millis() as u64)))),
+              None => actions
+                .push(Some(Action::TimedStatusLine("invalid watch args. watch [interval] / watch stop".into(), 3))),
+            },
+          }
+        } else if args.eq("env") || args.starts_with("env ") {
+          actions.push(Some(env_command(args.as_str())));
+        } else if args.eq("profile") || args.starts_with("profile ") {
+          actions.push(Some(profile_command(args.as_str())));
         } else if args.eq("history") {
           actions.push(Some(Action::History));
+        } else if args.eq("history clear") {
+          actions.push(Some(Action::ClearHistory));
+        } else if args.eq("invoke") {
+          actions.push(Some(Action::BulkInvoke));
+        } else if args.eq("smoke") {
+          actions.push(Some(Action::Smoke));
+        } else if args.eq("uncalled") {
+          state.uncalled_only = !state.uncalled_only;
+          state.active_operation_index = 0;
+          actions.push(Some(Action::Update));
+        } else if let Some(target) = args.strip_prefix("mark ").map(str::trim) {
+          if target.eq("clear") {
+            state.marked_operations.clear();
+          } else {
+            let operation_ids = state
+              .visible_operations()
+              .filter(|operation_item| matches!(operation_item.r#type, OperationItemType::Path))
+              .filter(|operation_item| target.eq("all") || operation_item.method.eq_ignore_ascii_case(target))
+              .filter_map(|operation_item| operation_item.operation.operation_id.clone())
+              .collect::<Vec<_>>();
+            state.marked_operations.extend(operation_ids);
+          }
+        } else if let Some(field) = args.strip_prefix("edit ").map(str::trim) {
+          match field.split_once(' ') {
+            None if field.eq("summary") => actions.push(Some(Action::EditSpec(SpecField::Summary))),
+            None if field.eq("description") => actions.push(Some(Action::EditSpec(SpecField::Description))),
+            Some(("param", name)) => {
+              actions.push(Some(Action::EditSpec(SpecField::ParameterDescription(name.trim().to_string()))))
+            },
+            _ => actions.push(Some(Action::TimedStatusLine(
+              "invalid edit args. edit summary / edit description / edit param <name>".into(),
+              3,
+            ))),
+          }
+        } else if let Some(format) =
+          ["postman", "insomnia"].into_iter().find(|format| args.trim().eq(&format!("export {format}")))
+        {
+          actions.push(Some(Action::PickFile(format!("export {format}"))));
+        } else if let Some(filepath) =
+          args.strip_prefix("export postman ").map(str::trim).filter(|path| !path.is_empty())
+        {
+          actions.push(Some(Action::ExportPostman(filepath.to_string())));
+        } else if let Some(filepath) =
+          args.strip_prefix("export insomnia ").map(str::trim).filter(|path| !path.is_empty())
+        {
+          actions.push(Some(Action::ExportInsomnia(filepath.to_string())));
+        } else if args.trim().eq("export list") {
+          actions.push(Some(Action::PickFile(String::from("export list"))));
+        } else if let Some(filepath) = args.strip_prefix("export list ").map(str::trim).filter(|path| !path.is_empty())
+        {
+          let operations = state.visible_operations().collect::<Vec<_>>();
+          let status_line = match export::write_operation_list(filepath, &operations, &state.openapi_spec) {
+            Ok(_) => format!("{} operations exported to {filepath}", operations.len()),
+            Err(error) => format!("can't export operations: {error}"),
+          };
+          actions.push(Some(Action::TimedStatusLine(status_line, 3)));
+        } else if let Some(target) = args.strip_prefix("new ").map(str::trim) {
+          match target.split_once(' ').map(|(method, path)| (method.to_uppercase(), path.trim())) {
+            Some((method, path)) if AD_HOC_METHODS.contains(&method.as_str()) && path.starts_with('/') => {
+              actions.push(Some(Action::NewAdHocCall(method, path.to_string())))
+            },
+            _ => actions.push(Some(Action::TimedStatusLine("invalid new args. new <method> </path>".into(), 3))),
+          }
+        } else if args.starts_with("secret ") {
+          actions.push(Some(secret_command(args.as_str())));
         } else {
           actions.push(Some(Action::TimedStatusLine("unknown command".into(), 1)));
         }
@@ -136,6 +308,17 @@ impl Page for Home {
           actions.push(pane.update(Action::Focus, state)?);
         }
       },
+      Action::OpenLink => {
+        // panes without a link of their own open the external docs
+        let link = match self.panes.get_mut(self.focused_pane_index) {
+          Some(pane) => pane.update(Action::OpenLink, state)?,
+          None => None,
+        };
+        actions.push(link.or_else(|| match state.external_docs_url() {
+          Some(url) => Some(Action::OpenInBrowser(url)),
+          None => Some(Action::TimedStatusLine("no external docs for this operation".into(), 3)),
+        }));
+      },
       _ => {
         if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
           actions.push(pane.update(action, state)?);
@@ -160,11 +343,18 @@ impl Page for Home {
           KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => EventResponse::Stop(Action::Down),
           KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => EventResponse::Stop(Action::Up),
           KeyCode::Char('g') | KeyCode::Char('G') => EventResponse::Stop(Action::Go),
+          KeyCode::Char('o') | KeyCode::Char('O') => EventResponse::Stop(Action::OpenLink),
+          KeyCode::Char('w') | KeyCode::Char('W') => EventResponse::Stop(Action::ToggleWrap),
+          KeyCode::Char('<') => EventResponse::Stop(Action::ScrollLeft),
+          KeyCode::Char('>') => EventResponse::Stop(Action::ScrollRight),
           KeyCode::Backspace | KeyCode::Char('b') | KeyCode::Char('B') => EventResponse::Stop(Action::Back),
+          KeyCode::Char('y') => EventResponse::Stop(Action::CopySchemaPointer),
+          KeyCode::Char('Y') => EventResponse::Stop(Action::CopySchemaName),
           KeyCode::Enter => EventResponse::Stop(Action::NewCall(
             state.active_operation().and_then(|op| op.operation.operation_id.clone()),
           )),
           KeyCode::Char('f') | KeyCode::Char('F') => EventResponse::Stop(Action::ToggleFullScreen),
+          KeyCode::Char(' ') => EventResponse::Stop(Action::ToggleMark),
           KeyCode::Char(c) if ('1'..='9').contains(&c) => {
             EventResponse::Stop(Action::Tab(c.to_digit(10).unwrap_or(0) - 1))
           },
@@ -184,7 +374,8 @@ impl Page for Home {
   }
 
   fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
-    if let Some(fullscreen_pane_index) = self.fullscreen_pane_index {
+    let screen_reader_pane_index = self.config.display.screen_reader.then_some(self.focused_pane_index);
+    if let Some(fullscreen_pane_index) = self.fullscreen_pane_index.or(screen_reader_pane_index) {
       self.panes[fullscreen_pane_index].draw(frame, area, state)?;
     } else {
       let outer_layout = Layout::default()
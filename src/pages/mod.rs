@@ -6,6 +6,7 @@ use tokio::sync::mpsc::UnboundedSender;
 use crate::{
   action::Action,
   config::Config,
+  history_store::Draft,
   state::State,
   tui::{Event, EventResponse, Frame},
 };
@@ -13,6 +14,39 @@ use crate::{
 pub mod home;
 pub mod phone;
 
+/// Action of the `env` command, shared by the pages since variables are used by every request.
+pub fn env_command(args: &str) -> Action {
+  match args.strip_prefix("env").map(str::trim) {
+    Some("") => Action::ShowVariables,
+    Some("load") => Action::PickFile(String::from("env load")),
+    Some(argument) => match argument.strip_prefix("load ").map(str::trim) {
+      Some(filepath) => Action::LoadEnvFile(filepath.to_string()),
+      None => Action::TimedStatusLine("invalid env args. env / env load <file>".into(), 3),
+    },
+    None => Action::TimedStatusLine("invalid env args. env / env load <file>".into(), 3),
+  }
+}
+
+/// Action of the `profile` command, shared by the pages since profiles apply to every request.
+pub fn profile_command(args: &str) -> Action {
+  match args.strip_prefix("profile").map(str::trim) {
+    Some("") => Action::ShowProfiles,
+    Some("none") => Action::SwitchProfile(None),
+    Some(name) => Action::SwitchProfile(Some(name.to_string())),
+    None => Action::TimedStatusLine("invalid profile args. profile / profile <name> / profile none".into(), 3),
+  }
+}
+
+/// Action of the `secret` command, shared by the pages. The value of a secret is typed into a masked prompt
+/// rather than on the command line, which is kept in the history.
+pub fn secret_command(args: &str) -> Action {
+  match args.split_whitespace().collect::<Vec<_>>()[..] {
+    ["secret", "set", name] => Action::PromptSecret(name.to_string()),
+    ["secret", "rm", name] => Action::DeleteSecret(name.to_string()),
+    _ => Action::TimedStatusLine("invalid secret args. secret set <name> / secret rm <name>".into(), 3),
+  }
+}
+
 pub trait Page {
   #[allow(unused_variables)]
   fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
@@ -24,6 +58,33 @@ pub trait Page {
     Ok(())
   }
 
+  /// Operation the page is working on, if any.
+  fn operation_id(&self) -> Option<String> {
+    None
+  }
+
+  /// Whether the page holds edits that are not saved anywhere yet.
+  fn is_dirty(&self) -> bool {
+    false
+  }
+
+  /// Whether the page has a parameter with this name to set.
+  #[allow(unused_variables)]
+  fn has_parameter(&self, name: &str) -> bool {
+    false
+  }
+
+  /// Request built from the current state of the page, used to replay drafts.
+  fn request(&self) -> Option<Result<reqwest::Request>> {
+    None
+  }
+
+  /// Values typed into the page and its last response, kept in history across sessions.
+  #[allow(unused_variables)]
+  fn draft(&self, state: &State) -> Option<Draft> {
+    None
+  }
+
   fn init(&mut self, _state: &State) -> Result<()> {
     Ok(())
   }
@@ -40,6 +101,7 @@ pub trait Page {
     let r = match event {
       Event::Key(key_event) => self.handle_key_events(key_event, state)?,
       Event::Mouse(mouse_event) => self.handle_mouse_events(mouse_event, state)?,
+      Event::Paste(text) => self.handle_paste_events(text, state)?,
       _ => None,
     };
     Ok(r)
@@ -50,6 +112,11 @@ pub trait Page {
     Ok(None)
   }
 
+  #[allow(unused_variables)]
+  fn handle_paste_events(&mut self, text: String, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    Ok(None)
+  }
+
   #[allow(unused_variables)]
   fn handle_mouse_events(&mut self, mouse: MouseEvent, state: &mut State) -> Result<Option<EventResponse<Action>>> {
     Ok(None)
@@ -1,21 +1,40 @@
-use std::{collections::VecDeque, sync::Arc};
+use std::{
+  collections::VecDeque,
+  sync::Arc,
+  time::{Duration, Instant},
+};
 
-use color_eyre::eyre::{ContextCompat, Result};
+use color_eyre::eyre::{bail, ContextCompat, Result};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
   prelude::*,
-  widgets::{Block, Borders, Paragraph},
+  widgets::{Block, BorderType, Borders, Paragraph},
 };
+use reqwest::header::{CONTENT_TYPE, LOCATION};
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
   action::Action,
+  assertions::Assertion,
+  auth,
+  client::Client,
   config::Config,
-  pages::Page,
-  panes::{body_editor::BodyEditor, parameter_editor::ParameterEditor, response_viewer::ResponseViewer, Pane},
-  request::Request,
-  state::{InputMode, OperationItem, State},
+  executor,
+  history_store::{Draft, StoredResponse},
+  http_file, jsonpath,
+  pages::{env_command, profile_command, secret_command, Page},
+  pagination::{self, PageDirection},
+  panes::{
+    address::server_line, body_editor::BodyEditor, parameter_editor::ParameterEditor, response_viewer::ResponseViewer,
+    Pane,
+  },
+  request::{self, Request},
+  response::BodyFormat,
+  sample,
+  state::{InputMode, OperationItem, OperationItemType, State},
   tui::{Event, EventResponse},
+  utils::parse_duration,
+  variables,
 };
 
 #[derive(Default)]
@@ -27,22 +46,92 @@ pub struct Phone {
   focused_pane_index: usize,
   panes: Vec<Box<dyn RequestPane>>,
   fullscreen_pane_index: Option<usize>,
+  pane_sizes: [PaneSize; 3],
+  /// Pane focused before a response took the focus, and whether the response pane was expanded for it.
+  focus_before_response: Option<(usize, bool)>,
   base_urls: VecDeque<String>,
+  dry_run: bool,
+  poll: Option<Poll>,
+  client: Client,
+}
+
+/// Room a pane of the request page takes, `z` collapses it to its title and `Z` gives it most of the screen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum PaneSize {
+  Collapsed,
+  #[default]
+  Normal,
+  Expanded,
+}
+
+/// Fill weight of an expanded pane, against the `Fill(1)` to `Fill(3)` of the others.
+const EXPANDED_FILL: u16 = 8;
+
+/// Height of the parameters and body row and of the response pane, then the widths of parameters and body, from
+/// the size of each pane and the height each one asks for.
+fn layout_constraints(sizes: [PaneSize; 3], heights: [Constraint; 3]) -> ([Constraint; 2], [Constraint; 2]) {
+  let [parameters, body, response] = sizes;
+  let row_height = match (parameters, body) {
+    (PaneSize::Expanded, _) | (_, PaneSize::Expanded) => Constraint::Fill(EXPANDED_FILL),
+    (PaneSize::Collapsed, PaneSize::Collapsed) => Constraint::Length(3),
+    (_, PaneSize::Collapsed) => heights[0],
+    _ => heights[1],
+  };
+  let response_height = match response {
+    PaneSize::Collapsed => Constraint::Length(3),
+    PaneSize::Normal => heights[2],
+    PaneSize::Expanded => Constraint::Fill(EXPANDED_FILL),
+  };
+  let width = |size: PaneSize, name: &str, other: PaneSize| match (size, other) {
+    (PaneSize::Collapsed, PaneSize::Collapsed) => Constraint::Fill(1),
+    (PaneSize::Collapsed, _) => Constraint::Length(name.chars().count() as u16 + 4),
+    (PaneSize::Expanded, _) => Constraint::Fill(3),
+    (PaneSize::Normal, _) => Constraint::Fill(1),
+  };
+  (
+    [row_height, response_height],
+    [width(parameters, Phone::PANE_NAMES[0], body), width(body, Phone::PANE_NAMES[1], parameters)],
+  )
+}
+
+struct Poll {
+  interval: Duration,
+  until: Option<(String, String)>,
+  iteration: usize,
+  /// When the first request of the poll was sent, `None` while it waits for a request sent before it to finish.
+  started_at: Option<Instant>,
+  sent_at: Instant,
+  /// Status of the first response to the poll's own requests.
+  initial_status: Option<reqwest::StatusCode>,
 }
 
 pub trait RequestBuilder {
-  fn path(&self, url: String) -> String {
-    url
+  /// Fills the parts of the url edited in the pane in, failing when a secret they reference can't be read.
+  fn path(&self, url: String) -> Result<String> {
+    Ok(url)
+  }
+
+  /// Adds the part of the request edited in the pane, failing with what can't be sent as it is rather than
+  /// leaving it out.
+  fn request(&self, request: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder> {
+    Ok(request)
   }
 
-  fn reqeust(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-    request
+  /// Adds the values typed into the pane to a draft kept across sessions.
+  fn draft(&self, _draft: &mut Draft) {}
+
+  /// Whether the pane has a parameter row with this name.
+  fn has_parameter(&self, _name: &str) -> bool {
+    false
   }
 }
 
 pub trait RequestPane: Pane + RequestBuilder {}
 
 impl Phone {
+  const PANE_NAMES: [&'static str; 3] = ["Parameters", "Body", "Response"];
+  const RESPONSE_PANE_INDEX: usize = 2;
+
   pub fn new(operation_item: OperationItem, request_tx: UnboundedSender<Request>, state: &State) -> Result<Self> {
     let focused_border_style = Style::default().fg(Color::LightGreen);
     let operation_item = Arc::new(operation_item);
@@ -58,18 +147,41 @@ impl Phone {
       panes: vec![Box::new(parameter_editor), Box::new(body_editor), Box::new(response_viewer)],
       focused_pane_index: 0,
       fullscreen_pane_index: None,
+      pane_sizes: [PaneSize::Normal; 3],
+      focus_before_response: None,
       base_urls,
+      dry_run: false,
+      poll: None,
+      client: state.client.clone(),
     })
   }
 
-  fn method_color(method: &str) -> Color {
-    match method {
-      "GET" => Color::LightCyan,
-      "POST" => Color::LightBlue,
-      "PUT" => Color::LightYellow,
-      "DELETE" => Color::LightRed,
-      _ => Color::Gray,
+  /// Moves the focus to another pane, returning what the panes reply.
+  fn focus_pane(&mut self, index: usize, state: &mut State) -> Result<Vec<Option<Action>>> {
+    let mut actions = vec![];
+    if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
+      actions.push(pane.update(Action::UnFocus, state)?);
+    }
+    self.focused_pane_index = index;
+    if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
+      actions.push(pane.update(Action::Focus, state)?);
     }
+    actions.push(self.focus_announcement());
+    Ok(actions)
+  }
+
+  fn focus_announcement(&self) -> Option<Action> {
+    self.config.display.screen_reader.then(|| {
+      Action::TimedStatusLine(
+        format!(
+          "{} pane focused, {} of {}",
+          Self::PANE_NAMES.get(self.focused_pane_index).unwrap_or(&"unknown"),
+          self.focused_pane_index + 1,
+          self.panes.len()
+        ),
+        3,
+      )
+    })
   }
 
   fn default_base_urls(operation_item: &OperationItem, state: &State) -> VecDeque<String> {
@@ -78,16 +190,297 @@ impl Phone {
 
   fn build_request(&self) -> Result<reqwest::Request> {
     let base_url = self.base_urls.front().context("no base url found")?;
-    let url = self.panes.iter().fold(format!("{}{}", base_url, self.operation_item.path), |url, pane| pane.path(url));
+    let url =
+      self.panes.iter().try_fold(format!("{}{}", base_url, self.operation_item.path), |url, pane| pane.path(url))?;
     let method = reqwest::Method::from_bytes(self.operation_item.method.as_bytes())?;
-    let request_builder = self
-      .panes
-      .iter()
-      .fold(reqwest::Client::new().request(method, url), |request_builder, pane| pane.reqeust(request_builder));
+    let mut errors = vec![];
+    let request_builder =
+      self.panes.iter().fold(self.client.request(method.clone(), url.as_str()), |request_builder, pane| {
+        pane.request(request_builder).unwrap_or_else(|error| {
+          errors.push(error.to_string());
+          self.client.request(method.clone(), url.as_str())
+        })
+      });
+    if !errors.is_empty() {
+      bail!("{}", errors.join(", "));
+    }
 
     Ok(request_builder.build()?)
   }
 
+  fn dial(&mut self, state: &mut State, url: Option<String>, append: bool) -> Result<Vec<Option<Action>>> {
+    let request = match self.build_request().and_then(|mut request| {
+      if let Some(url) = url {
+        *request.url_mut() = request.url().join(url.as_str())?;
+      }
+      Ok(request)
+    }) {
+      Ok(request) => request,
+      Err(error) => return Ok(vec![Some(Action::TimedStatusLine(format!("can't build request: {error}"), 5))]),
+    };
+    let operation_id = self.operation_item.operation.operation_id.clone().unwrap_or_default();
+    self.dispatch(request, operation_id, append, state)
+  }
+
+  /// Webhook call sent to a consumer, with the body typed in the body pane or else a payload made up from the
+  /// documented example or schema, its fields named like captured variables set to their values.
+  fn sample_request(&self, consumer_url: &str, state: &State) -> Result<reqwest::Request> {
+    let mut request = self.build_request()?;
+    *request.url_mut() = reqwest::Url::parse(consumer_url)?;
+    if request.body().and_then(|body| body.as_bytes()).is_some_and(|body| !body.is_empty()) {
+      return Ok(request);
+    }
+    let (content_type, media_type) = self
+      .operation_item
+      .operation
+      .request_body
+      .as_ref()
+      .and_then(|request_body| request_body.resolve(&state.openapi_spec).ok())
+      .and_then(|request_body| request_body.content.into_iter().next())
+      .context("the webhook documents no payload")?;
+    let mut payload = match (media_type.example, media_type.schema) {
+      (Some(example), _) => example,
+      (None, Some(schema)) => {
+        let components = state.openapi_spec.components.as_ref().and_then(|components| components.schemas.clone());
+        sample::generate(&schema, &components.unwrap_or_default())
+      },
+      (None, None) => bail!("the webhook payload has no example or schema"),
+    };
+    sample::fill_variables(&mut payload, &state.variables);
+    request.headers_mut().insert(CONTENT_TYPE, content_type.parse()?);
+    *request.body_mut() = Some(serde_json::to_string_pretty(&payload)?.into());
+    Ok(request)
+  }
+
+  /// Whether a request goes to another host or port than the server of the page, like a followed `Location` or a
+  /// `Link` of a paginated response, where its credentials aren't sent.
+  fn is_foreign(&self, request: &reqwest::Request, state: &State) -> bool {
+    let parse = |url: &str| reqwest::Url::parse(variables::substitute(url, &state.variables).as_str()).ok();
+    let server = self.base_urls.front().and_then(|base_url| parse(base_url));
+    server
+      .zip(parse(request::template_url(request).as_str()))
+      .is_some_and(|(server, url)| !executor::same_origin(&server, &url))
+  }
+
+  fn dispatch(
+    &mut self,
+    mut request: reqwest::Request,
+    operation_id: String,
+    append: bool,
+    state: &mut State,
+  ) -> Result<Vec<Option<Action>>> {
+    let (operation, foreign) = (&self.operation_item.operation, self.is_foreign(&request, state));
+    let traceparent = self.config.trace.traceparent;
+    if let Err(error) = request::prepare(&mut request, operation, &operation_id, state, !foreign, traceparent) {
+      return Ok(vec![Some(Action::TimedStatusLine(format!("{error:#}"), 5))]);
+    }
+    let mut actions = vec![];
+    if foreign {
+      executor::strip_credentials(&mut request);
+      let host = request.url().host_str().unwrap_or_default();
+      actions.push(Some(Action::TimedStatusLine(format!("{host} isn't the server, sent without credentials"), 5)));
+    }
+    state.selected_server = self.base_urls.front().cloned();
+    let dry_run_output = if self.dry_run {
+      Some(format!("{}\n\n{}", request::to_http(&request), request::to_curl(&request)))
+    } else {
+      if let Some(request_tx) = &self.request_tx {
+        request_tx.send(Request { request, operation_id, append })?;
+      }
+      None
+    };
+    for pane in self.panes.iter_mut() {
+      actions.push(pane.update(Action::DryRunOutput(dry_run_output.clone()), state)?);
+    }
+    Ok(actions)
+  }
+
+  /// Opens the `Location` of the response in the browser, or the server when the response has none.
+  fn open_link(&self, state: &State) -> Action {
+    let location = self
+      .operation_item
+      .operation
+      .operation_id
+      .as_ref()
+      .and_then(|operation_id| state.responses.get(operation_id))
+      .and_then(|response| response.headers.get(LOCATION))
+      .and_then(|location| location.to_str().ok());
+    let link = match location {
+      Some(location) => self
+        .build_request()
+        .and_then(|request| Ok(request.url().join(location)?))
+        .map(|url| url.to_string())
+        .map_err(|error| format!("invalid location {location}: {error}")),
+      None => self
+        .base_urls
+        .front()
+        .filter(|base_url| reqwest::Url::parse(base_url).is_ok())
+        .cloned()
+        .ok_or_else(|| String::from("no absolute server url to open")),
+    };
+    match link {
+      Ok(link) => Action::OpenInBrowser(link),
+      Err(error) => Action::TimedStatusLine(error, 3),
+    }
+  }
+
+  fn follow_location(&mut self, state: &mut State) -> Result<Vec<Option<Action>>> {
+    let Some(location) = self
+      .operation_item
+      .operation
+      .operation_id
+      .as_ref()
+      .and_then(|operation_id| state.responses.get(operation_id))
+      .and_then(|response| response.headers.get(LOCATION))
+      .and_then(|location| location.to_str().ok())
+      .map(String::from)
+    else {
+      return Ok(vec![Some(Action::TimedStatusLine("no location header in response".into(), 3))]);
+    };
+    let mut request = match self.build_request() {
+      Ok(request) => request,
+      Err(error) => return Ok(vec![Some(Action::TimedStatusLine(format!("can't build request: {error}"), 5))]),
+    };
+    let url = match request.url().join(location.as_str()) {
+      Ok(url) => url,
+      Err(error) => return Ok(vec![Some(Action::TimedStatusLine(format!("invalid location {location}: {error}"), 5))]),
+    };
+    *request.method_mut() = reqwest::Method::GET;
+    *request.body_mut() = None;
+    request.headers_mut().remove(CONTENT_TYPE);
+    *request.url_mut() = url.clone();
+
+    let base_url = self.base_urls.front().cloned().unwrap_or_default();
+    let path = url
+      .as_str()
+      .strip_prefix(base_url.as_str())
+      .filter(|path| path.is_empty() || path.starts_with(['/', '?', '#']))
+      .map(|path| path.split(['?', '#']).next().unwrap_or_default().to_string())
+      .unwrap_or_else(|| url.path().to_string());
+
+    let current_operation_id = self.operation_item.operation.operation_id.clone();
+    match state
+      .match_operation("GET", path.as_str())
+      .map(|(item, parameters)| (item.operation.operation_id.clone(), parameters))
+    {
+      Some((operation_id, parameters)) if !self.dry_run && operation_id != current_operation_id => {
+        let mut actions = self.dispatch(request, operation_id.clone().unwrap_or_default(), false, state)?;
+        actions.push(Some(Action::NewCall(operation_id)));
+        actions.push(Some(Action::SetPathParameters(parameters)));
+        Ok(actions)
+      },
+      _ => self.dispatch(request, current_operation_id.unwrap_or_default(), false, state),
+    }
+  }
+
+  /// Question to ask before sending the request, when it looks inconsistent or its method has to be confirmed.
+  fn dial_confirmation(&self) -> Option<String> {
+    let base_url = self.base_urls.front().cloned().unwrap_or_default();
+    let warnings = match self.config.dial.consistency_checks {
+      true => self.build_request().map(|request| request::consistency_warnings(&request)).unwrap_or_default(),
+      false => vec![],
+    };
+    if !warnings.is_empty() {
+      Some(format!("{}. Send anyway?", warnings.join(", ")))
+    } else if self.config.confirm.requires_confirmation(self.operation_item.method.as_str(), base_url.as_str()) {
+      Some(format!("Send {} {}{} ?", self.operation_item.method, base_url, self.operation_item.path))
+    } else {
+      None
+    }
+  }
+
+  fn start_poll(
+    &mut self,
+    interval: u64,
+    until: Option<(String, String)>,
+    state: &mut State,
+  ) -> Result<Vec<Option<Action>>> {
+    self.poll = Some(Poll {
+      interval: Duration::from_millis(interval),
+      until,
+      iteration: 0,
+      started_at: None,
+      sent_at: Instant::now(),
+      initial_status: None,
+    });
+    self.tick_poll(state)
+  }
+
+  fn tick_poll(&mut self, state: &mut State) -> Result<Vec<Option<Action>>> {
+    let operation_id = self.operation_item.operation.operation_id.as_ref();
+    let in_flight = operation_id.is_some_and(|operation_id| state.in_flight.contains(operation_id));
+    let response = operation_id.and_then(|operation_id| state.responses.get(operation_id));
+    let Some(poll) = self.poll.as_mut() else {
+      return Ok(vec![]);
+    };
+    // wait for the request in flight, so responses after the poll started answer its own requests
+    let started_at = poll.started_at.filter(|_| !in_flight);
+    let finished = response.filter(|response| started_at.is_some_and(|started_at| response.received_at >= started_at));
+    let finished = finished.and_then(|response| {
+      let initial_status = *poll.initial_status.get_or_insert(response.status);
+      match &poll.until {
+        Some((path, expected)) => serde_json::from_str::<serde_json::Value>(&response.body)
+          .ok()
+          .and_then(|body| jsonpath::select(&body, path).map(jsonpath::display))
+          .filter(|value| value.eq(expected))
+          .map(|value| format!("{path} is {value}")),
+        None => (response.status != initial_status).then(|| format!("status changed to {}", response.status)),
+      }
+    });
+    let elapsed = poll.started_at.map_or(0, |started_at| started_at.elapsed().as_secs());
+    let mut actions = vec![];
+    if let Some(reason) = finished {
+      let status_line = format!("polling finished after {} iterations in {elapsed}s, {reason}", poll.iteration);
+      self.poll = None;
+      actions.push(Some(Action::TimedStatusLine(status_line, 5)));
+      for pane in self.panes.iter_mut() {
+        actions.push(pane.update(Action::PollProgress(None), state)?);
+      }
+      return Ok(actions);
+    }
+    if !in_flight && (poll.started_at.is_none() || poll.sent_at.elapsed() >= poll.interval) {
+      poll.iteration += 1;
+      poll.sent_at = Instant::now();
+      poll.started_at.get_or_insert(poll.sent_at);
+      actions.extend(self.dial(state, None, false)?);
+    }
+    if let Some(poll) = &self.poll {
+      let progress = if self.config.display.reduced_motion {
+        format!("[poll #{}]", poll.iteration)
+      } else {
+        format!("[poll #{} {} {elapsed}s]", poll.iteration, symbols::DOT)
+      };
+      for pane in self.panes.iter_mut() {
+        actions.push(pane.update(Action::PollProgress(Some(progress.clone())), state)?);
+      }
+    }
+    Ok(actions)
+  }
+
+  fn paginate(&mut self, direction: PageDirection, append: bool, state: &mut State) -> Result<Vec<Option<Action>>> {
+    let Some(response) =
+      self.operation_item.operation.operation_id.as_ref().and_then(|operation_id| state.responses.get(operation_id))
+    else {
+      return Ok(vec![Some(Action::TimedStatusLine("response is not available".into(), 3))]);
+    };
+    if let Some(url) = pagination::link_url(&response.headers, direction) {
+      return self.dial(state, Some(url), append);
+    }
+
+    let mut actions = vec![];
+    let mut adjusted = false;
+    for pane in self.panes.iter_mut() {
+      match pane.update(Action::Paginate(direction, append), state)? {
+        Some(Action::Dial) => adjusted = true,
+        action => actions.push(action),
+      }
+    }
+    if adjusted {
+      actions.extend(self.dial(state, None, append)?);
+    }
+    Ok(actions)
+  }
+
   fn handle_commands(&self, command_args: String) -> Option<Action> {
     if command_args.eq("q") {
       return Some(Action::Quit);
@@ -95,6 +488,74 @@ impl Phone {
     if command_args.eq("send") || command_args.eq("s") {
       return Some(Action::Dial);
     }
+    if command_args.eq("dry-run") {
+      return Some(Action::ToggleDryRun);
+    }
+    if command_args.eq("idempotency") {
+      return Some(Action::ToggleIdempotencyKey);
+    }
+    if command_args.eq("changelog") {
+      return Some(Action::ShowChangelog);
+    }
+    if command_args.eq("notes") {
+      return Some(Action::ShowNotes);
+    }
+    if command_args.eq("auth") {
+      return Some(Action::ShowAuth(self.operation_item.operation.operation_id.clone().unwrap_or_default()));
+    }
+    if let Some(annotation) =
+      command_args.strip_prefix("annotate").filter(|rest| rest.is_empty() || rest.starts_with(' ')).map(str::trim)
+    {
+      return Some(Action::Annotate(
+        self.operation_item.operation.operation_id.clone().unwrap_or_default(),
+        Some(annotation).filter(|annotation| !annotation.is_empty() && *annotation != "clear").map(String::from),
+      ));
+    }
+    if command_args.starts_with("poll ") {
+      let command_parts = command_args.split(' ').filter(|item| !item.is_empty()).collect::<Vec<_>>();
+      if command_parts.len() == 2 && command_parts[1].eq("stop") {
+        return Some(Action::StopPoll);
+      }
+      let interval = command_parts.get(1).and_then(|interval| parse_duration(interval));
+      let until = command_parts
+        .get(3)
+        .and_then(|condition| condition.split_once('='))
+        .map(|(path, value)| (path.to_string(), value.to_string()));
+      match (interval, command_parts.len()) {
+        (Some(interval), 2) => return Some(Action::Poll(interval.as_millis() as u64, None)),
+        (Some(interval), 4) if command_parts[2].eq("until") && until.is_some() => {
+          return Some(Action::Poll(interval.as_millis() as u64, until))
+        },
+        _ => {},
+      }
+      return Some(Action::TimedStatusLine(
+        "invalid poll args. poll <interval> [until <jsonpath>=<value>] / poll stop".into(),
+        3,
+      ));
+    }
+    if let Some(assertion) = command_args.strip_prefix("assert ") {
+      if assertion.trim() == "clear" {
+        return Some(Action::ClearAssertions);
+      }
+      return match assertion.parse::<Assertion>() {
+        Ok(assertion) => Some(Action::AddAssertion(assertion.to_string())),
+        Err(error) => Some(Action::TimedStatusLine(format!("invalid assert args: {error}"), 5)),
+      };
+    }
+    if command_args.starts_with("sample ") {
+      let command_parts = command_args.split(' ').filter(|item| !item.is_empty()).collect::<Vec<_>>();
+      if command_parts.len() == 2 && matches!(self.operation_item.r#type, OperationItemType::Webhook) {
+        return Some(Action::SendSample(command_parts[1].into()));
+      }
+      return Some(Action::TimedStatusLine("invalid sample args. sample <consumer-url>, on webhooks".into(), 3));
+    }
+    if command_args.starts_with("capture ") {
+      let command_parts = command_args.split(' ').filter(|item| !item.is_empty()).collect::<Vec<_>>();
+      if command_parts.len() == 3 {
+        return Some(Action::Capture(command_parts[1].into(), command_parts[2].into()));
+      }
+      return Some(Action::TimedStatusLine("invalid capture args. capture <variable-name> <jsonpath>".into(), 3));
+    }
     if command_args.starts_with("query ") || command_args.starts_with("q ") {
       let command_parts = command_args.split(' ').filter(|item| !item.is_empty()).collect::<Vec<_>>();
       if command_parts.len() == 3 {
@@ -124,17 +585,108 @@ impl Phone {
       if command_parts.len() == 3 && command_parts[1].eq("open") {
         return Some(Action::OpenRequestPayload(command_parts[2].into()));
       }
-      return Some(Action::TimedStatusLine("invalid request args. request open <payload-file-name>".into(), 3));
+      if command_parts.len() == 2 && command_parts[1].eq("open") {
+        return Some(Action::PickFile(String::from("request open")));
+      }
+      if command_parts.len() == 2 && command_parts[1].eq("example") {
+        return Some(Action::SaveRequestExample);
+      }
+      return Some(Action::TimedStatusLine(
+        "invalid request args. request open <payload-file-name> / request example".into(),
+        3,
+      ));
+    }
+    if command_args.eq("env") || command_args.starts_with("env ") {
+      return Some(env_command(command_args.as_str()));
+    }
+    if command_args.eq("profile") || command_args.starts_with("profile ") {
+      return Some(profile_command(command_args.as_str()));
+    }
+    if command_args.starts_with("export ") {
+      let command_parts = command_args.split(' ').filter(|item| !item.is_empty()).collect::<Vec<_>>();
+      if command_parts.len() == 3 && command_parts[1].eq("http") {
+        return Some(Action::ExportHttp(command_parts[2].into()));
+      }
+      if command_parts.len() == 2 && command_parts[1].eq("http") {
+        return Some(Action::PickFile(String::from("export http")));
+      }
+      return Some(Action::TimedStatusLine("invalid export args. export http <file-name>".into(), 3));
+    }
+    if command_args.starts_with("secret ") {
+      return Some(secret_command(command_args.as_str()));
+    }
+    if command_args.eq("pipe") {
+      let pipes = self.config.response.pipes.keys().cloned().collect::<Vec<_>>();
+      let status_line = match pipes.is_empty() {
+        true => String::from("no pipes are configured, add them to response.pipes in the config"),
+        false => format!("configured pipes: {}", pipes.join(", ")),
+      };
+      return Some(Action::TimedStatusLine(status_line, 5));
+    }
+    if let Some(name) = command_args.strip_prefix("pipe ").map(str::trim) {
+      return Some(Action::PipeResponse(Some(name).filter(|name| !name.eq(&"off")).map(String::from)));
     }
     if command_args.starts_with("response ") || command_args.starts_with("s ") {
       let command_parts = command_args.split(' ').filter(|item| !item.is_empty()).collect::<Vec<_>>();
+      if command_parts.len() >= 3 && command_parts[1].eq("save") && command_parts[2].eq("--selection") {
+        return Some(match command_parts.get(3) {
+          Some(filepath) => Action::SaveResponseSelection(filepath.to_string()),
+          None => Action::PickFile(String::from("response save --selection")),
+        });
+      }
+      if command_parts.len() >= 3 && command_parts[1].eq("save") && command_parts[2].eq("--decoded") {
+        return Some(match command_parts.get(3) {
+          Some(filepath) => Action::SaveResponsePayload(filepath.to_string(), true),
+          None => Action::PickFile(String::from("response save --decoded")),
+        });
+      }
       if command_parts.len() == 3 && command_parts[1].eq("save") {
-        return Some(Action::SaveResponsePayload(command_parts[2].into()));
+        return Some(Action::SaveResponsePayload(command_parts[2].into(), false));
+      }
+      if command_parts.len() == 3 && command_parts[1].eq("schema") {
+        return Some(Action::ExportProposedSchema(command_parts[2].into()));
+      }
+      if command_parts.len() == 2 && ["save", "diff", "schema"].contains(&command_parts[1]) {
+        return Some(Action::PickFile(format!("response {}", command_parts[1])));
+      }
+      if command_parts.len() == 3 && command_parts[1].eq("diff") {
+        return Some(Action::DiffResponsePayload(command_parts[2].into()));
+      }
+      if command_parts.len() == 2 && command_parts[1].eq("split") {
+        return Some(Action::SplitResponsePayload);
+      }
+      if command_parts.len() == 2 && command_parts[1].eq("timestamps") {
+        return Some(Action::ToggleTimestamps);
       }
-      return Some(Action::TimedStatusLine("invalid response args. response save <payload-file-name>".into(), 3));
+      if command_parts.len() == 2 && command_parts[1].eq("humanize") {
+        return Some(Action::ToggleHumanize);
+      }
+      if command_parts.len() == 2 && command_parts[1].eq("raw") {
+        return Some(Action::ToggleRaw);
+      }
+      if command_parts.len() == 2 && command_parts[1].eq("pin") {
+        return Some(Action::PinResponse);
+      }
+      if command_parts.len() == 2 && command_parts[1].eq("unpin") {
+        return Some(Action::UnpinResponse);
+      }
+      if command_parts.len() == 3 && command_parts[1].eq("as") {
+        if command_parts[2].eq("auto") {
+          return Some(Action::InterpretResponseAs(None));
+        }
+        if let Ok(format) = command_parts[2].parse::<BodyFormat>() {
+          return Some(Action::InterpretResponseAs(Some(format)));
+        }
+      }
+      return Some(Action::TimedStatusLine(
+        "invalid response args. response save/diff <payload-file-name> / response split / response schema <file-name> / response timestamps|humanize|raw / response pin|unpin / response as json|yaml|xml|text|hex|auto"
+          .into(),
+        3,
+      ));
     }
     Some(Action::TimedStatusLine(
-      "unknown command. available commands are: send, query, header, request, response".into(),
+      "unknown command. available commands are: send, dry-run, idempotency, poll, query, header, request, response, pipe, secret, export, env, auth"
+        .into(),
       3,
     ))
   }
@@ -159,12 +711,46 @@ impl Page for Phone {
     Ok(())
   }
 
+  fn operation_id(&self) -> Option<String> {
+    self.operation_item.operation.operation_id.clone()
+  }
+
+  fn request(&self) -> Option<Result<reqwest::Request>> {
+    Some(self.build_request())
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.panes.iter().any(|pane| pane.is_dirty())
+  }
+
+  fn has_parameter(&self, name: &str) -> bool {
+    self.panes.iter().any(|pane| pane.has_parameter(name))
+  }
+
+  fn draft(&self, state: &State) -> Option<Draft> {
+    let mut draft = Draft::default();
+    self.panes.iter().for_each(|pane| pane.draft(&mut draft));
+    let history = &self.config.history;
+    draft.response = state
+      .responses
+      .get(self.operation_item.operation.operation_id.as_ref()?)
+      .filter(|_| history.keep_responses)
+      .and_then(|response| StoredResponse::from_response(response, history.max_response_bytes));
+    Some(draft)
+  }
+
   fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+    for pane in self.panes.iter_mut() {
+      pane.register_action_handler(tx.clone())?;
+    }
     self.command_tx = Some(tx);
     Ok(())
   }
 
   fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    for pane in self.panes.iter_mut() {
+      pane.register_config_handler(config.clone())?;
+    }
     self.config = config;
     Ok(())
   }
@@ -172,23 +758,41 @@ impl Page for Phone {
   fn handle_key_events(&mut self, key: KeyEvent, state: &mut State) -> Result<Option<EventResponse<Action>>> {
     match state.input_mode {
       InputMode::Normal => {
+        // Keys of the page come first, so a pane can't take away hanging up, moving between panes and tabs, sending
+        // or the command line.
+        let global = match key.code {
+          KeyCode::Esc => Some(Action::HangUp(self.operation_item.operation.operation_id.clone())),
+          KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('L') => Some(Action::FocusNext),
+          KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('H') => Some(Action::FocusPrev),
+          KeyCode::Char(']') => Some(Action::TabNext),
+          KeyCode::Char('[') => Some(Action::TabPrev),
+          KeyCode::Enter => Some(Action::Submit),
+          KeyCode::Char(':') => Some(Action::FocusFooter(":".into(), None)),
+          _ => None,
+        };
+        if let Some(action) = global {
+          return Ok(Some(EventResponse::Stop(action)));
+        }
+        if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
+          if let Some(response) = pane.handle_key_events(key, state)? {
+            return Ok(Some(response));
+          }
+        }
         let response = match key.code {
-          KeyCode::Esc => EventResponse::Stop(Action::HangUp(self.operation_item.operation.operation_id.clone())),
-          KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('L') => EventResponse::Stop(Action::FocusNext),
-          KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('H') => EventResponse::Stop(Action::FocusPrev),
           KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => EventResponse::Stop(Action::Down),
           KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => EventResponse::Stop(Action::Up),
           KeyCode::Char('f') | KeyCode::Char('F') => EventResponse::Stop(Action::ToggleFullScreen),
+          KeyCode::Char('z') => EventResponse::Stop(Action::CollapsePane),
+          KeyCode::Char('Z') => EventResponse::Stop(Action::ExpandPane),
+          KeyCode::Backspace | KeyCode::Char('b') if self.focus_before_response.is_some() => {
+            EventResponse::Stop(Action::Back)
+          },
+          KeyCode::Char('e') | KeyCode::Char('E') => EventResponse::Stop(Action::CycleEncoding),
+          KeyCode::Char('o') | KeyCode::Char('O') => EventResponse::Stop(Action::OpenLink),
           KeyCode::Char(c) if ('1'..='9').contains(&c) => {
             EventResponse::Stop(Action::Tab(c.to_digit(10).unwrap_or(0) - 1))
           },
-          KeyCode::Char(']') => EventResponse::Stop(Action::TabNext),
-          KeyCode::Char('[') => EventResponse::Stop(Action::TabPrev),
-          KeyCode::Enter => EventResponse::Stop(Action::Submit),
-          KeyCode::Char(':') => EventResponse::Stop(Action::FocusFooter(":".into(), None)),
-          _ => {
-            return Ok(None);
-          },
+          _ => return Ok(None),
         };
         Ok(Some(response))
       },
@@ -203,29 +807,29 @@ impl Page for Phone {
     }
   }
 
+  fn handle_paste_events(&mut self, text: String, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    if state.input_mode != InputMode::Insert {
+      return Ok(None);
+    }
+    match self.panes.get_mut(self.focused_pane_index) {
+      Some(pane) => pane.handle_paste_events(text, state),
+      None => Ok(None),
+    }
+  }
+
   fn update(&mut self, action: Action, state: &mut State) -> Result<Option<Action>> {
     let mut actions: Vec<Option<Action>> = vec![];
 
     match action {
       Action::FocusNext => {
+        self.focus_before_response = None;
         let next_index = self.focused_pane_index.saturating_add(1) % self.panes.len();
-        if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
-          actions.push(pane.update(Action::UnFocus, state)?);
-        }
-        self.focused_pane_index = next_index;
-        if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
-          actions.push(pane.update(Action::Focus, state)?);
-        }
+        actions.extend(self.focus_pane(next_index, state)?);
       },
       Action::FocusPrev => {
+        self.focus_before_response = None;
         let prev_index = self.focused_pane_index.saturating_add(self.panes.len() - 1) % self.panes.len();
-        if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
-          actions.push(pane.update(Action::UnFocus, state)?);
-        }
-        self.focused_pane_index = prev_index;
-        if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
-          actions.push(pane.update(Action::Focus, state)?);
-        }
+        actions.extend(self.focus_pane(prev_index, state)?);
       },
       Action::Up => {
         if let Some(front) = self.base_urls.pop_front() {
@@ -240,18 +844,143 @@ impl Page for Phone {
       Action::ToggleFullScreen => {
         self.fullscreen_pane_index = self.fullscreen_pane_index.map_or(Some(self.focused_pane_index), |_| None);
       },
+      Action::FocusResponse(operation_id)
+        if state.input_mode == InputMode::Normal
+          && self.operation_item.operation.operation_id.as_ref() == Some(&operation_id) =>
+      {
+        let expanded = self.pane_sizes[Self::RESPONSE_PANE_INDEX] != PaneSize::Expanded;
+        if expanded {
+          self.pane_sizes.iter_mut().filter(|size| **size == PaneSize::Expanded).for_each(|size| {
+            *size = PaneSize::Normal;
+          });
+          self.pane_sizes[Self::RESPONSE_PANE_INDEX] = PaneSize::Expanded;
+        }
+        // later responses, e.g. of a poll, keep the pane focused before the first one
+        let (index, was_expanded) = self.focus_before_response.unwrap_or((self.focused_pane_index, false));
+        self.focus_before_response = Some((index, was_expanded || expanded));
+        if self.focused_pane_index != Self::RESPONSE_PANE_INDEX {
+          actions.extend(self.focus_pane(Self::RESPONSE_PANE_INDEX, state)?);
+        }
+      },
+      Action::Back => {
+        if let Some((index, expanded)) = self.focus_before_response.take() {
+          if expanded && self.pane_sizes[Self::RESPONSE_PANE_INDEX] == PaneSize::Expanded {
+            self.pane_sizes[Self::RESPONSE_PANE_INDEX] = PaneSize::Normal;
+          }
+          actions.extend(self.focus_pane(index, state)?);
+        }
+      },
+      Action::CollapsePane | Action::ExpandPane => {
+        let size = if matches!(action, Action::CollapsePane) { PaneSize::Collapsed } else { PaneSize::Expanded };
+        if size == PaneSize::Expanded {
+          self.pane_sizes.iter_mut().filter(|size| **size == PaneSize::Expanded).for_each(|size| {
+            *size = PaneSize::Normal;
+          });
+        }
+        let pane_size = &mut self.pane_sizes[self.focused_pane_index];
+        *pane_size = if *pane_size == size { PaneSize::Normal } else { size };
+      },
       Action::Update => {
         for pane in self.panes.iter_mut() {
           actions.push(pane.update(action.clone(), state)?);
         }
       },
-      Action::Dial => {
-        if let Some(request_tx) = &self.request_tx {
-          request_tx.send(Request {
-            request: self.build_request()?,
-            operation_id: self.operation_item.operation.operation_id.clone().unwrap_or_default(),
-          })?;
+      Action::SetBaseUrl(_) => {
+        self.base_urls = Phone::default_base_urls(&self.operation_item, state);
+      },
+      Action::SwitchProfile(_) => {
+        self.base_urls = Phone::default_base_urls(&self.operation_item, state);
+        for pane in self.panes.iter_mut() {
+          actions.push(pane.update(action.clone(), state)?);
+        }
+      },
+      Action::SendSample(consumer_url) => match self.sample_request(consumer_url.as_str(), state) {
+        Ok(request) => {
+          let operation_id = self.operation_item.operation.operation_id.clone().unwrap_or_default();
+          actions.extend(self.dispatch(request, operation_id, false, state)?);
+        },
+        Err(error) => actions.push(Some(Action::TimedStatusLine(format!("can't send sample: {error}"), 5))),
+      },
+      Action::Dial if matches!(self.operation_item.r#type, OperationItemType::Webhook) => {
+        actions.push(self.operation_item.operation.operation_id.clone().map(Action::ListenWebhook));
+      },
+      Action::Dial => match self.dial_confirmation() {
+        Some(message) => actions.push(Some(Action::Confirm(message, Box::new(Action::ConfirmedDial)))),
+        None => actions.extend(self.dial(state, None, false)?),
+      },
+      Action::ConfirmedDial => {
+        actions.extend(self.dial(state, None, false)?);
+      },
+      Action::Paginate(direction, append) => {
+        actions.extend(self.paginate(direction, append, state)?);
+      },
+      Action::Poll(interval, until) => {
+        let method = reqwest::Method::from_bytes(self.operation_item.method.to_uppercase().as_bytes());
+        if !method.is_ok_and(|method| method.is_idempotent()) {
+          let status_line =
+            format!("{} isn't idempotent, polling would repeat its side effects", self.operation_item.method);
+          actions.push(Some(Action::TimedStatusLine(status_line, 5)));
+        } else if let Some(message) = self.dial_confirmation() {
+          let message = format!("Polling sends it every {:?}. {message}", Duration::from_millis(interval));
+          actions.push(Some(Action::Confirm(message, Box::new(Action::ConfirmedPoll(interval, until)))));
+        } else {
+          actions.extend(self.start_poll(interval, until, state)?);
+        }
+      },
+      Action::ConfirmedPoll(interval, until) => {
+        actions.extend(self.start_poll(interval, until, state)?);
+      },
+      Action::StopPoll => {
+        if let Some(poll) = self.poll.take() {
+          actions
+            .push(Some(Action::TimedStatusLine(format!("polling stopped after {} iterations", poll.iteration), 3)));
         }
+        for pane in self.panes.iter_mut() {
+          actions.push(pane.update(Action::PollProgress(None), state)?);
+        }
+      },
+      Action::Tick if self.poll.is_some() => {
+        actions.extend(self.tick_poll(state)?);
+      },
+      Action::FollowLocation => {
+        actions.extend(self.follow_location(state)?);
+      },
+      Action::OpenLink => {
+        actions.push(Some(self.open_link(state)));
+      },
+      Action::SetPathParameters(..)
+      | Action::SetParameter(..)
+      | Action::ImportParameter(..)
+      | Action::ImportBody(..)
+      | Action::WriteRequestExample(..)
+      | Action::PipedBody(..) => {
+        for pane in self.panes.iter_mut() {
+          actions.push(pane.update(action.clone(), state)?);
+        }
+      },
+      Action::ExportHttp(filepath) => {
+        let operation_id = self.operation_item.operation.operation_id.clone().unwrap_or_default();
+        let status_line = match self.build_request() {
+          Ok(request) => match std::fs::write(&filepath, http_file::to_http_file(operation_id.as_str(), &request)) {
+            Ok(_) => format!("request written to {filepath}"),
+            Err(error) => format!("can't write {filepath}: {error}"),
+          },
+          Err(error) => format!("can't build request: {error}"),
+        };
+        actions.push(Some(Action::TimedStatusLine(status_line, 3)));
+      },
+      Action::ToggleIdempotencyKey => {
+        let operation_id = self.operation_item.operation.operation_id.clone().unwrap_or_default();
+        let status_line = match state.toggle_idempotency_key(operation_id.as_str()) {
+          true => format!("a fresh Idempotency-Key is sent with each call of {operation_id}"),
+          false => format!("no Idempotency-Key is added to calls of {operation_id}"),
+        };
+        actions.push(Some(Action::TimedStatusLine(status_line, 3)));
+      },
+      Action::ToggleDryRun => {
+        self.dry_run = !self.dry_run;
+        let status_line = if self.dry_run { "dry-run mode enabled" } else { "dry-run mode disabled" };
+        actions.push(Some(Action::TimedStatusLine(status_line.into(), 3)));
       },
       Action::FocusFooter(..) => {
         if let Some(pane) = self.panes.get_mut(self.focused_pane_index) {
@@ -266,7 +995,27 @@ impl Page for Phone {
           for pane in self.panes.iter_mut() {
             actions.push(pane.update(action.clone(), state)?);
           }
-          if let Action::TimedStatusLine(_, _) = action {
+          if let Action::Dial | Action::ToggleDryRun = action {
+            actions.push(self.update(action.clone(), state)?);
+          }
+          if let Action::TimedStatusLine(..)
+          | Action::PromptSecret(..)
+          | Action::DeleteSecret(..)
+          | Action::SendSample(..)
+          | Action::ExportHttp(..)
+          | Action::ShowVariables
+          | Action::LoadEnvFile(..)
+          | Action::PickFile(..)
+          | Action::ToggleIdempotencyKey
+          | Action::ShowChangelog
+          | Action::ShowNotes
+          | Action::ShowProfiles
+          | Action::SwitchProfile(..)
+          | Action::ShowAuth(..)
+          | Action::Annotate(..)
+          | Action::Poll(..)
+          | Action::StopPoll = action
+          {
             actions.push(Some(action))
           }
         }
@@ -293,34 +1042,140 @@ impl Page for Phone {
   fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
     let base_url = self.base_urls.front().context("no base url found")?;
 
-    let outer_layout =
-      Layout::vertical(vec![Constraint::Max(3), self.panes[1].height_constraint(), self.panes[2].height_constraint()])
-        .split(area);
-    frame.render_widget(
-      Paragraph::new(Line::from(vec![
+    let heights =
+      [self.panes[0].height_constraint(), self.panes[1].height_constraint(), self.panes[2].height_constraint()];
+    let ([row_height, response_height], widths) = layout_constraints(self.pane_sizes, heights);
+    let outer_layout = Layout::vertical(vec![Constraint::Max(3), row_height, response_height]).split(area);
+    let mut address_line = match self.operation_item.r#type {
+      OperationItemType::Path => Line::from(vec![
         Span::styled(
           format!(" {} ", self.operation_item.method.as_str()),
-          Style::default().fg(Self::method_color(self.operation_item.method.as_str())),
+          self.config.palette.method_style(self.operation_item.method.as_str()),
         ),
         Span::styled(base_url, Style::default().fg(Color::DarkGray)),
         Span::styled(&self.operation_item.path, Style::default().fg(Color::White)),
-      ]))
-      .block(
-        Block::new().title(self.operation_item.operation.summary.clone().unwrap_or_default()).borders(Borders::ALL),
+      ]),
+      OperationItemType::Webhook => Line::from(vec![
+        Span::styled(" EVENT ", Style::default().fg(Color::LightMagenta)),
+        Span::styled(&self.operation_item.path, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" {}", self.operation_item.method.as_str()), Style::default().dim()),
+      ]),
+    };
+    let listening_address = state
+      .webhook_listener
+      .as_ref()
+      .filter(|(operation_id, _)| self.operation_item.operation.operation_id.as_ref() == Some(operation_id))
+      .map(|(_, address)| address.clone());
+    if self.operation_item.synthetic_id {
+      address_line.push_span(Span::styled(
+        format!(" #{}", self.operation_item.operation.operation_id.clone().unwrap_or_default()),
+        Style::default().dim().add_modifier(Modifier::ITALIC),
+      ));
+    }
+    frame.render_widget(
+      Paragraph::new(address_line).block(
+        Block::new()
+          .title(self.operation_item.operation.summary.clone().unwrap_or_default())
+          .title(if self.dry_run {
+            Line::styled("[dry-run]", Style::default().fg(Color::LightYellow)).right_aligned()
+          } else {
+            Line::default()
+          })
+          .title(self.auth_line(state))
+          .title(match listening_address {
+            Some(address) => {
+              Line::styled(format!("[listening on http://{address}]"), Style::default().fg(Color::LightMagenta))
+                .right_aligned()
+            },
+            None => Line::default(),
+          })
+          .title_bottom(server_line(
+            &self.base_urls,
+            state.server_label(base_url, &self.operation_item.operation.servers),
+          ))
+          .borders(Borders::ALL),
       ),
       outer_layout[0],
     );
 
-    if let Some(fullscreen_pane_index) = self.fullscreen_pane_index {
+    let screen_reader_pane_index = self.config.display.screen_reader.then_some(self.focused_pane_index);
+    if let Some(fullscreen_pane_index) = self.fullscreen_pane_index.or(screen_reader_pane_index) {
       let area = outer_layout[1].union(outer_layout[2]);
       self.panes[fullscreen_pane_index].draw(frame, area, state)?;
     } else {
-      let input_layout = Layout::horizontal(vec![Constraint::Fill(1), Constraint::Fill(1)]).split(outer_layout[1]);
-
-      self.panes[0].draw(frame, input_layout[0], state)?;
-      self.panes[1].draw(frame, input_layout[1], state)?;
-      self.panes[2].draw(frame, outer_layout[2], state)?;
+      let input_layout = Layout::horizontal(widths).split(outer_layout[1]);
+      let areas = [input_layout[0], input_layout[1], outer_layout[2]];
+      for (index, area) in areas.into_iter().enumerate() {
+        if self.pane_sizes[index] == PaneSize::Collapsed {
+          self.draw_collapsed(frame, area, index);
+        } else {
+          self.panes[index].draw(frame, area, state)?;
+        }
+      }
     }
     Ok(())
   }
 }
+
+impl Phone {
+  /// Security the operation requires, green once credentials are filled in for it and yellow while they're missing.
+  fn auth_line(&self, state: &State) -> Line<'static> {
+    let Some(requirements) = auth::requirements(&self.operation_item.operation, &state.openapi_spec)
+      .filter(|requirements| !requirements.is_empty())
+    else {
+      return Line::default();
+    };
+    let covered = state.security_schemes.covered(&requirements, &state.credentials);
+    match (covered.is_empty(), requirements.iter().any(Vec::is_empty)) {
+      (false, _) => Line::styled("[auth ✓]", Style::default().fg(Color::LightGreen)).right_aligned(),
+      (true, true) => Line::default(),
+      (true, false) => Line::styled(
+        format!("[no credentials for {}]", auth::describe(&requirements)),
+        Style::default().fg(Color::LightYellow),
+      )
+      .right_aligned(),
+    }
+  }
+
+  /// A collapsed pane, only its title in a border.
+  fn draw_collapsed(&self, frame: &mut Frame<'_>, area: Rect, index: usize) {
+    let focused = index == self.focused_pane_index;
+    let mut block = Block::default()
+      .borders(Borders::ALL)
+      .title(format!("▸ {}", Self::PANE_NAMES[index]))
+      .border_type(if focused { BorderType::Thick } else { BorderType::Plain });
+    if focused {
+      block = block
+        .border_style(Style::default().fg(Color::LightGreen))
+        .title_bottom(Line::styled("[z → expand]", Style::default().dim()).right_aligned());
+    }
+    frame.render_widget(block, area);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_layout_constraints() {
+    let heights = [Constraint::Fill(1), Constraint::Fill(3), Constraint::Fill(1)];
+    let normal = [PaneSize::Normal; 3];
+    assert_eq!(
+      layout_constraints(normal, heights),
+      ([Constraint::Fill(3), Constraint::Fill(1)], [Constraint::Fill(1), Constraint::Fill(1)])
+    );
+    let no_body = [PaneSize::Normal, PaneSize::Collapsed, PaneSize::Expanded];
+    assert_eq!(
+      layout_constraints(no_body, heights),
+      ([Constraint::Fill(1), Constraint::Fill(EXPANDED_FILL)], [Constraint::Fill(1), Constraint::Length(8)])
+    );
+    let response_only = [PaneSize::Collapsed, PaneSize::Collapsed, PaneSize::Normal];
+    assert_eq!(
+      layout_constraints(response_only, heights),
+      ([Constraint::Length(3), Constraint::Fill(1)], [Constraint::Fill(1), Constraint::Fill(1)])
+    );
+  }
+}
@@ -0,0 +1,196 @@
+use reqwest::header::{HeaderMap, LINK};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PageDirection {
+  Next,
+  Prev,
+}
+
+impl PageDirection {
+  fn rels(&self) -> &'static [&'static str] {
+    match self {
+      PageDirection::Next => &["next"],
+      PageDirection::Prev => &["prev", "previous"],
+    }
+  }
+
+  fn cursor_keys(&self) -> &'static [&'static str] {
+    match self {
+      PageDirection::Next => {
+        &["next_cursor", "nextCursor", "next_page_token", "nextPageToken", "endCursor", "next_token", "nextToken"]
+      },
+      PageDirection::Prev => &[
+        "prev_cursor",
+        "prevCursor",
+        "previous_cursor",
+        "previousCursor",
+        "prev_page_token",
+        "prevPageToken",
+        "startCursor",
+      ],
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageParameter {
+  Page,
+  Offset,
+  Cursor,
+  Limit,
+}
+
+impl PageParameter {
+  pub fn from_name(name: &str) -> Option<Self> {
+    let name = name.to_lowercase().replace(['_', '-'], "");
+    match name.as_str() {
+      "page" | "pagenumber" | "pageno" => Some(PageParameter::Page),
+      "offset" | "skip" | "start" => Some(PageParameter::Offset),
+      "cursor" | "after" | "pagetoken" | "startingafter" | "continuationtoken" | "nexttoken" => {
+        Some(PageParameter::Cursor)
+      },
+      "limit" | "perpage" | "pagesize" | "size" | "count" | "take" | "maxresults" => Some(PageParameter::Limit),
+      _ => None,
+    }
+  }
+
+  /// Computes the value of this parameter for the adjacent page, `limit` is the page size used by offsets and `body`
+  /// is the last response body where cursors are looked up.
+  pub fn step(
+    &self,
+    value: Option<&str>,
+    direction: PageDirection,
+    limit: Option<usize>,
+    body: &str,
+  ) -> Option<String> {
+    match self {
+      PageParameter::Page => {
+        let page = value.and_then(|value| value.parse::<usize>().ok()).unwrap_or(1);
+        match direction {
+          PageDirection::Next => Some(page + 1),
+          PageDirection::Prev if page > 1 => Some(page - 1),
+          PageDirection::Prev => None,
+        }
+        .map(|page| page.to_string())
+      },
+      PageParameter::Offset => {
+        let offset = value.and_then(|value| value.parse::<usize>().ok()).unwrap_or(0);
+        let limit = limit?;
+        match direction {
+          PageDirection::Next => Some(offset + limit),
+          PageDirection::Prev if offset > 0 => Some(offset.saturating_sub(limit)),
+          PageDirection::Prev => None,
+        }
+        .map(|offset| offset.to_string())
+      },
+      PageParameter::Cursor => cursor(body, direction),
+      PageParameter::Limit => None,
+    }
+  }
+}
+
+/// Returns the target of the `Link` header entry with the relation of the given direction, see RFC 8288.
+pub fn link_url(headers: &HeaderMap, direction: PageDirection) -> Option<String> {
+  headers.get_all(LINK).iter().filter_map(|value| value.to_str().ok()).flat_map(|value| value.split(',')).find_map(
+    |link| {
+      let mut parts = link.split(';').map(str::trim);
+      let target = parts.next()?.strip_prefix('<')?.strip_suffix('>')?;
+      parts
+        .filter_map(|param| param.split_once('='))
+        .filter(|(name, _)| name.trim().eq_ignore_ascii_case("rel"))
+        .flat_map(|(_, rels)| rels.trim().trim_matches('"').split_whitespace())
+        .any(|rel| direction.rels().iter().any(|expected| rel.eq_ignore_ascii_case(expected)))
+        .then(|| target.to_string())
+    },
+  )
+}
+
+/// Looks up the cursor of the adjacent page in a JSON body, at top level or inside a nested object like `meta`.
+pub fn cursor(body: &str, direction: PageDirection) -> Option<String> {
+  let body = serde_json::from_str::<Value>(body).ok()?;
+  let object = body.as_object()?;
+  std::iter::once(object)
+    .chain(object.values().filter_map(Value::as_object))
+    .find_map(|object| direction.cursor_keys().iter().find_map(|key| object.get(*key)))
+    .and_then(|value| match value {
+      Value::String(value) if !value.is_empty() => Some(value.clone()),
+      Value::Number(value) => Some(value.to_string()),
+      _ => None,
+    })
+}
+
+fn items_mut(value: &mut Value) -> Option<&mut Vec<Value>> {
+  match value {
+    Value::Array(items) => Some(items),
+    Value::Object(object) => object.values_mut().find_map(|value| value.as_array_mut()),
+    _ => None,
+  }
+}
+
+/// Number of items in a JSON page, either a top level array or the first array field of an object.
+pub fn item_count(body: &str) -> Option<usize> {
+  serde_json::from_str::<Value>(body).ok().as_mut().and_then(items_mut).map(|items| items.len())
+}
+
+/// Merges the items of the `next` page into the `previous` one. Fields other than the items are taken from the
+/// `next` page so cursors keep pointing forward.
+pub fn append_page(previous: &str, next: &str) -> String {
+  if let (Ok(mut previous), Ok(mut next)) =
+    (serde_json::from_str::<Value>(previous), serde_json::from_str::<Value>(next))
+  {
+    if let (Some(previous_items), Some(next_items)) = (items_mut(&mut previous), items_mut(&mut next)) {
+      previous_items.append(next_items);
+      std::mem::swap(previous_items, next_items);
+      if let Ok(body) = serde_json::to_string_pretty(&next) {
+        return body;
+      }
+    }
+  }
+  format!("{previous}\n{next}")
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use reqwest::header::HeaderValue;
+
+  use super::*;
+
+  #[test]
+  fn test_link_url() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      LINK,
+      HeaderValue::from_static(
+        "<https://api.example.com/items?page=3>; rel=\"next\", <https://api.example.com/items?page=1>; rel=\"prev first\"",
+      ),
+    );
+    assert_eq!(link_url(&headers, PageDirection::Next), Some("https://api.example.com/items?page=3".into()));
+    assert_eq!(link_url(&headers, PageDirection::Prev), Some("https://api.example.com/items?page=1".into()));
+    assert_eq!(link_url(&HeaderMap::new(), PageDirection::Next), None);
+  }
+
+  #[test]
+  fn test_page_parameter_step() {
+    let body = r#"{"data": [1, 2], "meta": {"next_cursor": "abc"}}"#;
+    assert_eq!(PageParameter::from_name("page_size"), Some(PageParameter::Limit));
+    assert_eq!(PageParameter::Page.step(Some("2"), PageDirection::Next, None, body), Some("3".into()));
+    assert_eq!(PageParameter::Page.step(None, PageDirection::Prev, None, body), None);
+    assert_eq!(PageParameter::Offset.step(Some("20"), PageDirection::Prev, Some(10), body), Some("10".into()));
+    assert_eq!(PageParameter::Offset.step(Some("20"), PageDirection::Next, None, body), None);
+    assert_eq!(PageParameter::Cursor.step(None, PageDirection::Next, None, body), Some("abc".into()));
+    assert_eq!(item_count(body), Some(2));
+  }
+
+  #[test]
+  fn test_append_page() {
+    assert_eq!(append_page("[1]", "[2, 3]"), "[\n  1,\n  2,\n  3\n]");
+    assert_eq!(
+      append_page(r#"{"items": [1], "next": "a"}"#, r#"{"items": [2], "next": "b"}"#),
+      "{\n  \"items\": [\n    1,\n    2\n  ],\n  \"next\": \"b\"\n}"
+    );
+    assert_eq!(append_page("a", "b"), "a\nb");
+  }
+}
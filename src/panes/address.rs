@@ -8,9 +8,11 @@ use ratatui::{
 
 use crate::{
   action::Action,
+  config::{Config, Palette},
   panes::Pane,
   state::{OperationItemType, State},
   tui::Frame,
+  warm_up::ServerHealth,
 };
 
 #[derive(Default)]
@@ -18,11 +20,13 @@ pub struct AddressPane {
   focused: bool,
   focused_border_style: Style,
   base_urls: VecDeque<String>,
+  palette: Palette,
+  warm_up: bool,
 }
 
 impl AddressPane {
   pub fn new(focused: bool, focused_border_style: Style) -> Self {
-    Self { focused, focused_border_style, base_urls: VecDeque::new() }
+    Self { focused, focused_border_style, ..Self::default() }
   }
 
   fn border_style(&self) -> Style {
@@ -39,17 +43,42 @@ impl AddressPane {
     }
   }
 
-  fn method_color(method: &str) -> Color {
-    match method {
-      "GET" => Color::LightCyan,
-      "POST" => Color::LightBlue,
-      "PUT" => Color::LightYellow,
-      "DELETE" => Color::LightRed,
-      _ => Color::Gray,
+  fn warm_up_action(&self) -> Option<Action> {
+    self.base_urls.front().filter(|_| self.warm_up).map(|base_url| Action::WarmUp(base_url.clone()))
+  }
+
+  fn health_badge(&self, base_url: &str, state: &State) -> Span<'static> {
+    match state.server_health.get(base_url) {
+      Some(ServerHealth::Resolving) => Span::styled(" … ", Style::default().dim()),
+      Some(ServerHealth::Ready(elapsed)) => {
+        Span::styled(format!(" ● {}ms ", elapsed.as_millis()), Style::default().fg(Color::LightGreen))
+      },
+      Some(ServerHealth::Failed(_)) => Span::styled(" ⚠ unreachable ", Style::default().fg(Color::LightRed)),
+      None => Span::default(),
     }
   }
 }
+
+/// Environment and description of the server shown at the bottom of an address bar, with how many servers there
+/// are to cycle through.
+pub fn server_line(base_urls: &VecDeque<String>, label: Option<String>) -> Line<'static> {
+  let mut line = Line::default();
+  if let Some(label) = label {
+    line.push_span(Span::styled(format!(" {label} "), Style::default().fg(Color::Gray)));
+  }
+  if base_urls.len() > 1 {
+    line.push_span(Span::styled(format!("[↑↓ {} servers]", base_urls.len()), Style::default().dim()));
+  }
+  line
+}
+
 impl Pane for AddressPane {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.palette = config.palette;
+    self.warm_up = config.dial.warm_up;
+    Ok(())
+  }
+
   fn height_constraint(&self) -> Constraint {
     Constraint::Max(3)
   }
@@ -59,8 +88,12 @@ impl Pane for AddressPane {
     Ok(())
   }
 
-  fn update(&mut self, action: Action, _state: &mut State) -> Result<Option<Action>> {
+  fn update(&mut self, action: Action, state: &mut State) -> Result<Option<Action>> {
     match action {
+      Action::SwitchProfile(_) => {
+        self.base_urls = state.default_server_urls(&None).into();
+        return Ok(self.warm_up_action());
+      },
       Action::Focus => {
         self.focused = true;
         static STATUS_LINE: &str = "[ENTER → request]";
@@ -73,11 +106,19 @@ impl Pane for AddressPane {
         if let Some(front) = self.base_urls.pop_front() {
           self.base_urls.push_back(front.to_string());
         }
+        return Ok(self.warm_up_action());
       },
       Action::Down => {
         if let Some(back) = self.base_urls.pop_back() {
           self.base_urls.push_front(back.to_string());
         }
+        return Ok(self.warm_up_action());
+      },
+      Action::OpenLink => {
+        return Ok(self.base_urls.front().map(|base_url| match reqwest::Url::parse(base_url) {
+          Ok(_) => Action::OpenInBrowser(base_url.clone()),
+          Err(_) => Action::TimedStatusLine(format!("{base_url} isn't an absolute url"), 3),
+        }));
       },
       Action::Update => {},
       Action::Submit => {},
@@ -93,31 +134,39 @@ impl Pane for AddressPane {
       let title = operation_item.operation.summary.clone().unwrap_or_default();
 
       let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
-      frame.render_widget(
-        match operation_item.r#type {
-          OperationItemType::Path => Paragraph::new(Line::from(vec![
-            Span::styled(
-              format!("{:7}", operation_item.method.as_str()),
-              Style::default().fg(Self::method_color(operation_item.method.as_str())),
-            ),
-            Span::styled(base_url, Style::default().fg(Color::DarkGray)),
-            Span::styled(&operation_item.path, Style::default().fg(Color::White)),
-          ])),
-          OperationItemType::Webhook => Paragraph::new(Line::from(vec![
-            Span::styled("EVENT ", Style::default().fg(Color::LightMagenta)),
-            Span::styled(
-              format!("{} ", operation_item.method.as_str()),
-              Style::default().fg(Self::method_color(operation_item.method.as_str())),
-            ),
-            Span::styled(&operation_item.path, Style::default().fg(Color::White)),
-          ])),
-        },
-        inner,
-      );
+      let mut line = match operation_item.r#type {
+        OperationItemType::Path => Line::from(vec![
+          Span::styled(
+            format!("{:7}", operation_item.method.as_str()),
+            self.palette.method_style(operation_item.method.as_str()),
+          ),
+          Span::styled(base_url, Style::default().fg(Color::DarkGray)),
+          Span::styled(&operation_item.path, Style::default().fg(Color::White)),
+        ]),
+        OperationItemType::Webhook => Line::from(vec![
+          Span::styled("EVENT ", Style::default().fg(Color::LightMagenta)),
+          Span::styled(
+            format!("{} ", operation_item.method.as_str()),
+            self.palette.method_style(operation_item.method.as_str()),
+          ),
+          Span::styled(&operation_item.path, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+        ]),
+      };
+      if operation_item.synthetic_id {
+        line.push_span(Span::styled(
+          format!(" #{}", operation_item.operation.operation_id.clone().unwrap_or_default()),
+          Style::default().dim().add_modifier(Modifier::ITALIC),
+        ));
+      }
+      frame.render_widget(Paragraph::new(line), inner);
 
+      let badge = self.health_badge(self.base_urls.front().map(String::as_str).unwrap_or_default(), state);
+      let label = self.base_urls.front().and_then(|base_url| state.server_label(base_url, &None));
       frame.render_widget(
         Block::default()
           .title(title)
+          .title(Line::from(badge).right_aligned())
+          .title_bottom(server_line(&self.base_urls, label))
           .borders(Borders::ALL)
           .border_style(self.border_style())
           .border_type(self.border_type()),
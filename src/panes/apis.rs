@@ -6,6 +6,7 @@ use ratatui::{
 
 use crate::{
   action::Action,
+  config::{Config, Palette},
   panes::Pane,
   state::{OperationItemType, State},
   tui::Frame,
@@ -15,11 +16,12 @@ pub struct ApisPane {
   focused: bool,
   focused_border_style: Style,
   current_operation_index: usize,
+  palette: Palette,
 }
 
 impl ApisPane {
   pub fn new(focused: bool, focused_border_style: Style) -> Self {
-    Self { focused, focused_border_style, current_operation_index: 0 }
+    Self { focused, focused_border_style, current_operation_index: 0, palette: Palette::default() }
   }
 
   fn border_style(&self) -> Style {
@@ -35,19 +37,14 @@ impl ApisPane {
       false => BorderType::Plain,
     }
   }
-
-  fn method_color(method: &str) -> Color {
-    match method {
-      "GET" => Color::LightCyan,
-      "POST" => Color::LightBlue,
-      "PUT" => Color::LightYellow,
-      "DELETE" => Color::LightRed,
-      _ => Color::Gray,
-    }
-  }
 }
 
 impl Pane for ApisPane {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.palette = config.palette;
+    Ok(())
+  }
+
   fn height_constraint(&self) -> Constraint {
     match self.focused {
       true => Constraint::Fill(3),
@@ -76,12 +73,23 @@ impl Pane for ApisPane {
       },
       Action::Focus => {
         self.focused = true;
-        static STATUS_LINE: &str = "[j,k → movement] [ENTER → request]";
+        static STATUS_LINE: &str = "[j,k → movement] [ENTER → request] [SPACE → mark]";
         return Ok(Some(Action::TimedStatusLine(STATUS_LINE.into(), 3)));
       },
       Action::UnFocus => {
         self.focused = false;
       },
+      Action::ToggleMark => {
+        if let Some(operation_id) = state
+          .active_operation()
+          .filter(|operation_item| matches!(operation_item.r#type, OperationItemType::Path))
+          .and_then(|operation_item| operation_item.operation.operation_id.clone())
+        {
+          if !state.marked_operations.remove(&operation_id) {
+            state.marked_operations.insert(operation_id);
+          }
+        }
+      },
       Action::Submit => {},
       Action::Update => {
         self.current_operation_index = state.active_operation_index;
@@ -93,31 +101,37 @@ impl Pane for ApisPane {
   }
 
   fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
-    let items = state.openapi_operations.iter().filter_map(|operation_item| {
-      if let Some(active_tag) = &state.active_tag_name {
-        if !operation_item.has_tag(active_tag) {
-          return None;
-        }
-      }
-      if !operation_item.path.contains(state.active_filter.as_str()) {
-        return None;
-      }
-      Some(Line::from(vec![
+    let items = state.visible_operations().map(|operation_item| {
+      Line::from(vec![
+        Span::styled(
+          if state.is_marked(operation_item) { symbols::DOT } else { " " },
+          Style::default().fg(Color::LightGreen),
+        ),
         Span::styled(
           format!(
-            " {:7}",
+            "{:7}",
             match operation_item.r#type {
               OperationItemType::Path => operation_item.method.as_str(),
               OperationItemType::Webhook => "EVENT",
             }
           ),
           match operation_item.r#type {
-            OperationItemType::Path => Self::method_color(operation_item.method.as_str()),
-            OperationItemType::Webhook => Color::LightMagenta,
+            OperationItemType::Path => self.palette.method_style(operation_item.method.as_str()),
+            OperationItemType::Webhook => Style::default().fg(Color::LightMagenta),
           },
         ),
-        Span::styled(format!(" {:7}", operation_item.path), Color::White),
-      ]))
+        match operation_item.r#type {
+          OperationItemType::Path => Span::styled(format!(" {:7}", operation_item.path), Color::White),
+          OperationItemType::Webhook => Span::styled(
+            format!(" {:7}", operation_item.path),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+          ),
+        },
+        Span::styled(
+          state.annotation(operation_item).map(|annotation| format!("  {annotation}")).unwrap_or_default(),
+          Style::default().fg(Color::LightYellow).add_modifier(Modifier::ITALIC),
+        ),
+      ])
     });
 
     let list = List::new(items)
@@ -128,10 +142,14 @@ impl Pane for ApisPane {
     let mut list_state = ListState::default().with_selected(Some(self.current_operation_index));
 
     frame.render_stateful_widget(list, area, &mut list_state);
-    let active_tag = format!("[{}]", state.active_tag_name.clone().unwrap_or(String::from("ALL")));
+    let active_tag = format!(
+      "[{}{}]",
+      state.active_tag_name.clone().unwrap_or(String::from(if state.webhooks_only { "WEBHOOKS" } else { "ALL" })),
+      if state.uncalled_only { ", UNCALLED" } else { "" }
+    );
     frame.render_widget(
       Block::default()
-        .title("APIs")
+        .title(if state.webhooks_only { "Webhooks" } else { "APIs" })
         .borders(Borders::ALL)
         .border_style(self.border_style())
         .border_type(self.border_type())
@@ -143,6 +161,11 @@ impl Pane for ApisPane {
           ))
           .right_aligned(),
         )
+        .title_bottom(if state.marked_operations.is_empty() {
+          Line::default()
+        } else {
+          Line::styled(format!("{} marked", state.marked_operations.len()), Style::default().fg(Color::LightGreen))
+        })
         .title(Line::styled(active_tag, Style::default().add_modifier(Modifier::ITALIC)).right_aligned()),
       area,
     );
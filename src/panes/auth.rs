@@ -0,0 +1,171 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+use tui_textarea::TextArea;
+
+use crate::{
+  action::Action,
+  auth, panes,
+  panes::Pane,
+  secrets,
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+/// Popup with the security schemes an operation accepts and the credentials filled in for them, sent with its
+/// requests.
+pub struct AuthPane<'a> {
+  requirements: Option<Vec<Vec<String>>>,
+  /// Schemes of the requirements, in the order they first appear.
+  schemes: Vec<String>,
+  index: usize,
+  input: Option<TextArea<'a>>,
+}
+
+impl AuthPane<'_> {
+  pub fn new(operation_id: &str, state: &State) -> Self {
+    let requirements = state
+      .get_operation(Some(operation_id.to_string()))
+      .and_then(|operation_item| auth::requirements(&operation_item.operation, &state.openapi_spec));
+    let mut schemes: Vec<String> = vec![];
+    for name in requirements.iter().flatten().flatten() {
+      if !schemes.contains(name) {
+        schemes.push(name.clone());
+      }
+    }
+    Self { requirements, schemes, index: 0, input: None }
+  }
+
+  /// Credential as shown in the list, only placeholders and secret references are readable.
+  fn display(credential: &str) -> String {
+    if secrets::is_reference(credential) || (credential.starts_with("{{") && credential.ends_with("}}")) {
+      credential.to_string()
+    } else {
+      "•".repeat(credential.chars().count().min(12))
+    }
+  }
+}
+
+impl Pane for AuthPane<'_> {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(2)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    if let Some(input) = self.input.as_mut() {
+      match key.code {
+        KeyCode::Esc => self.input = None,
+        KeyCode::Enter => {
+          let credential = input.lines().join("");
+          self.input = None;
+          if let Some(name) = self.schemes.get(self.index) {
+            let credential = Some(credential).filter(|credential| !credential.is_empty());
+            return Ok(Some(EventResponse::Stop(Action::SetCredential(name.clone(), credential))));
+          }
+        },
+        _ => {
+          input.input(key);
+        },
+      }
+      return Ok(Some(EventResponse::Stop(Action::Noop)));
+    }
+    match key.code {
+      KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(EventResponse::Stop(Action::ClosePopup))),
+      KeyCode::Down | KeyCode::Char('j') => self.index = (self.index + 1).min(self.schemes.len().saturating_sub(1)),
+      KeyCode::Up | KeyCode::Char('k') => self.index = self.index.saturating_sub(1),
+      KeyCode::Enter | KeyCode::Char('e') => {
+        if let Some(name) = self.schemes.get(self.index) {
+          let mut input = TextArea::from([state.credentials.get(name).cloned().unwrap_or_default()]);
+          input.set_cursor_line_style(Style::default());
+          input.move_cursor(tui_textarea::CursorMove::End);
+          self.input = Some(input);
+        }
+      },
+      KeyCode::Char('d') => {
+        if let Some(name) = self.schemes.get(self.index).filter(|name| state.credentials.contains_key(*name)) {
+          return Ok(Some(EventResponse::Stop(Action::SetCredential(name.clone(), None))));
+        }
+      },
+      _ => {},
+    }
+    Ok(Some(EventResponse::Stop(Action::Noop)))
+  }
+
+  fn handle_paste_events(&mut self, text: String, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let Some(input) = self.input.as_mut() else {
+      return Ok(None);
+    };
+    Ok(Some(panes::paste(text.trim(), |text| {
+      input.insert_str(text);
+    })))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
+    let input_height = if self.input.is_some() { 2 } else { 0 };
+    let [requirements_area, table_area, input_area] =
+      Layout::vertical([Constraint::Length(2), Constraint::Fill(1), Constraint::Length(input_height)]).areas(inner);
+    let requirements = match &self.requirements {
+      Some(requirements) => {
+        let covered = state.security_schemes.covered(requirements, &state.credentials);
+        let status = match (covered.is_empty(), requirements.iter().any(Vec::is_empty)) {
+          (false, _) => Span::styled("  credentials sent", Style::default().fg(Color::LightGreen)),
+          (true, true) => Span::styled("  optional, sent without credentials", Style::default().dim()),
+          (true, false) => {
+            Span::styled("  fill in the credentials of one alternative", Style::default().fg(Color::LightYellow))
+          },
+        };
+        Line::from(vec![Span::from(format!("accepts {}", auth::describe(requirements))), status])
+      },
+      None => Line::styled("The spec declares no security for this operation", Style::default().dim()),
+    };
+    frame.render_widget(Paragraph::new(requirements), requirements_area);
+
+    let rows = self.schemes.iter().map(|name| {
+      let summary = match state.security_schemes.get(name) {
+        Some(scheme) => scheme.summary(),
+        None => String::from("not in components.securitySchemes"),
+      };
+      let value = match state.credentials.get(name) {
+        Some(credential) => Cell::from(Self::display(credential)),
+        None => Cell::from(Span::styled("not set", Style::default().dim())),
+      };
+      Row::new(vec![Cell::from(name.as_str()), Cell::from(Span::styled(summary, Style::default().dim())), value])
+    });
+    let widths = [Constraint::Fill(1), Constraint::Fill(2), Constraint::Fill(2)];
+    let table = Table::new(rows, widths)
+      .header(Row::new(["Scheme", "Sends", "Credential"]).style(Style::default().bold()))
+      .highlight_symbol(symbols::scrollbar::HORIZONTAL.end)
+      .highlight_spacing(HighlightSpacing::Always)
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+    let mut table_state = TableState::default().with_selected((!self.schemes.is_empty()).then_some(self.index));
+    frame.render_stateful_widget(table, table_area, &mut table_state);
+
+    let hint = match self.input.as_mut() {
+      Some(input) => {
+        let [label_area, input_area] = Layout::vertical([Constraint::Length(1); 2]).areas(input_area);
+        let name = self.schemes.get(self.index).map_or("", String::as_str);
+        let label =
+          format!("credential of {name}, a {{{{name}}}} placeholder or secret://name keeps it off the screen");
+        frame.render_widget(Paragraph::new(label).style(Style::default().dim()), label_area);
+        input.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_widget(&*input, input_area);
+        "[enter → save] [esc → cancel]"
+      },
+      None => "[j,k → select] [e → edit] [d → clear] [esc → close]",
+    };
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title("Auth")
+        .title_bottom(Line::from(hint).right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
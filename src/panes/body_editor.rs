@@ -1,7 +1,11 @@
-use std::{io::Read, sync::Arc};
+use std::{
+  collections::{BTreeMap, HashMap},
+  io::Read,
+  sync::Arc,
+};
 
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
   prelude::*,
   widgets::{block::*, *},
@@ -10,8 +14,10 @@ use tui_textarea::TextArea;
 
 use crate::{
   action::Action,
+  history_store::Draft,
+  media_type,
   pages::phone::{RequestBuilder, RequestPane},
-  panes::Pane,
+  panes::{self, Pane},
   state::{InputMode, OperationItem, State},
   tui::{EventResponse, Frame},
 };
@@ -21,8 +27,11 @@ pub struct BodyEditor<'a> {
   focused_border_style: Style,
   operation_item: Arc<OperationItem>,
   input: TextArea<'a>,
+  drafts: HashMap<usize, TextArea<'a>>,
   content_types: Vec<String>,
   content_type_index: usize,
+  /// Body last saved as the example of each content type, bodies differing from it are unsaved.
+  saved: HashMap<usize, String>,
 }
 
 impl<'a> BodyEditor<'a> {
@@ -32,8 +41,10 @@ impl<'a> BodyEditor<'a> {
       focused,
       focused_border_style,
       input: TextArea::default(),
+      drafts: HashMap::default(),
       content_types: vec![],
       content_type_index: 0,
+      saved: HashMap::default(),
     }
   }
 
@@ -50,24 +61,54 @@ impl<'a> BodyEditor<'a> {
       false => BorderType::Plain,
     }
   }
+
+  fn new_input() -> TextArea<'a> {
+    let mut input = TextArea::default();
+    input.set_cursor_line_style(Style::default());
+    input.set_line_number_style(Style::default().dim());
+    input
+  }
+
+  fn body_of(&self, index: usize) -> Option<String> {
+    match index == self.content_type_index {
+      true => Some(self.input.lines().join("\n")),
+      false => self.drafts.get(&index).map(|draft| draft.lines().join("\n")),
+    }
+  }
+
+  /// Switches to another content type, keeping the body typed for the current one as its draft.
+  fn select_content_type(&mut self, index: usize) {
+    if index == self.content_type_index {
+      return;
+    }
+    let draft = self.drafts.remove(&index).unwrap_or_else(Self::new_input);
+    self.drafts.insert(self.content_type_index, std::mem::replace(&mut self.input, draft));
+    self.content_type_index = index;
+  }
 }
 
 impl RequestPane for BodyEditor<'_> {}
 
 impl RequestBuilder for BodyEditor<'_> {
-  fn reqeust(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-    if let Some(content_type) = self.content_types.get(self.content_type_index) {
-      request.header("content-type", content_type).body(self.input.lines().join("\n"))
-    } else {
-      request
+  fn request(&self, request: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder> {
+    let Some(content_type) = self.content_types.get(self.content_type_index) else {
+      return Ok(request);
+    };
+    Ok(request.header("content-type", content_type).body(self.input.lines().join("\n")))
+  }
+
+  fn draft(&self, draft: &mut Draft) {
+    let body = self.input.lines().join("\n");
+    if let (Some(content_type), false) = (self.content_types.get(self.content_type_index), body.trim().is_empty()) {
+      draft.content_type = Some(content_type.clone());
+      draft.body = Some(body);
     }
   }
 }
 
 impl Pane for BodyEditor<'_> {
   fn init(&mut self, state: &State) -> Result<()> {
-    self.input.set_cursor_line_style(Style::default());
-    self.input.set_line_number_style(Style::default().dim());
+    self.input = Self::new_input();
     self.content_types = self
       .operation_item
       .operation
@@ -79,6 +120,13 @@ impl Pane for BodyEditor<'_> {
     Ok(())
   }
 
+  fn is_dirty(&self) -> bool {
+    (0..self.content_types.len()).any(|index| {
+      let body = self.body_of(index).unwrap_or_default();
+      body != self.saved.get(&index).cloned().unwrap_or_default()
+    })
+  }
+
   fn height_constraint(&self) -> Constraint {
     if self.content_types.is_empty() {
       return Constraint::Fill(1);
@@ -98,10 +146,28 @@ impl Pane for BodyEditor<'_> {
           Ok(Some(EventResponse::Stop(Action::Noop)))
         },
       },
+      InputMode::Normal if !self.content_types.is_empty() => {
+        let (done, verb) = match key.code {
+          KeyCode::Char('u') => (self.input.undo(), "undo"),
+          KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => (self.input.redo(), "redo"),
+          _ => return Ok(None),
+        };
+        let action = if done { Action::Noop } else { Action::TimedStatusLine(format!("nothing to {verb}"), 3) };
+        Ok(Some(EventResponse::Stop(action)))
+      },
       _ => Ok(None),
     }
   }
 
+  fn handle_paste_events(&mut self, text: String, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    if state.input_mode != InputMode::Insert || self.content_types.is_empty() {
+      return Ok(None);
+    }
+    Ok(Some(panes::paste(text.as_str(), |text| {
+      self.input.insert_str(text);
+    })))
+  }
+
   fn update(&mut self, action: Action, state: &mut State) -> Result<Option<Action>> {
     if self.content_types.is_empty() {
       return Ok(None);
@@ -115,16 +181,22 @@ impl Pane for BodyEditor<'_> {
         state.input_mode = InputMode::Normal;
       },
       Action::Tab(index) if index < self.content_types.len().try_into()? => {
-        self.content_type_index = index.try_into()?;
+        self.select_content_type(index.try_into()?);
       },
       Action::TabNext => {
         let next_tab_index = self.content_type_index + 1;
-        self.content_type_index =
-          if next_tab_index < self.content_types.len() { next_tab_index } else { self.content_type_index };
+        self.select_content_type(if next_tab_index < self.content_types.len() {
+          next_tab_index
+        } else {
+          self.content_type_index
+        });
       },
       Action::TabPrev => {
-        self.content_type_index =
-          if self.content_type_index > 0 { self.content_type_index - 1 } else { self.content_type_index };
+        self.select_content_type(if self.content_type_index > 0 {
+          self.content_type_index - 1
+        } else {
+          self.content_type_index
+        });
       },
       Action::Focus => {
         self.focused = true;
@@ -145,6 +217,29 @@ impl Pane for BodyEditor<'_> {
           return Ok(Some(Action::TimedStatusLine(format!("can't open or read file content: {error}"), 5)));
         }
       },
+      Action::ImportBody(content_type, body) => {
+        let indexes = self.content_types.iter().cloned().enumerate().map(|(index, content_type)| (content_type, index));
+        let indexes = indexes.collect::<BTreeMap<_, _>>();
+        if let Some(&index) = content_type.and_then(|content_type| media_type::find(&indexes, content_type.as_str())) {
+          self.select_content_type(index);
+        }
+        self.input = TextArea::from(body.lines());
+      },
+      Action::WriteRequestExample(_, content_type, body) => {
+        if let Some(index) = self.content_types.iter().position(|candidate| *candidate == content_type) {
+          self.saved.insert(index, body);
+        }
+      },
+      Action::SaveRequestExample => {
+        let Some(operation_id) = self.operation_item.operation.operation_id.clone() else {
+          return Ok(None);
+        };
+        let content_type = self.content_types.get(self.content_type_index).cloned().unwrap_or_default();
+        return Ok(Some(Action::Confirm(
+          format!("Save the request body as the {content_type} example in the spec file? A .bak copy is kept."),
+          Box::new(Action::WriteRequestExample(operation_id, content_type, self.input.lines().join("\n"))),
+        )));
+      },
       _ => {},
     }
     Ok(None)
@@ -174,7 +269,7 @@ impl Pane for BodyEditor<'_> {
     }
 
     let content_types = if !self.content_types.is_empty() {
-      let ctype = self.content_types[self.content_type_index].clone();
+      let ctype = media_type::labels(&self.content_types).swap_remove(self.content_type_index);
       let ctype_progress = if self.content_types.len() > 1 {
         format!("[{}/{}]", self.content_type_index + 1, self.content_types.len())
       } else {
@@ -187,7 +282,7 @@ impl Pane for BodyEditor<'_> {
 
     frame.render_widget(
       Block::default()
-        .title(format!("Body{content_types}"))
+        .title(format!("Body{}{content_types}", if self.is_dirty() { "*" } else { "" }))
         .borders(Borders::ALL)
         .border_style(self.border_style())
         .border_type(self.border_type()),
@@ -197,3 +292,24 @@ impl Pane for BodyEditor<'_> {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_drafts_per_content_type() {
+    let mut editor = BodyEditor::new(Arc::new(OperationItem::default()), true, Style::default());
+    editor.content_types = vec!["application/json".into(), "application/xml".into()];
+    editor.input.insert_str("{}");
+    editor.select_content_type(1);
+    assert!(editor.input.is_empty());
+    editor.input.insert_str("<pet/>");
+    editor.select_content_type(0);
+    assert_eq!(editor.input.lines(), ["{}"]);
+    editor.select_content_type(1);
+    assert_eq!(editor.input.lines(), ["<pet/>"]);
+  }
+}
@@ -0,0 +1,124 @@
+use std::{ops::Not, time::Duration};
+
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+
+use crate::{
+  action::Action,
+  config::{Config, Palette},
+  panes::Pane,
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+pub struct InvokeResult {
+  pub operation_id: String,
+  pub method: String,
+  pub path: String,
+  pub status: Result<reqwest::StatusCode, String>,
+  pub elapsed: Duration,
+  /// Assertions of the operation failing on the response, with what was found instead.
+  pub failed_assertions: Vec<String>,
+}
+
+impl InvokeResult {
+  fn succeeded(&self) -> bool {
+    self.status.as_ref().is_ok_and(|status| status.is_success()) && self.failed_assertions.is_empty()
+  }
+}
+
+pub struct BulkSummaryPane {
+  title: String,
+  results: Vec<InvokeResult>,
+  table_state: TableState,
+  palette: Palette,
+}
+
+impl BulkSummaryPane {
+  pub fn new(title: String, results: Vec<InvokeResult>) -> Self {
+    let table_state = TableState::default().with_selected(results.is_empty().not().then_some(0));
+    Self { title, results, table_state, palette: Palette::default() }
+  }
+}
+
+impl Pane for BulkSummaryPane {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.palette = config.palette;
+    Ok(())
+  }
+
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(3)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let response = match key.code {
+      KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => EventResponse::Stop(Action::Down),
+      KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => EventResponse::Stop(Action::Up),
+      KeyCode::Esc => EventResponse::Stop(Action::ClosePopup),
+      KeyCode::Enter => match self.table_state.selected().and_then(|index| self.results.get(index)) {
+        Some(result) => EventResponse::Stop(Action::NewCall(Some(result.operation_id.clone()))),
+        None => EventResponse::Stop(Action::Noop),
+      },
+      _ => EventResponse::Stop(Action::Noop),
+    };
+    Ok(Some(response))
+  }
+
+  fn update(&mut self, action: Action, _state: &mut State) -> Result<Option<Action>> {
+    match action {
+      Action::Down if !self.results.is_empty() => {
+        let index = self.table_state.selected().map_or(0, |index| index.saturating_add(1) % self.results.len());
+        self.table_state.select(Some(index));
+      },
+      Action::Up if !self.results.is_empty() => {
+        let index = self
+          .table_state
+          .selected()
+          .map_or(0, |index| index.saturating_add(self.results.len().saturating_sub(1)) % self.results.len());
+        self.table_state.select(Some(index));
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let rows = self.results.iter().map(|result| {
+      let mut status = match &result.status {
+        Ok(status) => status.to_string(),
+        Err(error) => error.clone(),
+      };
+      let status_style = match result.failed_assertions.is_empty() {
+        true => self.palette.status_style(status.as_str()),
+        false => Style::default().fg(Color::Red),
+      };
+      if !result.failed_assertions.is_empty() {
+        status.push_str(format!(" {} ✗ {}", symbols::DOT, result.failed_assertions.join(", ")).as_str());
+      }
+      Row::new(vec![
+        Cell::from(result.method.clone()).style(self.palette.method_style(result.method.as_str())),
+        Cell::from(result.path.clone()),
+        Cell::from(format!("{}{status}", self.palette.status_symbol(status.as_str()))).style(status_style),
+        Cell::from(format!("{}ms", result.elapsed.as_millis())),
+      ])
+    });
+    let succeeded = self.results.iter().filter(|result| result.succeeded());
+    let table =
+      Table::new(rows, [Constraint::Length(8), Constraint::Fill(2), Constraint::Fill(1), Constraint::Length(8)])
+        .header(Row::new(vec!["Method", "Path", "Status", "Time"]).style(Style::default().add_modifier(Modifier::BOLD)))
+        .highlight_symbol(symbols::scrollbar::HORIZONTAL.end)
+        .highlight_spacing(HighlightSpacing::Always)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .block(Block::default().borders(Borders::ALL).title(self.title.as_str()).title_bottom(
+          Line::from(format!("{} of {} succeeded", succeeded.count(), self.results.len())).right_aligned(),
+        ));
+    frame.render_stateful_widget(table, area, &mut self.table_state);
+    Ok(())
+  }
+}
@@ -0,0 +1,68 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+
+use crate::{
+  action::Action,
+  panes::Pane,
+  state::State,
+  tui::{EventResponse, Frame},
+  update_check::Release,
+};
+
+/// Popup with the notes of the releases newer than the running version.
+#[derive(Default)]
+pub struct ChangelogPane {
+  scroll: u16,
+}
+
+impl ChangelogPane {
+  fn lines(releases: &[Release]) -> Vec<Line<'_>> {
+    releases
+      .iter()
+      .flat_map(|release| {
+        let title = Line::styled(release.version.clone(), Style::default().fg(Color::LightGreen).bold());
+        std::iter::once(title).chain(release.notes.lines().map(Line::raw)).chain(std::iter::once(Line::default()))
+      })
+      .collect()
+  }
+}
+
+impl Pane for ChangelogPane {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(3)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    match key.code {
+      KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(EventResponse::Stop(Action::ClosePopup))),
+      KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+      KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+      KeyCode::PageDown | KeyCode::Char('J') => self.scroll = self.scroll.saturating_add(10),
+      KeyCode::PageUp | KeyCode::Char('K') => self.scroll = self.scroll.saturating_sub(10),
+      _ => {},
+    }
+    Ok(Some(EventResponse::Stop(Action::Noop)))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
+    frame.render_widget(
+      Paragraph::new(Self::lines(&state.newer_releases)).wrap(Wrap { trim: false }).scroll((self.scroll, 0)),
+      inner,
+    );
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!("Changelog since {}", env!("CARGO_PKG_VERSION")))
+        .title_bottom(Line::from("[j,k → scroll] [esc → close]").right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
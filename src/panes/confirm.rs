@@ -0,0 +1,58 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+
+use crate::{
+  action::Action,
+  panes::Pane,
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+pub struct ConfirmPane {
+  title: String,
+  message: String,
+  action: Action,
+}
+
+impl ConfirmPane {
+  pub fn new(title: String, message: String, action: Action) -> Self {
+    Self { title, message, action }
+  }
+}
+
+impl Pane for ConfirmPane {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Length(self.message.lines().count() as u16 + 4)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let response = match key.code {
+      KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+        EventResponse::Stop(Action::Confirmed(Box::new(self.action.clone())))
+      },
+      KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => EventResponse::Stop(Action::ClosePopup),
+      _ => EventResponse::Stop(Action::Noop),
+    };
+    Ok(Some(response))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
+    frame.render_widget(Paragraph::new(self.message.as_str()).wrap(Wrap { trim: false }), inner);
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .border_style(Style::default().fg(Color::LightRed))
+        .title(self.title.as_str())
+        .title_bottom(Line::from("[y → confirm] [n → cancel]").right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,224 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::Result;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{
+  action::Action,
+  panes::Pane,
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+struct Entry {
+  name: String,
+  is_dir: bool,
+}
+
+/// Popup to browse for a file, the picked path is appended to the command that opened it and run.
+pub struct FilePickerPane {
+  command: String,
+  directory: PathBuf,
+  entries: Vec<Entry>,
+  input: Input,
+  item_index: usize,
+  error: Option<String>,
+}
+
+impl FilePickerPane {
+  pub fn new(command: String) -> Self {
+    let directory = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let mut file_picker =
+      Self { command, directory, entries: vec![], input: Input::default(), item_index: 0, error: None };
+    file_picker.change_directory(file_picker.directory.clone());
+    file_picker
+  }
+
+  /// Directories first, then files, both sorted by name.
+  fn read_entries(directory: &Path) -> std::io::Result<Vec<Entry>> {
+    let mut entries = std::fs::read_dir(directory)?
+      .filter_map(|entry| entry.ok())
+      .map(|entry| Entry {
+        name: entry.file_name().to_string_lossy().to_string(),
+        is_dir: entry.file_type().is_ok_and(|file_type| file_type.is_dir()),
+      })
+      .collect::<Vec<_>>();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+  }
+
+  fn change_directory(&mut self, directory: PathBuf) {
+    match Self::read_entries(&directory) {
+      Ok(entries) => {
+        self.directory = directory;
+        self.entries = entries;
+        self.error = None;
+      },
+      Err(error) => self.error = Some(format!("can't read {}: {error}", directory.display())),
+    }
+    self.input.reset();
+    self.item_index = 0;
+  }
+
+  fn visible_entries(&self) -> impl Iterator<Item = &Entry> {
+    let query = self.input.value().to_lowercase();
+    self.entries.iter().filter(move |entry| entry.name.to_lowercase().contains(query.as_str()))
+  }
+
+  fn pick(&self, path: PathBuf) -> Action {
+    Action::Confirmed(Box::new(Action::FooterResult(":".into(), Some(format!("{} {}", self.command, path.display())))))
+  }
+
+  fn submit(&mut self) -> Action {
+    let selected = self.visible_entries().nth(self.item_index).map(|entry| (entry.name.clone(), entry.is_dir));
+    match selected {
+      Some((name, true)) => {
+        self.change_directory(self.directory.join(name));
+        Action::Noop
+      },
+      Some((name, false)) => self.pick(self.directory.join(name)),
+      None if !self.input.value().is_empty() => self.pick(self.directory.join(self.input.value())),
+      None => Action::Noop,
+    }
+  }
+
+  fn create_directory(&mut self) {
+    if self.input.value().is_empty() {
+      return;
+    }
+    let directory = self.directory.join(self.input.value());
+    match std::fs::create_dir_all(&directory) {
+      Ok(_) => self.change_directory(directory),
+      Err(error) => self.error = Some(format!("can't create {}: {error}", directory.display())),
+    }
+  }
+}
+
+impl Pane for FilePickerPane {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(3)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let response = match (key.code, key.modifiers) {
+      (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => EventResponse::Stop(Action::Down),
+      (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => EventResponse::Stop(Action::Up),
+      (KeyCode::Esc, _) => EventResponse::Stop(Action::ClosePopup),
+      (KeyCode::Enter, _) => EventResponse::Stop(self.submit()),
+      (KeyCode::Char('s'), KeyModifiers::CONTROL) if !self.input.value().is_empty() => {
+        EventResponse::Stop(self.pick(self.directory.join(self.input.value())))
+      },
+      (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
+        self.create_directory();
+        EventResponse::Stop(Action::Noop)
+      },
+      (KeyCode::Backspace, _) if self.input.value().is_empty() => {
+        if let Some(parent) = self.directory.parent().map(Path::to_path_buf) {
+          self.change_directory(parent);
+        }
+        EventResponse::Stop(Action::Noop)
+      },
+      _ => {
+        self.input.handle_event(&Event::Key(key));
+        self.item_index = 0;
+        EventResponse::Stop(Action::Noop)
+      },
+    };
+    Ok(Some(response))
+  }
+
+  fn update(&mut self, action: Action, _state: &mut State) -> Result<Option<Action>> {
+    let items_len = self.visible_entries().count();
+    match action {
+      Action::Down if items_len > 0 => {
+        self.item_index = self.item_index.saturating_add(1) % items_len;
+      },
+      Action::Up if items_len > 0 => {
+        self.item_index = self.item_index.saturating_add(items_len.saturating_sub(1)) % items_len;
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
+    let [input_area, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+
+    frame.render_widget(
+      Line::from(vec![Span::styled("> ", Style::default().fg(Color::LightBlue)), Span::raw(self.input.value())]),
+      input_area,
+    );
+    frame.set_cursor_position(Position::new(
+      input_area.x.saturating_add(2).saturating_add(self.input.visual_cursor() as u16),
+      input_area.y,
+    ));
+
+    let items = self
+      .visible_entries()
+      .map(|entry| match entry.is_dir {
+        true => Line::styled(format!("{}/", entry.name), Style::default().fg(Color::LightBlue)),
+        false => Line::raw(entry.name.clone()),
+      })
+      .collect::<Vec<_>>();
+    let items_len = items.len();
+    let list = List::new(items)
+      .highlight_symbol(symbols::scrollbar::HORIZONTAL.end)
+      .highlight_spacing(HighlightSpacing::Always)
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+    let mut list_state = ListState::default().with_selected((items_len > 0).then_some(self.item_index));
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+
+    let hint = match &self.error {
+      Some(error) => Line::styled(error.clone(), Style::default().fg(Color::LightRed)),
+      None => Line::from("[⏎ → open/pick] [ctrl-s → pick typed name] [ctrl-d → create directory] [⌫ → parent]"),
+    };
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .title(format!("{} {}", self.command, self.directory.display()))
+        .title_bottom(hint.right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_browse_and_pick() {
+    let directory = std::env::temp_dir().join(format!("openapi-tui-file-picker-{}", std::process::id()));
+    std::fs::create_dir_all(directory.join("payloads")).unwrap();
+    std::fs::write(directory.join("pet.json"), "{}").unwrap();
+
+    let mut file_picker = FilePickerPane::new("request open".into());
+    file_picker.change_directory(directory.clone());
+    assert_eq!(
+      file_picker.entries,
+      [Entry { name: "payloads".into(), is_dir: true }, Entry { name: "pet.json".into(), is_dir: false }]
+    );
+
+    assert_eq!(file_picker.submit(), Action::Noop);
+    assert_eq!(file_picker.directory, directory.join("payloads"));
+
+    file_picker.change_directory(directory.clone());
+    file_picker.input = Input::from("pet");
+    assert_eq!(
+      file_picker.submit(),
+      Action::Confirmed(Box::new(Action::FooterResult(
+        ":".into(),
+        Some(format!("request open {}", directory.join("pet.json").display()))
+      )))
+    );
+    std::fs::remove_dir_all(directory).ok();
+  }
+}
@@ -7,7 +7,7 @@ use tui_input::{backend::crossterm::EventHandler, Input};
 
 use crate::{
   action::Action,
-  panes::Pane,
+  panes::{self, Pane},
   state::{InputMode, State},
   tui::{EventResponse, Frame},
 };
@@ -96,6 +96,13 @@ impl Pane for FooterPane {
     }
   }
 
+  fn handle_paste_events(&mut self, text: String, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    if state.input_mode != InputMode::Command {
+      return Ok(None);
+    }
+    Ok(Some(panes::paste(text.as_str(), |text| self.input = panes::paste_into_input(&self.input, text))))
+  }
+
   fn update(&mut self, action: Action, state: &mut State) -> Result<Option<Action>> {
     match action {
       Action::FocusFooter(cmd, args) => {
@@ -119,6 +126,7 @@ impl Pane for FooterPane {
         Ok(None)
       },
       Action::TimedStatusLine(status_line, show_time) => {
+        state.push_message(status_line.as_str());
         self.timed_status_line = Some(TimedStatusLine { status_line, show_time, created: Instant::now() });
         Ok(None)
       },
@@ -145,21 +153,32 @@ impl Pane for FooterPane {
         area.y + 1,
       ))
     } else {
+      let width = area.width.saturating_sub(4) as usize;
+      let status_line = self.get_status_line();
+      let status_line = if status_line.chars().count() > width || status_line.contains('\n') {
+        let summary = status_line.lines().next().unwrap_or_default().chars().take(width.saturating_sub(13));
+        format!("{}… [ctrl-g]", summary.collect::<String>())
+      } else {
+        status_line.clone()
+      };
       frame.render_widget(
-        Line::from(vec![Span::styled(self.get_status_line(), Style::default())])
-          .style(Style::default().fg(Color::DarkGray)),
+        Line::from(vec![Span::styled(status_line, Style::default())]).style(Style::default().fg(Color::DarkGray)),
         area,
       );
     }
-    frame.render_widget(
-      Line::from(vec![match state.input_mode {
-        InputMode::Normal => Span::from("[N]"),
-        InputMode::Insert => Span::from("[I]"),
-        InputMode::Command => Span::from("[C]"),
-      }])
-      .right_aligned(),
-      area,
-    );
+    let mut mode_line = Line::from(vec![match state.input_mode {
+      InputMode::Normal => Span::from("[N]"),
+      InputMode::Insert => Span::from("[I]"),
+      InputMode::Command => Span::from("[C]"),
+    }]);
+    if let Some((register, _)) = &state.recording_macro {
+      mode_line.spans.insert(0, Span::styled(format!("recording @{register} "), Style::default().fg(Color::LightRed)));
+    }
+    if let Some(release) = state.newer_releases.first().filter(|_| !self.focused) {
+      let hint = format!("{} available [:changelog] ", release.version);
+      mode_line.spans.insert(0, Span::styled(hint, Style::default().fg(Color::DarkGray).italic()));
+    }
+    frame.render_widget(mode_line.right_aligned(), area);
 
     Ok(())
   }
@@ -18,8 +18,23 @@ impl Pane for HeaderPane {
   }
 
   fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+    let (called, total) = state.coverage();
+    let percentage = (called * 100).checked_div(total).unwrap_or_default();
     frame.render_widget(
       Line::from(vec![
+        Span::styled(
+          format!(
+            "[ {called}/{total} called {} {percentage}% {} {} this session ] ",
+            symbols::DOT,
+            symbols::DOT,
+            state.session_operations.len()
+          ),
+          Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+          state.active_profile.as_ref().map(|profile| format!("[ profile {profile} ] ")).unwrap_or_default(),
+          Style::default().fg(Color::Yellow),
+        ),
         Span::styled(
           format!("[ {} {} ", state.openapi_spec.info.title, symbols::DOT),
           Style::default().fg(Color::Blue),
@@ -1,4 +1,4 @@
-use std::ops::Not;
+use std::{cmp::Reverse, ops::Not, time::Instant};
 
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
@@ -9,9 +9,11 @@ use ratatui::{
 
 use crate::{
   action::Action,
+  config::{Config, Palette},
   panes::Pane,
   state::{InputMode, OperationItem, State},
   tui::{EventResponse, Frame},
+  utils::ago,
 };
 
 #[derive(Default)]
@@ -19,42 +21,47 @@ struct OperationHistoryItem {
   operation_id: String,
   method: String,
   path: String,
+  synthetic_id: bool,
+  dirty: bool,
+  /// When the last response of the operation arrived and its status.
+  last_call: Option<(Instant, reqwest::StatusCode)>,
 }
 
 #[derive(Default)]
 pub struct HistoryPane {
   history: Vec<OperationHistoryItem>,
   history_item_index: Option<usize>,
+  palette: Palette,
 }
 
 impl HistoryPane {
-  pub fn new(operation_ids: Vec<&OperationItem>) -> Self {
-    let history = operation_ids
+  /// Drafts of the operations, with whether each one has unsaved edits, the most recently called first.
+  pub fn new(operation_ids: Vec<(&OperationItem, bool)>, state: &State) -> Self {
+    let mut history = operation_ids
       .iter()
-      .filter_map(|opertation_item| {
+      .filter_map(|(opertation_item, dirty)| {
         opertation_item.operation.operation_id.as_ref().map(|operation_id| OperationHistoryItem {
           operation_id: operation_id.clone(),
           method: opertation_item.method.clone(),
           path: opertation_item.path.clone(),
+          synthetic_id: opertation_item.synthetic_id,
+          dirty: *dirty,
+          last_call: state.responses.get(operation_id).map(|response| (response.received_at, response.status)),
         })
       })
       .collect::<Vec<OperationHistoryItem>>();
+    history.sort_by_key(|item| Reverse(item.last_call.map(|(received_at, _)| received_at)));
     let history_item_index = history.is_empty().not().then_some(0);
-    Self { history, history_item_index }
-  }
-
-  fn method_color(method: &str) -> Color {
-    match method {
-      "GET" => Color::LightCyan,
-      "POST" => Color::LightBlue,
-      "PUT" => Color::LightYellow,
-      "DELETE" => Color::LightRed,
-      _ => Color::Gray,
-    }
+    Self { history, history_item_index, palette: Palette::default() }
   }
 }
 
 impl Pane for HistoryPane {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.palette = config.palette;
+    Ok(())
+  }
+
   fn height_constraint(&self) -> Constraint {
     Constraint::Fill(3)
   }
@@ -69,7 +76,17 @@ impl Pane for HistoryPane {
         let response = match key.code {
           KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => EventResponse::Stop(Action::Down),
           KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => EventResponse::Stop(Action::Up),
-          KeyCode::Esc => EventResponse::Stop(Action::CloseHistory),
+          KeyCode::Esc => EventResponse::Stop(Action::ClosePopup),
+          KeyCode::Char('d') | KeyCode::Char('D') => {
+            match self.history_item_index.and_then(|item_index| self.history.get(item_index)) {
+              Some(item) if item.dirty => EventResponse::Stop(Action::Confirm(
+                format!("Remove the draft of {} with unsaved edits?", item.operation_id),
+                Box::new(Action::RemoveDraft(item.operation_id.clone())),
+              )),
+              Some(item) => EventResponse::Stop(Action::RemoveDraft(item.operation_id.clone())),
+              None => return Ok(Some(EventResponse::Stop(Action::Noop))),
+            }
+          },
           KeyCode::Enter => {
             if let Some(item_index) = self.history_item_index {
               EventResponse::Stop(Action::NewCall(self.history.get(item_index).map(|item| item.operation_id.clone())))
@@ -99,6 +116,14 @@ impl Pane for HistoryPane {
         }
         return Ok(Some(Action::Update));
       },
+      Action::RemoveDraft(operation_id) => {
+        self.history.retain(|item| item.operation_id != operation_id);
+        self.history_item_index = self
+          .history_item_index
+          .map(|item_idx| item_idx.min(self.history.len().saturating_sub(1)))
+          .filter(|_| !self.history.is_empty());
+        return Ok(Some(Action::Update));
+      },
       Action::Up => {
         let history_len = self.history.len();
         if history_len > 0 {
@@ -118,10 +143,26 @@ impl Pane for HistoryPane {
   fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
     frame.render_widget(Clear, area);
     let items = self.history.iter().map(|item| {
-      Line::from(vec![
-        Span::styled(format!(" {:7}", item.method), Self::method_color(item.method.as_str())),
+      let mut line = Line::from(vec![
+        Span::styled(format!(" {:7}", item.method), self.palette.method_style(item.method.as_str())),
         Span::from(item.path.clone()),
-      ])
+      ]);
+      if item.synthetic_id {
+        line.push_span(Span::styled(format!(" #{}", item.operation_id), Style::default().dim()));
+      }
+      if item.dirty {
+        line.push_span(Span::styled(" *", Style::default().fg(Color::Yellow)));
+      }
+      if let Some((received_at, status)) = item.last_call {
+        let status = status.as_str();
+        line.push_span(Span::raw("  "));
+        line.push_span(Span::styled(
+          format!("{}{status}", self.palette.status_symbol(status)),
+          self.palette.status_style(status),
+        ));
+        line.push_span(Span::styled(format!(" {} {}", symbols::DOT, ago(received_at)), Style::default().dim()));
+      }
+      line
     });
     let list = List::new(items)
       .block(Block::default().borders(Borders::ALL))
@@ -131,7 +172,14 @@ impl Pane for HistoryPane {
     let mut list_state = ListState::default().with_selected(self.history_item_index);
 
     frame.render_stateful_widget(list, area, &mut list_state);
-    frame.render_widget(Block::default().borders(Borders::ALL).title("Request History").style(Style::default()), area);
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .title("Request History")
+        .title_bottom(Line::from("[⏎ → open] [d → delete] [esc → close]").right_aligned())
+        .style(Style::default()),
+      area,
+    );
     Ok(())
   }
 }
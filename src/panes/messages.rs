@@ -0,0 +1,88 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+
+use crate::{
+  action::Action,
+  panes::Pane,
+  state::State,
+  tui::{EventResponse, Frame},
+  utils::ago,
+};
+
+/// Popup with the history of status messages, the selected one shown in full.
+#[derive(Default)]
+pub struct MessagesPane {
+  message_index: usize,
+  scroll: u16,
+}
+
+impl Pane for MessagesPane {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(3)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let messages_len = state.messages.len();
+    match key.code {
+      KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(EventResponse::Stop(Action::ClosePopup))),
+      KeyCode::Down | KeyCode::Char('j') if messages_len > 0 => {
+        self.message_index = (self.message_index + 1).min(messages_len - 1);
+        self.scroll = 0;
+      },
+      KeyCode::Up | KeyCode::Char('k') => {
+        self.message_index = self.message_index.saturating_sub(1);
+        self.scroll = 0;
+      },
+      KeyCode::PageDown | KeyCode::Char('J') => self.scroll = self.scroll.saturating_add(1),
+      KeyCode::PageUp | KeyCode::Char('K') => self.scroll = self.scroll.saturating_sub(1),
+      KeyCode::Char('y') | KeyCode::Char('Y') => {
+        if let Some((_, message)) = state.messages.get(self.message_index) {
+          return Ok(Some(EventResponse::Stop(Action::CopyToClipboard(message.clone()))));
+        }
+      },
+      _ => {},
+    }
+    Ok(Some(EventResponse::Stop(Action::Noop)))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
+    let [message_area, list_area] = Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(inner);
+
+    let message = state.messages.get(self.message_index).map(|(_, message)| message.as_str()).unwrap_or_default();
+    frame.render_widget(Paragraph::new(message).wrap(Wrap { trim: false }).scroll((self.scroll, 0)), message_area);
+
+    let items = state
+      .messages
+      .iter()
+      .map(|(at, message)| {
+        Line::from(vec![
+          Span::styled(format!("{:>8} ", ago(*at)), Style::default().dim()),
+          Span::raw(message.lines().next().unwrap_or_default().to_string()),
+        ])
+      })
+      .collect::<Vec<_>>();
+    let list = List::new(items)
+      .block(Block::default().borders(Borders::TOP))
+      .highlight_symbol(symbols::scrollbar::HORIZONTAL.end)
+      .highlight_spacing(HighlightSpacing::Always)
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+    let mut list_state = ListState::default().with_selected((!state.messages.is_empty()).then_some(self.message_index));
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!("Messages [{}]", state.messages.len()))
+        .title_bottom(Line::from("[j,k → select] [J,K → scroll] [y → copy] [esc → close]").right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
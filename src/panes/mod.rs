@@ -1,36 +1,68 @@
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::layout::{Constraint, Rect};
+use tokio::sync::mpsc::UnboundedSender;
+use tui_input::Input;
 
 use crate::{
   action::Action,
+  config::Config,
   state::State,
   tui::{Event, EventResponse, Frame},
 };
 
 pub mod address;
 pub mod apis;
+pub mod auth;
 pub mod body_editor;
+pub mod bulk_summary;
+pub mod changelog;
+pub mod confirm;
+pub mod file_picker;
 pub mod footer;
 pub mod header;
 pub mod history;
+pub mod messages;
+pub mod notes;
 pub mod parameter_editor;
+pub mod profiles;
+pub mod recent;
 pub mod request;
 pub mod response;
 pub mod response_viewer;
+pub mod secret;
+pub mod spec_editor;
+pub mod status_info;
 pub mod tags;
+pub mod value_editor;
+pub mod variables;
 
 pub trait Pane {
   fn init(&mut self, _state: &State) -> Result<()> {
     Ok(())
   }
 
+  /// Sender for the actions of work the pane runs in the background.
+  fn register_action_handler(&mut self, _tx: UnboundedSender<Action>) -> Result<()> {
+    Ok(())
+  }
+
+  fn register_config_handler(&mut self, _config: Config) -> Result<()> {
+    Ok(())
+  }
+
   fn height_constraint(&self) -> Constraint;
 
+  /// Whether the pane holds edits that are not saved anywhere yet, shown as a `*` in its title.
+  fn is_dirty(&self) -> bool {
+    false
+  }
+
   fn handle_events(&mut self, event: Event, state: &mut State) -> Result<Option<EventResponse<Action>>> {
     let r = match event {
       Event::Key(key_event) => self.handle_key_events(key_event, state)?,
       Event::Mouse(mouse_event) => self.handle_mouse_events(mouse_event, state)?,
+      Event::Paste(text) => self.handle_paste_events(text, state)?,
       _ => None,
     };
     Ok(r)
@@ -40,6 +72,11 @@ pub trait Pane {
     Ok(None)
   }
 
+  /// Text pasted at once with bracketed paste, instead of as a key press per character.
+  fn handle_paste_events(&mut self, _text: String, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    Ok(None)
+  }
+
   fn handle_mouse_events(&mut self, _mouse: MouseEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
     Ok(None)
   }
@@ -50,3 +87,56 @@ pub trait Pane {
 
   fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()>;
 }
+
+/// Pastes above this size are refused, a body that large is better loaded from a file.
+const MAX_PASTE_BYTES: usize = 8 * 1024 * 1024;
+/// Pastes above this size report it once inserted, the terminal takes a moment to send them.
+const LARGE_PASTE_BYTES: usize = 64 * 1024;
+
+/// Inserts a paste in one go with its line endings normalized, refusing huge ones and reporting the size of large
+/// ones.
+pub fn paste(text: &str, insert: impl FnOnce(&str)) -> EventResponse<Action> {
+  let size = humansize::format_size(text.len(), humansize::DECIMAL);
+  if text.len() > MAX_PASTE_BYTES {
+    return EventResponse::Stop(Action::TimedStatusLine(
+      format!("paste of {size} is too large, load it from a file"),
+      5,
+    ));
+  }
+  insert(text.replace("\r\n", "\n").replace('\r', "\n").as_str());
+  match text.len() > LARGE_PASTE_BYTES {
+    true => EventResponse::Stop(Action::TimedStatusLine(format!("pasted {size}"), 3)),
+    false => EventResponse::Stop(Action::Noop),
+  }
+}
+
+/// Input with a paste inserted at its cursor, on one line with a trailing newline dropped.
+pub fn paste_into_input(input: &Input, text: &str) -> Input {
+  let text = text.trim_end_matches('\n').replace('\n', " ");
+  let value = input.value();
+  let at = value.char_indices().nth(input.cursor()).map_or(value.len(), |(index, _)| index);
+  Input::new(format!("{}{text}{}", &value[..at], &value[at..])).with_cursor(input.cursor() + text.chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_paste() {
+    let input = Input::new(String::from("Bearer ")).with_cursor(7);
+    let mut pasted = input.clone();
+    paste("tökén\r\n", |text| pasted = paste_into_input(&input, text));
+    assert_eq!((pasted.value(), pasted.cursor()), ("Bearer tökén", 12));
+
+    let input = Input::new(String::from("ab")).with_cursor(1);
+    assert_eq!(paste_into_input(&input, "1\n2").value(), "a1 2b");
+
+    let mut inserted = false;
+    let response = paste(" ".repeat(MAX_PASTE_BYTES + 1).as_str(), |_| inserted = true);
+    assert!(!inserted);
+    assert!(matches!(response, EventResponse::Stop(Action::TimedStatusLine(..))));
+  }
+}
@@ -0,0 +1,106 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+use tui_textarea::TextArea;
+
+use crate::{
+  action::Action,
+  components::markdown,
+  panes::{self, Pane},
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+/// Popup with the markdown notes of the spec, rendered until `e` opens them for editing.
+#[derive(Default)]
+pub struct NotesPane<'a> {
+  scroll: u16,
+  input: Option<TextArea<'a>>,
+}
+
+impl Pane for NotesPane<'_> {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(3)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    if let Some(input) = self.input.as_mut() {
+      match (key.code, key.modifiers) {
+        (KeyCode::Esc, _) => self.input = None,
+        (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+          let notes = input.lines().join("\n");
+          self.input = None;
+          return Ok(Some(EventResponse::Stop(Action::SaveNotes(notes))));
+        },
+        _ => {
+          input.input(key);
+        },
+      }
+      return Ok(Some(EventResponse::Stop(Action::Noop)));
+    }
+    match key.code {
+      KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(EventResponse::Stop(Action::ClosePopup))),
+      KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+      KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+      KeyCode::PageDown | KeyCode::Char('J') => self.scroll = self.scroll.saturating_add(10),
+      KeyCode::PageUp | KeyCode::Char('K') => self.scroll = self.scroll.saturating_sub(10),
+      KeyCode::Enter | KeyCode::Char('e') => {
+        let mut input = TextArea::from(state.notes.lines());
+        input.set_cursor_line_style(Style::default());
+        input.move_cursor(tui_textarea::CursorMove::Bottom);
+        input.move_cursor(tui_textarea::CursorMove::End);
+        self.input = Some(input);
+      },
+      _ => {},
+    }
+    Ok(Some(EventResponse::Stop(Action::Noop)))
+  }
+
+  fn handle_paste_events(&mut self, text: String, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let Some(input) = self.input.as_mut() else {
+      return Ok(None);
+    };
+    Ok(Some(panes::paste(text.as_str(), |text| {
+      input.insert_str(text);
+    })))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
+    let hint = match self.input.as_mut() {
+      Some(input) => {
+        input.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_widget(&*input, inner);
+        "[ctrl-s → save] [esc → cancel]"
+      },
+      None if state.notes.trim().is_empty() => {
+        frame.render_widget(
+          Paragraph::new("No notes yet, press e to write down what you find out about this API")
+            .style(Style::default().dim()),
+          inner,
+        );
+        "[e → edit] [esc → close]"
+      },
+      None => {
+        frame.render_widget(
+          Paragraph::new(markdown::render(state.notes.as_str())).wrap(Wrap { trim: false }).scroll((self.scroll, 0)),
+          inner,
+        );
+        "[j,k → scroll] [e → edit] [esc → close]"
+      },
+    };
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title("Notes")
+        .title_bottom(Line::from(hint).right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
@@ -1,16 +1,20 @@
 use std::{str::FromStr, sync::Arc};
 
-use color_eyre::eyre::Result;
-use crossterm::event::{Event, KeyCode, KeyEvent};
+use color_eyre::eyre::{bail, Result};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use openapi_31::v31::parameter::In;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS, NON_ALPHANUMERIC};
 use ratatui::{prelude::*, widgets::*};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use tui_input::{backend::crossterm::EventHandler, Input};
 
 use crate::{
   action::Action,
+  history_store::{self, Draft},
   pages::phone::{RequestBuilder, RequestPane},
-  panes::Pane,
+  pagination::{self, PageDirection, PageParameter},
+  panes::{self, Pane},
+  profiles, request, secrets,
   state::{InputMode, OperationItem, State},
   tui::{EventResponse, Frame},
 };
@@ -22,6 +26,56 @@ pub struct ParameterEditor {
   parameters: Vec<ParameterTab>,
   selected_parameter: usize,
   input: Input,
+  undo_stack: Vec<ValueEdit>,
+  redo_stack: Vec<ValueEdit>,
+  /// Rows of each tab as the page opened with them, to tell whether they were edited since.
+  baseline: Vec<Vec<(String, Option<String>)>>,
+}
+
+/// A value typed into a parameter row, kept to undo or redo it.
+#[derive(Debug, Clone, PartialEq)]
+struct ValueEdit {
+  tab: usize,
+  row: usize,
+  before: Option<String>,
+  after: Option<String>,
+}
+
+const FORM_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'*').remove(b'-').remove(b'.').remove(b'_');
+const PERCENT_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryEncoding {
+  #[default]
+  Form,
+  Percent,
+  Raw,
+}
+
+impl QueryEncoding {
+  pub fn next(self) -> Self {
+    match self {
+      QueryEncoding::Form => QueryEncoding::Percent,
+      QueryEncoding::Percent => QueryEncoding::Raw,
+      QueryEncoding::Raw => QueryEncoding::Form,
+    }
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      QueryEncoding::Form => "+",
+      QueryEncoding::Percent => "%20",
+      QueryEncoding::Raw => "raw",
+    }
+  }
+
+  pub fn encode(&self, value: &str) -> String {
+    match self {
+      QueryEncoding::Form => utf8_percent_encode(value, FORM_ENCODE_SET).to_string().replace("%20", "+"),
+      QueryEncoding::Percent => utf8_percent_encode(value, PERCENT_ENCODE_SET).to_string(),
+      QueryEncoding::Raw => utf8_percent_encode(value, CONTROLS).to_string(),
+    }
+  }
 }
 
 #[derive(Default)]
@@ -30,6 +84,29 @@ pub struct ParameterItem {
   pub value: Option<String>,
   pub required: bool,
   pub schema: Option<serde_json::Value>,
+  pub encoding: QueryEncoding,
+  pub declared: bool,
+  pub session: bool,
+  pub configured: bool,
+  pub profile: bool,
+}
+
+impl ParameterItem {
+  /// Value as sent in a header, lines are folded into one since header values can't hold line breaks, and non-ASCII
+  /// text is encoded.
+  fn header_value(value: &str) -> String {
+    let value = value.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>().join(" ");
+    request::encode_header_value(value.as_str()).into_owned()
+  }
+
+  /// Whether a parameter named `name` at `location` ends up in the same place, header names are case-insensitive.
+  fn shadowed_by(&self, location: &str, name: &str) -> bool {
+    if location.eq_ignore_ascii_case("header") {
+      self.name.eq_ignore_ascii_case(name)
+    } else {
+      self.name.eq(name)
+    }
+  }
 }
 
 #[derive(Default)]
@@ -48,9 +125,20 @@ impl ParameterEditor {
       parameters: vec![],
       selected_parameter: 0,
       input: Input::default(),
+      undo_stack: vec![],
+      redo_stack: vec![],
+      baseline: vec![],
     }
   }
 
+  fn rows(&self) -> Vec<Vec<(String, Option<String>)>> {
+    self
+      .parameters
+      .iter()
+      .map(|parameters| parameters.items.iter().map(|item| (item.name.clone(), item.value.clone())).collect())
+      .collect()
+  }
+
   fn border_style(&self) -> Style {
     match self.focused {
       true => self.focused_border_style,
@@ -103,8 +191,18 @@ impl ParameterEditor {
           value,
           required: parameter.required.unwrap_or(false),
           schema: parameter.schema.clone(),
+          declared: true,
+          ..Default::default()
         });
       });
+      header_items.extend(state.session_headers.iter().map(|(name, value)| ParameterItem {
+        name: name.clone(),
+        value: Some(value.clone()),
+        session: true,
+        ..Default::default()
+      }));
+      let configured_headers = Self::configured_headers(&header_items, state);
+      header_items.extend(configured_headers);
       if !path_items.is_empty() {
         self.parameters.push(ParameterTab {
           location: "Path".to_string(),
@@ -134,6 +232,39 @@ impl ParameterEditor {
     Ok(())
   }
 
+  /// Default and profile headers not already in the header tab, ahead of the user's own rows.
+  fn configured_headers(header_items: &[ParameterItem], state: &State) -> Vec<ParameterItem> {
+    let profile_headers = state.profile().map(|profile| profile.headers()).unwrap_or_default();
+    state
+      .standing_headers()
+      .into_iter()
+      .filter(|(name, _)| !header_items.iter().any(|item| item.shadowed_by("header", name)))
+      .map(|(name, value)| ParameterItem {
+        profile: profiles::has_header(&profile_headers, name.as_str()),
+        name,
+        value: Some(value),
+        configured: true,
+        ..Default::default()
+      })
+      .collect()
+  }
+
+  /// Replaces the default and profile headers after switching profiles, keeping the header tab clean when it was.
+  fn switch_profile(&mut self, state: &State) {
+    let Some(tab) = self.parameters.iter().position(|tab| tab.location.eq_ignore_ascii_case("header")) else {
+      return;
+    };
+    let clean = self.rows().get(tab) == self.baseline.get(tab);
+    let items = &mut self.parameters[tab].items;
+    items.retain(|item| !item.configured);
+    let configured_headers = Self::configured_headers(items, state);
+    items.extend(configured_headers);
+    self.parameters[tab].table_state.select(Some(0));
+    if let (true, Some(rows)) = (clean, self.rows().into_iter().nth(tab)) {
+      self.baseline[tab] = rows;
+    }
+  }
+
   fn select_parameters<'a>(&'a self, parameter_type: &'a str) -> impl Iterator<Item = &ParameterItem> + 'a {
     self
       .parameters
@@ -166,54 +297,253 @@ impl ParameterEditor {
   fn cookie_parameters(&self) -> impl Iterator<Item = &ParameterItem> {
     self.select_parameters("cookie")
   }
+
+  /// Query string of the parameters, `secret://` references are left as placeholders resolved when the request is
+  /// sent.
+  fn query_string(&self) -> String {
+    self
+      .query_parameters()
+      .filter(|query_param| query_param.required || query_param.value.is_some())
+      .map(|query_param| {
+        let value = query_param.value.as_deref().unwrap_or_default();
+        let value = match secrets::is_reference(value) {
+          true => secrets::placeholder(value),
+          false => query_param.encoding.encode(value),
+        };
+        format!("{}={value}", query_param.encoding.encode(query_param.name.as_str()))
+      })
+      .collect::<Vec<_>>()
+      .join("&")
+  }
+
+  fn paginate(&mut self, direction: PageDirection, state: &State) -> Option<Action> {
+    let body = self
+      .operation_item
+      .operation
+      .operation_id
+      .as_ref()
+      .and_then(|operation_id| state.responses.get(operation_id))
+      .map(|response| response.body.as_str())
+      .unwrap_or_default();
+    let query_items = &mut self.parameters.iter_mut().find(|item| item.location.eq("Query"))?.items;
+    let limit = query_items
+      .iter()
+      .filter(|query_param| PageParameter::from_name(&query_param.name) == Some(PageParameter::Limit))
+      .find_map(|query_param| query_param.value.as_deref().and_then(|value| value.parse::<usize>().ok()))
+      .or_else(|| pagination::item_count(body));
+    let query_param = query_items.iter_mut().find(|query_param| {
+      PageParameter::from_name(&query_param.name).is_some_and(|parameter| parameter != PageParameter::Limit)
+    });
+    let Some(query_param) = query_param else {
+      return Some(Action::TimedStatusLine("no pagination parameter or link header found".into(), 3));
+    };
+    let parameter = PageParameter::from_name(&query_param.name)?;
+    match parameter.step(query_param.value.as_deref(), direction, limit, body) {
+      Some(value) => {
+        query_param.value = Some(value);
+        Some(Action::Dial)
+      },
+      None => {
+        Some(Action::TimedStatusLine(format!("no {direction:?} page for {}", query_param.name).to_lowercase(), 3))
+      },
+    }
+  }
+
+  /// Appends a user-added parameter, warning when it shadows one declared in the spec with the same name.
+  fn add_parameter(&mut self, location: &str, name: String) -> Option<Action> {
+    let param_tab = self.parameters.iter_mut().find(|item| item.location.to_lowercase().eq(location))?;
+    let shadowed = param_tab.items.iter().any(|item| item.declared && item.shadowed_by(location, &name));
+    param_tab.items.push(ParameterItem { name: name.clone(), ..Default::default() });
+    shadowed.then(|| Action::TimedStatusLine(format!("{location} {name} shadows the one declared in the spec"), 3))
+  }
+
+  /// Deletes the selected row of the current tab, parameters declared by the spec are kept.
+  fn delete_selected(&mut self) -> Option<Action> {
+    let parameters = self.parameters.get_mut(self.selected_parameter)?;
+    let index = parameters.table_state.selected()?;
+    let item = parameters.items.get(index)?;
+    if item.declared {
+      return Some(Action::TimedStatusLine(format!("{} is declared in the spec and can't be deleted", item.name), 3));
+    }
+    let item = parameters.items.remove(index);
+    parameters.table_state.select(Some(index.min(parameters.items.len().saturating_sub(1))));
+    self.forget_row(self.selected_parameter, index);
+    let parameters = &self.parameters[self.selected_parameter];
+    Some(Action::TimedStatusLine(format!("{} {} deleted", parameters.location.to_lowercase(), item.name), 3))
+  }
+
+  /// Swaps the selected row with the one above (`offset` -1) or below (`offset` 1), the selection follows the row.
+  fn move_selected(&mut self, offset: isize) {
+    if let Some(parameters) = self.parameters.get_mut(self.selected_parameter) {
+      let Some(index) = parameters.table_state.selected() else {
+        return;
+      };
+      let Some(target) = index.checked_add_signed(offset).filter(|target| *target < parameters.items.len()) else {
+        return;
+      };
+      parameters.items.swap(index, target);
+      parameters.table_state.select(Some(target));
+      for edit in self.undo_stack.iter_mut().chain(self.redo_stack.iter_mut()) {
+        if edit.tab == self.selected_parameter && (edit.row == index || edit.row == target) {
+          edit.row = if edit.row == index { target } else { index };
+        }
+      }
+    }
+  }
+
+  /// Drops the edits of a removed row and shifts the ones of the rows below it.
+  fn forget_row(&mut self, tab: usize, row: usize) {
+    for stack in [&mut self.undo_stack, &mut self.redo_stack] {
+      stack.retain(|edit| edit.tab != tab || edit.row != row);
+      stack.iter_mut().filter(|edit| edit.tab == tab && edit.row > row).for_each(|edit| edit.row -= 1);
+    }
+  }
+
+  /// Sets the value of the selected row, remembering the previous one for undo.
+  fn edit_selected(&mut self, value: Option<String>) {
+    let tab = self.selected_parameter;
+    let Some(parameters) = self.parameters.get_mut(tab) else {
+      return;
+    };
+    let Some((row, parameter)) =
+      parameters.table_state.selected().and_then(|row| parameters.items.get_mut(row).map(|item| (row, item)))
+    else {
+      return;
+    };
+    if parameter.value != value {
+      let before = std::mem::replace(&mut parameter.value, value.clone());
+      self.undo_stack.push(ValueEdit { tab, row, before, after: value });
+      self.redo_stack.clear();
+    }
+  }
+
+  /// Reverts the last edit (`redo` false) or applies the last reverted one again, selecting its row.
+  fn undo(&mut self, redo: bool) -> Option<Action> {
+    let (from, to) =
+      if redo { (&mut self.redo_stack, &mut self.undo_stack) } else { (&mut self.undo_stack, &mut self.redo_stack) };
+    let Some(edit) = from.pop() else {
+      return Some(Action::TimedStatusLine(format!("nothing to {}", if redo { "redo" } else { "undo" }), 3));
+    };
+    let parameters = self.parameters.get_mut(edit.tab)?;
+    let parameter = parameters.items.get_mut(edit.row)?;
+    parameter.value = if redo { edit.after.clone() } else { edit.before.clone() };
+    parameters.table_state.select(Some(edit.row));
+    self.selected_parameter = edit.tab;
+    to.push(edit);
+    None
+  }
+
+  /// Opens the command line with the add command of the current tab, only queries and headers can be added.
+  fn add_prompt(&self) -> Action {
+    let location = self.parameters.get(self.selected_parameter).map(|parameters| parameters.location.to_lowercase());
+    match location.as_deref() {
+      Some(location @ ("query" | "header")) => Action::FocusFooter(":".into(), Some(format!("{location} add "))),
+      Some(location) => Action::TimedStatusLine(format!("{location} parameters can't be added"), 3),
+      None => Action::Noop,
+    }
+  }
+
+  /// Opens the selected value in the multi-line editor popup.
+  fn expand_selected(&self, value: Option<String>) -> Option<Action> {
+    let parameters = self.parameters.get(self.selected_parameter)?;
+    let parameter = parameters.table_state.selected().and_then(|i| parameters.items.get(i))?;
+    let value = value.or_else(|| parameter.value.clone()).unwrap_or_default();
+    Some(Action::EditParameterValue(format!("{} {}", parameters.location.to_lowercase(), parameter.name), value))
+  }
+
+  fn is_query_tab(&self) -> bool {
+    self.parameters.get(self.selected_parameter).is_some_and(|parameters| parameters.location.eq("Query"))
+  }
+
+  /// Non-ASCII value of the selected header, or the one being typed, as it will be sent.
+  fn header_preview(&self, state: &State) -> Option<String> {
+    let parameters =
+      self.parameters.get(self.selected_parameter).filter(|parameters| parameters.location.eq("Header"))?;
+    let value = match state.input_mode {
+      InputMode::Insert if self.focused => self.input.value(),
+      _ => parameters.items.get(parameters.table_state.selected()?)?.value.as_deref()?,
+    };
+    (!value.is_ascii()).then(|| ParameterItem::header_value(value))
+  }
 }
 
 impl RequestPane for ParameterEditor {}
 
 impl RequestBuilder for ParameterEditor {
-  fn path(&self, url: String) -> String {
-    self.path_parameters().fold(url, |url, path_param| {
-      if let Some(value) = &path_param.value {
-        url.replace(format!("{{{}}}", path_param.name).as_str(), value.as_str())
-      } else {
-        url
-      }
+  fn has_parameter(&self, name: &str) -> bool {
+    self.parameters.iter().any(|param_tab| param_tab.items.iter().any(|item| item.name.eq(name)))
+  }
+
+  fn path(&self, url: String) -> Result<String> {
+    let url = self.path_parameters().fold(url, |url, path_param| match &path_param.value {
+      Some(value) if secrets::is_reference(value) => {
+        url.replace(format!("{{{}}}", path_param.name).as_str(), secrets::placeholder(value).as_str())
+      },
+      Some(value) => url.replace(format!("{{{}}}", path_param.name).as_str(), value),
+      None => url,
+    });
+    let query_string = self.query_string();
+    Ok(if query_string.is_empty() {
+      url
+    } else if url.contains('?') {
+      format!("{url}&{query_string}")
+    } else {
+      format!("{url}?{query_string}")
     })
   }
 
-  fn reqeust(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-    let query_params = self
-      .query_parameters()
-      .filter_map(|query_param| {
-        let name = query_param.name.clone();
-        let value = query_param.value.clone();
-        if !query_param.required && value.is_none() {
-          return None;
-        }
-        Some((name, value.unwrap()))
-      })
-      .collect::<Vec<_>>();
-
-    let header_params = self
-      .header_parameters()
-      .filter_map(|header_param| {
-        let name = header_param.name.as_str();
-        let value = header_param.value.as_deref().unwrap_or_default();
-        HeaderName::from_str(name)
-          .ok()
-          .and_then(|header_name| HeaderValue::from_str(value).ok().map(|header_value| (header_name, header_value)))
-      })
-      .collect::<HeaderMap<_>>();
-    request.query(&query_params).headers(header_params)
+  fn request(&self, request: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder> {
+    let mut header_params = HeaderMap::new();
+    let mut errors = vec![];
+    for header_param in self.header_parameters() {
+      let name = header_param.name.as_str();
+      let raw_value = header_param.value.as_deref().unwrap_or_default();
+      let value = match secrets::resolve(raw_value) {
+        Ok(value) => ParameterItem::header_value(&value),
+        Err(error) => {
+          errors.push(error.to_string());
+          continue;
+        },
+      };
+      let Ok(header_name) = HeaderName::from_str(name) else {
+        errors.push(format!("invalid header name {name:?}"));
+        continue;
+      };
+      let Ok(mut header_value) = HeaderValue::from_str(value.as_str()) else {
+        errors.push(format!("invalid value of header {name}"));
+        continue;
+      };
+      header_value.set_sensitive(secrets::is_reference(raw_value));
+      header_params.append(header_name, header_value);
+    }
+    if !errors.is_empty() {
+      bail!("{}", errors.join(", "));
+    }
+    Ok(request.headers(header_params))
+  }
+
+  fn draft(&self, draft: &mut Draft) {
+    for tab in &self.parameters {
+      let items = tab.items.iter().filter(|item| !item.configured && !item.session);
+      let parameters = items
+        .filter_map(|item| Some((tab.location.to_lowercase(), item.name.clone(), item.value.clone()?)))
+        .filter(|(location, name, value)| history_store::is_storable(location, name, value));
+      draft.parameters.extend(parameters);
+    }
   }
 }
 
 impl Pane for ParameterEditor {
   fn init(&mut self, state: &State) -> Result<()> {
     self.init_parameters(state)?;
+    self.baseline = self.rows();
     Ok(())
   }
 
+  fn is_dirty(&self) -> bool {
+    self.rows() != self.baseline
+  }
+
   fn height_constraint(&self) -> Constraint {
     Constraint::Fill(1)
   }
@@ -222,18 +552,52 @@ impl Pane for ParameterEditor {
     match state.input_mode {
       InputMode::Insert => match key.code {
         KeyCode::Enter => Ok(Some(EventResponse::Stop(Action::Submit))),
+        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+          state.input_mode = InputMode::Normal;
+          let action = self.expand_selected(Some(self.input.value().to_string()));
+          self.input.reset();
+          Ok(action.map(EventResponse::Stop))
+        },
         _ => {
           self.input.handle_event(&Event::Key(key));
           Ok(Some(EventResponse::Stop(Action::Noop)))
         },
       },
+      InputMode::Normal => match key.code {
+        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+          Ok(self.expand_selected(None).map(EventResponse::Stop))
+        },
+        KeyCode::Char('d') => Ok(self.delete_selected().map(EventResponse::Stop)),
+        KeyCode::Char('a') => Ok(Some(EventResponse::Stop(self.add_prompt()))),
+        KeyCode::Char('u') => Ok(Some(EventResponse::Stop(self.undo(false).unwrap_or(Action::Noop)))),
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+          Ok(Some(EventResponse::Stop(self.undo(true).unwrap_or(Action::Noop))))
+        },
+        KeyCode::Char('U') => {
+          self.move_selected(-1);
+          Ok(Some(EventResponse::Stop(Action::Noop)))
+        },
+        KeyCode::Char('P') => {
+          self.move_selected(1);
+          Ok(Some(EventResponse::Stop(Action::Noop)))
+        },
+        _ => Ok(None),
+      },
       _ => Ok(None),
     }
   }
 
+  fn handle_paste_events(&mut self, text: String, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    if state.input_mode != InputMode::Insert {
+      return Ok(None);
+    }
+    Ok(Some(panes::paste(text.as_str(), |text| self.input = panes::paste_into_input(&self.input, text))))
+  }
+
   fn update(&mut self, action: Action, state: &mut State) -> Result<Option<Action>> {
     match action {
       Action::Update => {},
+      Action::SwitchProfile(_) => self.switch_profile(state),
       Action::Down => {
         if let Some(parameters) = self.parameters.get_mut(self.selected_parameter).as_mut() {
           let i = match parameters.table_state.selected() {
@@ -281,36 +645,72 @@ impl Pane for ParameterEditor {
       Action::UnFocus => {
         self.focused = false;
       },
-      Action::Submit if state.input_mode == InputMode::Normal && !self.parameters.is_empty() => {
-        state.input_mode = InputMode::Insert;
+      Action::CycleEncoding if self.is_query_tab() => {
         if let Some(parameter) = self
+          .parameters
+          .get_mut(self.selected_parameter)
+          .and_then(|parameters| parameters.table_state.selected().and_then(|i| parameters.items.get_mut(i)))
+        {
+          parameter.encoding = parameter.encoding.next();
+          return Ok(Some(Action::TimedStatusLine(
+            format!("{} is encoded as [{}]", parameter.name, parameter.encoding.label()),
+            3,
+          )));
+        }
+      },
+      Action::SetPathParameters(path_parameters) => {
+        if let Some(param_tab) = self.parameters.iter_mut().find(|item| item.location.to_lowercase().eq("path")) {
+          for (name, value) in path_parameters {
+            if let Some(item) = param_tab.items.iter_mut().find(|item| item.name.eq(&name)) {
+              item.value = Some(value);
+            }
+          }
+        }
+      },
+      Action::SetParameter(name, value) => {
+        let item =
+          self.parameters.iter_mut().find_map(|param_tab| param_tab.items.iter_mut().find(|item| item.name.eq(&name)));
+        match item {
+          Some(item) => item.value = Some(value),
+          None => return Ok(Some(Action::TimedStatusLine(format!("no parameter named {name}"), 3))),
+        }
+      },
+      Action::ImportParameter(location, name, value) => {
+        if let Some(param_tab) = self.parameters.iter_mut().find(|item| item.location.to_lowercase().eq(&location)) {
+          match param_tab.items.iter_mut().find(|item| item.shadowed_by(location.as_str(), name.as_str())) {
+            Some(item) => item.value = Some(value),
+            None => param_tab.items.push(ParameterItem { name, value: Some(value), ..Default::default() }),
+          }
+        }
+      },
+      Action::Paginate(direction, _) => {
+        return Ok(self.paginate(direction, state));
+      },
+      Action::Submit if state.input_mode == InputMode::Normal && !self.parameters.is_empty() => {
+        let value = self
           .parameters
           .get(self.selected_parameter)
           .and_then(|parameters| parameters.table_state.selected().and_then(|i| parameters.items.get(i)))
-        {
-          self.input = self.input.clone().with_value(parameter.value.clone().unwrap_or_default());
+          .map(|parameter| parameter.value.clone().unwrap_or_default());
+        if value.as_ref().is_some_and(|value| value.contains('\n')) {
+          return Ok(self.expand_selected(None));
+        }
+        state.input_mode = InputMode::Insert;
+        if let Some(value) = value {
+          self.input = self.input.clone().with_value(value);
         }
       },
+      Action::SaveParameterValue(value) => {
+        self.edit_selected(Some(value).filter(|value| !value.is_empty()));
+      },
       Action::Submit if state.input_mode == InputMode::Insert && !self.parameters.is_empty() => {
         state.input_mode = InputMode::Normal;
-
-        if let Some(parameter) = self
-          .parameters
-          .get_mut(self.selected_parameter)
-          .and_then(|parameters| parameters.table_state.selected().and_then(|i| parameters.items.get_mut(i)))
-        {
-          if !self.input.value().is_empty() {
-            parameter.value = Some(self.input.value().to_string());
-          } else {
-            parameter.value = None;
-          }
-        }
+        let value = Some(self.input.value().to_string()).filter(|value| !value.is_empty());
+        self.edit_selected(value);
         self.input.reset();
       },
       Action::AddHeader(header_name) => {
-        if let Some(param_tab) = self.parameters.iter_mut().find(|item| item.location.to_lowercase().eq("header")) {
-          param_tab.items.push(ParameterItem { name: header_name, ..Default::default() });
-        }
+        return Ok(self.add_parameter("header", header_name));
       },
       Action::RemoveHeader(header_name) => {
         if let Some(param_tab) = self.parameters.iter_mut().find(|item| item.location.to_lowercase().eq("header")) {
@@ -322,13 +722,13 @@ impl Pane for ParameterEditor {
             .last()
           {
             param_tab.items.remove(last_header_index);
+            let tab = self.parameters.iter().position(|item| item.location.to_lowercase().eq("header"));
+            self.forget_row(tab.unwrap_or_default(), last_header_index);
           }
         }
       },
       Action::AddQuery(query_name) => {
-        if let Some(param_tab) = self.parameters.iter_mut().find(|item| item.location.to_lowercase().eq("query")) {
-          param_tab.items.push(ParameterItem { name: query_name, ..Default::default() });
-        }
+        return Ok(self.add_parameter("query", query_name));
       },
       Action::RemoveQuery(query_name) => {
         if let Some(param_tab) = self.parameters.iter_mut().find(|item| item.location.to_lowercase().eq("query")) {
@@ -340,6 +740,8 @@ impl Pane for ParameterEditor {
             .last()
           {
             param_tab.items.remove(last_query_index);
+            let tab = self.parameters.iter().position(|item| item.location.to_lowercase().eq("query"));
+            self.forget_row(tab.unwrap_or_default(), last_query_index);
           }
         }
       },
@@ -371,6 +773,7 @@ impl Pane for ParameterEditor {
           false => "   ",
         };
         let value = match &item.value {
+          Some(value) if value.contains('\n') => Span::from(value.lines().collect::<Vec<_>>().join(" ↵ ")),
           Some(value) => Span::from(value),
           None => Span::styled(String::from("No Value"), Style::default().dim()),
         };
@@ -379,10 +782,25 @@ impl Pane for ParameterEditor {
           InputMode::Insert if selected == index && self.focused => Span::default(),
           _ => value,
         };
-        Row::new(vec![
-          Cell::from(Line::from(vec![Span::from(required).style(Color::Red), Span::from(item.name.clone())])),
-          Cell::from(Line::from(vec![Span::from(symbols::line::VERTICAL), value])),
-        ])
+        let mut value_line = Line::from(vec![Span::from(symbols::line::VERTICAL), value]);
+        if parameters.location.eq("Query") && item.encoding != QueryEncoding::Form {
+          value_line.push_span(Span::styled(format!(" [{}]", item.encoding.label()), Style::default().dim()));
+        }
+        let mut name_line = Line::from(vec![Span::from(required).style(Color::Red), Span::from(item.name.clone())]);
+        if !item.declared {
+          name_line = name_line.italic();
+          let shadows = parameters.items.iter().any(|declared| {
+            declared.declared && declared.shadowed_by(parameters.location.as_str(), item.name.as_str())
+          });
+          name_line.push_span(match (shadows, item.session, item.configured, item.profile) {
+            (true, ..) => Span::styled(" [shadows spec]", Style::default().fg(Color::Yellow)),
+            (false, true, ..) => Span::styled(" [session]", Style::default().fg(Color::LightCyan).dim()),
+            (false, false, true, true) => Span::styled(" [profile]", Style::default().fg(Color::LightCyan).dim()),
+            (false, false, true, false) => Span::styled(" [default]", Style::default().fg(Color::LightCyan).dim()),
+            (false, false, false, _) => Span::styled(" [user]", Style::default().dim()),
+          });
+        }
+        Row::new(vec![Cell::from(name_line), Cell::from(value_line)])
       });
       let row_widths = [Constraint::Fill(1), Constraint::Fill(2)];
       let column_widths = Layout::horizontal(row_widths).split(inner);
@@ -411,7 +829,8 @@ impl Pane for ParameterEditor {
           height: 1,
         };
 
-        let scroll = self.input.visual_scroll(input_area.width as usize);
+        // one column is kept for the cursor after the last character.
+        let scroll = self.input.visual_scroll(input_area.width.saturating_sub(1) as usize);
         let input =
           Paragraph::new(Line::from(vec![
             Span::styled(self.input.value(), Style::default().fg(Color::LightBlue)).not_dim()
@@ -425,15 +844,229 @@ impl Pane for ParameterEditor {
       }
     }
 
+    let query_preview = if self.is_query_tab() {
+      let query_string = self.query_string();
+      if query_string.is_empty() {
+        Line::default()
+      } else {
+        Line::from(format!("?{query_string}")).style(Style::default().fg(Color::White).dim())
+      }
+    } else if let Some(header_value) = self.header_preview(state) {
+      Line::from(format!("sent as {header_value}")).style(Style::default().fg(Color::White).dim())
+    } else {
+      Line::default()
+    };
+
     frame.render_widget(
       Block::default()
-        .title("Parameters")
+        .title(if self.is_dirty() { "Parameters*" } else { "Parameters" })
         .borders(Borders::ALL)
         .border_style(self.border_style())
-        .border_type(self.border_type()),
+        .border_type(self.border_type())
+        .title_bottom(query_preview.left_aligned()),
       area,
     );
 
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use std::collections::BTreeMap;
+
+  use super::*;
+  use crate::profiles::Profile;
+
+  #[test]
+  fn test_query_encoding() {
+    assert_eq!(QueryEncoding::Form.encode("a b&c=d~"), "a+b%26c%3Dd%7E");
+    assert_eq!(QueryEncoding::Percent.encode("a b&c=d~"), "a%20b%26c%3Dd~");
+    assert_eq!(QueryEncoding::Raw.encode("a%20b"), "a%20b");
+    assert_eq!(QueryEncoding::Raw.encode("café"), "caf%C3%A9");
+  }
+
+  #[test]
+  fn test_row_operations() {
+    let mut editor = ParameterEditor::new(Arc::new(OperationItem::default()), true, Style::default());
+    editor.parameters.push(ParameterTab {
+      location: "Query".to_string(),
+      items: vec![
+        ParameterItem { name: "limit".into(), declared: true, ..Default::default() },
+        ParameterItem { name: "debug".into(), ..Default::default() },
+        ParameterItem { name: "trace".into(), ..Default::default() },
+      ],
+      table_state: TableState::default().with_selected(0),
+    });
+    let names = |editor: &ParameterEditor| editor.query_parameters().map(|item| item.name.clone()).collect::<Vec<_>>();
+
+    editor.delete_selected();
+    assert_eq!(names(&editor), ["limit", "debug", "trace"]);
+
+    editor.move_selected(1);
+    editor.move_selected(1);
+    editor.move_selected(1);
+    assert_eq!(names(&editor), ["debug", "trace", "limit"]);
+
+    editor.move_selected(-1);
+    editor.move_selected(-1);
+    assert_eq!(names(&editor), ["limit", "debug", "trace"]);
+    editor.parameters[0].table_state.select(Some(2));
+    editor.delete_selected();
+    assert_eq!(names(&editor), ["limit", "debug"]);
+    assert_eq!(editor.parameters[0].table_state.selected(), Some(1));
+    assert_eq!(editor.add_prompt(), Action::FocusFooter(":".into(), Some("query add ".into())));
+
+    assert_eq!(editor.add_parameter("query", "page".into()), None);
+    assert!(editor.add_parameter("query", "limit".into()).is_some());
+    assert_eq!(names(&editor), ["limit", "debug", "page", "limit"]);
+  }
+
+  #[test]
+  fn test_undo_redo() {
+    let mut editor = ParameterEditor::new(Arc::new(OperationItem::default()), true, Style::default());
+    editor.parameters.push(ParameterTab {
+      location: "Query".to_string(),
+      items: vec![ParameterItem { name: "limit".into(), ..Default::default() }, ParameterItem::default()],
+      table_state: TableState::default().with_selected(0),
+    });
+    let value = |editor: &ParameterEditor, row: usize| editor.parameters[0].items[row].value.clone();
+
+    editor.edit_selected(Some("10".into()));
+    editor.edit_selected(Some("20".into()));
+    editor.move_selected(1);
+    editor.undo(false);
+    assert_eq!(value(&editor, 1).as_deref(), Some("10"));
+    editor.undo(false);
+    assert_eq!(value(&editor, 1), None);
+    assert!(editor.undo(false).is_some());
+    editor.undo(true);
+    assert_eq!(value(&editor, 1).as_deref(), Some("10"));
+
+    editor.edit_selected(Some("5".into()));
+    assert!(editor.undo(true).is_some());
+  }
+
+  #[test]
+  fn test_session_headers() {
+    let state = State {
+      session_headers: vec![("x-trace".into(), "1".into())],
+      default_headers: vec![("X-Trace".into(), "0".into()), ("Accept-Language".into(), "fa".into())],
+      ..Default::default()
+    };
+    let mut editor = ParameterEditor::new(Arc::new(OperationItem::default()), true, Style::default());
+    editor.init(&state).unwrap();
+    let mut headers = editor.header_parameters();
+    let header = headers.next().unwrap();
+    assert_eq!((header.name.as_str(), header.value.as_deref(), header.session), ("x-trace", Some("1"), true));
+    let header = headers.next().unwrap();
+    assert_eq!(
+      (header.name.as_str(), header.value.as_deref(), header.configured),
+      ("Accept-Language", Some("fa"), true)
+    );
+    assert!(headers.next().is_none());
+    drop(headers);
+
+    assert!(!editor.is_dirty());
+    let header_tab = editor.parameters.iter().position(|tab| tab.location.eq_ignore_ascii_case("header")).unwrap();
+    editor.selected_parameter = header_tab;
+    editor.parameters[header_tab].table_state.select(Some(0));
+    editor.edit_selected(Some("2".into()));
+    assert!(editor.is_dirty());
+    editor.undo(false);
+    assert!(!editor.is_dirty());
+
+    let mut state = state;
+    let profile = Profile { token: Some("t0k3n".into()), ..Profile::default() };
+    state.profiles.insert("dev".into(), profile);
+    state.active_profile = Some("dev".into());
+    editor.update(Action::SwitchProfile(state.active_profile.clone()), &mut state).unwrap();
+    let headers = editor.header_parameters().map(|header| (header.name.as_str(), header.profile)).collect::<Vec<_>>();
+    assert_eq!(headers, [("x-trace", false), ("Authorization", true), ("Accept-Language", false)]);
+    assert!(!editor.is_dirty());
+  }
+
+  #[cfg(feature = "keyring")]
+  #[test]
+  fn test_profile_secret_header() {
+    secrets::memory_store::install();
+    secrets::store("profile-test-auth", "Bearer s3cr3t").unwrap();
+    let profile = Profile {
+      headers: BTreeMap::from([("Authorization".into(), "secret://profile-test-auth".into())]),
+      ..Profile::default()
+    };
+    let state = State {
+      profiles: BTreeMap::from([("prod".into(), profile)]),
+      active_profile: Some("prod".into()),
+      ..Default::default()
+    };
+    let mut editor = ParameterEditor::new(Arc::new(OperationItem::default()), true, Style::default());
+    editor.init(&state).unwrap();
+    let request = editor.request(reqwest::Client::new().get("https://example.com")).unwrap().build().unwrap();
+    let authorization = &request.headers()[reqwest::header::AUTHORIZATION];
+    assert_eq!(authorization, "Bearer s3cr3t");
+    assert!(authorization.is_sensitive());
+
+    secrets::delete("profile-test-auth").unwrap();
+    let error = editor.request(reqwest::Client::new().get("https://example.com")).unwrap_err();
+    assert!(error.to_string().starts_with("can't read secret `profile-test-auth`"));
+  }
+
+  #[cfg(feature = "keyring")]
+  #[test]
+  fn test_secret_parameters_resolved_when_sent() {
+    secrets::memory_store::install();
+    secrets::store("parameter-test-id", "p/1").unwrap();
+    secrets::store("parameter-test-key", "k&1").unwrap();
+    let mut editor = ParameterEditor::new(Arc::new(OperationItem::default()), true, Style::default());
+    for (location, name, value) in
+      [("Path", "id", "secret://parameter-test-id"), ("Query", "key", "secret://parameter-test-key")]
+    {
+      let items = vec![ParameterItem { name: name.into(), value: Some(value.into()), ..Default::default() }];
+      editor.parameters.push(ParameterTab { location: location.into(), items, table_state: TableState::default() });
+    }
+    let url = editor.path(String::from("https://example.com/pets/{id}")).unwrap();
+    let mut request = reqwest::Client::new().get(url).build().unwrap();
+    assert_eq!(
+      crate::request::template_url(&request),
+      "https://example.com/pets/{{secret://parameter-test-id}}?key={{secret://parameter-test-key}}"
+    );
+    secrets::resolve_url(&mut request).unwrap();
+    assert_eq!(request.url().as_str(), "https://example.com/pets/p%2F1?key=k%261");
+  }
+
+  #[test]
+  fn test_request_errors() {
+    let mut editor = ParameterEditor::new(Arc::new(OperationItem::default()), true, Style::default());
+    editor.parameters.push(ParameterTab {
+      location: "Header".to_string(),
+      items: vec![
+        ParameterItem { name: "x-ok".into(), value: Some("1".into()), ..Default::default() },
+        ParameterItem { name: "bad name".into(), ..Default::default() },
+        ParameterItem { name: "x-bad".into(), value: Some("caf\u{7f}".into()), ..Default::default() },
+      ],
+      table_state: TableState::default(),
+    });
+    let request = reqwest::Client::new().get("http://localhost");
+    let error = editor.request(request).unwrap_err();
+    assert_eq!(error.to_string(), r#"invalid header name "bad name", invalid value of header x-bad"#);
+    editor.parameters[0].items.truncate(1);
+    let request = editor.request(reqwest::Client::new().get("http://localhost")).unwrap().build().unwrap();
+    assert_eq!(request.headers()["x-ok"], "1");
+  }
+
+  #[test]
+  fn test_header_value() {
+    assert_eq!(ParameterItem::header_value("a=1;\n  b=2\r\n\n"), "a=1; b=2");
+    assert_eq!(QueryEncoding::Form.encode("a\nb"), "a%0Ab");
+  }
+
+  #[test]
+  fn test_shadowed_by() {
+    let item = ParameterItem { name: "X-Api-Key".into(), declared: true, ..Default::default() };
+    assert!(item.shadowed_by("header", "x-api-key"));
+    assert!(!item.shadowed_by("query", "x-api-key"));
+  }
+}
@@ -0,0 +1,102 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{
+  action::Action,
+  panes::Pane,
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+/// Popup switching between the profiles of the config, the first row goes back to no profile.
+#[derive(Default)]
+pub struct ProfilesPane {
+  item_index: usize,
+}
+
+impl ProfilesPane {
+  pub fn new(state: &State) -> Self {
+    let item_index = state
+      .active_profile
+      .as_ref()
+      .and_then(|active| state.profiles.keys().position(|name| name == active))
+      .map_or(0, |index| index + 1);
+    Self { item_index }
+  }
+
+  fn profile_names(state: &State) -> Vec<Option<String>> {
+    std::iter::once(None).chain(state.profiles.keys().cloned().map(Some)).collect()
+  }
+}
+
+impl Pane for ProfilesPane {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(2)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let response = match key.code {
+      KeyCode::Down | KeyCode::Char('j') => EventResponse::Stop(Action::Down),
+      KeyCode::Up | KeyCode::Char('k') => EventResponse::Stop(Action::Up),
+      KeyCode::Esc | KeyCode::Char('q') => EventResponse::Stop(Action::ClosePopup),
+      KeyCode::Enter => match Self::profile_names(state).get(self.item_index) {
+        Some(name) => EventResponse::Stop(Action::SwitchProfile(name.clone())),
+        None => EventResponse::Stop(Action::Noop),
+      },
+      _ => EventResponse::Stop(Action::Noop),
+    };
+    Ok(Some(response))
+  }
+
+  fn update(&mut self, action: Action, state: &mut State) -> Result<Option<Action>> {
+    let items_len = state.profiles.len() + 1;
+    match action {
+      Action::Down => self.item_index = self.item_index.saturating_add(1) % items_len,
+      Action::Up => self.item_index = self.item_index.saturating_add(items_len - 1) % items_len,
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let items = Self::profile_names(state)
+      .into_iter()
+      .map(|name| {
+        let active = name == state.active_profile;
+        let mut line = match &name {
+          Some(name) => Line::from(name.clone()),
+          None => Line::styled("no profile", Style::default().italic()),
+        };
+        if let Some(profile) = name.as_ref().and_then(|name| state.profiles.get(name)) {
+          line.push_span(Span::styled(format!("  {}", profile.summary()), Style::default().dim()));
+        }
+        if active {
+          line.push_span(Span::styled(" ●", Style::default().fg(Color::LightGreen)));
+        }
+        line
+      })
+      .collect::<Vec<_>>();
+    let list = List::new(items)
+      .highlight_symbol(symbols::scrollbar::HORIZONTAL.end)
+      .highlight_spacing(HighlightSpacing::Always)
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+    let mut list_state = ListState::default().with_selected(Some(self.item_index));
+    frame.render_stateful_widget(list, area.inner(Margin { horizontal: 1, vertical: 1 }), &mut list_state);
+
+    let hint = match state.profiles.is_empty() {
+      true => "no profiles in the config",
+      false => "[j,k → select] [enter → switch] [esc → close]",
+    };
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title("Profiles")
+        .title_bottom(Line::from(hint).right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,152 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{
+  action::Action,
+  config::{Config, Palette},
+  panes::Pane,
+  state::{OperationItem, State},
+  tui::{EventResponse, Frame},
+};
+
+struct RecentItem {
+  operation_id: String,
+  method: String,
+  path: String,
+}
+
+/// Quick switch popup over the recently viewed or called operations, filtered with a fuzzy query.
+#[derive(Default)]
+pub struct RecentPane {
+  items: Vec<RecentItem>,
+  input: Input,
+  item_index: usize,
+  palette: Palette,
+}
+
+impl RecentPane {
+  pub fn new(operation_items: Vec<&OperationItem>) -> Self {
+    let items = operation_items
+      .iter()
+      .filter_map(|operation_item| {
+        operation_item.operation.operation_id.as_ref().map(|operation_id| RecentItem {
+          operation_id: operation_id.clone(),
+          method: operation_item.method.clone(),
+          path: operation_item.path.clone(),
+        })
+      })
+      .collect();
+    Self { items, ..Self::default() }
+  }
+
+  fn visible_items(&self) -> impl Iterator<Item = &RecentItem> {
+    self.items.iter().filter(|item| {
+      fuzzy_match(self.input.value(), format!("{} {} {}", item.method, item.path, item.operation_id).as_str())
+    })
+  }
+}
+
+/// Whether all characters of the query appear in the text in the same order, ignoring case.
+fn fuzzy_match(query: &str, text: &str) -> bool {
+  let mut text = text.chars().flat_map(char::to_lowercase);
+  query.chars().flat_map(char::to_lowercase).all(|query_char| text.any(|text_char| text_char == query_char))
+}
+
+impl Pane for RecentPane {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.palette = config.palette;
+    Ok(())
+  }
+
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(3)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let response = match (key.code, key.modifiers) {
+      (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => EventResponse::Stop(Action::Down),
+      (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => EventResponse::Stop(Action::Up),
+      (KeyCode::Esc, _) => EventResponse::Stop(Action::ClosePopup),
+      (KeyCode::Enter, _) => match self.visible_items().nth(self.item_index) {
+        Some(item) => EventResponse::Stop(Action::NewCall(Some(item.operation_id.clone()))),
+        None => EventResponse::Stop(Action::Noop),
+      },
+      _ => {
+        self.input.handle_event(&Event::Key(key));
+        self.item_index = 0;
+        EventResponse::Stop(Action::Noop)
+      },
+    };
+    Ok(Some(response))
+  }
+
+  fn update(&mut self, action: Action, _state: &mut State) -> Result<Option<Action>> {
+    let items_len = self.visible_items().count();
+    match action {
+      Action::Down if items_len > 0 => {
+        self.item_index = self.item_index.saturating_add(1) % items_len;
+      },
+      Action::Up if items_len > 0 => {
+        self.item_index = self.item_index.saturating_add(items_len.saturating_sub(1)) % items_len;
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
+    let [input_area, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+
+    frame.render_widget(
+      Line::from(vec![Span::styled("> ", Style::default().fg(Color::LightBlue)), Span::raw(self.input.value())]),
+      input_area,
+    );
+    frame.set_cursor_position(Position::new(
+      input_area.x.saturating_add(2).saturating_add(self.input.visual_cursor() as u16),
+      input_area.y,
+    ));
+
+    let items = self
+      .visible_items()
+      .map(|item| {
+        Line::from(vec![
+          Span::styled(format!("{:7}", item.method), self.palette.method_style(item.method.as_str())),
+          Span::from(item.path.clone()),
+          Span::styled(format!(" #{}", item.operation_id), Style::default().dim()),
+        ])
+      })
+      .collect::<Vec<_>>();
+    let items_len = items.len();
+    let list = List::new(items)
+      .highlight_symbol(symbols::scrollbar::HORIZONTAL.end)
+      .highlight_spacing(HighlightSpacing::Always)
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+    let mut list_state = ListState::default().with_selected((items_len > 0).then_some(self.item_index));
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .title("Recent")
+        .title_bottom(Line::from(format!("{items_len} of {}", self.items.len())).right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fuzzy_match() {
+    assert!(fuzzy_match("gpet", "GET /pets/{petId} getPetById"));
+    assert!(fuzzy_match("", "GET /pets"));
+    assert!(!fuzzy_match("tep", "GET /pe"));
+  }
+}
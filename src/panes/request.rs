@@ -5,7 +5,13 @@ use ratatui::{
   widgets::{block::*, *},
 };
 
-use crate::{action::Action, components::schema_viewer::SchemaViewer, panes::Pane, state::State, tui::Frame};
+use crate::{
+  action::Action,
+  components::{markdown, schema_viewer::SchemaViewer},
+  panes::Pane,
+  state::{OperationItem, OperationItemType, State},
+  tui::Frame,
+};
 
 pub struct RequestType {
   location: String,
@@ -21,6 +27,8 @@ pub struct RequestPane {
   schemas: Vec<RequestType>,
   schemas_index: usize,
   schema_viewer: SchemaViewer,
+  docs: Vec<Line<'static>>,
+  docs_scroll: u16,
 }
 
 impl RequestPane {
@@ -31,6 +39,8 @@ impl RequestPane {
       schemas: Vec::default(),
       schemas_index: 0,
       schema_viewer: SchemaViewer::default(),
+      docs: vec![],
+      docs_scroll: 0,
     }
   }
 
@@ -70,6 +80,7 @@ impl RequestPane {
   fn init_schema(&mut self, state: &State) -> Result<()> {
     {
       self.schemas = vec![];
+      self.docs_scroll = 0;
 
       macro_rules! push_schema {
         ($map:ident, $title:expr, $location:expr) => {{
@@ -92,7 +103,10 @@ impl RequestPane {
             }
           });
 
-          push_schema!(bodies, "Body", "body");
+          match operation_item.r#type {
+            OperationItemType::Path => push_schema!(bodies, "Body", "body"),
+            OperationItemType::Webhook => push_schema!(bodies, "Payload", "body"),
+          }
         }
         let mut query_parameters = serde_json::Map::new();
         let mut header_parameters = serde_json::Map::new();
@@ -113,9 +127,18 @@ impl RequestPane {
         push_schema!(header_parameters, "Header", "header");
         push_schema!(path_parameters, "Path", "path");
         push_schema!(cookie_parameters, "Cookie", "cookie");
+
+        self.docs = markdown::render(Self::docs(operation_item, state).as_str());
+        if !self.docs.is_empty() {
+          self.schemas.push(RequestType {
+            location: String::from("docs"),
+            schema: serde_json::Value::Null,
+            title: String::from("Docs"),
+          });
+        }
       }
     }
-    if let Some(request_type) = self.schemas.get(self.schemas_index) {
+    if let Some(request_type) = self.schemas.get(self.schemas_index).filter(|item| item.location != "docs") {
       self.schema_viewer.set(request_type.schema.clone())?;
     } else {
       self.schema_viewer.clear();
@@ -123,6 +146,49 @@ impl RequestPane {
     Ok(())
   }
 
+  /// Summary and descriptions of an operation, its parameters and its body, in markdown.
+  fn docs(operation_item: &OperationItem, state: &State) -> String {
+    let operation = &operation_item.operation;
+    let mut docs = vec![];
+    if let Some(summary) = &operation.summary {
+      docs.push(format!("**{summary}**"));
+    }
+    if let Some(description) = &operation.description {
+      docs.push(description.clone());
+    }
+    let parameters = operation
+      .parameters
+      .iter()
+      .flatten()
+      .filter_map(|parameter_or_ref| parameter_or_ref.resolve(&state.openapi_spec).ok())
+      .filter_map(|parameter| {
+        let location = match parameter.r#in {
+          In::Query => "query",
+          In::Header => "header",
+          In::Path => "path",
+          In::Cookie => "cookie",
+        };
+        Some(format!("- `{}` ({location}): {}", parameter.name, parameter.description.as_ref()?))
+      })
+      .collect::<Vec<_>>();
+    if !parameters.is_empty() {
+      docs.push(format!("**Parameters**\n\n{}", parameters.join("\n")));
+    }
+    if let Some(description) = operation
+      .request_body
+      .as_ref()
+      .and_then(|request_body| request_body.resolve(&state.openapi_spec).ok())
+      .and_then(|request_body| request_body.description.clone())
+    {
+      docs.push(format!("**Body**\n\n{description}"));
+    }
+    docs.join("\n\n")
+  }
+
+  fn is_docs_tab(&self) -> bool {
+    self.schemas.get(self.schemas_index).is_some_and(|item| item.location == "docs")
+  }
+
   fn nested_schema_path_line(&self) -> Line {
     let schema_path = self.schema_viewer.schema_path();
     if schema_path.is_empty() {
@@ -158,6 +224,12 @@ impl Pane for RequestPane {
         self.schemas_index = 0;
         self.init_schema(state)?;
       },
+      Action::Down if self.is_docs_tab() => {
+        self.docs_scroll = self.docs_scroll.saturating_add(1).min(self.docs.len().saturating_sub(1) as u16);
+      },
+      Action::Up if self.is_docs_tab() => {
+        self.docs_scroll = self.docs_scroll.saturating_sub(1);
+      },
       Action::Down => {
         self.schema_viewer.down();
       },
@@ -179,13 +251,32 @@ impl Pane for RequestPane {
       },
       Action::Focus => {
         self.focused = true;
-        static STATUS_LINE: &str = "[1-9 → select tab] [g,b → go/back definitions]";
+        static STATUS_LINE: &str =
+          "[1-9 → select tab] [g,b → go/back definitions] [y,Y → copy pointer/name] [w → wrap] [<,> → scroll]";
         return Ok(Some(Action::TimedStatusLine(STATUS_LINE.into(), 3)));
       },
       Action::UnFocus => {
         self.focused = false;
       },
       Action::Go => self.schema_viewer.go()?,
+      Action::ToggleWrap => {
+        let status_line = if self.schema_viewer.toggle_wrap() { "long lines wrapped" } else { "long lines clipped" };
+        return Ok(Some(Action::TimedStatusLine(status_line.into(), 3)));
+      },
+      Action::ScrollLeft => self.schema_viewer.scroll_left(),
+      Action::ScrollRight => self.schema_viewer.scroll_right(),
+      Action::CopySchemaPointer => {
+        return Ok(Some(match self.schema_viewer.schema_pointer() {
+          Some(pointer) => Action::CopyToClipboard(pointer),
+          None => Action::TimedStatusLine("go into a $ref definition to copy its pointer".into(), 3),
+        }));
+      },
+      Action::CopySchemaName => {
+        return Ok(Some(match self.schema_viewer.schema_name() {
+          Some(schema_name) => Action::CopyToClipboard(schema_name),
+          None => Action::TimedStatusLine("go into a $ref definition to copy its name".into(), 3),
+        }));
+      },
       Action::Back => {
         if let Some(request_type) = self.schemas.get(self.schemas_index) {
           self.schema_viewer.back(request_type.schema.clone())?;
@@ -197,7 +288,7 @@ impl Pane for RequestPane {
     Ok(None)
   }
 
-  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
     let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
 
     frame.render_widget(
@@ -212,8 +303,21 @@ impl Pane for RequestPane {
 
     let mut inner = inner.inner(Margin { horizontal: 1, vertical: 1 });
     inner.height = inner.height.saturating_add(1);
-    self.schema_viewer.render_widget(frame, inner);
+    if self.is_docs_tab() {
+      frame.render_widget(
+        Paragraph::new(self.docs.clone()).wrap(Wrap { trim: false }).scroll((self.docs_scroll, 0)),
+        inner,
+      );
+    } else {
+      self.schema_viewer.render_widget(frame, inner);
+    }
 
+    let docs_hint = match state.external_docs_url() {
+      Some(_) => {
+        Line::styled(format!("[o {} external docs]", symbols::scrollbar::HORIZONTAL.end), Style::default().dim())
+      },
+      None => Line::default(),
+    };
     frame.render_widget(
       Block::default()
         .title("Request")
@@ -225,7 +329,8 @@ impl Pane for RequestPane {
             .nested_schema_path_line()
             .style(Style::default().fg(Color::White).dim().add_modifier(Modifier::ITALIC))
             .left_aligned(),
-        ),
+        )
+        .title_bottom(docs_hint.right_aligned()),
       area,
     );
 
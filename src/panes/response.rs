@@ -4,7 +4,14 @@ use ratatui::{
   widgets::{block::*, *},
 };
 
-use crate::{action::Action, components::schema_viewer::SchemaViewer, panes::Pane, state::State, tui::Frame};
+use crate::{
+  action::Action,
+  components::schema_viewer::SchemaViewer,
+  config::{Config, Palette},
+  panes::Pane,
+  state::State,
+  tui::Frame,
+};
 
 pub struct ResponseType {
   status: String,
@@ -20,6 +27,7 @@ pub struct ResponsePane {
   schemas: Vec<ResponseType>,
   schemas_index: usize,
   schema_viewer: SchemaViewer,
+  palette: Palette,
 }
 
 impl ResponsePane {
@@ -30,6 +38,7 @@ impl ResponsePane {
       schemas: Vec::default(),
       schemas_index: 0,
       schema_viewer: SchemaViewer::default(),
+      palette: Palette::default(),
     }
   }
 
@@ -47,22 +56,6 @@ impl ResponsePane {
     }
   }
 
-  fn status_color(&self, status: &str) -> Color {
-    if status.starts_with('2') || status.starts_with("default") {
-      return Color::LightCyan;
-    }
-    if status.starts_with('3') {
-      return Color::LightBlue;
-    }
-    if status.starts_with('4') {
-      return Color::LightYellow;
-    }
-    if status.starts_with('5') {
-      return Color::LightRed;
-    }
-    Color::default()
-  }
-
   fn nested_schema_path_line(&self) -> Line {
     let schema_path = self.schema_viewer.schema_path();
     if schema_path.is_empty() {
@@ -117,6 +110,11 @@ impl ResponsePane {
 }
 
 impl Pane for ResponsePane {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.palette = config.palette;
+    Ok(())
+  }
+
   fn init(&mut self, state: &State) -> Result<()> {
     self.schema_viewer.set_components(state);
     self.init_schema(state)?;
@@ -161,13 +159,32 @@ impl Pane for ResponsePane {
       },
       Action::Focus => {
         self.focused = true;
-        static STATUS_LINE: &str = "[1-9 → select tab] [g,b → go/back definitions]";
+        static STATUS_LINE: &str =
+          "[1-9 → select tab] [g,b → go/back definitions] [y,Y → copy pointer/name] [w → wrap] [<,> → scroll]";
         return Ok(Some(Action::TimedStatusLine(STATUS_LINE.into(), 3)));
       },
       Action::UnFocus => {
         self.focused = false;
       },
       Action::Go => self.schema_viewer.go()?,
+      Action::ToggleWrap => {
+        let status_line = if self.schema_viewer.toggle_wrap() { "long lines wrapped" } else { "long lines clipped" };
+        return Ok(Some(Action::TimedStatusLine(status_line.into(), 3)));
+      },
+      Action::ScrollLeft => self.schema_viewer.scroll_left(),
+      Action::ScrollRight => self.schema_viewer.scroll_right(),
+      Action::CopySchemaPointer => {
+        return Ok(Some(match self.schema_viewer.schema_pointer() {
+          Some(pointer) => Action::CopyToClipboard(pointer),
+          None => Action::TimedStatusLine("go into a $ref definition to copy its pointer".into(), 3),
+        }));
+      },
+      Action::CopySchemaName => {
+        return Ok(Some(match self.schema_viewer.schema_name() {
+          Some(schema_name) => Action::CopyToClipboard(schema_name),
+          None => Action::TimedStatusLine("go into a $ref definition to copy its name".into(), 3),
+        }));
+      },
       Action::Back => {
         if let Some(response_type) = self.schemas.get(self.schemas_index) {
           self.schema_viewer.back(response_type.schema.clone())?;
@@ -184,8 +201,8 @@ impl Pane for ResponsePane {
     frame.render_widget(
       Tabs::new(self.schemas.iter().map(|resp| {
         Span::styled(
-          format!("{} [{}]", resp.status, resp.media_type),
-          Style::default().fg(self.status_color(resp.status.as_str())).dim(),
+          format!("{}{} [{}]", self.palette.status_symbol(resp.status.as_str()), resp.status, resp.media_type),
+          self.palette.status_style(resp.status.as_str()).dim(),
         )
       }))
       .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED).not_dim())
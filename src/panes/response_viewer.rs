@@ -1,31 +1,538 @@
-use std::{io::Write, sync::Arc};
+use std::{
+  collections::HashSet,
+  path::Path,
+  process::Stdio,
+  sync::Arc,
+  time::{Duration, Instant},
+};
 
-use color_eyre::eyre::Result;
-use crossterm::event::KeyEvent;
+use color_eyre::eyre::{bail, ContextCompat, Result};
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
   prelude::*,
   widgets::{block::*, *},
 };
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use tokio::{io::AsyncWriteExt, sync::mpsc::UnboundedSender};
 
 use crate::{
   action::Action,
+  assertions::{self, AssertionResults},
+  components::{image_preview::ImagePreview, json_tree::JsonTree},
+  config::Config,
+  discriminator::{self, Variant},
+  humanize,
+  json_diff::{self, Change},
+  jsonpath::{self, Segment},
+  media_type,
   pages::phone::{RequestBuilder, RequestPane},
+  pagination::PageDirection,
   panes::Pane,
+  response::{BodyFormat, Response},
+  schema_inference,
   state::{InputMode, OperationItem, State},
+  timestamps, trace,
   tui::{EventResponse, Frame},
+  utils::ago,
 };
 
+/// Which response of the operation is shown, cycled with [d] once there's a previous one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ResponseView {
+  #[default]
+  Latest,
+  Previous,
+  Diff,
+}
+
+const RESPONSE_TABS: [&str; 8] = ["Body", "Tree", "Raw", "Headers", "Cookies", "Timeline", "TLS", "Proposed schema"];
+const TREE_TAB_INDEX: usize = 1;
+const TIMELINE_TAB_INDEX: usize = 5;
+const TLS_TAB_INDEX: usize = 6;
+/// How long a pipe may run before it's killed, so a command waiting for input doesn't leave the body empty.
+const PIPE_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub struct ResponseViewer {
   focused: bool,
   focused_border_style: Style,
   operation_item: Arc<OperationItem>,
   content_types: Vec<String>,
   content_type_index: usize,
+  dry_run_output: Option<String>,
+  poll_progress: Option<String>,
+  diff: Option<Vec<Change>>,
+  image_preview: ImagePreview,
+  variant: Option<(Instant, Option<Variant>)>,
+  body_format: Option<BodyFormat>,
+  /// Name of the configured pipe the body is shown through, with its output for the shown response.
+  pipe: Option<String>,
+  pipe_output: Option<(Instant, String)>,
+  /// Pipe run in the background, with the response it runs for.
+  pipe_run: Option<(usize, Instant)>,
+  pipe_runs: usize,
+  command_tx: Option<UnboundedSender<Action>>,
+  tab_index: usize,
+  json_tree: JsonTree,
+  json_tree_received_at: Option<Instant>,
+  annotate_timestamps: bool,
+  humanize_numbers: bool,
+  /// Hides the timestamp and number annotations without turning them off.
+  raw: bool,
+  /// Results of the assertions of the operation on the shown response, computed once per response.
+  assertion_results: Option<(Instant, AssertionResults)>,
+  view: ResponseView,
+  /// Changes from the previous to the latest JSON response, computed once per latest response.
+  previous_changes: Option<(Instant, Option<Vec<Change>>)>,
+  /// Response pinned next to the shown one to compare them, with the server it came from.
+  pinned: Option<(Response, String)>,
+  /// Lines scrolled in both sides of the comparison with the pinned response.
+  pinned_scroll: u16,
+  /// Highlights the lines of each side of the comparison that the other side doesn't have.
+  pinned_overlay: bool,
+  config: Config,
 }
 
 impl ResponseViewer {
   pub fn new(operation_item: Arc<OperationItem>, focused: bool, focused_border_style: Style) -> Self {
-    Self { operation_item, focused, focused_border_style, content_types: vec![], content_type_index: 0 }
+    Self {
+      operation_item,
+      focused,
+      focused_border_style,
+      content_types: vec![],
+      content_type_index: 0,
+      dry_run_output: None,
+      poll_progress: None,
+      diff: None,
+      image_preview: ImagePreview::default(),
+      variant: None,
+      body_format: None,
+      pipe: None,
+      pipe_output: None,
+      pipe_run: None,
+      pipe_runs: 0,
+      command_tx: None,
+      tab_index: 0,
+      json_tree: JsonTree::default(),
+      json_tree_received_at: None,
+      annotate_timestamps: false,
+      humanize_numbers: false,
+      raw: false,
+      assertion_results: None,
+      view: ResponseView::default(),
+      previous_changes: None,
+      pinned: None,
+      pinned_scroll: 0,
+      pinned_overlay: false,
+      config: Config::default(),
+    }
+  }
+
+  fn diff_response(&self, filepath: &str, state: &State) -> Result<Vec<Change>> {
+    let response = self
+      .operation_item
+      .operation
+      .operation_id
+      .as_ref()
+      .and_then(|operation_id| state.responses.get(operation_id))
+      .context("response is not available")?;
+    let expected = serde_json::from_str::<serde_json::Value>(std::fs::read_to_string(filepath)?.as_str())?;
+    let actual = serde_json::from_str::<serde_json::Value>(response.body.as_str())?;
+    Ok(json_diff::diff(&expected, &actual, &self.config.diff.ignore))
+  }
+
+  fn split_response(&self, state: &State) -> Result<()> {
+    let operation_id = self.operation_item.operation.operation_id.clone().unwrap_or_default();
+    let response = state.responses.get(&operation_id).context("response is not available")?;
+    let command = self.config.split.command().context("no split command is configured")?;
+    let extension = if serde_json::from_str::<serde_json::Value>(&response.body).is_ok() { "json" } else { "txt" };
+    let filepath = std::env::temp_dir().join(split_file_name(operation_id.as_str(), extension));
+    std::fs::write(&filepath, response.body.as_bytes())?;
+    let output = split_command(command.as_str(), filepath.as_path()).stdin(Stdio::null()).output()?;
+    if !output.status.success() {
+      bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+  }
+
+  /// Output of the pipe the body is shown through, run once per response in the background and delivered with
+  /// [`Action::PipedBody`].
+  fn piped_body(&mut self, response: &Response) -> Option<String> {
+    let name = self.pipe.clone()?;
+    if let Some((received_at, output)) = &self.pipe_output {
+      if *received_at == response.received_at {
+        return Some(output.clone());
+      }
+    }
+    let Some(command) = self.config.response.pipes.get(&name).cloned() else {
+      return Some(format!("no pipe named {name} is configured\n\n{}", response.body));
+    };
+    if self.pipe_run.map(|(_, received_at)| received_at) != Some(response.received_at) {
+      let command_tx = self.command_tx.clone()?;
+      self.pipe_runs += 1;
+      self.pipe_run = Some((self.pipe_runs, response.received_at));
+      let run = self.pipe_runs;
+      let operation_id = self.operation_item.operation.operation_id.clone().unwrap_or_default();
+      let (content, body, pipe) = (response.content().into_owned(), response.body.clone(), name.clone());
+      tokio::spawn(async move {
+        let output = pipe_body(command.as_str(), content)
+          .await
+          .unwrap_or_else(|error| format!("can't pipe the body to {pipe}: {error}\n\n{body}"));
+        command_tx.send(Action::PipedBody(operation_id, run, output)).ok();
+      });
+    }
+    Some(format!("running {name}…"))
+  }
+
+  /// Variant of a polymorphic response schema matching the response, computed once per response.
+  fn variant(&mut self, response: &Response, state: &State) -> Option<Variant> {
+    if let Some((received_at, variant)) = &self.variant {
+      if *received_at == response.received_at {
+        return variant.clone();
+      }
+    }
+    let content_type = response.headers.get(CONTENT_TYPE).and_then(|content_type| content_type.to_str().ok());
+    let variant = discriminator::response_schema(
+      &self.operation_item.operation,
+      &state.openapi_spec,
+      response.status.as_str(),
+      content_type,
+    )
+    .zip(serde_json::from_str::<serde_json::Value>(response.body.as_str()).ok())
+    .and_then(|(schema, payload)| {
+      let components = state.openapi_spec.components.as_ref()?.schemas.as_ref()?;
+      discriminator::variant(&schema, &payload, components)
+    });
+    self.variant = Some((response.received_at, variant.clone()));
+    variant
+  }
+
+  /// Popup explaining the status of the response, with the responses documented for the operation.
+  fn status_info(&self, state: &State) -> Option<Action> {
+    let response = state.responses.get(self.operation_item.operation.operation_id.as_ref()?)?;
+    let documented = self
+      .operation_item
+      .operation
+      .responses
+      .iter()
+      .flatten()
+      .map(|(key, response)| {
+        let description =
+          response.resolve(&state.openapi_spec).map(|response| response.description).unwrap_or_default();
+        (key.clone(), description)
+      })
+      .collect();
+    Some(Action::ShowStatusInfo(response.status.as_u16(), documented))
+  }
+
+  /// Changes from the previous to the latest response of the operation, when both are JSON.
+  fn previous_changes(&mut self, state: &State) -> Option<Vec<Change>> {
+    let operation_id = self.operation_item.operation.operation_id.as_ref()?;
+    let latest = state.responses.get(operation_id)?;
+    if let Some((received_at, changes)) = &self.previous_changes {
+      if *received_at == latest.received_at {
+        return changes.clone();
+      }
+    }
+    let changes = state.previous_responses.get(operation_id).and_then(|previous| {
+      let previous = serde_json::from_str(previous.body.as_str()).ok()?;
+      let latest = serde_json::from_str(latest.body.as_str()).ok()?;
+      Some(json_diff::diff(&previous, &latest, &self.config.diff.ignore))
+    });
+    self.previous_changes = Some((latest.received_at, changes.clone()));
+    changes
+  }
+
+  /// Cycles the latest response, the previous one and the changes between them, skipping the diff of non-JSON
+  /// responses.
+  fn cycle_view(&mut self, state: &State) -> Option<Action> {
+    let operation_id = self.operation_item.operation.operation_id.as_ref()?;
+    if !state.previous_responses.contains_key(operation_id) {
+      return Some(Action::TimedStatusLine("no previous response to compare with".into(), 3));
+    }
+    let has_changes = self.previous_changes(state).is_some();
+    self.view = match self.view {
+      ResponseView::Latest => ResponseView::Previous,
+      ResponseView::Previous if has_changes => ResponseView::Diff,
+      _ => ResponseView::Latest,
+    };
+    None
+  }
+
+  /// Pass or fail chips of the assertions of the operation on a response.
+  fn assertion_chips(&mut self, response: &Response, state: &State) -> Line<'static> {
+    let assertions = self
+      .operation_item
+      .operation
+      .operation_id
+      .as_ref()
+      .and_then(|operation_id| state.assertions.get(operation_id))
+      .map(Vec::as_slice)
+      .unwrap_or_default();
+    if self.assertion_results.as_ref().map(|(received_at, _)| *received_at) != Some(response.received_at) {
+      self.assertion_results =
+        Some((response.received_at, assertions::evaluate(assertions, response.status, response.body.as_str())));
+    }
+    let results = self.assertion_results.as_ref().map(|(_, results)| results.as_slice()).unwrap_or_default();
+    let mut chips = Line::default();
+    for (text, result) in results {
+      let chip = match result {
+        Ok(()) => Span::styled(format!(" ✓ {text} "), Style::default().fg(Color::Black).bg(Color::LightGreen)),
+        Err(error) => Span::styled(format!(" ✗ {text}: {error} "), Style::default().fg(Color::White).bg(Color::Red)),
+      };
+      chips.push_span(chip);
+      chips.push_span(Span::raw(" "));
+    }
+    chips
+  }
+
+  /// Body, pretty printed or shown as the chosen format, with the discriminated variant schema next to it.
+  fn draw_body(&mut self, frame: &mut Frame<'_>, area: Rect, response: &Response, state: &State) {
+    let mut body_area = area;
+    if let Some(variant) = self.variant(response, state) {
+      let [area, variant_area] = Layout::horizontal([Constraint::Fill(3), Constraint::Fill(1)]).areas(area);
+      body_area = area;
+      frame.render_widget(
+        Paragraph::new(serde_yaml::to_string(&variant.schema).unwrap_or_default()).style(Style::default().dim()).block(
+          Block::default().borders(Borders::LEFT).title(Line::from(vec![
+            Span::styled(variant.name, Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!(" ({}={})", variant.property_name, variant.value)),
+          ])),
+        ),
+        variant_area,
+      );
+    }
+    if let Some(body) = self.piped_body(response) {
+      frame.render_widget(Paragraph::new(body).wrap(Wrap { trim: false }), body_area);
+    } else if let Some(body_format) = self.body_format {
+      let body = response
+        .format_body(body_format)
+        .unwrap_or_else(|error| format!("can't show the body as {body_format}: {error}\n\n{}", response.body));
+      frame.render_widget(Paragraph::new(body).wrap(Wrap { trim: false }), body_area);
+    } else if ImagePreview::is_image(response) {
+      self.image_preview.set(response);
+      self.image_preview.render_widget(frame, body_area, self.config.display.image_protocol());
+    } else if !self.raw && (self.annotate_timestamps || self.humanize_numbers) {
+      let now = chrono::Utc::now();
+      let lines = response
+        .pretty_body()
+        .lines()
+        .map(|line| {
+          let annotation = self
+            .annotate_timestamps
+            .then(|| timestamps::annotation(line, now, &chrono::Local))
+            .flatten()
+            .or_else(|| self.humanize_numbers.then(|| humanize::annotation(line)).flatten());
+          let mut spans = vec![Span::raw(line.to_string())];
+          if let Some(annotation) = annotation {
+            spans.push(Span::styled(format!("  {annotation}"), Style::default().dim().italic()));
+          }
+          Line::from(spans)
+        })
+        .collect::<Vec<_>>();
+      frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), body_area);
+    } else {
+      frame.render_widget(Paragraph::new(response.pretty_body()).wrap(Wrap { trim: false }), body_area);
+    }
+  }
+
+  /// Loads the body of a new response into the tree, false when the body is not JSON.
+  fn sync_json_tree(&mut self, response: &Response) -> bool {
+    if self.json_tree_received_at != Some(response.received_at) {
+      let Ok(body) = serde_json::from_str::<serde_json::Value>(response.body.as_str()) else {
+        self.json_tree_received_at = None;
+        return false;
+      };
+      self.json_tree.set(body);
+      self.json_tree_received_at = Some(response.received_at);
+    }
+    true
+  }
+
+  fn is_tree_active(&self) -> bool {
+    self.tab_index == TREE_TAB_INDEX && self.json_tree_received_at.is_some() && self.diff.is_none()
+  }
+
+  fn is_documented(&self, response: &Response, state: &State) -> bool {
+    let content_type = response.headers.get(CONTENT_TYPE).and_then(|content_type| content_type.to_str().ok());
+    discriminator::is_documented(
+      &self.operation_item.operation,
+      &state.openapi_spec,
+      response.status.as_str(),
+      content_type,
+    )
+  }
+
+  /// Responses entry with a schema inferred from the JSON body, for backfilling undocumented responses.
+  fn proposed_schema(response: &Response) -> Option<serde_json::Value> {
+    let body = serde_json::from_str::<serde_json::Value>(response.body.as_str()).ok()?;
+    let content_type = response
+      .headers
+      .get(CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .map(media_type::essence)
+      .unwrap_or_else(|| String::from("application/json"));
+    Some(schema_inference::response_snippet(
+      response.status.as_str(),
+      content_type.as_str(),
+      schema_inference::infer(&body),
+    ))
+  }
+
+  fn export_proposed_schema(&self, filepath: &str, state: &State) -> Result<()> {
+    let response = self
+      .operation_item
+      .operation
+      .operation_id
+      .as_ref()
+      .and_then(|operation_id| state.responses.get(operation_id))
+      .context("response is not available")?;
+    let snippet = Self::proposed_schema(response).context("response body is not JSON")?;
+    std::fs::write(filepath, serde_yaml::to_string(&snippet)?)?;
+    Ok(())
+  }
+
+  /// Link to the trace of the response in the configured tracing ui.
+  fn trace_link(&self, state: &State) -> Option<String> {
+    let trace_id = self
+      .operation_item
+      .operation
+      .operation_id
+      .as_ref()
+      .and_then(|operation_id| state.responses.get(operation_id))
+      .and_then(|response| response.trace_id.as_ref())?;
+    let template = self.config.trace.link_template.as_str();
+    (!template.is_empty()).then(|| trace::link(template, trace_id))
+  }
+
+  /// Keys scrolling both sides of the comparison with the pinned response or toggling its overlay.
+  fn is_pinnable_key(&self, key_code: KeyCode) -> bool {
+    matches!(
+      key_code,
+      KeyCode::Down
+        | KeyCode::Up
+        | KeyCode::PageDown
+        | KeyCode::PageUp
+        | KeyCode::Char('j')
+        | KeyCode::Char('k')
+        | KeyCode::Char('J')
+        | KeyCode::Char('K')
+        | KeyCode::Char('x')
+    )
+  }
+
+  /// Shown response, the latest or the previous one.
+  fn shown_response<'a>(&self, state: &'a State) -> Option<&'a Response> {
+    let operation_id = self.operation_item.operation.operation_id.as_ref()?;
+    match self.view {
+      ResponseView::Previous => state.previous_responses.get(operation_id),
+      _ => state.responses.get(operation_id),
+    }
+  }
+
+  /// Pinned response on the left and the shown one on the right. Lines aren't wrapped so both sides scroll together
+  /// line by line.
+  fn draw_pinned(&self, frame: &mut Frame<'_>, area: Rect, response: &Response) {
+    let Some((pinned, server)) = &self.pinned else {
+      return;
+    };
+    let (pinned_body, shown_body) = (pinned.pretty_body(), response.pretty_body());
+    let overlay = |body: &str, other: &str, style: Style| {
+      let other = other.lines().collect::<HashSet<_>>();
+      body
+        .lines()
+        .map(|line| match self.pinned_overlay && !other.contains(line) {
+          true => Line::styled(line.to_string(), style),
+          false => Line::raw(line.to_string()),
+        })
+        .collect::<Vec<_>>()
+    };
+    let [pinned_area, shown_area] = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+    let title = |response: &Response, name: &str| {
+      let status = response.status.as_str();
+      Line::from(vec![
+        Span::styled(
+          format!("{}{status}", self.config.palette.status_symbol(status)),
+          self.config.palette.status_style(status),
+        ),
+        Span::styled(format!(" {name} {} {}", symbols::DOT, ago(response.received_at)), Style::default().dim()),
+      ])
+    };
+    frame.render_widget(
+      Paragraph::new(overlay(&pinned_body, &shown_body, Style::default().fg(Color::LightRed)))
+        .scroll((self.pinned_scroll, 0))
+        .block(Block::default().borders(Borders::RIGHT).title(title(pinned, format!("pinned {server}").trim_end()))),
+      pinned_area,
+    );
+    let shown = if self.view == ResponseView::Previous { "previous" } else { "latest" };
+    frame.render_widget(
+      Paragraph::new(overlay(&shown_body, &pinned_body, Style::default().fg(Color::LightGreen)))
+        .scroll((self.pinned_scroll, 0))
+        .block(Block::default().padding(Padding::left(1)).title(title(response, shown))),
+      shown_area,
+    );
+  }
+
+  /// HTTP version of the response and the certificate the server presented.
+  fn draw_tls(&self, frame: &mut Frame<'_>, area: Rect, response: &Response) {
+    let row = |name: &str, value: Line<'static>| {
+      Row::new(vec![
+        Cell::from(name.to_string()).style(Style::default().add_modifier(Modifier::BOLD)),
+        Cell::from(value),
+      ])
+    };
+    let mut rows = vec![row("HTTP version", Line::raw(format!("{:?}", response.version)))];
+    match &response.certificate {
+      Some(certificate) => {
+        let now = chrono::Utc::now();
+        let time = |time: Option<chrono::DateTime<chrono::Utc>>| {
+          time.map_or(String::from("unknown"), |time| {
+            format!("{}, {}", time.format("%Y-%m-%d %H:%M:%S UTC"), timestamps::relative(time, now))
+          })
+        };
+        let expiry_style = match certificate.not_after {
+          Some(not_after) if not_after < now => Style::default().fg(Color::LightRed),
+          Some(not_after) if not_after < now + chrono::Duration::days(14) => Style::default().fg(Color::LightYellow),
+          _ => Style::default(),
+        };
+        rows.extend([
+          row("Subject", Line::raw(certificate.subject.clone())),
+          row("Issuer", Line::raw(certificate.issuer.clone())),
+          row("Valid from", Line::raw(time(certificate.not_before))),
+          row("Valid until", Line::styled(time(certificate.not_after), expiry_style)),
+          row("Alternative names", Line::raw(certificate.alt_names.join(", "))),
+          row("Fingerprint", Line::raw(certificate.fingerprint.clone())),
+        ]);
+        if let Some(previous) = &response.certificate_changed {
+          let changed = Style::default().fg(Color::LightRed);
+          rows.push(row("Remembered", Line::styled(format!("{previous}, the certificate changed"), changed)));
+        }
+      },
+      None => {
+        rows.push(row("Certificate", Line::styled("none, the call wasn't made over TLS", Style::default().dim())))
+      },
+    }
+    frame.render_widget(Table::new(rows, [Constraint::Length(18), Constraint::Fill(1)]), area);
+  }
+
+  fn diff_lines<'a>(changes: &'a [Change], unchanged: &'a str) -> Vec<Line<'a>> {
+    if changes.is_empty() {
+      return vec![Line::styled(unchanged, Style::default().fg(Color::LightGreen))];
+    }
+    changes
+      .iter()
+      .map(|change| match change {
+        Change::Added(path, value) => {
+          Line::styled(format!("+ {path}: {value}"), Style::default().fg(Color::LightGreen))
+        },
+        Change::Removed(path, value) => {
+          Line::styled(format!("- {path}: {value}"), Style::default().fg(Color::LightRed))
+        },
+        Change::Changed(path, expected, actual) => Line::styled(
+          format!("~ {path}: {expected} {} {actual}", symbols::scrollbar::HORIZONTAL.end),
+          Style::default().fg(Color::LightYellow),
+        ),
+      })
+      .collect()
   }
 
   fn border_style(&self) -> Style {
@@ -46,12 +553,11 @@ impl ResponseViewer {
 impl RequestPane for ResponseViewer {}
 
 impl RequestBuilder for ResponseViewer {
-  fn reqeust(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-    if let Some(content_type) = self.content_types.get(self.content_type_index) {
-      request.header("accept", content_type)
-    } else {
-      request
-    }
+  fn request(&self, request: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder> {
+    let Some(content_type) = self.content_types.get(self.content_type_index) else {
+      return Ok(request);
+    };
+    Ok(request.header("accept", content_type))
   }
 }
 
@@ -71,6 +577,18 @@ impl Pane for ResponseViewer {
     Ok(())
   }
 
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+    self.command_tx = Some(tx);
+    Ok(())
+  }
+
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.annotate_timestamps = config.response.annotate_timestamps;
+    self.humanize_numbers = config.response.humanize_numbers;
+    self.config = config;
+    Ok(())
+  }
+
   fn height_constraint(&self) -> Constraint {
     match self.focused {
       true => Constraint::Fill(3),
@@ -78,9 +596,70 @@ impl Pane for ResponseViewer {
     }
   }
 
-  fn handle_key_events(&mut self, _key: KeyEvent, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+  fn handle_key_events(&mut self, key: KeyEvent, state: &mut State) -> Result<Option<EventResponse<Action>>> {
     match state.input_mode {
-      InputMode::Normal => Ok(None),
+      InputMode::Normal if self.is_tree_active() => {
+        match key.code {
+          KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => self.json_tree.down(),
+          KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => self.json_tree.up(),
+          KeyCode::Char(' ') => self.json_tree.toggle(),
+          KeyCode::Char('c') | KeyCode::Char('C') => {
+            return Ok(
+              self.json_tree.selected_value().map(|value| EventResponse::Stop(Action::CopyToClipboard(value))),
+            );
+          },
+          KeyCode::Char('y') => {
+            let pointer = self.json_tree.selected_path().map(|path| jsonpath::pointer(&path));
+            return Ok(pointer.map(|pointer| EventResponse::Stop(Action::CopyToClipboard(pointer))));
+          },
+          KeyCode::Char('Y') => {
+            let path = self.json_tree.selected_path().map(|path| jsonpath::format(&path));
+            return Ok(path.map(|path| EventResponse::Stop(Action::CopyToClipboard(path))));
+          },
+          KeyCode::Char('v') | KeyCode::Char('V') => {
+            let capture = self.json_tree.selected_path().map(|path| {
+              let name = match path.last() {
+                Some(Segment::Key(key)) => key.clone(),
+                _ => String::from("value"),
+              };
+              Action::Capture(name, jsonpath::format(&path))
+            });
+            return Ok(capture.map(EventResponse::Stop));
+          },
+          _ => return Ok(None),
+        }
+        Ok(Some(EventResponse::Stop(Action::Noop)))
+      },
+      InputMode::Normal if self.pinned.is_some() && self.tab_index == 0 && self.is_pinnable_key(key.code) => {
+        match key.code {
+          KeyCode::Down | KeyCode::Char('j') => self.pinned_scroll = self.pinned_scroll.saturating_add(1),
+          KeyCode::Up | KeyCode::Char('k') => self.pinned_scroll = self.pinned_scroll.saturating_sub(1),
+          KeyCode::PageDown | KeyCode::Char('J') => self.pinned_scroll = self.pinned_scroll.saturating_add(10),
+          KeyCode::PageUp | KeyCode::Char('K') => self.pinned_scroll = self.pinned_scroll.saturating_sub(10),
+          _ => self.pinned_overlay = !self.pinned_overlay,
+        }
+        Ok(Some(EventResponse::Stop(Action::Noop)))
+      },
+      InputMode::Normal => match key.code {
+        KeyCode::Char('n') => Ok(Some(EventResponse::Stop(Action::Paginate(PageDirection::Next, false)))),
+        KeyCode::Char('N') => Ok(Some(EventResponse::Stop(Action::Paginate(PageDirection::Next, true)))),
+        KeyCode::Char('p') | KeyCode::Char('P') => {
+          Ok(Some(EventResponse::Stop(Action::Paginate(PageDirection::Prev, false))))
+        },
+        KeyCode::Char('g') | KeyCode::Char('G') => Ok(Some(EventResponse::Stop(Action::FollowLocation))),
+        KeyCode::Char('s') | KeyCode::Char('S') => Ok(self.status_info(state).map(EventResponse::Stop)),
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+          Ok(Some(EventResponse::Stop(self.cycle_view(state).unwrap_or(Action::Noop))))
+        },
+        KeyCode::Char('t') | KeyCode::Char('T') => {
+          Ok(self.trace_link(state).map(|link| EventResponse::Stop(Action::OpenInBrowser(link))))
+        },
+        KeyCode::Char('a') | KeyCode::Char('A') if !self.content_types.is_empty() => {
+          self.content_type_index = (self.content_type_index + 1) % self.content_types.len();
+          Ok(Some(EventResponse::Stop(Action::Noop)))
+        },
+        _ => Ok(None),
+      },
       InputMode::Insert => Ok(None),
       InputMode::Command => Ok(None),
     }
@@ -90,17 +669,14 @@ impl Pane for ResponseViewer {
     match action {
       Action::Update => {},
       Action::Submit => return Ok(Some(Action::Dial)),
-      Action::Tab(index) if !self.content_types.is_empty() && index < self.content_types.len().try_into()? => {
-        self.content_type_index = index.try_into()?;
+      Action::Tab(index) if index < RESPONSE_TABS.len().try_into()? => {
+        self.tab_index = index.try_into()?;
       },
-      Action::TabNext if !self.content_types.is_empty() => {
-        let next_tab_index = self.content_type_index + 1;
-        self.content_type_index =
-          if next_tab_index < self.content_types.len() { next_tab_index } else { self.content_type_index };
+      Action::TabNext => {
+        self.tab_index = (self.tab_index + 1).min(RESPONSE_TABS.len() - 1);
       },
-      Action::TabPrev if !self.content_types.is_empty() => {
-        self.content_type_index =
-          if self.content_type_index > 0 { self.content_type_index - 1 } else { self.content_type_index };
+      Action::TabPrev => {
+        self.tab_index = self.tab_index.saturating_sub(1);
       },
       Action::Focus => {
         self.focused = true;
@@ -108,19 +684,162 @@ impl Pane for ResponseViewer {
       Action::UnFocus => {
         self.focused = false;
       },
-      Action::SaveResponsePayload(filepath) => {
-        if let Some(response) =
-          self.operation_item.operation.operation_id.as_ref().and_then(|operation_id| state.responses.get(operation_id))
-        {
-          if let Err(error) =
-            std::fs::File::create(filepath).and_then(|mut file| file.write_all(response.body.as_bytes()))
-          {
-            return Ok(Some(Action::TimedStatusLine(format!("can't create or write file content: {error}"), 5)));
-          }
+      Action::PollProgress(progress) => {
+        self.poll_progress = progress;
+      },
+      Action::DryRunOutput(output) => {
+        self.dry_run_output = output;
+        self.diff = None;
+      },
+      Action::InterpretResponseAs(body_format) => {
+        self.body_format = body_format;
+        let status_line = match body_format {
+          Some(body_format) => format!("response body shown as {body_format}"),
+          None => String::from("response body shown as received"),
+        };
+        return Ok(Some(Action::TimedStatusLine(status_line, 3)));
+      },
+      Action::PipeResponse(name) => {
+        let status_line = match &name {
+          Some(name) if !self.config.response.pipes.contains_key(name) => {
+            return Ok(Some(Action::TimedStatusLine(format!("no pipe named {name} is configured"), 3)));
+          },
+          Some(name) => format!("response body shown through {name}"),
+          None => String::from("response body shown as received"),
+        };
+        self.pipe = name;
+        self.pipe_output = None;
+        self.pipe_run = None;
+        return Ok(Some(Action::TimedStatusLine(status_line, 3)));
+      },
+      Action::PipedBody(operation_id, run, output)
+        if self.operation_item.operation.operation_id.as_ref() == Some(&operation_id) =>
+      {
+        if let Some((_, received_at)) = self.pipe_run.filter(|(pipe_run, _)| *pipe_run == run) {
+          self.pipe_run = None;
+          self.pipe_output = Some((received_at, output));
+        }
+      },
+      Action::ToggleTimestamps => {
+        self.annotate_timestamps = !self.annotate_timestamps;
+        let status_line = if self.annotate_timestamps {
+          "timestamps are shown in the local timezone and relative to now"
         } else {
-          return Ok(Some(Action::TimedStatusLine("response is not available".into(), 5)));
+          "timestamps are shown as received"
+        };
+        return Ok(Some(Action::TimedStatusLine(status_line.into(), 3)));
+      },
+      Action::ToggleHumanize => {
+        self.humanize_numbers = !self.humanize_numbers;
+        let status_line = if self.humanize_numbers {
+          "large numbers and sizes are shown in a readable form"
+        } else {
+          "numbers are shown as received"
+        };
+        return Ok(Some(Action::TimedStatusLine(status_line.into(), 3)));
+      },
+      Action::AddAssertion(assertion) => {
+        let Some(operation_id) = self.operation_item.operation.operation_id.clone() else {
+          return Ok(None);
+        };
+        let mut assertions = state.assertions.get(&operation_id).cloned().unwrap_or_default();
+        assertions.push(assertion.clone());
+        state.set_assertions(operation_id.as_str(), assertions);
+        self.assertion_results = None;
+        return Ok(Some(Action::TimedStatusLine(format!("assert {assertion} added"), 3)));
+      },
+      Action::ClearAssertions => {
+        if let Some(operation_id) = self.operation_item.operation.operation_id.as_deref() {
+          state.set_assertions(operation_id, vec![]);
+          self.assertion_results = None;
+          return Ok(Some(Action::TimedStatusLine("assertions cleared".into(), 3)));
+        }
+      },
+      Action::PinResponse => {
+        let Some(response) = self.shown_response(state) else {
+          return Ok(Some(Action::TimedStatusLine("response is not available".into(), 3)));
+        };
+        self.pinned = Some((response.clone(), state.selected_server.clone().unwrap_or_default()));
+        self.pinned_scroll = 0;
+        self.tab_index = 0;
+        return Ok(Some(Action::TimedStatusLine(
+          "response pinned, send the request again to compare, e.g. after switching servers".into(),
+          3,
+        )));
+      },
+      Action::UnpinResponse => {
+        self.pinned = None;
+        return Ok(Some(Action::TimedStatusLine("response unpinned".into(), 3)));
+      },
+      Action::ToggleRaw => {
+        self.raw = !self.raw;
+        let status_line = if self.raw { "annotations are hidden" } else { "annotations are shown" };
+        return Ok(Some(Action::TimedStatusLine(status_line.into(), 3)));
+      },
+      Action::SplitResponsePayload => {
+        if let Err(error) = self.split_response(state) {
+          return Ok(Some(Action::TimedStatusLine(format!("can't split response: {error}"), 5)));
         }
       },
+      Action::DiffResponsePayload(filepath) => match self.diff_response(filepath.as_str(), state) {
+        Ok(changes) => {
+          let status_line = format!("{} differences with {filepath}", changes.len());
+          self.diff = Some(changes);
+          return Ok(Some(Action::TimedStatusLine(status_line, 3)));
+        },
+        Err(error) => return Ok(Some(Action::TimedStatusLine(format!("can't diff response: {error}"), 5))),
+      },
+      Action::Capture(name, path) => {
+        let value = self
+          .operation_item
+          .operation
+          .operation_id
+          .as_ref()
+          .and_then(|operation_id| state.responses.get(operation_id))
+          .and_then(|response| serde_json::from_str::<serde_json::Value>(response.body.as_str()).ok())
+          .and_then(|body| jsonpath::select(&body, path.as_str()).map(jsonpath::display));
+        let status_line = match value {
+          Some(value) => {
+            let status_line = format!("captured {name} = {value}, use it as {{{{{name}}}}}");
+            state.variable_sources.remove(&name);
+            state.variables.insert(name, value);
+            status_line
+          },
+          None => format!("nothing to capture at {path}"),
+        };
+        return Ok(Some(Action::TimedStatusLine(status_line, 3)));
+      },
+      Action::ExportProposedSchema(filepath) => {
+        let status_line = match self.export_proposed_schema(filepath.as_str(), state) {
+          Ok(_) => format!("proposed schema exported to {filepath}"),
+          Err(error) => format!("can't export proposed schema: {error}"),
+        };
+        return Ok(Some(Action::TimedStatusLine(status_line, 3)));
+      },
+      Action::SaveResponseSelection(filepath) => {
+        let status_line = match self.json_tree_received_at.and(self.json_tree.selected_json()) {
+          Some(json) => match std::fs::write(filepath.as_str(), json) {
+            Ok(_) => {
+              let path = self.json_tree.selected_path().map(|path| jsonpath::format(&path)).unwrap_or_default();
+              format!("{path} saved to {filepath}")
+            },
+            Err(error) => format!("can't create or write file content: {error}"),
+          },
+          None => String::from("nothing selected, pick a node in the JSON tree tab first"),
+        };
+        return Ok(Some(Action::TimedStatusLine(status_line, 5)));
+      },
+      Action::SaveResponsePayload(filepath, decoded) => {
+        let operation_id = self.operation_item.operation.operation_id.clone().unwrap_or_default();
+        let status_line = match state.responses.get(&operation_id) {
+          Some(response) => match response.save(filepath.as_str(), operation_id.as_str(), decoded) {
+            Ok(path) => format!("response saved to {}", path.display()),
+            Err(error) => format!("can't create or write file content: {error}"),
+          },
+          None => String::from("response is not available"),
+        };
+        return Ok(Some(Action::TimedStatusLine(status_line, 5)));
+      },
       _ => {},
     }
     Ok(None)
@@ -128,41 +847,182 @@ impl Pane for ResponseViewer {
 
   fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
     let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
-    let inner_panes = Layout::horizontal([Constraint::Fill(3), Constraint::Fill(1)]).split(inner);
 
     let mut status_line = String::default();
+    let mut hint_line = String::default();
+    let mut status_style = Style::default();
+    let mut assertion_chips = Line::default();
 
-    if let Some(response) =
-      self.operation_item.operation.operation_id.as_ref().and_then(|operation_id| state.responses.get(operation_id))
-    {
+    if let Some(changes) = &self.diff {
+      status_line = String::from("[diff: - expected + actual]");
+      let lines = Self::diff_lines(changes, "response matches the expected payload");
+      frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    } else if let Some(dry_run_output) = &self.dry_run_output {
+      status_line = String::from("[dry-run: request was not sent]");
+      frame.render_widget(Paragraph::new(dry_run_output.as_str()).wrap(Wrap { trim: false }), inner);
+    } else if let Some(changes) = self.previous_changes(state).filter(|_| self.view == ResponseView::Diff) {
+      status_line = String::from("[diff: - previous + latest]");
+      hint_line = format!("[d {} latest]", symbols::scrollbar::HORIZONTAL.end);
+      let lines = Self::diff_lines(&changes, "latest response matches the previous one");
+      frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    } else if let Some(response) = self.shown_response(state) {
+      if response.headers.contains_key(LOCATION) {
+        let arrow = symbols::scrollbar::HORIZONTAL.end;
+        hint_line = match response.status {
+          reqwest::StatusCode::CREATED | reqwest::StatusCode::ACCEPTED => {
+            format!("[g {arrow} follow location] [o {arrow} open location]")
+          },
+          _ => format!("[o {arrow} open location]"),
+        };
+      }
+      status_style = self.config.palette.status_style(response.status.as_str());
+      assertion_chips = self.assertion_chips(response, state);
       status_line = format!(
-        "[{:?} {} {} {}]",
+        "[{}{:?} {} {} {}]",
+        self.config.palette.status_symbol(response.status.as_str()),
         response.version,
         response.status.as_str(),
         symbols::DOT,
         humansize::format_size(response.content_length.unwrap_or(response.body.len() as u64), humansize::DECIMAL)
       );
+      if let Some(trace_id) = &response.trace_id {
+        status_line.insert_str(status_line.len() - 1, format!(" {} trace {trace_id}", symbols::DOT).as_str());
+      }
+      if let Some(idempotency_key) = &response.idempotency_key {
+        status_line
+          .insert_str(status_line.len() - 1, format!(" {} idempotency key {idempotency_key}", symbols::DOT).as_str());
+      }
+      let rate_limit = match &response.quota {
+        Some(quota) => Some(quota.label(response.received_at.elapsed())),
+        None => {
+          let declared = state.declared_rate_limits.of(&self.operation_item.method, &self.operation_item.path);
+          declared.map(|limit| format!("limit {limit}"))
+        },
+      };
+      if let Some(rate_limit) = rate_limit {
+        status_line.insert_str(status_line.len() - 1, format!(" {} {rate_limit}", symbols::DOT).as_str());
+      }
+      if response.certificate_changed.is_some() {
+        status_line.insert_str(status_line.len() - 1, format!(" {} certificate changed", symbols::DOT).as_str());
+      }
+      let documented = response.body.is_empty() || self.is_documented(response, state);
+      if !documented {
+        status_line.insert_str(status_line.len() - 1, format!(" {} undocumented", symbols::DOT).as_str());
+      }
+      let arrow = symbols::scrollbar::HORIZONTAL.end;
+      if self.view == ResponseView::Previous {
+        status_line.insert_str(0, "[previous] ");
+        hint_line.push_str(format!("[d {arrow} diff]").as_str());
+      } else if let Some(changes) = self.previous_changes(state) {
+        hint_line.push_str(format!("[d {arrow} {} changed since previous]", changes.len()).as_str());
+      } else if state
+        .previous_responses
+        .contains_key(self.operation_item.operation.operation_id.as_deref().unwrap_or_default())
+      {
+        hint_line.push_str(format!("[d {arrow} previous]").as_str());
+      }
+      if self.trace_link(state).is_some() {
+        hint_line.push_str(format!("[t {} open trace]", symbols::scrollbar::HORIZONTAL.end).as_str());
+      }
+      let [tabs_area, content_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
       frame.render_widget(
-        Paragraph::new(response.body.clone()).wrap(Wrap { trim: false }).block(
-          Block::default().borders(Borders::RIGHT).border_style(self.border_style()).border_type(self.border_type()),
-        ),
-        inner_panes[0],
+        Tabs::new(RESPONSE_TABS)
+          .select(self.tab_index)
+          .padding("", " ")
+          .divider(symbols::DOT)
+          .style(Style::default().dim())
+          .highlight_style(Style::default().not_dim().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)),
+        tabs_area,
       );
-      frame.render_widget(
-        List::new(
-          response
-            .headers
-            .iter()
-            .map(|(hk, hv)| {
-              Line::from(vec![
-                Span::styled(format!("{}: ", hk), Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(hv.to_str().unwrap_or("ERROR")),
+      match self.tab_index {
+        0 => {
+          if self.pinned.is_some() {
+            status_line.insert_str(0, "[pinned] ");
+            let overlay = if self.pinned_overlay { "hide" } else { "show" };
+            hint_line = format!("[j,k {arrow} scroll both] [x {arrow} {overlay} differences]");
+            self.draw_pinned(frame, content_area, response);
+          } else {
+            if let Some(pipe) = &self.pipe {
+              status_line.insert_str(0, format!("[| {pipe}] ").as_str());
+            } else if let Some(body_format) = self.body_format {
+              status_line.insert_str(0, format!("[as {body_format}] ").as_str());
+            }
+            self.draw_body(frame, content_area, response, state);
+          }
+        },
+        TREE_TAB_INDEX => {
+          if self.sync_json_tree(response) {
+            if let Some(path) = self.json_tree.selected_path() {
+              hint_line = jsonpath::format(&path);
+            }
+            self.json_tree.render(frame, content_area);
+          } else {
+            frame
+              .render_widget(Paragraph::new("response body is not JSON").style(Style::default().dim()), content_area);
+          }
+        },
+        2 => frame.render_widget(Paragraph::new(response.body.clone()).wrap(Wrap { trim: false }), content_area),
+        3 => frame.render_widget(
+          Table::new(
+            response.headers.iter().map(|(name, value)| {
+              Row::new(vec![
+                Cell::from(name.to_string()).style(Style::default().add_modifier(Modifier::BOLD)),
+                Cell::from(value.to_str().unwrap_or("<binary>").to_string()),
               ])
-            })
-            .collect::<Vec<_>>(),
+            }),
+            [Constraint::Fill(1), Constraint::Fill(3)],
+          ),
+          content_area,
         ),
-        inner_panes[1],
-      );
+        4 => {
+          let cookies = response.cookies();
+          if cookies.is_empty() {
+            frame.render_widget(Paragraph::new("no cookies were set").style(Style::default().dim()), content_area);
+          } else {
+            frame.render_widget(
+              Table::new(
+                cookies.into_iter().map(|(name, value, attributes)| {
+                  Row::new(vec![
+                    Cell::from(name).style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(value),
+                    Cell::from(attributes).style(Style::default().dim()),
+                  ])
+                }),
+                [Constraint::Fill(1), Constraint::Fill(2), Constraint::Fill(2)],
+              )
+              .header(Row::new(vec!["name", "value", "attributes"]).style(Style::default().dim())),
+              content_area,
+            );
+          }
+        },
+        TLS_TAB_INDEX => self.draw_tls(frame, content_area, response),
+        TIMELINE_TAB_INDEX => frame.render_widget(
+          List::new(response.timeline.iter().map(|(at, event)| {
+            Line::from(vec![
+              Span::styled(format!("{:>8.1}ms ", at.as_secs_f64() * 1000.0), Style::default().dim()),
+              Span::raw(event.as_str()),
+            ])
+          })),
+          content_area,
+        ),
+        _ => match Self::proposed_schema(response) {
+          Some(snippet) => {
+            let note = if documented {
+              "# inferred from the body, the response is documented in the spec"
+            } else {
+              "# inferred from the body of an undocumented response, export it with `:response schema <file>`"
+            };
+            let mut lines = vec![Line::styled(note, Style::default().dim())];
+            lines.extend(
+              serde_yaml::to_string(&snippet).unwrap_or_default().lines().map(|line| Line::raw(line.to_string())),
+            );
+            frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), content_area);
+          },
+          None => {
+            frame.render_widget(Paragraph::new("response body is not JSON").style(Style::default().dim()), content_area)
+          },
+        },
+      }
     } else {
       frame.render_widget(
         Paragraph::new(" No response is available. Press enter or try [send] command.").style(Style::default().dim()),
@@ -171,7 +1031,7 @@ impl Pane for ResponseViewer {
     }
 
     let content_types = if !self.content_types.is_empty() {
-      let ctype = self.content_types[self.content_type_index].clone();
+      let ctype = media_type::labels(&self.content_types).swap_remove(self.content_type_index);
       let ctype_progress = if self.content_types.len() > 1 {
         format!("[{}/{}]", self.content_type_index + 1, self.content_types.len())
       } else {
@@ -182,16 +1042,94 @@ impl Pane for ResponseViewer {
       String::default()
     };
 
+    if let Some(poll_progress) = &self.poll_progress {
+      hint_line = poll_progress.clone();
+    }
+
     frame.render_widget(
       Block::default()
         .title(format!("Response{content_types}"))
+        .title(assertion_chips.right_aligned())
         .borders(Borders::ALL)
         .border_style(self.border_style())
         .border_type(self.border_type())
-        .title_bottom(Line::from(status_line).right_aligned()),
+        .title_bottom(Line::from(hint_line))
+        .title_bottom(Line::from(status_line).style(status_style).right_aligned()),
       area,
     );
 
     Ok(())
   }
 }
+
+/// Name of the file a response is split into. The operation id comes from the spec, only its `[A-Za-z0-9_-]`
+/// characters are kept so the file stays in the temp directory.
+fn split_file_name(operation_id: &str, extension: &str) -> String {
+  let operation_id = operation_id
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+    .collect::<String>();
+  format!("{}-{operation_id}.{extension}", env!("CARGO_PKG_NAME"))
+}
+
+/// Split command run by the shell with `{file}` standing for the path, which is passed as an argument of the shell
+/// instead of being written into the command.
+fn split_command(command: &str, path: &Path) -> std::process::Command {
+  let mut process;
+  if cfg!(windows) {
+    process = std::process::Command::new("cmd");
+    process.arg("/C").arg(command.replace("{file}", format!("\"{}\"", path.display()).as_str()));
+  } else {
+    process = std::process::Command::new("sh");
+    process.arg("-c").arg(command.replace("{file}", "\"$1\"")).arg(env!("CARGO_PKG_NAME")).arg(path);
+  }
+  process
+}
+
+/// Stdout of a shell command given the body on stdin, without its color escapes.
+async fn pipe_body(command: &str, body: Vec<u8>) -> Result<String> {
+  let (shell, shell_arg) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+  let mut child = tokio::process::Command::new(shell)
+    .arg(shell_arg)
+    .arg(command)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()?;
+  let mut stdin = child.stdin.take().context("can't write to the command")?;
+  // written from another task so a command filling its stdout before reading all of stdin doesn't block both
+  let writer = tokio::spawn(async move { stdin.write_all(&body).await });
+  let output = match tokio::time::timeout(PIPE_TIMEOUT, child.wait_with_output()).await {
+    Ok(output) => output?,
+    Err(_) => bail!("no output after {} seconds", PIPE_TIMEOUT.as_secs()),
+  };
+  writer.abort();
+  if !output.status.success() {
+    bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+  }
+  Ok(strip_ansi_escapes::strip_str(String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_pipe_body() {
+    assert_eq!(pipe_body("tr a-z A-Z", b"{\"name\": \"rex\"}".to_vec()).await.unwrap(), "{\"NAME\": \"REX\"}");
+    assert_eq!(pipe_body("printf '\\033[31mred\\033[0m'", vec![]).await.unwrap(), "red");
+    assert_eq!(pipe_body("echo broken >&2; exit 1", vec![]).await.unwrap_err().to_string(), "broken");
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_split_command() {
+    assert_eq!(split_file_name("pets/get';touch x'", "json"), "openapi-tui-pets_get__touch_x_.json");
+    let output = split_command("printf %s {file}", Path::new("/tmp/it's $(here).json")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "/tmp/it's $(here).json");
+  }
+}
@@ -0,0 +1,77 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+use tui_textarea::TextArea;
+
+use crate::{
+  action::Action,
+  panes::{self, Pane},
+  secrets,
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+/// Popup reading the value of a secret with its characters masked, stored in the OS keyring, so it stays off the
+/// screen and out of the command history.
+pub struct SecretPane<'a> {
+  name: String,
+  input: TextArea<'a>,
+}
+
+impl SecretPane<'_> {
+  pub fn new(name: String) -> Self {
+    let mut input = TextArea::default();
+    input.set_cursor_line_style(Style::default());
+    input.set_mask_char('•');
+    Self { name, input }
+  }
+}
+
+impl Pane for SecretPane<'_> {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Length(4)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let response = match key.code {
+      KeyCode::Esc => EventResponse::Stop(Action::ClosePopup),
+      KeyCode::Enter => match self.input.lines().join("") {
+        value if value.is_empty() => EventResponse::Stop(Action::Noop),
+        value => EventResponse::Stop(Action::StoreSecret(self.name.clone(), value)),
+      },
+      _ => {
+        self.input.input(key);
+        EventResponse::Stop(Action::Noop)
+      },
+    };
+    Ok(Some(response))
+  }
+
+  fn handle_paste_events(&mut self, text: String, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    Ok(Some(panes::paste(text.trim(), |text| {
+      self.input.insert_str(text);
+    })))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let inner = area.inner(Margin { horizontal: 1, vertical: 1 });
+    let [label_area, input_area] = Layout::vertical([Constraint::Length(1); 2]).areas(inner);
+    let label = format!("value of {}{}, stored in the OS keyring", secrets::SECRET_SCHEME, self.name);
+    frame.render_widget(Paragraph::new(label).style(Style::default().dim()), label_area);
+    self.input.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_widget(&self.input, input_area);
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title("Secret")
+        .title_bottom(Line::from("[enter → store] [esc → cancel]").right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,79 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+use tui_textarea::TextArea;
+
+use crate::{
+  action::Action,
+  panes::Pane,
+  spec_patch::SpecField,
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+/// Popup editing a documentation field of an operation, saved back into the spec file.
+pub struct SpecEditorPane<'a> {
+  operation_id: String,
+  field: SpecField,
+  input: TextArea<'a>,
+}
+
+impl SpecEditorPane<'_> {
+  pub fn new(operation_id: String, field: SpecField, value: String) -> Self {
+    let mut input = TextArea::from(value.lines());
+    input.set_cursor_line_style(Style::default());
+    input.move_cursor(tui_textarea::CursorMove::Bottom);
+    input.move_cursor(tui_textarea::CursorMove::End);
+    Self { operation_id, field, input }
+  }
+}
+
+impl Pane for SpecEditorPane<'_> {
+  fn height_constraint(&self) -> Constraint {
+    match self.field {
+      SpecField::Summary => Constraint::Length(3),
+      _ => Constraint::Fill(2),
+    }
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let response = match (key.code, key.modifiers) {
+      (KeyCode::Esc, _) => EventResponse::Stop(Action::ClosePopup),
+      (KeyCode::Char('s'), KeyModifiers::CONTROL) | (KeyCode::Enter, _) if self.field == SpecField::Summary => {
+        EventResponse::Stop(Action::PatchSpec(
+          self.operation_id.clone(),
+          self.field.clone(),
+          self.input.lines().join(" "),
+        ))
+      },
+      (KeyCode::Char('s'), KeyModifiers::CONTROL) => EventResponse::Stop(Action::PatchSpec(
+        self.operation_id.clone(),
+        self.field.clone(),
+        self.input.lines().join("\n").trim_end().to_string(),
+      )),
+      _ => {
+        self.input.input(key);
+        EventResponse::Stop(Action::Noop)
+      },
+    };
+    Ok(Some(response))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    self.input.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_widget(&self.input, area.inner(Margin { horizontal: 1, vertical: 1 }));
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!("Edit {}", self.field))
+        .title_bottom(Line::from("[ctrl-s → save] [esc → cancel]").right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,100 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+
+use crate::{
+  action::Action,
+  http_status,
+  panes::Pane,
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+/// Popup explaining the status of a response, with the responses the spec documents for the operation.
+pub struct StatusInfoPane {
+  status: u16,
+  /// Documented response keys, like `200` or `4XX`, with their descriptions.
+  documented: Vec<(String, String)>,
+  scroll: u16,
+}
+
+impl StatusInfoPane {
+  pub fn new(status: u16, documented: Vec<(String, String)>) -> Self {
+    Self { status, documented, scroll: 0 }
+  }
+
+  fn lines(&self) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    if let Some(info) = http_status::info_of(self.status) {
+      lines.push(Line::from(info.summary));
+      if !info.causes.is_empty() {
+        lines.push(Line::default());
+        lines.push(Line::from(vec![Span::styled("Common causes: ", Style::default().bold()), Span::raw(info.causes)]));
+      }
+      lines.push(Line::default());
+      lines.push(Line::from(vec![Span::styled("Reference: ", Style::default().bold()), Span::raw(info.reference)]));
+    }
+    lines.push(Line::default());
+    let keys = self.documented.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>();
+    let covering = http_status::covering(self.status, &keys);
+    if self.documented.is_empty() {
+      lines.push(Line::styled("The spec documents no responses for this operation.", Style::default().dim()));
+    } else {
+      lines.push(Line::styled("Documented responses", Style::default().bold()));
+      for (key, description) in &self.documented {
+        let marker = if covering == Some(key.as_str()) { "▶ " } else { "  " };
+        let style = if covering == Some(key.as_str()) { Style::default().bold() } else { Style::default() };
+        lines.push(Line::from(vec![
+          Span::styled(format!("{marker}{key:<8}"), style),
+          Span::styled(description.clone(), Style::default().dim()),
+        ]));
+      }
+    }
+    if covering.is_none() {
+      lines.push(Line::default());
+      lines.push(Line::styled(
+        format!("{} is not documented for this operation.", self.status),
+        Style::default().fg(Color::Yellow),
+      ));
+    }
+    lines
+  }
+}
+
+impl Pane for StatusInfoPane {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(2)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    match key.code {
+      KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(EventResponse::Stop(Action::ClosePopup))),
+      KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+      KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+      _ => {},
+    }
+    Ok(Some(EventResponse::Stop(Action::Noop)))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let reason = reqwest::StatusCode::from_u16(self.status)
+      .ok()
+      .and_then(|status| status.canonical_reason())
+      .unwrap_or("Unknown status");
+    frame.render_widget(
+      Paragraph::new(self.lines()).wrap(Wrap { trim: false }).scroll((self.scroll, 0)).block(
+        Block::default()
+          .borders(Borders::ALL)
+          .border_type(BorderType::Thick)
+          .title(format!("{} {reason}", self.status))
+          .title_bottom(Line::from("[esc → close]").right_aligned()),
+      ),
+      area,
+    );
+    Ok(())
+  }
+}
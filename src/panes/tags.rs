@@ -11,11 +11,13 @@ pub struct TagsPane {
   focused: bool,
   focused_border_style: Style,
   current_tag_index: usize,
+  /// One line high while not focused.
+  collapsed: bool,
 }
 
 impl TagsPane {
-  pub fn new(focused: bool, focused_border_style: Style) -> Self {
-    Self { focused, focused_border_style, current_tag_index: 0 }
+  pub fn new(focused: bool, focused_border_style: Style, collapsed: bool) -> Self {
+    Self { focused, focused_border_style, current_tag_index: 0, collapsed }
   }
 
   fn border_style(&self) -> Style {
@@ -32,23 +34,42 @@ impl TagsPane {
     }
   }
 
+  /// Number of items in the list: `[ALL]`, the tags and `[WEBHOOKS]` when the spec has webhooks.
+  fn items_len(&self, state: &State) -> usize {
+    state.openapi_spec.tags.as_ref().into_iter().flatten().count() + 1 + usize::from(state.has_webhooks())
+  }
+
   fn update_active_tag(&mut self, state: &mut State) {
+    state.webhooks_only = false;
     if self.current_tag_index > 0 {
       if let Some(tag) = state.openapi_spec.tags.as_ref().into_iter().flatten().nth(self.current_tag_index - 1) {
         state.active_tag_name = Some(tag.name.clone());
-        state.active_operation_index = 0;
+      } else {
+        state.active_tag_name = None;
+        state.webhooks_only = true;
       }
     } else {
       state.active_tag_name = None;
-      state.active_operation_index = 0;
     }
+    state.active_operation_index = 0;
   }
 }
 
 impl Pane for TagsPane {
+  fn init(&mut self, state: &State) -> Result<()> {
+    let mut tags = state.openapi_spec.tags.iter().flatten();
+    self.current_tag_index = match &state.active_tag_name {
+      Some(active_tag) => tags.position(|tag| tag.name.eq(active_tag)).map(|index| index + 1).unwrap_or_default(),
+      None if state.webhooks_only => self.items_len(state) - 1,
+      None => 0,
+    };
+    Ok(())
+  }
+
   fn height_constraint(&self) -> Constraint {
     match self.focused {
       true => Constraint::Fill(3),
+      false if self.collapsed => Constraint::Length(3),
       false => Constraint::Fill(1),
     }
   }
@@ -56,22 +77,13 @@ impl Pane for TagsPane {
   fn update(&mut self, action: Action, state: &mut State) -> Result<Option<Action>> {
     match action {
       Action::Down => {
-        {
-          let tags_list_len = state.openapi_spec.tags.as_ref().into_iter().flatten().count().saturating_add(1);
-          if tags_list_len > 0 {
-            self.current_tag_index = self.current_tag_index.saturating_add(1) % tags_list_len;
-          }
-        }
+        self.current_tag_index = self.current_tag_index.saturating_add(1) % self.items_len(state);
         self.update_active_tag(state);
         return Ok(Some(Action::Update));
       },
       Action::Up => {
-        {
-          let tags_list_len = state.openapi_spec.tags.as_ref().into_iter().flatten().count().saturating_add(1);
-          if tags_list_len > 0 {
-            self.current_tag_index = self.current_tag_index.saturating_add(tags_list_len - 1) % tags_list_len;
-          }
-        }
+        let items_len = self.items_len(state);
+        self.current_tag_index = self.current_tag_index.saturating_add(items_len - 1) % items_len;
         self.update_active_tag(state);
         return Ok(Some(Action::Update));
       },
@@ -83,6 +95,15 @@ impl Pane for TagsPane {
       Action::UnFocus => {
         self.focused = false;
       },
+      Action::OpenLink => {
+        let tag =
+          self.current_tag_index.checked_sub(1).and_then(|index| state.openapi_spec.tags.iter().flatten().nth(index));
+        return Ok(
+          tag
+            .and_then(|tag| tag.external_docs.as_ref())
+            .map(|external_docs| Action::OpenInBrowser(external_docs.url.clone())),
+        );
+      },
       Action::Submit => {},
       _ => {},
     }
@@ -100,6 +121,9 @@ impl Pane for TagsPane {
       .collect();
 
     items.insert(0, Line::styled(" [ALL]", Style::default()));
+    if state.has_webhooks() {
+      items.push(Line::styled(" [WEBHOOKS]", Style::default().fg(Color::LightMagenta)));
+    }
 
     let list = List::new(items)
       .block(Block::default().borders(Borders::ALL))
@@ -109,7 +133,7 @@ impl Pane for TagsPane {
     let mut list_state = ListState::default().with_selected(Some(self.current_tag_index));
 
     frame.render_stateful_widget(list, area, &mut list_state);
-    let items_len = state.openapi_spec.tags.as_ref().into_iter().flatten().count() + 1;
+    let items_len = self.items_len(state);
     frame.render_widget(
       Block::default()
         .title("Tags")
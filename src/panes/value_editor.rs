@@ -0,0 +1,71 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+use tui_textarea::TextArea;
+
+use crate::{
+  action::Action,
+  panes::{self, Pane},
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+/// Popup editing the value of a parameter over several lines, saved back into the selected parameter row.
+pub struct ValueEditorPane<'a> {
+  name: String,
+  input: TextArea<'a>,
+}
+
+impl ValueEditorPane<'_> {
+  pub fn new(name: String, value: String) -> Self {
+    let mut input = TextArea::from(value.lines());
+    input.set_cursor_line_style(Style::default());
+    input.move_cursor(tui_textarea::CursorMove::Bottom);
+    input.move_cursor(tui_textarea::CursorMove::End);
+    Self { name, input }
+  }
+}
+
+impl Pane for ValueEditorPane<'_> {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(2)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    let response = match (key.code, key.modifiers) {
+      (KeyCode::Esc, _) => EventResponse::Stop(Action::ClosePopup),
+      (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+        EventResponse::Stop(Action::SaveParameterValue(self.input.lines().join("\n")))
+      },
+      _ => {
+        self.input.input(key);
+        EventResponse::Stop(Action::Noop)
+      },
+    };
+    Ok(Some(response))
+  }
+
+  fn handle_paste_events(&mut self, text: String, _state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    Ok(Some(panes::paste(text.as_str(), |text| {
+      self.input.insert_str(text);
+    })))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    self.input.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_widget(&self.input, area.inner(Margin { horizontal: 1, vertical: 1 }));
+    frame.render_widget(
+      Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!("Edit {}", self.name))
+        .title_bottom(Line::from("[ctrl-s → save] [esc → cancel]").right_aligned()),
+      area,
+    );
+    Ok(())
+  }
+}
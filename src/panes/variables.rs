@@ -0,0 +1,77 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+  prelude::*,
+  widgets::{block::*, *},
+};
+
+use crate::{
+  action::Action,
+  panes::Pane,
+  state::State,
+  tui::{EventResponse, Frame},
+};
+
+/// Popup with the effective variables substituted for `{{name}}` placeholders and where each one came from.
+#[derive(Default)]
+pub struct VariablesPane {
+  index: usize,
+}
+
+impl Pane for VariablesPane {
+  fn height_constraint(&self) -> Constraint {
+    Constraint::Fill(3)
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent, state: &mut State) -> Result<Option<EventResponse<Action>>> {
+    match key.code {
+      KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(EventResponse::Stop(Action::ClosePopup))),
+      KeyCode::Down | KeyCode::Char('j') => {
+        self.index = (self.index + 1).min(state.variables.len().saturating_sub(1));
+      },
+      KeyCode::Up | KeyCode::Char('k') => self.index = self.index.saturating_sub(1),
+      KeyCode::Char('y') | KeyCode::Char('Y') => {
+        if let Some(value) = state.variables.values().nth(self.index) {
+          return Ok(Some(EventResponse::Stop(Action::CopyToClipboard(value.clone()))));
+        }
+      },
+      _ => {},
+    }
+    Ok(Some(EventResponse::Stop(Action::Noop)))
+  }
+
+  fn draw(&mut self, frame: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+    frame.render_widget(Clear, area);
+    let rows = state.variables.iter().map(|(name, value)| {
+      let source = state.variable_sources.get(name).map_or("captured", String::as_str);
+      Row::new(vec![
+        Cell::from(name.as_str()),
+        Cell::from(value.replace('\n', " ↵ ")),
+        Cell::from(Span::styled(source, Style::default().dim())),
+      ])
+    });
+    let table = Table::new(rows, [Constraint::Fill(1), Constraint::Fill(2), Constraint::Fill(1)])
+      .header(Row::new(["Name", "Value", "Source"]).style(Style::default().bold()))
+      .highlight_symbol(symbols::scrollbar::HORIZONTAL.end)
+      .highlight_spacing(HighlightSpacing::Always)
+      .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+      .block(
+        Block::default()
+          .borders(Borders::ALL)
+          .border_type(BorderType::Thick)
+          .title(format!("Variables [{}]", state.variables.len()))
+          .title_bottom(Line::from("[j,k → select] [y → copy value] [esc → close]").right_aligned()),
+      );
+    let mut table_state = TableState::default().with_selected((!state.variables.is_empty()).then_some(self.index));
+    frame.render_stateful_widget(table, area, &mut table_state);
+    if state.variables.is_empty() {
+      let inner = area.inner(Margin { horizontal: 2, vertical: 2 });
+      frame.render_widget(
+        Paragraph::new("No variables, load a dotenv file with :env load <file> or capture one from a response")
+          .style(Style::default().dim()),
+        inner,
+      );
+    }
+    Ok(())
+  }
+}
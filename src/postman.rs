@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use color_eyre::eyre::{bail, Result};
+use serde_json::{json, Value};
+
+use crate::{
+  import::{split_url, Collection, ImportedRequest},
+  request,
+};
+
+fn text(value: &Value) -> String {
+  match value {
+    Value::String(text) => text.clone(),
+    Value::Null => String::new(),
+    value => value.to_string(),
+  }
+}
+
+/// Enabled `key`/`value` pairs, as Postman keeps headers, query parameters and variables.
+fn pairs(value: &Value) -> Vec<(String, String)> {
+  value
+    .as_array()
+    .into_iter()
+    .flatten()
+    .filter(|pair| !pair["disabled"].as_bool().unwrap_or_default())
+    .filter_map(|pair| Some((pair["key"].as_str()?.to_string(), text(&pair["value"]))))
+    .collect()
+}
+
+fn body(body: &Value) -> Option<String> {
+  match body["mode"].as_str()? {
+    "raw" => body["raw"].as_str().map(String::from),
+    "urlencoded" => Some(
+      pairs(&body["urlencoded"]).iter().map(|(name, value)| format!("{name}={value}")).collect::<Vec<_>>().join("&"),
+    ),
+    _ => None,
+  }
+}
+
+fn request(name: &str, request: &Value) -> ImportedRequest {
+  let url = &request["url"];
+  let (segments, query) = match url {
+    Value::String(raw) => split_url(raw),
+    url => {
+      let (raw_segments, raw_query) = split_url(url["raw"].as_str().unwrap_or_default());
+      let segments = match &url["path"] {
+        Value::Array(path) => path.iter().map(text).filter(|segment| !segment.is_empty()).collect(),
+        Value::String(path) => split_url(format!("/{path}").as_str()).0,
+        _ => raw_segments,
+      };
+      let query = if url["query"].is_array() { pairs(&url["query"]) } else { raw_query };
+      (segments, query)
+    },
+  };
+  ImportedRequest {
+    name: name.to_string(),
+    method: request["method"].as_str().unwrap_or("GET").to_uppercase(),
+    segments,
+    path_variables: pairs(&url["variable"]),
+    query,
+    headers: pairs(&request["header"]),
+    body: body(&request["body"]),
+  }
+}
+
+fn collect(items: &Value, requests: &mut Vec<ImportedRequest>) {
+  for item in items.as_array().into_iter().flatten() {
+    match item.get("request") {
+      Some(Value::String(url)) => {
+        requests.push(request(item["name"].as_str().unwrap_or_default(), &serde_json::json!({"url": url})))
+      },
+      Some(value) => requests.push(request(item["name"].as_str().unwrap_or_default(), value)),
+      None => collect(&item["item"], requests),
+    }
+  }
+}
+
+/// Reads a Postman collection, format v2.0 or v2.1.
+pub fn parse(text: &str) -> Result<Collection> {
+  let collection = serde_json::from_str::<Value>(text)?;
+  if !collection["item"].is_array() {
+    bail!("not a Postman collection, it has no items");
+  }
+  let mut requests = vec![];
+  collect(&collection["item"], &mut requests);
+  Ok(Collection { requests, variables: pairs(&collection["variable"]) })
+}
+
+/// Postman collection v2.1 of requests named after their operation, with the variables as collection variables.
+/// Secrets are written as `<secret>`.
+pub fn export(name: &str, requests: &[(String, reqwest::Request)], variables: &BTreeMap<String, String>) -> Value {
+  let items = requests
+    .iter()
+    .map(|(operation_id, request)| {
+      let headers = request::header_lines(request)
+        .into_iter()
+        .map(|(name, value)| json!({"key": name, "value": value}))
+        .collect::<Vec<_>>();
+      let mut item = json!({
+        "name": operation_id,
+        "request": {"method": request.method().as_str(), "header": headers, "url": {"raw": request::template_url(request)}},
+      });
+      if let Some(body) = request::body_text(request) {
+        item["request"]["body"] = json!({"mode": "raw", "raw": body});
+      }
+      item
+    })
+    .collect::<Vec<_>>();
+  json!({
+    "info": {"name": name, "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+    "item": items,
+    "variable": variables.iter().map(|(key, value)| json!({"key": key, "value": value})).collect::<Vec<_>>(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::import::match_path;
+
+  #[test]
+  fn test_parse_and_match() {
+    let collection = parse(
+      r#"{
+        "info": {"name": "pets"},
+        "variable": [{"key": "baseUrl", "value": "https://api.example.com/v1"}, {"key": "off", "disabled": true}],
+        "item": [
+          {"name": "pets", "item": [
+            {"name": "list", "request": {"method": "GET", "url": "{{baseUrl}}/pets?limit=10"}},
+            {"name": "get", "request": {
+              "method": "get",
+              "header": [{"key": "X-Trace", "value": "1"}, {"key": "X-Off", "value": "1", "disabled": true}],
+              "url": {"raw": "{{baseUrl}}/pets/:id", "host": ["{{baseUrl}}"], "path": ["pets", ":id"],
+                "variable": [{"key": "id", "value": "42"}]}
+            }}
+          ]},
+          {"name": "add", "request": {
+            "method": "POST",
+            "header": [{"key": "Content-Type", "value": "application/json"}],
+            "body": {"mode": "raw", "raw": "{\"name\": \"rex\"}"},
+            "url": "https://api.example.com/v1/pets/{{petId}}/toys"
+          }}
+        ]
+      }"#,
+    )
+    .unwrap();
+    assert_eq!(collection.variables, [(String::from("baseUrl"), String::from("https://api.example.com/v1"))]);
+    let [list, get, add] = collection.requests.as_slice() else { panic!("three requests expected") };
+    assert_eq!(
+      (list.segments.clone(), list.query.clone()),
+      (vec![String::from("pets")], vec![("limit".into(), "10".into())])
+    );
+    assert_eq!((get.method.as_str(), get.headers.len()), ("GET", 1));
+    assert_eq!((add.content_type(), add.body.as_deref()), (Some("application/json"), Some(r#"{"name": "rex"}"#)));
+
+    assert_eq!(match_path("/pets/{petId}", get), Some(vec![("petId".into(), "42".into())]));
+    assert_eq!(match_path("/pets/{petId}/toys", add), Some(vec![("petId".into(), "{{petId}}".into())]));
+    assert_eq!(match_path("/pets/{petId}/toys", list), None);
+    assert_eq!(match_path("/owners/{ownerId}", get), None);
+  }
+
+  #[test]
+  fn test_export() {
+    let request = reqwest::Client::new().get("https://api.example.com/v1/pets/{{petId}}").build().unwrap();
+    let variables = BTreeMap::from([(String::from("petId"), String::from("42"))]);
+    let exported = export("pets", &[(String::from("getPet"), request)], &variables);
+    let collection = parse(exported.to_string().as_str()).unwrap();
+    assert_eq!(collection.requests[0].name, "getPet");
+    assert_eq!(match_path("/pets/{petId}", &collection.requests[0]), Some(vec![("petId".into(), "{{petId}}".into())]));
+    assert_eq!(collection.variables, [(String::from("petId"), String::from("42"))]);
+  }
+}
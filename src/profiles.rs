@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// A named environment like `dev`, `staging` or `prod` of the `profiles` config, switched at runtime.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Profile {
+  /// Server used first while the profile is active, relative urls are resolved like the spec servers.
+  pub base_url: Option<String>,
+  /// Headers added to every request, taking over the `default_headers` of the client with the same name.
+  pub headers: BTreeMap<String, String>,
+  /// Sent as a bearer `Authorization` header, a `{{name}}` placeholder keeps it out of the config file. A
+  /// `secret://` reference holding the whole value goes in `headers` instead.
+  pub token: Option<String>,
+  /// Settings of the `client` config the profile changes.
+  pub client: ClientOverrides,
+}
+
+/// Client settings of a profile, the ones left out are taken from the `client` config.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ClientOverrides {
+  pub timeout_secs: Option<u64>,
+  pub connect_timeout_secs: Option<u64>,
+  pub proxy: Option<String>,
+  pub accept_invalid_certs: Option<bool>,
+}
+
+impl Profile {
+  /// Headers of the profile with the token as `Authorization`, unless the headers already set one.
+  pub fn headers(&self) -> Vec<(String, String)> {
+    let mut headers = self.headers.iter().map(|(name, value)| (name.clone(), value.clone())).collect::<Vec<_>>();
+    if let Some(token) = self.token.as_ref().filter(|_| !has_header(&headers, "authorization")) {
+      headers.push((String::from("Authorization"), format!("Bearer {token}")));
+    }
+    headers
+  }
+
+  /// What the profile changes, e.g. `https://staging.example.com · 2 headers · token`.
+  pub fn summary(&self) -> String {
+    let mut parts = vec![];
+    if let Some(base_url) = &self.base_url {
+      parts.push(base_url.clone());
+    }
+    match self.headers.len() {
+      0 => {},
+      1 => parts.push(String::from("1 header")),
+      count => parts.push(format!("{count} headers")),
+    }
+    if self.token.is_some() {
+      parts.push(String::from("token"));
+    }
+    if self.client != ClientOverrides::default() {
+      parts.push(String::from("client"));
+    }
+    parts.join(" · ")
+  }
+}
+
+/// Whether a header is in the list, header names are case-insensitive.
+pub fn has_header(headers: &[(String, String)], name: &str) -> bool {
+  headers.iter().any(|(header, _)| header.eq_ignore_ascii_case(name))
+}
+
+/// Standing headers of the header tab while a profile is active, its own first and then the defaults it doesn't
+/// replace.
+pub fn standing_headers(profile: Option<&Profile>, defaults: &[(String, String)]) -> Vec<(String, String)> {
+  let mut headers = profile.map(Profile::headers).unwrap_or_default();
+  let defaults = defaults.iter().filter(|(name, _)| !has_header(&headers, name)).cloned().collect::<Vec<_>>();
+  headers.extend(defaults);
+  headers
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_standing_headers() {
+    let profile = Profile {
+      base_url: Some(String::from("https://staging.example.com")),
+      headers: BTreeMap::from([(String::from("Accept-Language"), String::from("de"))]),
+      token: Some(String::from("{{staging_token}}")),
+      client: ClientOverrides::default(),
+    };
+    let defaults = vec![
+      (String::from("User-Agent"), String::from("openapi-tui")),
+      (String::from("accept-language"), String::from("en-US")),
+    ];
+    assert_eq!(
+      standing_headers(Some(&profile), &defaults),
+      [
+        (String::from("Accept-Language"), String::from("de")),
+        (String::from("Authorization"), String::from("Bearer {{staging_token}}")),
+        (String::from("User-Agent"), String::from("openapi-tui")),
+      ]
+    );
+    assert_eq!(standing_headers(None, &defaults), defaults);
+    assert_eq!(profile.summary(), "https://staging.example.com · 1 header · token");
+  }
+}
@@ -0,0 +1,205 @@
+use std::{
+  collections::HashMap,
+  fmt::Display,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+
+use crate::utils::parse_duration;
+
+/// Period of a declared limit that doesn't say one.
+const DEFAULT_PERIOD: Duration = Duration::from_secs(60);
+
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Reset values above this are unix timestamps rather than seconds from now.
+const EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+/// A limit declared in the spec, `limit` requests every `period`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+  pub limit: u64,
+  pub period: Duration,
+}
+
+impl Display for RateLimit {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} per {}s", self.limit, self.period.as_secs())
+  }
+}
+
+fn period(value: &Value) -> Option<Duration> {
+  match value {
+    Value::Number(seconds) => seconds.as_f64().filter(|seconds| *seconds > 0.0).map(Duration::from_secs_f64),
+    Value::String(period) => parse_duration(period.trim()),
+    _ => None,
+  }
+}
+
+/// Limit of the `x-ratelimit` or `x-rate-limit` extension of a spec object, as `{limit, period}` or as
+/// `x-ratelimit-limit` with `x-ratelimit-period`. Periods are seconds or durations like `1m`.
+fn from_extensions(object: &Value) -> Option<RateLimit> {
+  let (limit, period_value) = ["x-ratelimit", "x-rate-limit"]
+    .iter()
+    .find_map(|key| {
+      let extension = object.get(key)?;
+      let limit = ["limit", "requests"].iter().find_map(|key| extension[key].as_u64())?;
+      Some((limit, ["period", "window", "interval"].iter().find_map(|key| extension.get(key))))
+    })
+    .or_else(|| {
+      ["x-ratelimit-", "x-rate-limit-"].iter().find_map(|prefix| {
+        let limit = object[format!("{prefix}limit")].as_u64()?;
+        Some((limit, ["period", "window"].iter().find_map(|key| object.get(format!("{prefix}{key}")))))
+      })
+    })?;
+  Some(RateLimit { limit, period: period_value.and_then(period).unwrap_or(DEFAULT_PERIOD) })
+}
+
+/// Limits declared by the vendor extensions of a spec, for the whole API and per operation. Operations inherit the
+/// limit of their path item.
+#[derive(Debug, Default, PartialEq)]
+pub struct Declared {
+  pub api: Option<RateLimit>,
+  operations: HashMap<String, RateLimit>,
+}
+
+impl Declared {
+  /// Reads the limits from the text of a spec, in json or yaml.
+  pub fn from_spec(text: &str) -> Self {
+    let Ok(spec) = serde_yaml::from_str::<Value>(text) else {
+      return Self::default();
+    };
+    let api = from_extensions(&spec).or_else(|| from_extensions(&spec["info"]));
+    let mut operations = HashMap::new();
+    for (path, path_item) in spec["paths"].as_object().into_iter().flatten() {
+      let path_limit = from_extensions(path_item);
+      let path_operations = path_item.as_object().into_iter().flatten();
+      for (method, operation) in path_operations.filter(|(method, _)| METHODS.contains(&method.as_str())) {
+        if let Some(limit) = from_extensions(operation).or(path_limit) {
+          operations.insert(format!("{} {path}", method.to_uppercase()), limit);
+        }
+      }
+    }
+    Self { api, operations }
+  }
+
+  /// Limit of an operation, or of the whole API when the operation declares none.
+  pub fn of(&self, method: &str, path: &str) -> Option<RateLimit> {
+    self.operation(method, path).or(self.api)
+  }
+
+  /// Limit the operation or its path item declares, without the one of the whole API.
+  pub fn operation(&self, method: &str, path: &str) -> Option<RateLimit> {
+    self.operations.get(&format!("{} {path}", method.to_uppercase())).copied()
+  }
+}
+
+/// Quota reported by the rate limit headers of a response.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Quota {
+  pub limit: Option<u64>,
+  pub remaining: Option<u64>,
+  /// Time from the response until the quota resets.
+  pub reset: Option<Duration>,
+}
+
+fn reset(value: u64) -> Duration {
+  if value > EPOCH_THRESHOLD {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    Duration::from_secs(value.saturating_sub(now))
+  } else {
+    Duration::from_secs(value)
+  }
+}
+
+impl Quota {
+  /// Quota of the `x-ratelimit-*`, `x-rate-limit-*`, `ratelimit-*` or `ratelimit` headers, with `retry-after` as
+  /// the reset when they don't have one.
+  pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+    let number = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<u64>().ok();
+    let field = |field: &str| {
+      ["x-ratelimit-", "x-rate-limit-", "ratelimit-"].iter().find_map(|prefix| number(&format!("{prefix}{field}")))
+    };
+    let combined = headers.get("ratelimit").and_then(|value| value.to_str().ok()).unwrap_or_default();
+    let combined_field = |field: &str| {
+      combined.split([',', ';']).find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        name.trim().eq(field).then(|| value.trim().parse::<u64>().ok()).flatten()
+      })
+    };
+    let quota = Self {
+      limit: field("limit").or_else(|| combined_field("limit")),
+      remaining: field("remaining").or_else(|| combined_field("remaining")),
+      reset: field("reset").or_else(|| combined_field("reset")).or_else(|| number("retry-after")).map(reset),
+    };
+    (quota != Self::default()).then_some(quota)
+  }
+
+  /// Time left until the quota resets, `elapsed` after the response.
+  pub fn reset_in(&self, elapsed: Duration) -> Option<Duration> {
+    self.reset.and_then(|reset| reset.checked_sub(elapsed))
+  }
+
+  /// Remaining quota and when it resets, e.g. `quota 42/100 resets in 30s`.
+  pub fn label(&self, elapsed: Duration) -> String {
+    let mut label = match (self.remaining, self.limit) {
+      (Some(remaining), Some(limit)) => format!("quota {remaining}/{limit}"),
+      (Some(remaining), None) => format!("quota {remaining} left"),
+      (None, Some(limit)) => format!("quota of {limit}"),
+      (None, None) => String::from("rate limited"),
+    };
+    if let Some(reset_in) = self.reset_in(elapsed) {
+      label.push_str(format!(" resets in {}s", reset_in.as_secs()).as_str());
+    }
+    label
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use reqwest::header::HeaderValue;
+
+  use super::*;
+
+  #[test]
+  fn test_declared() {
+    let declared = Declared::from_spec(
+      r#"
+x-ratelimit: {limit: 1000, period: 1m}
+paths:
+  /pets:
+    x-ratelimit-limit: 10
+    get: {}
+    post:
+      x-rate-limit: {requests: 2, window: 5}
+"#,
+    );
+    assert_eq!(declared.api, Some(RateLimit { limit: 1000, period: Duration::from_secs(60) }));
+    assert_eq!(declared.of("get", "/pets"), Some(RateLimit { limit: 10, period: DEFAULT_PERIOD }));
+    assert_eq!(declared.of("POST", "/pets"), Some(RateLimit { limit: 2, period: Duration::from_secs(5) }));
+    assert_eq!(declared.of("GET", "/owners"), declared.api);
+    assert_eq!(declared.operation("GET", "/owners"), None);
+  }
+
+  #[test]
+  fn test_quota() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(Quota::from_headers(&headers), None);
+    headers.insert("x-ratelimit-limit", HeaderValue::from_static("100"));
+    headers.insert("x-ratelimit-remaining", HeaderValue::from_static("42"));
+    headers.insert("retry-after", HeaderValue::from_static("30"));
+    let quota = Quota::from_headers(&headers).unwrap();
+    assert_eq!(quota.label(Duration::from_secs(10)), "quota 42/100 resets in 20s");
+    assert_eq!(quota.label(Duration::from_secs(40)), "quota 42/100");
+
+    let mut headers = HeaderMap::new();
+    headers.insert("ratelimit", HeaderValue::from_static("limit=10, remaining=0, reset=5"));
+    assert_eq!(
+      Quota::from_headers(&headers),
+      Some(Quota { limit: Some(10), remaining: Some(0), reset: Some(Duration::from_secs(5)) })
+    );
+  }
+}
@@ -0,0 +1,272 @@
+use std::net::SocketAddr;
+
+use color_eyre::eyre::{ContextCompat, Result};
+use openapi_31::v31::Openapi;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tokio::{
+  io::{AsyncWriteExt, BufReader},
+  net::TcpListener,
+  sync::mpsc::UnboundedSender,
+  task::JoinHandle,
+};
+
+use crate::{action::Action, client::Client, schema_inference, webhook};
+
+/// Headers of the caller that are not forwarded, the client sets them for the upstream request.
+const HOP_HEADERS: [&str; 5] = ["host", "connection", "content-length", "transfer-encoding", "proxy-connection"];
+
+/// A request forwarded by the recording proxy and the response it got.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+  pub method: String,
+  /// Path relative to the target, without the query string.
+  pub path: String,
+  pub query: Vec<String>,
+  pub request_content_type: Option<String>,
+  pub request_body: Vec<u8>,
+  pub status: u16,
+  pub response_content_type: Option<String>,
+  pub response_body: Vec<u8>,
+}
+
+/// Forwards calls to the listener to `target`, answering them with the upstream response, and sends every call with
+/// its response back as `Action::Recorded`. Absolute urls, as sent to a forward proxy, are forwarded as they are.
+pub async fn listen(
+  address: &str,
+  target: reqwest::Url,
+  client: Client,
+  action_tx: UnboundedSender<Action>,
+) -> Result<(SocketAddr, JoinHandle<()>)> {
+  let listener = TcpListener::bind(address).await?;
+  let local_address = listener.local_addr()?;
+  let handle = tokio::spawn(async move {
+    while let Ok((stream, _)) = listener.accept().await {
+      let (target, client, action_tx) = (target.clone(), client.clone(), action_tx.clone());
+      tokio::spawn(async move {
+        let (reader, mut writer) = stream.into_split();
+        let reply = match forward(&mut BufReader::new(reader), &target, &client).await {
+          Ok((reply, exchange)) => {
+            action_tx.send(Action::Recorded(exchange)).ok();
+            reply
+          },
+          Err(error) => {
+            log::error!("can't forward recorded request: {error}");
+            b"HTTP/1.1 502 Bad Gateway\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_vec()
+          },
+        };
+        writer.write_all(&reply).await.ok();
+      });
+    }
+  });
+  Ok((local_address, handle))
+}
+
+async fn forward(
+  reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>,
+  target: &reqwest::Url,
+  client: &Client,
+) -> Result<(Vec<u8>, Exchange)> {
+  let delivery = webhook::read_delivery(reader).await?;
+  let url = if delivery.path.starts_with("http://") || delivery.path.starts_with("https://") {
+    reqwest::Url::parse(delivery.path.as_str())?
+  } else {
+    reqwest::Url::parse(format!("{}{}", target.as_str().trim_end_matches('/'), delivery.path).as_str())?
+  };
+  let header = |name: &str| delivery.headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.clone());
+  let mut request = client.request(delivery.method.parse()?, url.clone()).body(delivery.body.clone());
+  for (name, value) in delivery.headers.iter().filter(|(name, _)| !HOP_HEADERS.contains(&name.as_str())) {
+    request = request.header(name, value);
+  }
+  let response = client.execute(request.build()?).await?;
+  let status = response.status();
+  let response_content_type =
+    response.headers().get(reqwest::header::CONTENT_TYPE).and_then(|value| value.to_str().ok()).map(String::from);
+  let mut reply = format!("HTTP/1.1 {} {}\r\n", status.as_str(), status.canonical_reason().unwrap_or_default());
+  for (name, value) in response.headers() {
+    if !HOP_HEADERS.contains(&name.as_str()) {
+      reply.push_str(format!("{name}: {}\r\n", String::from_utf8_lossy(value.as_bytes())).as_str());
+    }
+  }
+  let body = response.bytes().await?;
+  reply.push_str(format!("content-length: {}\r\nconnection: close\r\n\r\n", body.len()).as_str());
+  let mut reply = reply.into_bytes();
+  reply.extend_from_slice(&body);
+
+  let path = url.path().strip_prefix(target.path().trim_end_matches('/')).filter(|path| path.starts_with('/'));
+  let exchange = Exchange {
+    method: delivery.method.to_lowercase(),
+    path: path.unwrap_or(url.path()).to_string(),
+    query: url.query_pairs().map(|(name, _)| name.to_string()).collect(),
+    request_content_type: header("content-type"),
+    request_body: delivery.body,
+    status: status.as_u16(),
+    response_content_type,
+    response_body: body.to_vec(),
+  };
+  Ok((reply, exchange))
+}
+
+/// Path with ids, like numbers, uuids or long hex strings, replaced by parameters named after the previous segment,
+/// e.g. `/pets/42` is `/pets/{petsId}`, and the names of those parameters.
+pub fn path_template(path: &str) -> (String, Vec<String>) {
+  let is_id = |segment: &str| {
+    segment.bytes().all(|byte| byte.is_ascii_digit())
+      || (segment.len() == 36 && segment.bytes().all(|byte| byte.is_ascii_hexdigit() || byte == b'-'))
+      || (segment.len() >= 16 && segment.bytes().all(|byte| byte.is_ascii_hexdigit()))
+  };
+  let mut parameters: Vec<String> = vec![];
+  let mut previous = "";
+  let segments = path
+    .split('/')
+    .map(|segment| {
+      let template = if !segment.is_empty() && is_id(segment) {
+        let base = previous.chars().filter(char::is_ascii_alphanumeric).collect::<String>();
+        let mut name = if base.is_empty() { String::from("id") } else { format!("{base}Id") };
+        if parameters.contains(&name) {
+          name = format!("{name}{}", parameters.len() + 1);
+        }
+        parameters.push(name.clone());
+        format!("{{{name}}}")
+      } else {
+        segment.to_string()
+      };
+      previous = segment;
+      template
+    })
+    .collect::<Vec<_>>();
+  (segments.join("/"), parameters)
+}
+
+fn json_schema(content_type: Option<&String>, body: &[u8]) -> Option<(String, Value)> {
+  let body = serde_json::from_slice::<Value>(body).ok()?;
+  let content_type = content_type.map_or(String::from("application/json"), |content_type| {
+    content_type.split(';').next().unwrap_or_default().trim().to_string()
+  });
+  Some((content_type, schema_inference::infer(&body)))
+}
+
+/// Adds a schema to a content map, merging it with the one seen before for the same media type.
+fn add_content(content: &mut Value, content_type: String, schema: Value) {
+  let merged = match content.get(&content_type).and_then(|media_type| media_type.get("schema")) {
+    Some(existing) => schema_inference::merge(existing, &schema),
+    None => schema,
+  };
+  content[content_type] = json!({"schema": merged});
+}
+
+/// Draft path items of the recorded exchanges, with path and query parameters and request and response schemas
+/// inferred from JSON bodies.
+pub fn path_items(exchanges: &[Exchange]) -> Map<String, Value> {
+  let mut paths = Map::new();
+  for exchange in exchanges {
+    let (path, path_parameters) = path_template(exchange.path.as_str());
+    let path_item = paths.entry(path).or_insert_with(|| json!({}));
+    let operation = path_item.as_object_mut().and_then(|path_item| {
+      let operation = path_item.entry(exchange.method.clone()).or_insert_with(|| {
+        let parameters = path_parameters
+          .iter()
+          .map(|name| json!({"name": name, "in": "path", "required": true, "schema": {"type": "string"}}))
+          .collect::<Vec<_>>();
+        json!({"parameters": parameters, "responses": {}})
+      });
+      operation.as_object_mut()
+    });
+    let Some(operation) = operation else {
+      continue;
+    };
+    if let Some(parameters) = operation.get_mut("parameters").and_then(Value::as_array_mut) {
+      for name in &exchange.query {
+        if !parameters.iter().any(|parameter| parameter["name"] == *name && parameter["in"] == "query") {
+          parameters.push(json!({"name": name, "in": "query", "schema": {"type": "string"}}));
+        }
+      }
+    }
+    if let Some((content_type, schema)) =
+      json_schema(exchange.request_content_type.as_ref(), exchange.request_body.as_slice())
+    {
+      let request_body = operation.entry("requestBody").or_insert_with(|| json!({"content": {}}));
+      add_content(&mut request_body["content"], content_type, schema);
+    }
+    let response = &mut operation["responses"][exchange.status.to_string()];
+    if response.is_null() {
+      *response = json!({"description": ""});
+    }
+    if let Some((content_type, schema)) =
+      json_schema(exchange.response_content_type.as_ref(), exchange.response_body.as_slice())
+    {
+      if response.get("content").is_none() {
+        response["content"] = json!({});
+      }
+      add_content(&mut response["content"], content_type, schema);
+    }
+  }
+  paths
+}
+
+/// Draft spec with the path items of the recorded exchanges.
+pub fn draft_spec(exchanges: &[Exchange], target: &str) -> Value {
+  json!({
+    "openapi": "3.1.0",
+    "info": {"title": format!("Recorded from {target}"), "version": "0.0.0"},
+    "servers": [{"url": target}],
+    "paths": path_items(exchanges),
+  })
+}
+
+/// Spec with the recorded path items added, operations already in the spec are kept as they are.
+pub fn merge(spec: &Openapi, exchanges: &[Exchange]) -> Result<Openapi> {
+  let mut merged = serde_json::to_value(spec)?;
+  let paths = merged.as_object_mut().context("spec is not an object")?.entry("paths").or_insert_with(|| json!({}));
+  let paths = paths.as_object_mut().context("paths is not an object")?;
+  for (path, path_item) in path_items(exchanges) {
+    let existing = paths.entry(path).or_insert_with(|| json!({}));
+    if let (Some(existing), Value::Object(operations)) = (existing.as_object_mut(), path_item) {
+      for (method, operation) in operations {
+        existing.entry(method).or_insert(operation);
+      }
+    }
+  }
+  Ok(serde_json::from_value(merged)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn exchange(method: &str, path: &str, status: u16, response_body: &str) -> Exchange {
+    Exchange {
+      method: method.into(),
+      path: path.into(),
+      query: vec![],
+      request_content_type: None,
+      request_body: vec![],
+      status,
+      response_content_type: Some("application/json; charset=utf-8".into()),
+      response_body: response_body.as_bytes().to_vec(),
+    }
+  }
+
+  #[test]
+  fn test_path_items() {
+    assert_eq!(
+      path_template("/pets/42/toys/0b9e3c4a-7d2f-4c1e-9a8b-1f2e3d4c5b6a"),
+      (String::from("/pets/{petsId}/toys/{toysId}"), vec![String::from("petsId"), String::from("toysId")])
+    );
+    let mut list = exchange("get", "/pets", 200, r#"[{"id": 1}]"#);
+    list.query = vec!["limit".into()];
+    let exchanges =
+      [list, exchange("get", "/pets/1", 200, r#"{"id": 1, "tag": "cat"}"#), exchange("get", "/pets/2", 404, "")];
+    let paths = path_items(&exchanges);
+    assert_eq!(paths["/pets"]["get"]["parameters"][0]["name"], "limit");
+    let get_pet = &paths["/pets/{petsId}"]["get"];
+    assert_eq!(get_pet["parameters"][0]["in"], "path");
+    assert_eq!(get_pet["responses"]["200"]["content"]["application/json"]["schema"]["required"], json!(["id", "tag"]));
+    assert_eq!(get_pet["responses"]["404"], json!({"description": ""}));
+
+    let spec = merge(&Openapi::default(), &exchanges).unwrap();
+    assert_eq!(spec.into_operations().count(), 2);
+  }
+}
@@ -1,4 +1,263 @@
+use std::borrow::Cow;
+
+use color_eyre::eyre::{Result, WrapErr};
+use openapi_31::v31::Operation;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS, NON_ALPHANUMERIC};
+
+use crate::{auth, idempotency, media_type, state::State, trace, variables};
+
+/// Characters left as they are in an RFC 8187 extended parameter value.
+const ATTR_CHARS: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'!')
+  .remove(b'#')
+  .remove(b'$')
+  .remove(b'&')
+  .remove(b'+')
+  .remove(b'-')
+  .remove(b'.')
+  .remove(b'^')
+  .remove(b'_')
+  .remove(b'`')
+  .remove(b'|')
+  .remove(b'~');
+
 pub struct Request {
   pub operation_id: String,
   pub request: reqwest::Request,
+  pub append: bool,
+}
+
+/// Adds what a request of an operation is sent with besides its parameters and body: the credentials of the
+/// security schemes it accepts unless `credentials` is unset, e.g. for a request to another host, the variables, a
+/// `traceparent` header when `traceparent` is set and an `Idempotency-Key` when the operation is marked idempotent.
+pub fn prepare(
+  request: &mut reqwest::Request,
+  operation: &Operation,
+  operation_id: &str,
+  state: &State,
+  credentials: bool,
+  traceparent: bool,
+) -> Result<()> {
+  if credentials {
+    let requirements = auth::requirements(operation, &state.openapi_spec).unwrap_or_default();
+    let covered = state.security_schemes.covered(&requirements, &state.credentials);
+    auth::apply(request, &covered, &state.variables).wrap_err("can't add credentials")?;
+  }
+  variables::apply(request, &state.variables);
+  if traceparent {
+    trace::apply(request);
+  }
+  if state.idempotent_operations.contains(operation_id) {
+    idempotency::apply(request);
+  }
+  Ok(())
+}
+
+/// Header value with its non-ASCII text encoded, since servers can only be expected to read ASCII in headers.
+/// Parameters after a `;` become RFC 8187 extended parameters, `attachment; filename="Grüße.txt"` is sent as
+/// `attachment; filename*=UTF-8''Gr%C3%BC%C3%9Fe.txt`, and the rest is percent encoded as UTF-8.
+pub fn encode_header_value(value: &str) -> Cow<'_, str> {
+  if value.is_ascii() {
+    return Cow::Borrowed(value);
+  }
+  let parts = value.split(';').enumerate().map(|(index, part)| match part.split_once('=') {
+    _ if part.is_ascii() => part.to_string(),
+    Some((name, parameter)) if index > 0 && !name.trim().is_empty() => {
+      let parameter = parameter.trim().trim_matches('"');
+      format!("{}*=UTF-8''{}", name.trim_end(), utf8_percent_encode(parameter, ATTR_CHARS))
+    },
+    _ => utf8_percent_encode(part, CONTROLS).to_string(),
+  });
+  Cow::Owned(parts.collect::<Vec<_>>().join(";"))
+}
+
+/// Headers of a request, values of secrets are shown as `<secret>`.
+pub fn header_lines(request: &reqwest::Request) -> Vec<(String, String)> {
+  request
+    .headers()
+    .iter()
+    .map(|(name, value)| {
+      let value =
+        if value.is_sensitive() { String::from("<secret>") } else { value.to_str().unwrap_or("<binary>").to_string() };
+      (name.to_string(), value)
+    })
+    .collect()
+}
+
+pub fn body_text(request: &reqwest::Request) -> Option<String> {
+  request.body().and_then(|body| body.as_bytes()).map(|bytes| String::from_utf8_lossy(bytes).to_string())
+}
+
+/// Url of a request with its `{{name}}` placeholders as they were typed rather than percent encoded.
+pub fn template_url(request: &reqwest::Request) -> String {
+  request.url().as_str().replace("%7B%7B", "{{").replace("%7D%7D", "}}")
+}
+
+fn shell_quote(value: &str) -> String {
+  format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+pub fn to_http(request: &reqwest::Request) -> String {
+  let url = request.url();
+  let target = match url.query() {
+    Some(query) => format!("{}?{}", url.path(), query),
+    None => url.path().to_string(),
+  };
+  let mut lines = vec![format!("{} {} HTTP/1.1", request.method(), target)];
+  if let Some(host) = url.host_str() {
+    match url.port() {
+      Some(port) => lines.push(format!("host: {host}:{port}")),
+      None => lines.push(format!("host: {host}")),
+    }
+  }
+  lines.extend(header_lines(request).into_iter().map(|(name, value)| format!("{name}: {value}")));
+  if let Some(body) = body_text(request) {
+    lines.push(String::default());
+    lines.push(body);
+  }
+  lines.join("\n")
+}
+
+/// Common copy-paste mistakes in a request about to be sent: a body with GET/HEAD, a `content-length` header not
+/// matching the body and a body that looks like JSON or XML sent as another content type.
+pub fn consistency_warnings(request: &reqwest::Request) -> Vec<String> {
+  let mut warnings = vec![];
+  let body = request.body().and_then(|body| body.as_bytes()).filter(|bytes| !bytes.is_empty());
+  let Some(body) = body else {
+    return warnings;
+  };
+  if matches!(*request.method(), reqwest::Method::GET | reqwest::Method::HEAD) {
+    warnings.push(format!("{} request carries a body", request.method()));
+  }
+  if let Some(content_length) = request
+    .headers()
+    .get(reqwest::header::CONTENT_LENGTH)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.trim().parse::<usize>().ok())
+    .filter(|content_length| *content_length != body.len())
+  {
+    warnings.push(format!("content-length is {content_length} but the body is {} bytes", body.len()));
+  }
+  let content_type =
+    request.headers().get(reqwest::header::CONTENT_TYPE).and_then(|value| value.to_str().ok()).map(media_type::essence);
+  if let (Some(content_type), Some(apparent)) = (content_type, apparent_format(body)) {
+    let declared = if media_type::is_json(&content_type) {
+      Some("JSON")
+    } else if media_type::is_xml(&content_type) {
+      Some("XML")
+    } else {
+      None
+    };
+    if declared != Some(apparent) {
+      warnings.push(format!("{apparent} body is sent as {content_type}"));
+    }
+  }
+  warnings
+}
+
+fn apparent_format(body: &[u8]) -> Option<&'static str> {
+  let text = String::from_utf8_lossy(body);
+  let text = text.trim();
+  if (text.starts_with('{') || text.starts_with('[')) && serde_json::from_str::<serde_json::Value>(text).is_ok() {
+    return Some("JSON");
+  }
+  if text.starts_with('<') && text.ends_with('>') {
+    return Some("XML");
+  }
+  None
+}
+
+pub fn to_curl(request: &reqwest::Request) -> String {
+  let mut lines = vec![format!("curl -X {} {}", request.method(), shell_quote(request.url().as_str()))];
+  lines.extend(
+    header_lines(request).into_iter().map(|(name, value)| format!("  -H {}", shell_quote(&format!("{name}: {value}")))),
+  );
+  if let Some(body) = body_text(request) {
+    lines.push(format!("  --data-raw {}", shell_quote(&body)));
+  }
+  lines.join(" \\\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_encode_header_value() {
+    assert_eq!(encode_header_value("plain; q=1"), "plain; q=1");
+    assert_eq!(
+      encode_header_value("attachment; filename=\"Grüße.txt\""),
+      "attachment; filename*=UTF-8''Gr%C3%BC%C3%9Fe.txt"
+    );
+    assert_eq!(encode_header_value("東京 office"), "%E6%9D%B1%E4%BA%AC office");
+  }
+
+  #[test]
+  fn test_request_rendering() {
+    let mut secret = reqwest::header::HeaderValue::from_static("abc");
+    secret.set_sensitive(true);
+    let request = reqwest::Client::new()
+      .post("https://example.com/pets?limit=1")
+      .header("content-type", "application/json")
+      .header("x-api-key", secret)
+      .body("{\"name\":\"it's\"}")
+      .build()
+      .unwrap();
+
+    assert_eq!(
+      to_curl(&request),
+      "curl -X POST 'https://example.com/pets?limit=1' \\\n  -H 'content-type: application/json' \\\n  -H 'x-api-key: \
+       <secret>' \\\n  --data-raw '{\"name\":\"it'\\''s\"}'"
+    );
+    assert_eq!(
+      to_http(&request),
+      "POST /pets?limit=1 HTTP/1.1\nhost: example.com\ncontent-type: application/json\nx-api-key: <secret>\n\n{\"name\":\"it's\"}"
+    );
+  }
+
+  #[test]
+  fn test_prepare() {
+    let mut state = State {
+      security_schemes: auth::SecuritySchemes::from_spec(
+        "components: {securitySchemes: {api_key: {type: apiKey, name: X-API-Key, in: header}}}",
+      ),
+      ..Default::default()
+    };
+    state.credentials.insert("api_key".into(), "{{key}}".into());
+    state.variables.insert("key".into(), "k1".into());
+    state.variables.insert("id".into(), "7".into());
+    state.idempotent_operations.insert("createPet".into());
+    let operation = serde_yaml::from_str::<Operation>("security: [{api_key: []}]").unwrap();
+    let mut request = reqwest::Client::new().post("https://example.com/pets/{{id}}").build().unwrap();
+    prepare(&mut request, &operation, "createPet", &state, true, true).unwrap();
+    assert_eq!(request.url().as_str(), "https://example.com/pets/7");
+    assert_eq!(request.headers()["x-api-key"], "k1");
+    assert!(request.headers().contains_key(trace::TRACEPARENT));
+    assert!(idempotency::key(&request).is_some());
+
+    let mut request = reqwest::Client::new().get("https://elsewhere.com/pets").build().unwrap();
+    prepare(&mut request, &operation, "listPets", &state, false, false).unwrap();
+    assert!(request.headers().is_empty());
+  }
+
+  #[test]
+  fn test_consistency_warnings() {
+    let client = reqwest::Client::new();
+    let request = client.post("https://example.com").header("content-type", "application/json").body("{}");
+    assert!(consistency_warnings(&request.build().unwrap()).is_empty());
+
+    let request = client.post("https://example.com").header("content-type", "application/json").body("<pet/>");
+    assert_eq!(consistency_warnings(&request.build().unwrap()), ["XML body is sent as application/json"]);
+
+    let request = client.get("https://example.com").header("content-length", "5").body("[1]");
+    assert_eq!(
+      consistency_warnings(&request.build().unwrap()),
+      ["GET request carries a body", "content-length is 5 but the body is 3 bytes"]
+    );
+
+    let request = client.post("https://example.com").header("content-type", "application/problem+json").body("[]");
+    assert!(consistency_warnings(&request.build().unwrap()).is_empty());
+  }
 }
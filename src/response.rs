@@ -1,7 +1,335 @@
+use std::{
+  borrow::Cow,
+  fmt::Write,
+  io::Read,
+  path::{Path, PathBuf},
+  time::{Duration, Instant},
+};
+
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+use crate::{media_type, rate_limit::Quota, tls, webhook::Delivery};
+
+/// How a response body is shown, overriding what its content type says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum BodyFormat {
+  Json,
+  Yaml,
+  Xml,
+  Text,
+  Hex,
+}
+
+#[derive(Clone)]
 pub struct Response {
   pub status: reqwest::StatusCode,
   pub version: reqwest::Version,
   pub headers: reqwest::header::HeaderMap,
   pub content_length: Option<u64>,
   pub body: String,
+  pub bytes: Vec<u8>,
+  pub received_at: Instant,
+  /// What happened while the request was in flight, relative to when it was sent.
+  pub timeline: Vec<(Duration, String)>,
+  /// Trace id of the `traceparent` header the request was sent with.
+  pub trace_id: Option<String>,
+  /// `Idempotency-Key` header the request was sent with.
+  pub idempotency_key: Option<String>,
+  /// Rate limit quota reported by the response headers.
+  pub quota: Option<Quota>,
+  /// Certificate the server presented, for HTTPS calls.
+  pub certificate: Option<tls::Certificate>,
+  /// Fingerprint remembered for the host when it presented another certificate, in trust on first use mode.
+  pub certificate_changed: Option<String>,
+}
+
+impl Response {
+  pub async fn from_reqwest(response: reqwest::Response) -> Result<Self> {
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let content_length = response.content_length();
+    let certificate = tls::certificate(&response);
+    let bytes = response.bytes().await?.to_vec();
+    Ok(Self {
+      status,
+      version,
+      body: decode(&decompress(&bytes, &headers), &headers),
+      quota: Quota::from_headers(&headers),
+      headers,
+      content_length,
+      bytes,
+      received_at: Instant::now(),
+      timeline: vec![],
+      trace_id: None,
+      idempotency_key: None,
+      certificate,
+      certificate_changed: None,
+    })
+  }
+
+  /// A webhook call received in test mode, shown like a response to the listener.
+  pub fn from_delivery(delivery: &Delivery) -> Self {
+    Self::from_parts(reqwest::StatusCode::OK, &delivery.headers, delivery.body.clone())
+  }
+
+  /// Response made of a status, headers and body that didn't come from a call, like a webhook delivery or a response
+  /// restored from history.
+  pub fn from_parts(status: reqwest::StatusCode, headers: &[(String, String)], bytes: Vec<u8>) -> Self {
+    let headers = headers
+      .iter()
+      .filter_map(|(name, value)| {
+        Some((
+          reqwest::header::HeaderName::from_bytes(name.as_bytes()).ok()?,
+          reqwest::header::HeaderValue::from_str(value).ok()?,
+        ))
+      })
+      .collect::<reqwest::header::HeaderMap>();
+    Self {
+      status,
+      version: reqwest::Version::HTTP_11,
+      body: decode(&decompress(&bytes, &headers), &headers),
+      headers,
+      content_length: Some(bytes.len() as u64),
+      bytes,
+      received_at: Instant::now(),
+      timeline: vec![],
+      trace_id: None,
+      idempotency_key: None,
+      quota: None,
+      certificate: None,
+      certificate_changed: None,
+    }
+  }
+}
+
+impl Response {
+  /// Body pretty printed when the content type, or the body itself, is json or xml.
+  pub fn pretty_body(&self) -> String {
+    let content_type = self
+      .headers
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .unwrap_or_default();
+    if let Ok(body) = self.format_body(BodyFormat::Json) {
+      return body;
+    }
+    if media_type::is_xml(content_type) {
+      return pretty_xml(&self.body);
+    }
+    self.body.clone()
+  }
+
+  /// Body with its `Content-Encoding` undone, the bytes as received when it's not gzip or deflate.
+  pub fn content(&self) -> Cow<'_, [u8]> {
+    decompress(&self.bytes, &self.headers)
+  }
+
+  /// Writes the body as received, or decoded and pretty printed, to a file. A directory gets a file named after the
+  /// operation, and a path without extension gets the one of the content type. Returns the path written to.
+  pub fn save(&self, filepath: &str, operation_id: &str, decoded: bool) -> Result<PathBuf> {
+    let header = |name| self.headers.get(name).and_then(|value| value.to_str().ok()).unwrap_or_default();
+    let content_type = header(reqwest::header::CONTENT_TYPE);
+    let mut extension = media_type::extension(content_type).unwrap_or("txt").to_string();
+    let encoding = header(reqwest::header::CONTENT_ENCODING).trim().to_lowercase();
+    if !decoded && ["gzip", "x-gzip"].contains(&encoding.as_str()) {
+      extension.push_str(".gz");
+    }
+    let path = Path::new(filepath);
+    let path = if path.is_dir() {
+      path.join(format!("{operation_id}.{extension}"))
+    } else if path.extension().is_none() {
+      PathBuf::from(format!("{filepath}.{extension}"))
+    } else {
+      path.to_path_buf()
+    };
+    let content = match decoded {
+      true if content_type.is_empty() || media_type::extension(content_type).is_some_and(is_text_extension) => {
+        Cow::Owned(self.pretty_body().into_bytes())
+      },
+      true => self.content(),
+      false => Cow::Borrowed(self.bytes.as_slice()),
+    };
+    std::fs::write(&path, content)?;
+    Ok(path)
+  }
+
+  /// Name, value and attributes of the cookies set by the response.
+  pub fn cookies(&self) -> Vec<(String, String, String)> {
+    self
+      .headers
+      .get_all(reqwest::header::SET_COOKIE)
+      .iter()
+      .filter_map(|header| header.to_str().ok())
+      .filter_map(|header| {
+        let (cookie, attributes) = header.split_once(';').unwrap_or((header, ""));
+        let (name, value) = cookie.split_once('=')?;
+        let attributes = attributes.split(';').map(str::trim).filter(|item| !item.is_empty()).collect::<Vec<_>>();
+        Some((name.trim().to_string(), value.trim().to_string(), attributes.join("; ")))
+      })
+      .collect()
+  }
+
+  /// Body as the given format: pretty printed json, yaml or xml, plain text or a hex dump.
+  pub fn format_body(&self, format: BodyFormat) -> Result<String> {
+    Ok(match format {
+      BodyFormat::Json => serde_json::to_string_pretty(&serde_json::from_str::<serde_json::Value>(&self.body)?)?,
+      BodyFormat::Yaml => serde_yaml::to_string(&serde_yaml::from_str::<serde_yaml::Value>(&self.body)?)?,
+      BodyFormat::Xml => pretty_xml(&self.body),
+      BodyFormat::Text => self.body.clone(),
+      BodyFormat::Hex => hex_dump(&self.bytes),
+    })
+  }
+}
+
+/// Whether a file extension names a format that is read as text.
+fn is_text_extension(extension: &str) -> bool {
+  ["json", "xml", "html", "csv", "txt", "md", "yaml", "jsonl"].contains(&extension)
+}
+
+/// Undoes a gzip or deflate `Content-Encoding`, leaving other encodings and broken streams as they are.
+fn decompress<'a>(bytes: &'a [u8], headers: &reqwest::header::HeaderMap) -> Cow<'a, [u8]> {
+  let encoding = headers
+    .get(reqwest::header::CONTENT_ENCODING)
+    .and_then(|encoding| encoding.to_str().ok())
+    .unwrap_or_default()
+    .trim()
+    .to_lowercase();
+  let mut content = vec![];
+  let decompressed = match encoding.as_str() {
+    "gzip" | "x-gzip" => flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut content),
+    "deflate" => flate2::read::ZlibDecoder::new(bytes).read_to_end(&mut content).or_else(|_| {
+      content.clear();
+      flate2::read::DeflateDecoder::new(bytes).read_to_end(&mut content)
+    }),
+    _ => return Cow::Borrowed(bytes),
+  };
+  match decompressed {
+    Ok(_) => Cow::Owned(content),
+    Err(error) => {
+      log::error!("can't decode {encoding} body: {error}");
+      Cow::Borrowed(bytes)
+    },
+  }
+}
+
+/// Decodes a body with the charset of its `Content-Type`, utf-8 when there is none or it's unknown.
+fn decode(bytes: &[u8], headers: &reqwest::header::HeaderMap) -> String {
+  let encoding = headers
+    .get(reqwest::header::CONTENT_TYPE)
+    .and_then(|content_type| content_type.to_str().ok())
+    .and_then(|content_type| {
+      content_type.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim().eq_ignore_ascii_case("charset").then(|| value.trim().trim_matches('"').to_string())
+      })
+    })
+    .and_then(|charset| encoding_rs::Encoding::for_label(charset.as_bytes()))
+    .unwrap_or(encoding_rs::UTF_8);
+  encoding.decode(bytes).0.into_owned()
+}
+
+fn pretty_xml(body: &str) -> String {
+  let mut result = String::new();
+  let mut depth = 0usize;
+  let mut rest = body.trim();
+  while !rest.is_empty() {
+    let (token, remaining) = match rest.strip_prefix('<') {
+      Some(_) => rest.split_at(rest.find('>').map_or(rest.len(), |end| end + 1)),
+      None => rest.split_at(rest.find('<').unwrap_or(rest.len())),
+    };
+    rest = remaining.trim_start();
+    let token = token.trim();
+    if token.is_empty() {
+      continue;
+    }
+    let closing = token.starts_with("</");
+    if closing {
+      depth = depth.saturating_sub(1);
+    }
+    writeln!(result, "{}{token}", "  ".repeat(depth)).ok();
+    let opening = token.starts_with('<') && !closing && !token.starts_with("<?") && !token.starts_with("<!");
+    if opening && !token.ends_with("/>") {
+      depth += 1;
+    }
+  }
+  result
+}
+
+fn hex_dump(bytes: &[u8]) -> String {
+  let mut result = String::new();
+  for (index, chunk) in bytes.chunks(16).enumerate() {
+    let hex = chunk.iter().map(|byte| format!("{byte:02x}")).collect::<Vec<_>>().join(" ");
+    let ascii = chunk
+      .iter()
+      .map(|byte| if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' })
+      .collect::<String>();
+    writeln!(result, "{:08x}  {hex:<47}  {ascii}", index * 16).ok();
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_decode_charset() {
+    let mut headers = reqwest::header::HeaderMap::new();
+    assert_eq!(decode(b"caf\xc3\xa9", &headers), "café");
+    headers.insert(reqwest::header::CONTENT_TYPE, "text/plain; charset=ISO-8859-1".parse().unwrap());
+    assert_eq!(decode(b"caf\xe9", &headers), "café");
+  }
+
+  #[test]
+  fn test_decompress() {
+    let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    std::io::Write::write_all(&mut encoder, br#"{"id":1}"#).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let mut headers = reqwest::header::HeaderMap::new();
+    assert_eq!(decompress(&compressed, &headers), compressed.as_slice());
+    headers.insert(reqwest::header::CONTENT_ENCODING, "gzip".parse().unwrap());
+    assert_eq!(decompress(&compressed, &headers), br#"{"id":1}"#.as_slice());
+    assert_eq!(decompress(b"plain", &headers), b"plain".as_slice());
+  }
+
+  #[test]
+  fn test_cookies() {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.append(reqwest::header::SET_COOKIE, "session=abc; Path=/; HttpOnly".parse().unwrap());
+    headers.append(reqwest::header::SET_COOKIE, "theme=dark".parse().unwrap());
+    let response = Response {
+      status: reqwest::StatusCode::OK,
+      version: reqwest::Version::HTTP_11,
+      headers,
+      content_length: None,
+      body: String::default(),
+      bytes: vec![],
+      received_at: Instant::now(),
+      timeline: vec![],
+      trace_id: None,
+      idempotency_key: None,
+      quota: None,
+      certificate: None,
+      certificate_changed: None,
+    };
+    assert_eq!(
+      response.cookies(),
+      vec![
+        ("session".to_string(), "abc".to_string(), "Path=/; HttpOnly".to_string()),
+        ("theme".to_string(), "dark".to_string(), String::default()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_format_body() {
+    assert_eq!(pretty_xml("<a><b x=\"1\">text</b><c/></a>"), "<a>\n  <b x=\"1\">\n    text\n  </b>\n  <c/>\n</a>\n");
+    assert_eq!(hex_dump(b"hi\n"), format!("00000000  68 69 0a{}  hi.\n", " ".repeat(39)));
+  }
 }
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::discriminator;
+
+/// Nesting depth after which recursive schemas are cut short.
+const MAX_DEPTH: usize = 8;
+
+fn string(schema: &Value) -> Value {
+  let sample = match schema["format"].as_str() {
+    Some("date-time") => "2024-01-01T00:00:00Z",
+    Some("date") => "2024-01-01",
+    Some("time") => "00:00:00",
+    Some("email") => "user@example.com",
+    Some("uuid") => "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+    Some("uri" | "url") => "https://example.com",
+    Some("hostname") => "example.com",
+    Some("ipv4") => "192.0.2.1",
+    Some("ipv6") => "2001:db8::1",
+    Some("byte") => "c3RyaW5n",
+    _ => "string",
+  };
+  Value::from(sample)
+}
+
+fn generate_at(schema: &Value, components: &BTreeMap<String, Value>, depth: usize) -> Value {
+  let Some(schema) = discriminator::resolve(schema, components) else {
+    return Value::Null;
+  };
+  if let Some(value) = ["example", "default", "const"].iter().find_map(|key| schema.get(key)) {
+    return value.clone();
+  }
+  if let Some(value) = ["examples", "enum"].iter().find_map(|key| schema[key].get(0)) {
+    return value.clone();
+  }
+  if depth > MAX_DEPTH {
+    return Value::Null;
+  }
+  if let Some(variant) = ["oneOf", "anyOf"].iter().find_map(|key| schema[key].get(0)) {
+    return generate_at(variant, components, depth + 1);
+  }
+  if let Some(parts) = schema["allOf"].as_array() {
+    let mut merged = Map::new();
+    for part in parts {
+      if let Value::Object(fields) = generate_at(part, components, depth + 1) {
+        merged.extend(fields);
+      }
+    }
+    return Value::Object(merged);
+  }
+  let schema_type = match &schema["type"] {
+    Value::Array(types) => types.iter().filter_map(Value::as_str).find(|schema_type| *schema_type != "null"),
+    schema_type => schema_type.as_str(),
+  };
+  match schema_type {
+    Some("object") | None if schema.get("properties").is_some() => {
+      let properties = schema["properties"].as_object().into_iter().flatten();
+      Value::Object(
+        properties.map(|(name, property)| (name.clone(), generate_at(property, components, depth + 1))).collect(),
+      )
+    },
+    Some("object") => json!({}),
+    Some("array") => match schema.get("items") {
+      Some(items) => json!([generate_at(items, components, depth + 1)]),
+      None => json!([]),
+    },
+    Some("string") => string(schema),
+    Some("integer") => schema.get("minimum").cloned().unwrap_or(json!(0)),
+    Some("number") => schema.get("minimum").cloned().unwrap_or(json!(0.0)),
+    Some("boolean") => json!(true),
+    _ => Value::Null,
+  }
+}
+
+/// Payload matching a schema, taken from its examples, defaults and enums where it has them and made up from the
+/// types and formats otherwise.
+pub fn generate(schema: &Value, components: &BTreeMap<String, Value>) -> Value {
+  generate_at(schema, components, 0)
+}
+
+/// Sets the fields of a payload named like captured variables to their values, parsed as JSON when they are
+/// numbers or booleans.
+pub fn fill_variables(payload: &mut Value, variables: &BTreeMap<String, String>) {
+  match payload {
+    Value::Object(fields) => {
+      for (name, value) in fields.iter_mut() {
+        match variables.get(name).filter(|_| !value.is_object() && !value.is_array()) {
+          Some(variable) => {
+            *value = serde_json::from_str::<Value>(variable)
+              .ok()
+              .filter(|parsed| parsed.is_number() || parsed.is_boolean())
+              .unwrap_or_else(|| Value::from(variable.as_str()));
+          },
+          None => fill_variables(value, variables),
+        }
+      }
+    },
+    Value::Array(items) => items.iter_mut().for_each(|item| fill_variables(item, variables)),
+    _ => {},
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_generate() {
+    let components = BTreeMap::from([(
+      String::from("Pet"),
+      json!({
+        "type": "object",
+        "properties": {
+          "id": {"type": "integer", "minimum": 1},
+          "name": {"type": "string", "example": "rex"},
+          "status": {"type": "string", "enum": ["available", "sold"]},
+          "born": {"type": "string", "format": "date"},
+          "tags": {"type": "array", "items": {"type": ["string", "null"]}},
+          "parent": {"$ref": "#/components/schemas/Pet"},
+        },
+      }),
+    )]);
+    let mut sample = generate(&json!({"$ref": "#/components/schemas/Pet"}), &components);
+    assert_eq!(sample["id"], 1);
+    assert_eq!(sample["name"], "rex");
+    assert_eq!(sample["status"], "available");
+    assert_eq!(sample["born"], "2024-01-01");
+    assert_eq!(sample["tags"], json!(["string"]));
+    assert_eq!(sample["parent"]["parent"]["name"], "rex");
+    fill_variables(&mut sample, &BTreeMap::from([("id".into(), "42".into()), ("name".into(), "tom".into())]));
+    assert_eq!((&sample["id"], &sample["name"], &sample["parent"]["name"]), (&json!(42), &json!("tom"), &json!("tom")));
+    assert_eq!(
+      generate(&json!({"allOf": [{"properties": {"a": {"type": "boolean"}}}]}), &components),
+      json!({"a": true})
+    );
+  }
+}
@@ -0,0 +1,117 @@
+use serde_json::{json, Map, Value};
+
+use crate::timestamps;
+
+fn scalar(value: &Value) -> Value {
+  match value {
+    Value::Null => json!({"type": "null"}),
+    Value::Bool(_) => json!({"type": "boolean"}),
+    Value::Number(number) if number.is_i64() || number.is_u64() => json!({"type": "integer"}),
+    Value::Number(_) => json!({"type": "number"}),
+    Value::String(text) if timestamps::parse(text).is_some() => json!({"type": "string", "format": "date-time"}),
+    _ => json!({"type": "string"}),
+  }
+}
+
+/// JSON Schema describing a payload: objects with their properties, all required, arrays with a schema merged from
+/// all of their items and `date-time` strings.
+pub fn infer(value: &Value) -> Value {
+  match value {
+    Value::Object(object) => {
+      let properties = object.iter().map(|(key, value)| (key.clone(), infer(value))).collect::<Map<_, _>>();
+      let required = object.keys().cloned().map(Value::String).collect::<Vec<_>>();
+      json!({"type": "object", "properties": properties, "required": required})
+    },
+    Value::Array(items) => {
+      let mut schema = json!({"type": "array"});
+      if let Some(items) = items.iter().map(infer).reduce(|merged, item| merge(&merged, &item)) {
+        schema["items"] = items;
+      }
+      schema
+    },
+    value => scalar(value),
+  }
+}
+
+/// Schema accepting the payloads of both schemas, properties missing from either are optional.
+pub fn merge(a: &Value, b: &Value) -> Value {
+  if a == b {
+    return a.clone();
+  }
+  match (a["type"].as_str(), b["type"].as_str()) {
+    (Some("object"), Some("object")) => {
+      let (Some(a_properties), Some(b_properties)) = (a["properties"].as_object(), b["properties"].as_object()) else {
+        return a.clone();
+      };
+      let mut properties = a_properties.clone();
+      for (key, schema) in b_properties {
+        let merged = properties.get(key).map_or(schema.clone(), |existing| merge(existing, schema));
+        properties.insert(key.clone(), merged);
+      }
+      let required = a["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|key| b["required"].as_array().is_some_and(|required| required.contains(key)))
+        .cloned()
+        .collect::<Vec<_>>();
+      json!({"type": "object", "properties": properties, "required": required})
+    },
+    (Some("array"), Some("array")) => match (a.get("items"), b.get("items")) {
+      (Some(a_items), Some(b_items)) => json!({"type": "array", "items": merge(a_items, b_items)}),
+      (Some(_), None) => a.clone(),
+      _ => b.clone(),
+    },
+    (Some("integer"), Some("number")) | (Some("number"), Some("integer")) => json!({"type": "number"}),
+    (Some("string"), Some("string")) => json!({"type": "string"}),
+    _ => {
+      let mut variants = a.get("anyOf").and_then(Value::as_array).cloned().unwrap_or_else(|| vec![a.clone()]);
+      if !variants.contains(b) {
+        variants.push(b.clone());
+      }
+      json!({"anyOf": variants})
+    },
+  }
+}
+
+/// Responses entry documenting a status and content type with a schema, ready to be pasted under `responses`.
+pub fn response_snippet(status: &str, content_type: &str, schema: Value) -> Value {
+  json!({status: {"description": "", "content": {content_type: {"schema": schema}}}})
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_infer() {
+    let payload = json!({
+      "pets": [{"id": 1, "name": "rex", "born": "2020-01-01T00:00:00Z"}, {"id": 2.5, "tag": null}],
+    });
+    assert_eq!(
+      infer(&payload),
+      json!({
+        "type": "object",
+        "properties": {
+          "pets": {
+            "type": "array",
+            "items": {
+              "type": "object",
+              "properties": {
+                "id": {"type": "number"},
+                "name": {"type": "string"},
+                "born": {"type": "string", "format": "date-time"},
+                "tag": {"type": "null"},
+              },
+              "required": ["id"],
+            },
+          },
+        },
+        "required": ["pets"],
+      })
+    );
+    assert_eq!(merge(&json!({"type": "string"}), &json!({"type": "null"}))["anyOf"][1], json!({"type": "null"}));
+  }
+}
@@ -0,0 +1,141 @@
+use color_eyre::eyre::{ContextCompat, Result};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+use crate::request;
+
+pub const SECRET_SCHEME: &str = "secret://";
+
+pub fn is_reference(value: &str) -> bool {
+  value.starts_with(SECRET_SCHEME)
+}
+
+/// Placeholder of a `secret://` reference in a url, it stays in the url until the request is sent so exports, dry
+/// runs and the timeline never show the secret.
+pub fn placeholder(reference: &str) -> String {
+  format!("{{{{{reference}}}}}")
+}
+
+/// Replaces the secret placeholders in the url of a request with the secrets, percent encoded. Done right before
+/// sending.
+pub fn resolve_url(request: &mut reqwest::Request) -> Result<()> {
+  let prefix = format!("{{{{{SECRET_SCHEME}");
+  let url = request::template_url(request);
+  if !url.contains(prefix.as_str()) {
+    return Ok(());
+  }
+  let mut resolved = String::with_capacity(url.len());
+  let mut rest = url.as_str();
+  while let Some(start) = rest.find(prefix.as_str()) {
+    resolved.push_str(&rest[..start]);
+    let tail = &rest[start + prefix.len()..];
+    let end = tail.find("}}").context("unterminated secret placeholder in the url")?;
+    resolved.push_str(utf8_percent_encode(read(&tail[..end])?.as_str(), NON_ALPHANUMERIC).to_string().as_str());
+    rest = &tail[end + 2..];
+  }
+  resolved.push_str(rest);
+  *request.url_mut() = reqwest::Url::parse(resolved.as_str())?;
+  Ok(())
+}
+
+pub fn resolve(value: &str) -> Result<String> {
+  match value.strip_prefix(SECRET_SCHEME) {
+    Some(name) => read(name),
+    None => Ok(value.to_string()),
+  }
+}
+
+#[cfg(feature = "keyring")]
+fn entry(name: &str) -> Result<keyring::Entry> {
+  Ok(keyring::Entry::new(env!("CARGO_PKG_NAME"), name)?)
+}
+
+#[cfg(feature = "keyring")]
+pub fn read(name: &str) -> Result<String> {
+  entry(name)?.get_password().map_err(|error| color_eyre::eyre::eyre!("can't read secret `{name}`: {error}"))
+}
+
+#[cfg(feature = "keyring")]
+pub fn store(name: &str, value: &str) -> Result<()> {
+  Ok(entry(name)?.set_password(value)?)
+}
+
+#[cfg(feature = "keyring")]
+pub fn delete(name: &str) -> Result<()> {
+  Ok(entry(name)?.delete_credential()?)
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn read(name: &str) -> Result<String> {
+  Err(color_eyre::eyre::eyre!("can't read secret `{name}`: built without keyring support"))
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn store(_name: &str, _value: &str) -> Result<()> {
+  Err(color_eyre::eyre::eyre!("built without keyring support"))
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn delete(_name: &str) -> Result<()> {
+  Err(color_eyre::eyre::eyre!("built without keyring support"))
+}
+
+/// Keyring kept in memory for tests, its entries share their secrets unlike the ones of the mock store of keyring.
+#[cfg(all(test, feature = "keyring"))]
+pub mod memory_store {
+  use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{Arc, Mutex, Once},
+  };
+
+  use keyring::{
+    credential::{CredentialApi, CredentialBuilderApi},
+    Credential, Error,
+  };
+
+  type Secrets = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+  #[derive(Debug)]
+  struct Builder(Secrets);
+
+  #[derive(Debug)]
+  struct Entry {
+    secrets: Secrets,
+    name: String,
+  }
+
+  impl CredentialApi for Entry {
+    fn set_secret(&self, secret: &[u8]) -> keyring::Result<()> {
+      self.secrets.lock().unwrap().insert(self.name.clone(), secret.to_vec());
+      Ok(())
+    }
+
+    fn get_secret(&self) -> keyring::Result<Vec<u8>> {
+      self.secrets.lock().unwrap().get(&self.name).cloned().ok_or(Error::NoEntry)
+    }
+
+    fn delete_credential(&self) -> keyring::Result<()> {
+      self.secrets.lock().unwrap().remove(&self.name).map(|_| ()).ok_or(Error::NoEntry)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+      self
+    }
+  }
+
+  impl CredentialBuilderApi for Builder {
+    fn build(&self, _target: Option<&str>, _service: &str, name: &str) -> keyring::Result<Box<Credential>> {
+      Ok(Box::new(Entry { secrets: self.0.clone(), name: name.to_string() }))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+      self
+    }
+  }
+
+  /// Replaces the keyring of the platform for the whole test process.
+  pub fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| keyring::set_default_credential_builder(Box::new(Builder(Secrets::default()))));
+  }
+}
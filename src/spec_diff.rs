@@ -0,0 +1,100 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use openapi_31::v31::{Openapi, Operation};
+
+/// Operations and schemas added, removed or changed between two versions of a spec.
+#[derive(Debug, Default, PartialEq)]
+pub struct SpecChanges {
+  pub added: Vec<String>,
+  pub removed: Vec<String>,
+  pub changed: Vec<String>,
+}
+
+impl SpecChanges {
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+  }
+
+  fn compare<K: Display + Ord, V: PartialEq>(&mut self, old: &BTreeMap<K, V>, new: &BTreeMap<K, V>, kind: &str) {
+    for (key, value) in new {
+      match old.get(key) {
+        None => self.added.push(format!("{kind} {key}")),
+        Some(old_value) if old_value != value => self.changed.push(format!("{kind} {key}")),
+        Some(_) => {},
+      }
+    }
+    self.removed.extend(old.keys().filter(|key| !new.contains_key(key)).map(|key| format!("{kind} {key}")));
+  }
+}
+
+impl Display for SpecChanges {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let lines = [("+", &self.added), ("-", &self.removed), ("~", &self.changed)]
+      .into_iter()
+      .flat_map(|(marker, items)| items.iter().map(move |item| format!("{marker} {item}")))
+      .collect::<Vec<_>>();
+    write!(f, "{}", lines.join("\n"))
+  }
+}
+
+fn operations(spec: &Openapi) -> BTreeMap<String, Operation> {
+  spec
+    .into_operations()
+    .map(|(path, method, operation)| (format!("{} {path}", method.to_uppercase()), operation))
+    .collect()
+}
+
+/// Changes of the operations and component schemas from `old` to `new`.
+pub fn diff(old: &Openapi, new: &Openapi) -> SpecChanges {
+  let mut changes = SpecChanges::default();
+  changes.compare(&operations(old), &operations(new), "operation");
+  let schemas = |spec: &Openapi| spec.components.as_ref().and_then(|components| components.schemas.clone());
+  changes.compare(&schemas(old).unwrap_or_default(), &schemas(new).unwrap_or_default(), "schema");
+  changes
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_diff() {
+    let old = serde_yaml::from_str::<Openapi>(
+      r#"
+openapi: 3.1.0
+info: {title: pets, version: "1"}
+paths:
+  /pets:
+    get: {operationId: listPets}
+    delete: {operationId: deletePets}
+components:
+  schemas:
+    Pet: {type: object}
+"#,
+    )
+    .unwrap();
+    let new = serde_yaml::from_str::<Openapi>(
+      r#"
+openapi: 3.1.0
+info: {title: pets, version: "2"}
+paths:
+  /pets:
+    get: {operationId: listPets, summary: List pets}
+    post: {operationId: createPet}
+components:
+  schemas:
+    Pet: {type: object}
+    Owner: {type: object}
+"#,
+    )
+    .unwrap();
+    let changes = diff(&old, &new);
+    assert_eq!(
+      changes.to_string(),
+      "+ operation POST /pets\n+ schema Owner\n- operation DELETE /pets\n~ operation GET /pets"
+    );
+    assert!(diff(&new, &new).is_empty());
+  }
+}
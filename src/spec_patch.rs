@@ -0,0 +1,442 @@
+use std::{fmt::Display, ops::Range};
+
+use color_eyre::eyre::{bail, eyre, ContextCompat, Result};
+use openapi_31::v31::Openapi;
+use serde::{Deserialize, Serialize};
+
+/// Documentation field of an operation that can be edited in place.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpecField {
+  Summary,
+  Description,
+  ParameterDescription(String),
+}
+
+impl SpecField {
+  fn key(&self) -> &'static str {
+    match self {
+      SpecField::Summary => "summary",
+      SpecField::Description | SpecField::ParameterDescription(_) => "description",
+    }
+  }
+}
+
+impl Display for SpecField {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      SpecField::Summary => write!(f, "summary"),
+      SpecField::Description => write!(f, "description"),
+      SpecField::ParameterDescription(name) => write!(f, "description of {name} parameter"),
+    }
+  }
+}
+
+/// Loads a patched spec the way it's loaded on startup, a patch that broke the document is never written.
+pub fn validate(patched: &str) -> Result<()> {
+  match serde_yaml::from_str::<Openapi>(patched) {
+    Ok(_) => Ok(()),
+    Err(error) => Err(eyre!("the file is unchanged, the patched spec doesn't load: {error}")),
+  }
+}
+
+/// Replaces a documentation field of an operation in the yaml or json source of a spec, touching only the lines of
+/// that field so the rest of the formatting and comments stay as they are.
+pub fn patch(source: &str, path: &str, method: &str, field: &SpecField, value: &str) -> Result<String> {
+  let json = source.trim_start().starts_with('{');
+  let mut lines = source.split_inclusive('\n').map(String::from).collect::<Vec<_>>();
+
+  let (method_line, method_end, field_indent) = locate_operation(&lines, path, method)?;
+
+  match field {
+    SpecField::Summary | SpecField::Description => {
+      match find_key(&lines, method_line + 1..method_end, Some(field_indent), field.key()) {
+        Some(line) => set_value(&mut lines, line, value, json)?,
+        None => insert_value(&mut lines, method_line, field_indent, field.key(), value, json)?,
+      }
+    },
+    SpecField::ParameterDescription(name) => {
+      let parameters_line = find_key(&lines, method_line + 1..method_end, Some(field_indent), "parameters")
+        .context("operation has no parameters")?;
+      let parameters_end = block_end(&lines, parameters_line + 1, field_indent);
+      let name_line = (parameters_line + 1..parameters_end)
+        .find(|&index| {
+          entry(&lines[index]).is_some_and(|(_, key, value_start)| {
+            key == "name" && scalar(&lines[index].trim_end_matches(['\r', '\n'])[value_start..]) == *name
+          })
+        })
+        .context(format!("parameter {name} is not defined inline"))?;
+      let item_indent = entry(&lines[name_line]).map_or(0, |entry| entry.0);
+      let item_start = (parameters_line + 1..=name_line)
+        .rev()
+        .find(|&index| {
+          let content = lines[index].trim_start();
+          indent_of(&lines[index]) < item_indent && (content.starts_with('-') || content.starts_with('{'))
+        })
+        .unwrap_or(name_line);
+      let item_end = (item_start + 1..parameters_end)
+        .find(|&index| !is_blank(&lines[index]) && indent_of(&lines[index]) < item_indent)
+        .unwrap_or(parameters_end);
+      match find_key(&lines, item_start..item_end, Some(item_indent), "description") {
+        Some(line) => set_value(&mut lines, line, value, json)?,
+        None if json => insert_value(&mut lines, item_start, item_indent, "description", value, json)?,
+        None => insert_value(&mut lines, name_line, item_indent, "description", value, json)?,
+      }
+    },
+  }
+  Ok(lines.concat())
+}
+
+/// Sets the `example` of a request body media type of an operation, replacing the previous one.
+pub fn patch_example(
+  source: &str,
+  path: &str,
+  method: &str,
+  media_type: &str,
+  example: &serde_json::Value,
+) -> Result<String> {
+  let json = source.trim_start().starts_with('{');
+  let mut lines = source.split_inclusive('\n').map(String::from).collect::<Vec<_>>();
+
+  let (method_line, method_end, field_indent) = locate_operation(&lines, path, method)?;
+  let request_body_line = find_key(&lines, method_line + 1..method_end, Some(field_indent), "requestBody")
+    .context("operation has no request body")?;
+  let content_line = find_child(&lines, request_body_line, "content").context("request body is not defined inline")?;
+  let media_type_line = find_child(&lines, content_line, media_type).context(format!("{media_type} not found"))?;
+  let media_type_indent = entry(&lines[media_type_line]).map_or(0, |entry| entry.0);
+  let (_, example_indent) = children(&lines, media_type_line).unwrap_or((media_type_line + 1, media_type_indent + 2));
+
+  match find_child(&lines, media_type_line, "example") {
+    Some(line) if json => {
+      let (_, _, value_start) = entry(&lines[line]).context("not a mapping entry")?;
+      let rest = lines[line..].concat();
+      let mut stream = serde_json::Deserializer::from_str(&rest[value_start..]).into_iter::<serde_json::Value>();
+      stream.next().context("example is not valid json")??;
+      let value_end = value_start + stream.byte_offset();
+      let replaced = format!("{}{}{}", &rest[..value_start], json_block(example, example_indent)?, &rest[value_end..]);
+      lines.truncate(line);
+      lines.extend(replaced.split_inclusive('\n').map(String::from));
+    },
+    Some(line) => {
+      let (_, _, value_start) = entry(&lines[line]).context("not a mapping entry")?;
+      let ending = line_ending(&lines[line]);
+      let mut next = block_end(&lines, line + 1, example_indent);
+      while next > line + 1 && lines[next - 1].trim().is_empty() {
+        next -= 1;
+      }
+      lines.drain(line + 1..next);
+      let prefix = lines[line][..value_start].trim_end().to_string();
+      lines[line] = format!("{prefix}{}{ending}", yaml_block(example, example_indent, ending)?);
+    },
+    None if json => {
+      let ending = line_ending(&lines[media_type_line]);
+      if !lines[media_type_line].trim_end().ends_with('{') {
+        bail!("can't find where to add example");
+      }
+      let separator = match lines[media_type_line + 1..].iter().find(|line| !line.trim().is_empty()) {
+        Some(line) if line.trim_start().starts_with('}') => "",
+        _ => ",",
+      };
+      let line = format!(
+        "{}\"example\": {}{separator}{ending}",
+        " ".repeat(example_indent),
+        json_block(example, example_indent)?
+      );
+      lines.splice(media_type_line + 1..media_type_line + 1, line.split_inclusive('\n').map(String::from));
+    },
+    None => {
+      let ending = line_ending(&lines[media_type_line]);
+      let content = lines[media_type_line].trim_end_matches(['\r', '\n']);
+      if !content.trim_end().ends_with(':') {
+        bail!("can't find where to add example");
+      }
+      let line =
+        format!("{}example:{}{ending}", " ".repeat(example_indent), yaml_block(example, example_indent, ending)?);
+      lines.splice(media_type_line + 1..media_type_line + 1, line.split_inclusive('\n').map(String::from));
+    },
+  }
+  Ok(lines.concat())
+}
+
+/// Line of the operation, the end of its block and the indentation of its fields.
+fn locate_operation(lines: &[String], path: &str, method: &str) -> Result<(usize, usize, usize)> {
+  let section = if path.starts_with('/') { "paths" } else { "webhooks" };
+  let section_line = find_key(lines, 0..lines.len(), None, section).context(format!("{section} not found"))?;
+  let section_end = block_end(lines, section_line + 1, entry(&lines[section_line]).map_or(0, |entry| entry.0));
+  let path_line = find_key(lines, section_line + 1..section_end, None, path).context(format!("{path} not found"))?;
+  let path_indent = entry(&lines[path_line]).map_or(0, |entry| entry.0);
+  let path_end = block_end(lines, path_line + 1, path_indent);
+  let method_line = find_key(lines, path_line + 1..path_end, None, method.to_lowercase().as_str())
+    .context(format!("{method} {path} not found"))?;
+  let method_indent = entry(&lines[method_line]).map_or(0, |entry| entry.0);
+  let method_end = block_end(lines, method_line + 1, method_indent);
+  let field_indent = (method_line + 1..method_end)
+    .find_map(|index| entry(&lines[index]).map(|entry| entry.0))
+    .context("operation has no fields")?;
+  Ok((method_line, method_end, field_indent))
+}
+
+/// End of the block of a mapping entry and the indentation of its own entries, if it has any.
+fn children(lines: &[String], line: usize) -> Option<(usize, usize)> {
+  let indent = entry(&lines[line])?.0;
+  let end = block_end(lines, line + 1, indent);
+  let child_indent = (line + 1..end).find_map(|index| entry(&lines[index]).map(|entry| entry.0))?;
+  Some((end, child_indent))
+}
+
+fn find_child(lines: &[String], line: usize, key: &str) -> Option<usize> {
+  let (end, child_indent) = children(lines, line)?;
+  find_key(lines, line + 1..end, Some(child_indent), key)
+}
+
+fn indent_of(line: &str) -> usize {
+  line.len() - line.trim_start().len()
+}
+
+fn is_blank(line: &str) -> bool {
+  let content = line.trim();
+  content.is_empty() || content.starts_with('#')
+}
+
+fn line_ending(line: &str) -> &'static str {
+  if line.ends_with("\r\n") {
+    "\r\n"
+  } else {
+    "\n"
+  }
+}
+
+/// Indentation, key and the offset where the value starts, for lines holding a mapping entry. Entries of `- key: value`
+/// sequence items are indented past the dash.
+fn entry(line: &str) -> Option<(usize, String, usize)> {
+  let line = line.trim_end_matches(['\r', '\n']);
+  let mut indent = indent_of(line);
+  let mut rest = &line[indent..];
+  if let Some(item) = rest.strip_prefix("- ") {
+    indent += 2 + indent_of(item);
+    rest = item.trim_start();
+  }
+  let (key, after_key) = match rest.chars().next()? {
+    quote @ ('"' | '\'') => {
+      let end = rest[1..].find(quote)? + 1;
+      (&rest[1..end], &rest[end + 1..])
+    },
+    _ => {
+      let end = rest.find(':')?;
+      (rest[..end].trim_end(), &rest[end..])
+    },
+  };
+  let value = after_key.trim_start().strip_prefix(':')?;
+  if !(value.is_empty() || value.starts_with([' ', '\t'])) {
+    return None;
+  }
+  Some((indent, key.to_string(), line.len() - value.trim_start().len()))
+}
+
+fn scalar(value: &str) -> String {
+  value.trim().trim_end_matches(',').trim().trim_matches(['"', '\'']).to_string()
+}
+
+/// First line after `start` that is not nested deeper than `indent`. Sequence items at the same indentation still
+/// belong to the block, as yaml allows `key:\n- item`.
+fn block_end(lines: &[String], start: usize, indent: usize) -> usize {
+  (start..lines.len())
+    .find(|&index| {
+      let line = &lines[index];
+      !is_blank(line)
+        && (indent_of(line) < indent || (indent_of(line) == indent && !line.trim_start().starts_with("- ")))
+    })
+    .unwrap_or(lines.len())
+}
+
+fn find_key(lines: &[String], range: Range<usize>, indent: Option<usize>, key: &str) -> Option<usize> {
+  range.into_iter().find(|&index| {
+    entry(&lines[index])
+      .is_some_and(|(entry_indent, entry_key, _)| entry_key == key && indent.unwrap_or(entry_indent) == entry_indent)
+  })
+}
+
+fn yaml_value(value: &str, indent: usize, ending: &str) -> Result<String> {
+  if value.contains('\n') {
+    let block = value
+      .lines()
+      .map(|line| if line.is_empty() { String::default() } else { format!("{}{line}", " ".repeat(indent + 2)) })
+      .collect::<Vec<_>>()
+      .join(ending);
+    Ok(format!("|-{ending}{block}"))
+  } else {
+    Ok(serde_yaml::to_string(value)?.trim_end().to_string())
+  }
+}
+
+/// Value of a yaml entry written right after its colon, either inline or as a nested block.
+fn yaml_block(value: &serde_json::Value, indent: usize, ending: &str) -> Result<String> {
+  match value {
+    serde_json::Value::String(value) => Ok(format!(" {}", yaml_value(value, indent, ending)?)),
+    serde_json::Value::Object(map) if !map.is_empty() => Ok(nested_yaml(value, indent, ending)?),
+    serde_json::Value::Array(items) if !items.is_empty() => Ok(nested_yaml(value, indent, ending)?),
+    _ => Ok(format!(" {}", serde_json::to_string(value)?)),
+  }
+}
+
+fn nested_yaml(value: &serde_json::Value, indent: usize, ending: &str) -> Result<String> {
+  Ok(
+    serde_yaml::to_string(value)?
+      .lines()
+      .map(|line| format!("{ending}{}{line}", " ".repeat(indent + 2)))
+      .collect::<String>(),
+  )
+}
+
+fn json_block(value: &serde_json::Value, indent: usize) -> Result<String> {
+  Ok(serde_json::to_string_pretty(value)?.replace('\n', format!("\n{}", " ".repeat(indent)).as_str()))
+}
+
+fn set_value(lines: &mut Vec<String>, line: usize, value: &str, json: bool) -> Result<()> {
+  let (indent, _, value_start) = entry(&lines[line]).context("not a mapping entry")?;
+  let ending = line_ending(&lines[line]);
+  let content = lines[line].trim_end_matches(['\r', '\n']).to_string();
+  if json {
+    let mut stream = serde_json::Deserializer::from_str(&content[value_start..]).into_iter::<serde_json::Value>();
+    if !matches!(stream.next(), Some(Ok(serde_json::Value::String(_)))) {
+      bail!("value is not a string");
+    }
+    let value_end = value_start + stream.byte_offset();
+    lines[line] =
+      format!("{}{}{}{ending}", &content[..value_start], serde_json::to_string(value)?, &content[value_end..]);
+  } else {
+    // multi-line scalars continue on the lines nested deeper than the key
+    let mut next = line + 1;
+    while next < lines.len() && (lines[next].trim().is_empty() || indent_of(&lines[next]) > indent) {
+      next += 1;
+    }
+    while next > line + 1 && lines[next - 1].trim().is_empty() {
+      next -= 1;
+    }
+    lines.drain(line + 1..next);
+    let prefix = content[..value_start].trim_end();
+    lines[line] = format!("{prefix} {}{ending}", yaml_value(value, indent, ending)?);
+  }
+  Ok(())
+}
+
+fn insert_value(
+  lines: &mut Vec<String>,
+  after: usize,
+  indent: usize,
+  key: &str,
+  value: &str,
+  json: bool,
+) -> Result<()> {
+  let ending = line_ending(&lines[after]);
+  let line = if json {
+    if !lines[after].trim_end().ends_with('{') {
+      bail!("can't find where to add {key}");
+    }
+    let separator = match lines[after + 1..].iter().find(|line| !line.trim().is_empty()) {
+      Some(line) if line.trim_start().starts_with('}') => "",
+      _ => ",",
+    };
+    format!("{}\"{key}\": {}{separator}{ending}", " ".repeat(indent), serde_json::to_string(value)?)
+  } else {
+    format!("{}{key}: {}{ending}", " ".repeat(indent), yaml_value(value, indent, ending)?)
+  };
+  lines.insert(after + 1, line);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const YAML: &str = r#"openapi: 3.1.0
+paths:
+  /pets:
+    get:
+      # list them all
+      summary: List pets
+      description: |
+        Returns all pets
+        from the store.
+      parameters:
+      - name: limit
+        in: query
+      - in: query
+        name: tag
+        description: "filter by tag"
+      responses:
+        '200':
+          description: ok
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+            example:
+              name: old
+          text/plain: {}
+      responses:
+        '201':
+          description: created
+"#;
+
+  #[test]
+  fn test_validate() {
+    let source = "openapi: 3.1.0\ninfo:\n  title: pets\n  version: '1'\npaths: {}\n";
+    assert!(validate(source).is_ok());
+    assert!(validate(&source.replace("  title: pets\n", "  title: pets\n title: cats\n")).is_err());
+    assert!(validate(&source.replace("paths: {}", "paths: []")).is_err());
+  }
+
+  #[test]
+  fn test_patch_yaml() {
+    let patched = patch(YAML, "/pets", "GET", &SpecField::Summary, "List: pets").unwrap();
+    assert!(patched.contains("      # list them all\n      summary: 'List: pets'\n      description: |\n"));
+
+    let patched = patch(YAML, "/pets", "GET", &SpecField::Description, "one line").unwrap();
+    assert!(patched.contains("      description: one line\n      parameters:\n"));
+
+    let patched = patch(YAML, "/pets", "POST", &SpecField::Description, "first\nsecond").unwrap();
+    assert!(patched.contains("    post:\n      description: |-\n        first\n        second\n      requestBody:\n"));
+
+    let patched = patch(YAML, "/pets", "GET", &SpecField::ParameterDescription("limit".into()), "max items").unwrap();
+    assert!(patched.contains("      - name: limit\n        description: max items\n        in: query\n"));
+
+    let patched = patch(YAML, "/pets", "GET", &SpecField::ParameterDescription("tag".into()), "tag name").unwrap();
+    assert!(patched.contains("        name: tag\n        description: tag name\n      responses:\n"));
+  }
+
+  #[test]
+  fn test_patch_example_yaml() {
+    let example = serde_json::json!({"name": "rex", "tags": ["a"]});
+    let patched = patch_example(YAML, "/pets", "POST", "application/json", &example).unwrap();
+    assert!(patched.contains(
+      "              type: object\n            example:\n              name: rex\n              tags:\n              - a\n          text/plain: {}\n"
+    ));
+    assert!(patch_example(YAML, "/pets", "POST", "text/plain", &example).is_err());
+    assert!(patch_example(YAML, "/pets", "GET", "application/json", &example).is_err());
+  }
+
+  #[test]
+  fn test_patch_example_json() {
+    let source = "{\n  \"paths\": {\n    \"/pets\": {\n      \"post\": {\n        \"requestBody\": {\n          \"content\": {\n            \"application/json\": {\n              \"example\": {\n                \"name\": \"old\"\n              }\n            }\n          }\n        }\n      }\n    }\n  }\n}\n";
+    let patched = patch_example(source, "/pets", "POST", "application/json", &serde_json::json!({"id": 1})).unwrap();
+    assert!(patched.contains("            \"application/json\": {\n              \"example\": {\n                \"id\": 1\n              }\n            }\n"));
+    assert!(serde_json::from_str::<serde_json::Value>(&patched).is_ok());
+  }
+
+  #[test]
+  fn test_patch_json() {
+    let source = "{\n  \"paths\": {\n    \"/pets\": {\n      \"get\": {\n        \"summary\": \"List pets\",\n        \"responses\": {}\n      }\n    }\n  }\n}\n";
+    let patched = patch(source, "/pets", "GET", &SpecField::Summary, "All \"pets\"").unwrap();
+    assert!(patched.contains("        \"summary\": \"All \\\"pets\\\"\",\n"));
+
+    let patched = patch(source, "/pets", "GET", &SpecField::Description, "details").unwrap();
+    assert!(patched.contains("      \"get\": {\n        \"description\": \"details\",\n        \"summary\""));
+
+    let source = "{\n  \"paths\": {\n    \"/pets\": {\n      \"get\": {\n        \"parameters\": [\n          {\n            \"name\": \"limit\"\n          }\n        ]\n      }\n    }\n  }\n}\n";
+    let patched = patch(source, "/pets", "GET", &SpecField::ParameterDescription("limit".into()), "max").unwrap();
+    assert!(patched.contains("          {\n            \"description\": \"max\",\n            \"name\""));
+    assert!(serde_json::from_str::<serde_json::Value>(&patched).is_ok());
+  }
+}
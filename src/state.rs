@@ -1,9 +1,52 @@
-use std::{collections::HashMap, env};
+use std::{
+  collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+  env,
+  time::Instant,
+};
 
 use color_eyre::eyre::Result;
-use openapi_31::v31::{Openapi, Operation, Server};
+use crossterm::event::KeyEvent;
+use openapi_31::v31::{parameter::In, MediaType, ObjectOrRef, Openapi, Operation, RequestBody, Server};
+use serde::{Deserialize, Serialize};
 
-use crate::response::Response;
+use crate::{
+  auth::SecuritySchemes,
+  client::Client,
+  dotenv, environments,
+  known_hosts::{KnownHosts, Trust},
+  profiles::{self, Profile},
+  rate_limit,
+  response::Response,
+  storage,
+  update_check::Release,
+  utils::stable_hash,
+  warm_up::ServerHealth,
+};
+
+const COVERAGE_FILE: &str = "coverage.json";
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+const SETTINGS_FILE: &str = "settings.json";
+const MACROS_FILE: &str = "macros.json";
+const ASSERTIONS_FILE: &str = "assertions.json";
+const IDEMPOTENCY_FILE: &str = "idempotency.json";
+const NOTES_FILE: &str = "notes.json";
+const ANNOTATIONS_FILE: &str = "annotations.json";
+const MAX_RECENT_OPERATIONS: usize = 20;
+const MAX_MESSAGES: usize = 100;
+pub const AD_HOC_METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// Lightweight ui state restored when a spec is opened again.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+  pub server: Option<String>,
+  pub filter: String,
+  pub tag: Option<String>,
+  pub webhooks_only: bool,
+  pub operation: Option<String>,
+  pub base_url: Option<String>,
+  pub profile: Option<String>,
+}
 
 #[derive(Default)]
 pub struct State {
@@ -15,6 +58,68 @@ pub struct State {
   pub active_filter: String,
   pub input_mode: InputMode,
   pub responses: HashMap<String, Response>,
+  pub marked_operations: HashSet<String>,
+  pub called_operations: BTreeSet<String>,
+  pub session_operations: HashSet<String>,
+  pub uncalled_only: bool,
+  pub bookmarks: BTreeMap<u32, String>,
+  pub recent_operations: VecDeque<String>,
+  pub webhooks_only: bool,
+  /// Requests to paths that are not in the spec, opened with `:new`.
+  pub ad_hoc_operations: Vec<OperationItem>,
+  /// Server given with `--server`, a url or an index into the servers.
+  pub preferred_server: Option<String>,
+  /// Location relative server urls are resolved against: `--base-url`, else the one last set with `:base`, else the
+  /// url of the spec.
+  pub base_url: Option<String>,
+  /// Webhook operation being listened for and the address of the listener.
+  pub webhook_listener: Option<(String, String)>,
+  /// Values captured from responses, substituted for `{{name}}` placeholders in requests.
+  pub variables: BTreeMap<String, String>,
+  /// Server the last request was sent to.
+  pub selected_server: Option<String>,
+  /// Status messages shown in the footer, newest first.
+  pub messages: VecDeque<(Instant, String)>,
+  /// Reachability of the servers that were warmed up.
+  pub server_health: HashMap<String, ServerHealth>,
+  /// Headers from `--header` flags, added to the header tab of every request.
+  pub session_headers: Vec<(String, String)>,
+  /// Headers from the `client` config, added to the header tab of every request after the session ones.
+  pub default_headers: Vec<(String, String)>,
+  /// Environments of the `profiles` config by name.
+  pub profiles: BTreeMap<String, Profile>,
+  /// Profile whose base url and headers are used, switched with `:profile`.
+  pub active_profile: Option<String>,
+  /// Releases newer than the running version, found by the update check.
+  pub newer_releases: Vec<Release>,
+  /// Client shared by all requests, built from the `client` config.
+  pub client: Client,
+  /// Key sequences recorded with the `RecordMacro` key and a register, replayed with `@<register>`.
+  pub macros: BTreeMap<char, Vec<KeyEvent>>,
+  /// Register being recorded into and the keys pressed so far.
+  pub recording_macro: Option<(char, Vec<KeyEvent>)>,
+  /// Operations with a request in flight.
+  pub in_flight: HashSet<String>,
+  /// Checks of the responses of each operation, added with `:assert` and also run by smoke tests.
+  pub assertions: BTreeMap<String, Vec<String>>,
+  /// Response each operation got before its latest one, to compare them.
+  pub previous_responses: HashMap<String, Response>,
+  /// Dotenv file each variable was loaded from, variables without one were captured or imported.
+  pub variable_sources: BTreeMap<String, String>,
+  /// Rate limits declared by the vendor extensions of the spec.
+  pub declared_rate_limits: rate_limit::Declared,
+  /// How the security schemes of the spec are sent.
+  pub security_schemes: SecuritySchemes,
+  /// Credentials filled in the auth pane by security scheme, for the session only.
+  pub credentials: BTreeMap<String, String>,
+  /// Operations sent with a fresh `Idempotency-Key` header on each call, kept per spec.
+  pub idempotent_operations: BTreeSet<String>,
+  /// Free-form markdown notes about the spec, kept per spec.
+  pub notes: String,
+  /// Short labels of operations, like `broken in staging`, kept per spec.
+  pub annotations: BTreeMap<String, String>,
+  /// Certificate fingerprints trusted on first use, when the `client` config enables it.
+  pub known_hosts: Option<KnownHosts>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -30,6 +135,7 @@ pub struct OperationItem {
   pub method: String,
   pub operation: Operation,
   pub r#type: OperationItemType,
+  pub synthetic_id: bool,
 }
 
 #[derive(Default, PartialEq)]
@@ -42,19 +148,12 @@ pub enum InputMode {
 
 impl State {
   async fn from_path(openapi_path: String) -> Result<Self> {
-    let openapi_spec = tokio::fs::read_to_string(&openapi_path)
-      .await
-      .map(|content| serde_yaml::from_str::<Openapi>(content.as_str()))??;
+    let content = tokio::fs::read_to_string(&openapi_path).await?;
+    let openapi_spec = serde_yaml::from_str::<Openapi>(content.as_str())?;
 
     let openapi_operations = openapi_spec
       .into_operations()
-      .map(|(path, method, operation)| {
-        if path.starts_with('/') {
-          OperationItem { path, method, operation, r#type: OperationItemType::Path }
-        } else {
-          OperationItem { path, method, operation, r#type: OperationItemType::Webhook }
-        }
-      })
+      .map(|(path, method, operation)| OperationItem::new(path, method, operation))
       .collect::<Vec<_>>();
     Ok(Self {
       openapi_spec,
@@ -65,6 +164,40 @@ impl State {
       active_filter: String::default(),
       input_mode: InputMode::Normal,
       responses: HashMap::default(),
+      marked_operations: HashSet::default(),
+      called_operations: BTreeSet::default(),
+      session_operations: HashSet::default(),
+      uncalled_only: false,
+      bookmarks: BTreeMap::default(),
+      recent_operations: VecDeque::default(),
+      webhooks_only: false,
+      ad_hoc_operations: Vec::default(),
+      preferred_server: None,
+      base_url: None,
+      webhook_listener: None,
+      variables: BTreeMap::default(),
+      selected_server: None,
+      messages: VecDeque::default(),
+      server_health: HashMap::default(),
+      session_headers: vec![],
+      default_headers: vec![],
+      profiles: BTreeMap::default(),
+      active_profile: None,
+      macros: BTreeMap::default(),
+      recording_macro: None,
+      in_flight: HashSet::default(),
+      assertions: BTreeMap::default(),
+      previous_responses: HashMap::default(),
+      variable_sources: BTreeMap::default(),
+      declared_rate_limits: rate_limit::Declared::from_spec(content.as_str()),
+      security_schemes: SecuritySchemes::from_spec(content.as_str()),
+      credentials: BTreeMap::default(),
+      idempotent_operations: BTreeSet::default(),
+      notes: String::default(),
+      annotations: BTreeMap::default(),
+      known_hosts: None,
+      newer_releases: vec![],
+      client: Client::default(),
     })
   }
 
@@ -78,15 +211,10 @@ impl State {
 
     let openapi_operations = openapi_spec
       .into_operations()
-      .map(|(path, method, operation)| {
-        if path.starts_with('/') {
-          OperationItem { path, method, operation, r#type: OperationItemType::Path }
-        } else {
-          OperationItem { path, method, operation, r#type: OperationItemType::Webhook }
-        }
-      })
+      .map(|(path, method, operation)| OperationItem::new(path, method, operation))
       .collect::<Vec<_>>();
     Ok(Self {
+      base_url: Some(openapi_url.to_string()),
       openapi_spec,
       openapi_input_source: openapi_url.to_string(),
       openapi_operations,
@@ -95,88 +223,688 @@ impl State {
       active_filter: String::default(),
       input_mode: InputMode::Normal,
       responses: HashMap::default(),
+      marked_operations: HashSet::default(),
+      called_operations: BTreeSet::default(),
+      session_operations: HashSet::default(),
+      uncalled_only: false,
+      bookmarks: BTreeMap::default(),
+      recent_operations: VecDeque::default(),
+      webhooks_only: false,
+      ad_hoc_operations: Vec::default(),
+      preferred_server: None,
+      webhook_listener: None,
+      variables: BTreeMap::default(),
+      selected_server: None,
+      messages: VecDeque::default(),
+      server_health: HashMap::default(),
+      session_headers: vec![],
+      default_headers: vec![],
+      profiles: BTreeMap::default(),
+      active_profile: None,
+      macros: BTreeMap::default(),
+      recording_macro: None,
+      in_flight: HashSet::default(),
+      assertions: BTreeMap::default(),
+      previous_responses: HashMap::default(),
+      variable_sources: BTreeMap::default(),
+      declared_rate_limits: rate_limit::Declared::from_spec(resp.as_str()),
+      security_schemes: SecuritySchemes::from_spec(resp.as_str()),
+      credentials: BTreeMap::default(),
+      idempotent_operations: BTreeSet::default(),
+      notes: String::default(),
+      annotations: BTreeMap::default(),
+      known_hosts: None,
+      newer_releases: vec![],
+      client: Client::default(),
     })
   }
 
   pub async fn from_input(input: String) -> Result<Self> {
-    if let Ok(url) = reqwest::Url::parse(input.as_str()) {
-      State::from_url(url).await
+    let mut state = if let Ok(url) = reqwest::Url::parse(input.as_str()) {
+      State::from_url(url).await?
     } else {
-      State::from_path(input).await
+      State::from_path(input).await?
+    };
+    state.called_operations = storage::load(state.openapi_input_source.as_str(), COVERAGE_FILE);
+    state.bookmarks = storage::load(state.openapi_input_source.as_str(), BOOKMARKS_FILE);
+    state.macros = storage::load(state.openapi_input_source.as_str(), MACROS_FILE);
+    state.assertions = storage::load(state.openapi_input_source.as_str(), ASSERTIONS_FILE);
+    state.idempotent_operations = storage::load(state.openapi_input_source.as_str(), IDEMPOTENCY_FILE);
+    state.notes = storage::load(state.openapi_input_source.as_str(), NOTES_FILE);
+    state.annotations = storage::load(state.openapi_input_source.as_str(), ANNOTATIONS_FILE);
+    state.restore_settings(storage::load(state.openapi_input_source.as_str(), SETTINGS_FILE));
+    Ok(state)
+  }
+
+  fn restore_settings(&mut self, settings: Settings) {
+    let tags = self.openapi_spec.tags.iter().flatten().map(|tag| tag.name.as_str()).collect::<Vec<_>>();
+    self.active_tag_name = settings.tag.filter(|tag| tags.contains(&tag.as_str()));
+    self.webhooks_only = settings.webhooks_only && self.has_webhooks();
+    self.active_filter = settings.filter;
+    self.preferred_server = settings.server.clone();
+    self.selected_server = settings.server;
+    if settings.base_url.is_some() {
+      self.base_url = settings.base_url;
+    }
+    self.active_profile = settings.profile;
+    self.active_operation_index = settings
+      .operation
+      .and_then(|operation_id| {
+        self
+          .visible_operations()
+          .position(|operation_item| operation_item.operation.operation_id.as_ref() == Some(&operation_id))
+      })
+      .unwrap_or_default();
+  }
+
+  pub fn settings(&self) -> Settings {
+    Settings {
+      server: self.selected_server.clone(),
+      filter: self.active_filter.clone(),
+      tag: self.active_tag_name.clone(),
+      webhooks_only: self.webhooks_only,
+      operation: self.active_operation().and_then(|operation_item| operation_item.operation.operation_id.clone()),
+      base_url: self.base_url.clone(),
+      profile: self.active_profile.clone(),
     }
   }
 
-  pub fn get_operation(&self, operation_id: Option<String>) -> Option<&OperationItem> {
-    self.openapi_operations.iter().find(|operation_item| operation_item.operation.operation_id.eq(&operation_id))
+  pub fn save_settings(&self) {
+    if let Err(error) = storage::save(self.openapi_input_source.as_str(), SETTINGS_FILE, &self.settings()) {
+      log::error!("can't save settings: {error}");
+    }
   }
 
-  pub fn active_operation(&self) -> Option<&OperationItem> {
-    if let Some(active_tag) = &self.active_tag_name {
-      self
-        .openapi_operations
-        .iter()
-        .filter(|flat_operation| {
-          flat_operation.has_tag(active_tag) && flat_operation.path.contains(self.active_filter.as_str())
-        })
-        .nth(self.active_operation_index)
-    } else {
-      self
-        .openapi_operations
-        .iter()
-        .filter(|flat_operation| flat_operation.path.contains(self.active_filter.as_str()))
-        .nth(self.active_operation_index)
+  pub fn set_bookmark(&mut self, slot: u32, operation_id: String) {
+    self.bookmarks.insert(slot, operation_id);
+    if let Err(error) = storage::save(self.openapi_input_source.as_str(), BOOKMARKS_FILE, &self.bookmarks) {
+      log::error!("can't save bookmarks: {error}");
     }
   }
 
-  pub fn operations_len(&self) -> usize {
-    if let Some(active_tag) = &self.active_tag_name {
-      self
-        .openapi_operations
-        .iter()
-        .filter(|item| item.has_tag(active_tag) && item.path.contains(self.active_filter.as_str()))
-        .count()
+  /// Replaces the spec with a newer version of it, keeping the servers when the new one has none and the selected
+  /// operation when it's still there. The security schemes are read again from its text, without one they are kept
+  /// as a spec merged from recorded calls declares the same ones.
+  pub fn replace_spec(&mut self, mut openapi_spec: Openapi, text: Option<&str>) {
+    if let Some(text) = text {
+      self.security_schemes = SecuritySchemes::from_spec(text);
+    }
+    if openapi_spec.servers.is_none() {
+      openapi_spec.servers.clone_from(&self.openapi_spec.servers);
+    }
+    let operation_id = self.active_operation().and_then(|operation_item| operation_item.operation.operation_id.clone());
+    self.openapi_operations = openapi_spec
+      .into_operations()
+      .map(|(path, method, operation)| OperationItem::new(path, method, operation))
+      .collect::<Vec<_>>();
+    self.openapi_spec = openapi_spec;
+    let index =
+      self.visible_operations().position(|operation_item| operation_item.operation.operation_id == operation_id);
+    self.active_operation_index = index.unwrap_or_default();
+  }
+
+  pub fn set_macro(&mut self, register: char, keys: Vec<KeyEvent>) {
+    self.macros.insert(register, keys);
+    if let Err(error) = storage::save(self.openapi_input_source.as_str(), MACROS_FILE, &self.macros) {
+      log::error!("can't save macros: {error}");
+    }
+  }
+
+  /// Sets the latest response of an operation, keeping the one it replaces as the previous response. With trust on
+  /// first use, a certificate other than the one remembered for its host is flagged on the response, it already
+  /// passed validation or a pin in the handshake.
+  pub fn set_response(&mut self, operation_id: &str, mut response: Response) {
+    let trust = self
+      .known_hosts
+      .as_mut()
+      .zip(response.certificate.as_ref())
+      .map(|(known_hosts, certificate)| (certificate.host.clone(), known_hosts.check(certificate)));
+    if let Some((host, Trust::Changed(previous))) = trust {
+      self.push_message(format!("certificate of {host} changed since it was first trusted").as_str());
+      response.certificate_changed = Some(previous);
+    }
+    if let Some(previous) = self.responses.insert(operation_id.to_string(), response) {
+      self.previous_responses.insert(operation_id.to_string(), previous);
+    }
+  }
+
+  /// Loads the variables of a dotenv file over the current ones and returns how many it has.
+  pub fn load_env_file(&mut self, filepath: &str) -> Result<usize> {
+    let variables = dotenv::load(filepath)?;
+    let count = variables.len();
+    for (name, value) in variables {
+      self.variable_sources.insert(name.clone(), filepath.to_string());
+      self.variables.insert(name, value);
+    }
+    Ok(count)
+  }
+
+  /// The active profile, if it's still in the config.
+  pub fn profile(&self) -> Option<&Profile> {
+    self.active_profile.as_ref().and_then(|name| self.profiles.get(name))
+  }
+
+  /// Headers of the active profile followed by the default ones it doesn't replace.
+  pub fn standing_headers(&self) -> Vec<(String, String)> {
+    profiles::standing_headers(self.profile(), &self.default_headers)
+  }
+
+  pub fn set_notes(&mut self, notes: String) {
+    self.notes = notes;
+    if let Err(error) = storage::save(self.openapi_input_source.as_str(), NOTES_FILE, &self.notes) {
+      log::error!("can't save notes: {error}");
+    }
+  }
+
+  /// Sets the label of an operation, `None` removes it.
+  pub fn set_annotation(&mut self, operation_id: &str, annotation: Option<String>) {
+    match annotation {
+      Some(annotation) => self.annotations.insert(operation_id.to_string(), annotation),
+      None => self.annotations.remove(operation_id),
+    };
+    if let Err(error) = storage::save(self.openapi_input_source.as_str(), ANNOTATIONS_FILE, &self.annotations) {
+      log::error!("can't save annotations: {error}");
+    }
+  }
+
+  pub fn annotation(&self, operation_item: &OperationItem) -> Option<&str> {
+    self.annotations.get(operation_item.operation.operation_id.as_ref()?).map(String::as_str)
+  }
+
+  /// Whether an operation matches the `/` filter, by path or, for filters starting with `@`, by annotation.
+  fn matches_filter(&self, operation_item: &OperationItem) -> bool {
+    match self.active_filter.strip_prefix('@') {
+      Some(label) => self
+        .annotation(operation_item)
+        .is_some_and(|annotation| annotation.to_lowercase().contains(label.to_lowercase().as_str())),
+      None => operation_item.path.contains(self.active_filter.as_str()),
+    }
+  }
+
+  /// Replaces the assertions of an operation, an empty list removes them.
+  pub fn set_assertions(&mut self, operation_id: &str, assertions: Vec<String>) {
+    if assertions.is_empty() {
+      self.assertions.remove(operation_id);
     } else {
-      self
-        .openapi_operations
-        .iter()
-        .filter(|flat_operation| flat_operation.path.contains(self.active_filter.as_str()))
-        .count()
+      self.assertions.insert(operation_id.to_string(), assertions);
+    }
+    if let Err(error) = storage::save(self.openapi_input_source.as_str(), ASSERTIONS_FILE, &self.assertions) {
+      log::error!("can't save assertions: {error}");
+    }
+  }
+
+  /// Turns sending an `Idempotency-Key` header with each call of an operation on or off, returning whether it's on.
+  pub fn toggle_idempotency_key(&mut self, operation_id: &str) -> bool {
+    let enabled = !self.idempotent_operations.remove(operation_id);
+    if enabled {
+      self.idempotent_operations.insert(operation_id.to_string());
     }
+    if let Err(error) = storage::save(self.openapi_input_source.as_str(), IDEMPOTENCY_FILE, &self.idempotent_operations)
+    {
+      log::error!("can't save idempotent operations: {error}");
+    }
+    enabled
+  }
+
+  /// Moves an operation to the front of the recently viewed or called ones.
+  pub fn remember_operation(&mut self, operation_id: &str) {
+    self.recent_operations.retain(|recent| recent != operation_id);
+    self.recent_operations.push_front(operation_id.to_string());
+    self.recent_operations.truncate(MAX_RECENT_OPERATIONS);
+  }
+
+  pub fn push_message(&mut self, message: &str) {
+    if self.messages.front().is_some_and(|(_, last)| last == message) {
+      return;
+    }
+    self.messages.push_front((Instant::now(), message.to_string()));
+    self.messages.truncate(MAX_MESSAGES);
+  }
+
+  /// Marks an operation as exercised, in this session and in the coverage persisted for the spec.
+  pub fn record_call(&mut self, operation_id: &str) {
+    self.remember_operation(operation_id);
+    self.session_operations.insert(operation_id.to_string());
+    if self.called_operations.insert(operation_id.to_string()) {
+      if let Err(error) = storage::save(self.openapi_input_source.as_str(), COVERAGE_FILE, &self.called_operations) {
+        log::error!("can't save coverage: {error}");
+      }
+    }
+  }
+
+  pub fn is_called(&self, operation_item: &OperationItem) -> bool {
+    operation_item
+      .operation
+      .operation_id
+      .as_ref()
+      .is_some_and(|operation_id| self.called_operations.contains(operation_id))
+  }
+
+  /// Number of called operations and the number of operations that can be called, webhooks excluded.
+  pub fn coverage(&self) -> (usize, usize) {
+    let operations =
+      self.openapi_operations.iter().filter(|operation_item| matches!(operation_item.r#type, OperationItemType::Path));
+    operations
+      .fold((0, 0), |(called, total), operation_item| (called + usize::from(self.is_called(operation_item)), total + 1))
+  }
+
+  pub fn get_operation(&self, operation_id: Option<String>) -> Option<&OperationItem> {
+    self
+      .openapi_operations
+      .iter()
+      .chain(self.ad_hoc_operations.iter())
+      .find(|operation_item| operation_item.operation.operation_id.eq(&operation_id))
+  }
+
+  /// Adds an ad-hoc operation for a path that's not in the spec, returning its id.
+  pub fn add_ad_hoc_operation(&mut self, method: &str, path: &str) -> Option<String> {
+    let operation_item = OperationItem::ad_hoc(method, path);
+    let operation_id = operation_item.operation.operation_id.clone();
+    if self.get_operation(operation_id.clone()).is_none() {
+      self.ad_hoc_operations.push(operation_item);
+    }
+    operation_id
+  }
+
+  /// Finds the path operation whose template matches a concrete request path, preferring the most specific one,
+  /// along with the path parameter values captured from it.
+  pub fn match_operation(&self, method: &str, path: &str) -> Option<(&OperationItem, Vec<(String, String)>)> {
+    self
+      .openapi_operations
+      .iter()
+      .filter(|operation_item| {
+        matches!(operation_item.r#type, OperationItemType::Path) && operation_item.method.eq_ignore_ascii_case(method)
+      })
+      .filter_map(|operation_item| operation_item.match_path(path).map(|parameters| (operation_item, parameters)))
+      .min_by_key(|(_, parameters)| parameters.len())
+  }
+
+  pub fn active_operation(&self) -> Option<&OperationItem> {
+    self.visible_operations().nth(self.active_operation_index)
+  }
+
+  /// External docs of the active operation, or of the whole spec when the operation has none.
+  pub fn external_docs_url(&self) -> Option<String> {
+    self
+      .active_operation()
+      .and_then(|operation_item| operation_item.operation.external_docs.as_ref())
+      .or(self.openapi_spec.external_docs.as_ref())
+      .map(|external_docs| external_docs.url.clone())
+  }
+
+  pub fn visible_operations(&self) -> impl Iterator<Item = &OperationItem> {
+    self.openapi_operations.iter().filter(|operation_item| {
+      self.active_tag_name.iter().all(|active_tag| operation_item.has_tag(active_tag))
+        && (!self.webhooks_only || matches!(operation_item.r#type, OperationItemType::Webhook))
+        && self.matches_filter(operation_item)
+        && !(self.uncalled_only && self.is_called(operation_item))
+    })
+  }
+
+  pub fn is_marked(&self, operation_item: &OperationItem) -> bool {
+    operation_item
+      .operation
+      .operation_id
+      .as_ref()
+      .is_some_and(|operation_id| self.marked_operations.contains(operation_id))
+  }
+
+  pub fn has_webhooks(&self) -> bool {
+    self.openapi_operations.iter().any(|operation_item| matches!(operation_item.r#type, OperationItemType::Webhook))
   }
 
-  fn default_url(server: &Server) -> String {
+  pub fn operations_len(&self) -> usize {
+    self.visible_operations().count()
+  }
+
+  fn default_url(&self, server: &Server) -> String {
     let mut url = server.url.clone();
     if let Some(variables) = &server.variables {
       for (k, v) in variables {
         url = url.replace(format!("{{{}}}", k).as_str(), &v.default);
       }
     }
-    url.trim_end_matches('/').to_string()
+    self.resolve_server_url(url.as_str()).trim_end_matches('/').to_string()
   }
 
-  pub fn default_server_urls(&self, extra_servers: &Option<Vec<Server>>) -> Vec<String> {
+  /// Resolves a relative server url like `/api/v3` against the base url, unchanged when it's absolute or there's no
+  /// base url to resolve it against.
+  pub fn resolve_server_url(&self, url: &str) -> String {
+    if reqwest::Url::parse(url).is_ok() {
+      return url.to_string();
+    }
+    self
+      .base_url
+      .as_deref()
+      .and_then(|base_url| reqwest::Url::parse(base_url).ok()?.join(url).ok())
+      .map_or_else(|| url.to_string(), |url| url.to_string())
+  }
+
+  /// Whether some servers of the spec or its operations are relative with no base url to resolve them against.
+  pub fn has_unresolved_servers(&self) -> bool {
+    self
+      .openapi_spec
+      .servers
+      .iter()
+      .flatten()
+      .chain(
+        self.openapi_operations.iter().flat_map(|operation_item| operation_item.operation.servers.iter().flatten()),
+      )
+      .any(|server| reqwest::Url::parse(self.default_url(server).as_str()).is_err())
+  }
+
+  /// Servers in the order `--server <INDEX>` counts them: the one of `OPENAPI_TUI_DEFAULT_SERVER`, the extra ones and
+  /// then the ones of the spec.
+  pub fn server_urls(&self, extra_servers: &Option<Vec<Server>>) -> Vec<String> {
     let mut result = vec![];
     if let Ok(url) = env::var("OPENAPI_TUI_DEFAULT_SERVER") {
       result.push(url.trim_end_matches('/').to_string());
     }
 
     extra_servers.iter().flatten().for_each(|server| {
-      result.push(State::default_url(server));
+      result.push(self.default_url(server));
     });
 
     self.openapi_spec.servers.iter().flatten().for_each(|server| {
-      result.push(State::default_url(server));
+      result.push(self.default_url(server));
     });
+    result
+  }
+
+  pub fn default_server_urls(&self, extra_servers: &Option<Vec<Server>>) -> Vec<String> {
+    let mut result = self.server_urls(extra_servers);
+
+    match self.preferred_server.as_ref().map(|server| (server, server.parse::<usize>())) {
+      Some((_, Ok(index))) if index < result.len() => {
+        let url = result.remove(index);
+        result.insert(0, url);
+      },
+      Some((server, Err(_))) => {
+        let server = server.trim_end_matches('/').to_string();
+        result.retain(|url| url != &server);
+        result.insert(0, server);
+      },
+      _ => {},
+    }
+
+    if let Some(base_url) = self.profile().and_then(|profile| profile.base_url.as_deref()) {
+      let base_url = self.resolve_server_url(base_url).trim_end_matches('/').to_string();
+      result.retain(|url| url != &base_url);
+      result.insert(0, base_url);
+    }
 
     if result.is_empty() {
       result.push("http://localhost".to_string());
     }
-    result
+    environments::group(result, |url: &str| environments::environment(url, self.server_description(url, extra_servers)))
+  }
+
+  /// Description of a server url, from the servers of the operation or of the spec.
+  pub fn server_description<'a>(&'a self, url: &str, extra_servers: &'a Option<Vec<Server>>) -> Option<&'a str> {
+    extra_servers
+      .iter()
+      .flatten()
+      .chain(self.openapi_spec.servers.iter().flatten())
+      .find(|server| self.default_url(server) == url)
+      .and_then(|server| server.description.as_deref())
+  }
+
+  /// Environment and description of a server url shown next to it, e.g. `staging · EU cluster`.
+  pub fn server_label(&self, url: &str, extra_servers: &Option<Vec<Server>>) -> Option<String> {
+    let description = self.server_description(url, extra_servers);
+    let environment = environments::environment(url, description);
+    match (environment, description) {
+      (Some(environment), Some(description)) if !description.eq_ignore_ascii_case(environment) => {
+        Some(format!("{environment} · {description}"))
+      },
+      (Some(environment), _) => Some(environment.to_string()),
+      (None, description) => description.map(String::from),
+    }
   }
 }
 
 impl OperationItem {
+  pub fn new(path: String, method: String, mut operation: Operation) -> Self {
+    let r#type = if path.starts_with('/') { OperationItemType::Path } else { OperationItemType::Webhook };
+    let synthetic_id = operation.operation_id.is_none();
+    if synthetic_id {
+      operation.operation_id = Some(OperationItem::synthesize_id(&method, &path));
+    }
+    Self { path, method, operation, r#type, synthetic_id }
+  }
+
+  /// Operation for a request that's not described by the spec, with a json body for the methods that take one. Its id
+  /// has an `adhoc-` prefix so it never takes the id synthesized for a spec operation with the same method and path.
+  pub fn ad_hoc(method: &str, path: &str) -> Self {
+    let mut operation = Operation::new();
+    operation.summary = Some(String::from("ad-hoc request"));
+    if ["POST", "PUT", "PATCH"].contains(&method) {
+      let content = BTreeMap::from([(String::from("application/json"), MediaType::new())]);
+      operation.request_body = Some(ObjectOrRef::Object(RequestBody::new(content)));
+    }
+    let mut operation_item = OperationItem::new(path.to_string(), method.to_string(), operation);
+    operation_item.operation.operation_id = Some(format!("adhoc-{}", OperationItem::synthesize_id(method, path)));
+    operation_item
+  }
+
+  /// Builds a deterministic identifier for operations that don't declare an `operationId`, so they can be
+  /// keyed in responses and history the same way across sessions.
+  pub fn synthesize_id(method: &str, path: &str) -> String {
+    format!(
+      "{}-{:08x}",
+      method.to_lowercase(),
+      stable_hash(format!("{} {}", method.to_uppercase(), path).as_str()) as u32
+    )
+  }
+
+  pub fn match_path(&self, path: &str) -> Option<Vec<(String, String)>> {
+    let template_segments = self.path.trim_matches('/').split('/').collect::<Vec<_>>();
+    let path_segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    if template_segments.len() != path_segments.len() {
+      return None;
+    }
+    let mut parameters = vec![];
+    for (template_segment, path_segment) in template_segments.into_iter().zip(path_segments) {
+      if let Some(name) = template_segment.strip_prefix('{').and_then(|segment| segment.strip_suffix('}')) {
+        if path_segment.is_empty() {
+          return None;
+        }
+        parameters.push((name.to_string(), path_segment.to_string()));
+      } else if template_segment != path_segment {
+        return None;
+      }
+    }
+    Some(parameters)
+  }
+
+  /// Whether the operation can't be sent as is, because a required parameter or path parameter has no default or
+  /// a request body is required.
+  pub fn requires_input(&self, openapi_spec: &Openapi) -> bool {
+    let missing_parameter = self
+      .operation
+      .parameters
+      .iter()
+      .flatten()
+      .filter_map(|parameter_or_ref| parameter_or_ref.resolve(openapi_spec).ok())
+      .any(|parameter| {
+        (parameter.required.unwrap_or(false) || matches!(parameter.r#in, In::Path))
+          && parameter.schema.as_ref().and_then(|schema| schema.get("default")).is_none()
+      });
+    let required_body = self
+      .operation
+      .request_body
+      .as_ref()
+      .and_then(|request_body| request_body.resolve(openapi_spec).ok())
+      .is_some_and(|request_body| request_body.required.unwrap_or(false));
+    missing_parameter || required_body
+  }
+
   pub fn has_tag(&self, tag: &String) -> bool {
     self.operation.tags.as_ref().map_or(false, |tags| tags.contains(tag))
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_synthetic_id_is_deterministic() {
+    assert_eq!(OperationItem::synthesize_id("GET", "/pets/{id}"), OperationItem::synthesize_id("get", "/pets/{id}"));
+    assert!(OperationItem::synthesize_id("GET", "/pets/{id}").starts_with("get-"));
+    assert_ne!(OperationItem::synthesize_id("GET", "/pets"), OperationItem::synthesize_id("DELETE", "/pets"));
+  }
+
+  #[test]
+  fn test_declared_operation_id_is_kept() {
+    let operation = Operation { operation_id: Some("listPets".into()), ..Default::default() };
+    let item = OperationItem::new("/pets".into(), "GET".into(), operation);
+    assert_eq!(item.operation.operation_id, Some("listPets".into()));
+    assert!(!item.synthetic_id);
+
+    let item = OperationItem::new("/pets".into(), "GET".into(), Operation::default());
+    assert_eq!(item.operation.operation_id, Some(OperationItem::synthesize_id("GET", "/pets")));
+    assert!(item.synthetic_id);
+  }
+
+  #[test]
+  fn test_ad_hoc_id() {
+    let mut state = State::default();
+    state.openapi_operations.push(OperationItem::new("/pets".into(), "GET".into(), Operation::default()));
+    let operation_id = state.add_ad_hoc_operation("GET", "/pets").unwrap();
+    assert_eq!(operation_id, format!("adhoc-{}", OperationItem::synthesize_id("GET", "/pets")));
+    assert_eq!(state.ad_hoc_operations.len(), 1);
+    assert_eq!(state.add_ad_hoc_operation("GET", "/pets"), Some(operation_id));
+    assert_eq!(state.ad_hoc_operations.len(), 1);
+  }
+
+  #[test]
+  fn test_push_message() {
+    let mut state = State::default();
+    for message in ["sent", "sent", "failed", "sent"] {
+      state.push_message(message);
+    }
+    assert_eq!(
+      state.messages.iter().map(|(_, message)| message.as_str()).collect::<Vec<_>>(),
+      ["sent", "failed", "sent"]
+    );
+  }
+
+  #[test]
+  fn test_restore_settings() {
+    let mut state = State {
+      openapi_operations: vec![
+        OperationItem::new("/pets".into(), "GET".into(), Operation::default()),
+        OperationItem::new("/pets/{petId}".into(), "GET".into(), Operation::default()),
+        OperationItem::new("/stores".into(), "GET".into(), Operation::default()),
+      ],
+      ..Default::default()
+    };
+    let settings = Settings {
+      server: Some("https://staging.example.com".into()),
+      filter: "pets".into(),
+      tag: None,
+      webhooks_only: false,
+      operation: Some(OperationItem::synthesize_id("GET", "/pets/{petId}")),
+      base_url: None,
+      profile: None,
+    };
+    state.restore_settings(Settings { tag: Some("removed".into()), ..settings });
+    assert_eq!(state.active_operation_index, 1);
+    assert_eq!(state.default_server_urls(&None)[0], "https://staging.example.com");
+    assert_eq!(
+      state.settings(),
+      Settings {
+        server: Some("https://staging.example.com".into()),
+        filter: "pets".into(),
+        tag: None,
+        webhooks_only: false,
+        operation: Some(OperationItem::synthesize_id("GET", "/pets/{petId}")),
+        base_url: None,
+        profile: None,
+      }
+    );
+
+    let mut state = State { base_url: Some("https://example.com/openapi.json".into()), ..Default::default() };
+    state.restore_settings(Settings { base_url: Some("http://localhost:8080".into()), ..Default::default() });
+    assert_eq!(state.resolve_server_url("/api/v3"), "http://localhost:8080/api/v3");
+  }
+
+  #[test]
+  fn test_relative_servers() {
+    let mut state = State::default();
+    state.openapi_spec.servers = Some(vec![
+      Server::new("/api/v3".into()),
+      Server::new("v1/".into()),
+      Server::new("https://api.example.com/v3".into()),
+    ]);
+    assert!(state.has_unresolved_servers());
+    assert_eq!(state.default_server_urls(&None), ["/api/v3", "v1", "https://api.example.com/v3"]);
+
+    state.base_url = Some(String::from("https://petstore.example.com/specs/openapi.json"));
+    assert!(!state.has_unresolved_servers());
+    assert_eq!(
+      state.default_server_urls(&None),
+      ["https://petstore.example.com/api/v3", "https://petstore.example.com/specs/v1", "https://api.example.com/v3"]
+    );
+    state.base_url = Some(String::from("http://localhost:8080"));
+    assert_eq!(state.default_server_urls(&None)[0], "http://localhost:8080/api/v3");
+
+    state.profiles.insert(String::from("dev"), Profile { base_url: Some(String::from("/dev")), ..Profile::default() });
+    state.active_profile = Some(String::from("dev"));
+    assert_eq!(state.default_server_urls(&None)[0], "http://localhost:8080/dev");
+  }
+
+  #[test]
+  fn test_annotation_filter() {
+    let mut state = State {
+      openapi_operations: vec![
+        OperationItem::new("/pets".into(), "GET".into(), Operation::default()),
+        OperationItem::new("/pets/{petId}".into(), "GET".into(), Operation::default()),
+        OperationItem::new("/stores".into(), "GET".into(), Operation::default()),
+      ],
+      ..Default::default()
+    };
+    state.annotations.insert(OperationItem::synthesize_id("GET", "/pets/{petId}"), "Broken in staging".into());
+    state.annotations.insert(OperationItem::synthesize_id("GET", "/stores"), "needs auth fix".into());
+    let visible = |state: &State| state.visible_operations().map(|item| item.path.clone()).collect::<Vec<_>>();
+    state.active_filter = String::from("@broken");
+    assert_eq!(visible(&state), ["/pets/{petId}"]);
+    state.active_filter = String::from("@");
+    assert_eq!(visible(&state), ["/pets/{petId}", "/stores"]);
+    state.active_filter = String::from("pets");
+    assert_eq!(visible(&state), ["/pets", "/pets/{petId}"]);
+  }
+
+  #[test]
+  fn test_match_operation() {
+    let state = State {
+      openapi_operations: vec![
+        OperationItem::new("/pets".into(), "GET".into(), Operation::default()),
+        OperationItem::new("/pets/{petId}".into(), "GET".into(), Operation::default()),
+        OperationItem::new("/pets/mine".into(), "GET".into(), Operation::default()),
+      ],
+      ..Default::default()
+    };
+    let (item, parameters) = state.match_operation("get", "/pets/42").unwrap();
+    assert_eq!(item.path, "/pets/{petId}");
+    assert_eq!(parameters, vec![("petId".to_string(), "42".to_string())]);
+    assert_eq!(state.match_operation("GET", "/pets/mine").unwrap().0.path, "/pets/mine");
+    assert!(state.match_operation("DELETE", "/pets/42").is_none());
+    assert!(state.match_operation("GET", "/owners/42").is_none());
+  }
+
+  #[test]
+  fn test_macros_round_trip() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let macros = BTreeMap::from([(
+      'a',
+      vec![KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE), KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)],
+    )]);
+    let json = serde_json::to_string(&macros).unwrap();
+    assert_eq!(serde_json::from_str::<BTreeMap<char, Vec<KeyEvent>>>(json.as_str()).unwrap(), macros);
+  }
+}
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::Result;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::utils::{get_data_dir, stable_hash};
+
+/// Directory holding the data persisted for a spec, keyed by where the spec was loaded from.
+pub fn spec_dir(input_source: &str) -> PathBuf {
+  let input_source = std::fs::canonicalize(input_source)
+    .map(|path| path.display().to_string())
+    .unwrap_or_else(|_| input_source.to_string());
+  get_data_dir().join("specs").join(format!("{:016x}", stable_hash(input_source.as_str())))
+}
+
+/// Reads a JSON file of the spec data directory, falling back to the default value when it's missing or broken.
+pub fn load<T: DeserializeOwned + Default>(input_source: &str, name: &str) -> T {
+  let path = spec_dir(input_source).join(name);
+  match std::fs::read_to_string(&path) {
+    Ok(content) => serde_json::from_str(content.as_str()).unwrap_or_else(|error| {
+      log::error!("can't parse {}: {error}", path.display());
+      T::default()
+    }),
+    Err(_) => T::default(),
+  }
+}
+
+pub fn save<T: Serialize>(input_source: &str, name: &str, value: &T) -> Result<()> {
+  let directory = spec_dir(input_source);
+  std::fs::create_dir_all(&directory)?;
+  std::fs::write(directory.join(name), serde_json::to_string_pretty(value)?)?;
+  Ok(())
+}
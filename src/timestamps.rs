@@ -0,0 +1,81 @@
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+/// Quoted strings of a line of pretty printed JSON, escapes are kept as they are.
+fn quoted_strings(line: &str) -> Vec<&str> {
+  let mut strings = vec![];
+  let mut start = None;
+  let mut escaped = false;
+  for (index, character) in line.char_indices() {
+    match (character, start) {
+      (_, Some(_)) if escaped => escaped = false,
+      ('\\', Some(_)) => escaped = true,
+      ('"', Some(from)) => {
+        strings.push(&line[from..index]);
+        start = None;
+      },
+      ('"', None) => start = Some(index + 1),
+      _ => {},
+    }
+  }
+  strings
+}
+
+/// ISO-8601 timestamp with a date, a time and an offset, e.g. `2024-05-01T12:00:00Z`.
+pub fn parse(text: &str) -> Option<DateTime<FixedOffset>> {
+  DateTime::parse_from_rfc3339(text).ok()
+}
+
+/// How far a timestamp is from now, e.g. `3h ago` or `in 2d`.
+pub fn relative(timestamp: DateTime<Utc>, now: DateTime<Utc>) -> String {
+  let seconds = now.signed_duration_since(timestamp).num_seconds();
+  let amount = match seconds.unsigned_abs() {
+    0..=9 => return String::from("now"),
+    seconds @ 10..=59 => format!("{seconds}s"),
+    seconds @ 60..=3599 => format!("{}m", seconds / 60),
+    seconds @ 3600..=86399 => format!("{}h", seconds / 3600),
+    seconds => format!("{}d", seconds / 86400),
+  };
+  if seconds < 0 {
+    format!("in {amount}")
+  } else {
+    format!("{amount} ago")
+  }
+}
+
+/// The timestamps of a line in `timezone` and relative to now, `None` when the line has no timestamps.
+pub fn annotation<Tz: TimeZone>(line: &str, now: DateTime<Utc>, timezone: &Tz) -> Option<String>
+where
+  Tz::Offset: std::fmt::Display,
+{
+  let annotations = quoted_strings(line)
+    .into_iter()
+    .filter_map(parse)
+    .map(|timestamp| {
+      let timestamp = timestamp.with_timezone(&Utc);
+      format!("{}, {}", timestamp.with_timezone(timezone).format("%Y-%m-%d %H:%M:%S %:z"), relative(timestamp, now))
+    })
+    .collect::<Vec<_>>();
+  (!annotations.is_empty()).then(|| annotations.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_annotation() {
+    let now = parse("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+    let tehran = FixedOffset::east_opt(3 * 3600 + 1800).unwrap();
+    assert_eq!(
+      annotation(r#"  "createdAt": "2024-05-01T09:00:00+00:00","#, now, &tehran),
+      Some(String::from("2024-05-01 12:30:00 +03:30, 3h ago"))
+    );
+    assert_eq!(
+      annotation(r#"  "window": ["2024-05-01T11:59:55Z", "2024-05-03T12:00:00Z"]"#, now, &Utc),
+      Some(String::from("2024-05-01 11:59:55 +00:00, now; 2024-05-03 12:00:00 +00:00, in 2d"))
+    );
+    assert_eq!(annotation(r#"  "name": "2024-05-01", "note": "say \"hi\"""#, now, &Utc), None);
+  }
+}
@@ -0,0 +1,333 @@
+use std::{
+  collections::BTreeMap,
+  sync::{Arc, Mutex, RwLock},
+};
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{eyre, Result};
+use rustls::{
+  client::{
+    danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    WebPkiServerVerifier,
+  },
+  pki_types::{CertificateDer, ServerName, UnixTime},
+  CertificateError, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use sha2::{Digest, Sha256};
+use x509_parser::{certificate::X509Certificate, extensions::GeneralName, prelude::FromDer, time::ASN1Time};
+
+/// Server certificate of a TLS connection, the fields worth checking when a call fails on its certificate.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Certificate {
+  /// Host and port of the connection the certificate was presented on.
+  pub host: String,
+  /// SHA-256 of the DER encoded certificate, as colon separated hex.
+  pub fingerprint: String,
+  pub subject: String,
+  pub issuer: String,
+  pub not_before: Option<DateTime<Utc>>,
+  pub not_after: Option<DateTime<Utc>>,
+  /// DNS names of the subject alternative name extension.
+  pub alt_names: Vec<String>,
+}
+
+/// SHA-256 fingerprint of a DER encoded certificate, e.g. `AB:CD:…`.
+pub fn fingerprint(der: &[u8]) -> String {
+  Sha256::digest(der).iter().map(|byte| format!("{byte:02X}")).collect::<Vec<_>>().join(":")
+}
+
+/// Time of a validity period of a certificate.
+fn time(time: ASN1Time) -> Option<DateTime<Utc>> {
+  DateTime::from_timestamp(time.timestamp(), 0)
+}
+
+/// Reads a DER encoded X.509 certificate presented by `host`, `None` when it's malformed.
+pub fn parse_certificate(host: &str, der: &[u8]) -> Option<Certificate> {
+  let (_, certificate) = X509Certificate::from_der(der).ok()?;
+  let alt_names = match certificate.subject_alternative_name() {
+    Ok(Some(extension)) => extension
+      .value
+      .general_names
+      .iter()
+      .filter_map(|name| match name {
+        GeneralName::DNSName(name) => Some(name.to_string()),
+        _ => None,
+      })
+      .collect(),
+    _ => vec![],
+  };
+  Some(Certificate {
+    host: host.to_string(),
+    fingerprint: fingerprint(der),
+    subject: certificate.subject().to_string(),
+    issuer: certificate.issuer().to_string(),
+    not_before: time(certificate.validity().not_before),
+    not_after: time(certificate.validity().not_after),
+    alt_names,
+  })
+}
+
+/// Certificate the handshake refused, to offer trusting it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejected {
+  /// Server name of the handshake, without the port.
+  pub host: String,
+  pub fingerprint: String,
+  /// Fingerprint remembered on first use, when the certificate validates but isn't the remembered one.
+  pub remembered: Option<String>,
+}
+
+/// Fingerprints per `host:port` the handshake checks: the ones the user pinned, accepted although the certificate
+/// doesn't validate, e.g. a self-signed one of an internal environment, and the ones remembered on first use, which
+/// a validating certificate has to match. With the last certificate that was refused, to offer trusting it.
+///
+/// The handshake only knows the host name, so a fingerprint is checked against the ones of every port of its host.
+#[derive(Debug, Default)]
+pub struct Pins {
+  pinned: RwLock<BTreeMap<String, String>>,
+  remembered: RwLock<BTreeMap<String, String>>,
+  rejected: Mutex<Option<Rejected>>,
+}
+
+/// Host of a `host:port` key, a key without port is a host pinned before pins had ports.
+fn key_host(key: &str) -> &str {
+  let host = match key.rsplit_once(':') {
+    Some((host, port)) if port.parse::<u16>().is_ok() => host,
+    _ => key,
+  };
+  host.trim_start_matches('[').trim_end_matches(']')
+}
+
+/// Fingerprints of every port of a host.
+fn fingerprints_of<'a>(fingerprints: &'a BTreeMap<String, String>, host: &'a str) -> impl Iterator<Item = &'a str> {
+  fingerprints.iter().filter(move |(key, _)| key_host(key) == host).map(|(_, fingerprint)| fingerprint.as_str())
+}
+
+impl Pins {
+  pub fn new(pinned: BTreeMap<String, String>, remembered: BTreeMap<String, String>) -> Self {
+    Self { pinned: RwLock::new(pinned), remembered: RwLock::new(remembered), rejected: Mutex::default() }
+  }
+
+  pub fn pinned(&self) -> BTreeMap<String, String> {
+    self.pinned.read().map(|pinned| pinned.clone()).unwrap_or_default()
+  }
+
+  pub fn remembered(&self) -> BTreeMap<String, String> {
+    self.remembered.read().map(|remembered| remembered.clone()).unwrap_or_default()
+  }
+
+  pub fn pin(&self, host: &str, fingerprint: &str) {
+    if let Ok(mut pinned) = self.pinned.write() {
+      pinned.insert(host.to_string(), fingerprint.to_string());
+    }
+  }
+
+  pub fn remember(&self, host: &str, fingerprint: &str) {
+    if let Ok(mut remembered) = self.remembered.write() {
+      remembered.insert(host.to_string(), fingerprint.to_string());
+    }
+  }
+
+  fn is_pinned(&self, host: &str, fingerprint: &str) -> bool {
+    self.pinned.read().is_ok_and(|pinned| fingerprints_of(&pinned, host).any(|pinned| pinned == fingerprint))
+  }
+
+  /// Fingerprint remembered for the host when none of its ports remembers this one.
+  fn remembered_instead(&self, host: &str, fingerprint: &str) -> Option<String> {
+    let remembered = self.remembered.read().ok()?;
+    let mut fingerprints = fingerprints_of(&remembered, host).peekable();
+    let first = fingerprints.peek().map(|first| first.to_string());
+    if fingerprints.any(|remembered| remembered == fingerprint) {
+      None
+    } else {
+      first
+    }
+  }
+
+  fn reject(&self, rejected: Rejected) {
+    if let Ok(mut last) = self.rejected.lock() {
+      *last = Some(rejected);
+    }
+  }
+
+  /// Last certificate the handshake refused, once.
+  pub fn take_rejected(&self) -> Option<Rejected> {
+    self.rejected.lock().ok()?.take()
+  }
+}
+
+/// Validates certificates against the system CAs like any client, except the ones pinned for their host, and
+/// refuses a validating certificate that isn't the one remembered for its host.
+#[derive(Debug)]
+struct PinningVerifier {
+  verifier: Arc<WebPkiServerVerifier>,
+  pins: Arc<Pins>,
+}
+
+impl ServerCertVerifier for PinningVerifier {
+  fn verify_server_cert(
+    &self,
+    end_entity: &CertificateDer<'_>,
+    intermediates: &[CertificateDer<'_>],
+    server_name: &ServerName<'_>,
+    ocsp_response: &[u8],
+    now: UnixTime,
+  ) -> Result<ServerCertVerified, rustls::Error> {
+    let (host, fingerprint) = (server_name.to_str(), fingerprint(end_entity));
+    if self.pins.is_pinned(&host, &fingerprint) {
+      return Ok(ServerCertVerified::assertion());
+    }
+    match self.verifier.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now) {
+      Ok(verified) => match self.pins.remembered_instead(&host, &fingerprint) {
+        None => Ok(verified),
+        Some(remembered) => {
+          self.pins.reject(Rejected { host: host.to_string(), fingerprint, remembered: Some(remembered) });
+          Err(rustls::Error::InvalidCertificate(CertificateError::ApplicationVerificationFailure))
+        },
+      },
+      Err(error) => {
+        self.pins.reject(Rejected { host: host.to_string(), fingerprint, remembered: None });
+        Err(error)
+      },
+    }
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    certificate: &CertificateDer<'_>,
+    signature: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    self.verifier.verify_tls12_signature(message, certificate, signature)
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    certificate: &CertificateDer<'_>,
+    signature: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    self.verifier.verify_tls13_signature(message, certificate, signature)
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    self.verifier.supported_verify_schemes()
+  }
+}
+
+fn pinning_verifier(roots: RootCertStore, pins: Arc<Pins>) -> Result<PinningVerifier> {
+  let provider = Arc::new(rustls::crypto::ring::default_provider());
+  let verifier = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider).build()?;
+  Ok(PinningVerifier { verifier, pins })
+}
+
+/// TLS config checking certificates against the system CAs and accepting the pinned ones, offering HTTP/2 like the
+/// default config of the client.
+pub fn client_config(pins: Arc<Pins>) -> Result<rustls::ClientConfig> {
+  let mut roots = RootCertStore::empty();
+  roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+  if roots.is_empty() {
+    return Err(eyre!("no CA certificates found in the system store"));
+  }
+  let provider = Arc::new(rustls::crypto::ring::default_provider());
+  let mut config = rustls::ClientConfig::builder_with_provider(provider)
+    .with_safe_default_protocol_versions()?
+    .dangerous()
+    .with_custom_certificate_verifier(Arc::new(pinning_verifier(roots, pins)?))
+    .with_no_client_auth();
+  config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+  Ok(config)
+}
+
+/// Certificate of the TLS connection a response came on, when the client recorded it.
+pub fn certificate(response: &reqwest::Response) -> Option<Certificate> {
+  let tls_info = response.extensions().get::<reqwest::tls::TlsInfo>()?;
+  let url = response.url();
+  let host = format!("{}:{}", url.host_str()?, url.port_or_known_default()?);
+  parse_certificate(host.as_str(), tls_info.peer_certificate()?)
+}
+
+#[cfg(test)]
+mod tests {
+  use base64::{prelude::BASE64_STANDARD, Engine};
+  use chrono::TimeZone;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  const CERTIFICATE: &str = "\
+MIIB2DCCAX6gAwIBAgIUVzLuy0Kvm1nhpACgJ1GsusXG/JEwCgYIKoZIzj0EAwIwLDEYMBYGA1UEAwwPYXBpLmV4YW1wbGUuY29t\
+MRAwDgYDVQQKDAdFeGFtcGxlMB4XDTI2MTAxNjEwMzM1N1oXDTI2MTExNTEwMzM1N1owLDEYMBYGA1UEAwwPYXBpLmV4YW1wbGUu\
+Y29tMRAwDgYDVQQKDAdFeGFtcGxlMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEqVJiSiDdCpjm2iXmP98Y9yXHztJxrLT6dSjj\
+u20Z2oec5pV7qPCfUizdHNF8lgiBmk3t9ZywDI1bDD5HlYxWC6N+MHwwHQYDVR0OBBYEFOEpnnoDC7ITulWcHDPS1F9UodzwMB8G\
+A1UdIwQYMBaAFOEpnnoDC7ITulWcHDPS1F9UodzwMA8GA1UdEwEB/wQFMAMBAf8wKQYDVR0RBCIwIIIPYXBpLmV4YW1wbGUuY29t\
+gg0qLmV4YW1wbGUuY29tMAoGCCqGSM49BAMCA0gAMEUCIQCrJTxz0wq2evnuZTCmeGM7KHud4iqhU7mQ+9DAE8SZhQIgH1Kx//xY\
+uWQG2Zjtlnls9T8GMXVOGCGIvFq06Mml4SY=";
+
+  #[test]
+  fn test_parse_certificate() {
+    let der = BASE64_STANDARD.decode(CERTIFICATE).unwrap();
+    assert_eq!(
+      parse_certificate("api.example.com:443", &der),
+      Some(Certificate {
+        host: String::from("api.example.com:443"),
+        fingerprint: fingerprint(&der),
+        subject: String::from("CN=api.example.com, O=Example"),
+        issuer: String::from("CN=api.example.com, O=Example"),
+        not_before: Utc.with_ymd_and_hms(2026, 10, 16, 10, 33, 57).single(),
+        not_after: Utc.with_ymd_and_hms(2026, 11, 15, 10, 33, 57).single(),
+        alt_names: vec![String::from("api.example.com"), String::from("*.example.com")],
+      })
+    );
+    assert_eq!(parse_certificate("api.example.com:443", &der[..100]), None);
+  }
+
+  #[test]
+  fn test_pinning_verifier() {
+    let der = CertificateDer::from(BASE64_STANDARD.decode(CERTIFICATE).unwrap());
+    let mut roots = RootCertStore::empty();
+    roots.add(der.clone()).unwrap();
+    let pins = Arc::new(Pins::default());
+    let verifier = pinning_verifier(roots, pins.clone()).unwrap();
+    let server_name = ServerName::try_from("api.example.com").unwrap();
+    let verify = || verifier.verify_server_cert(&der, &[], &server_name, &[], UnixTime::now());
+
+    assert!(verify().is_err());
+    let rejected = Rejected { host: String::from("api.example.com"), fingerprint: fingerprint(&der), remembered: None };
+    assert_eq!(pins.take_rejected(), Some(rejected));
+    assert_eq!(pins.take_rejected(), None);
+
+    pins.pin("api.example.com:8443", fingerprint(&der).as_str());
+    assert!(verify().is_ok());
+    assert!(pins.take_rejected().is_none());
+  }
+
+  #[test]
+  fn test_remembered_instead() {
+    let remembered = BTreeMap::from([
+      (String::from("api.example.com:443"), String::from("AA")),
+      (String::from("api.example.com:8443"), String::from("BB")),
+      (String::from("[::1]:8443"), String::from("CC")),
+      (String::from("legacy.example.com"), String::from("DD")),
+    ]);
+    let pins = Pins::new(BTreeMap::default(), remembered);
+    assert_eq!(pins.remembered_instead("api.example.com", "BB"), None);
+    assert_eq!(pins.remembered_instead("api.example.com", "EE"), Some(String::from("AA")));
+    assert_eq!(pins.remembered_instead("::1", "EE"), Some(String::from("CC")));
+    assert_eq!(pins.remembered_instead("legacy.example.com", "EE"), Some(String::from("DD")));
+    assert_eq!(pins.remembered_instead("other.example.com", "EE"), None);
+  }
+
+  #[test]
+  fn test_fingerprint() {
+    assert_eq!(
+      fingerprint(b"abc"),
+      "BA:78:16:BF:8F:01:CF:EA:41:41:40:DE:5D:AE:22:23:B0:03:61:A3:96:17:7A:9C:B4:10:FF:61:F2:00:15:AD"
+    );
+    let der = BASE64_STANDARD.decode(CERTIFICATE).unwrap();
+    assert_eq!(
+      fingerprint(&der),
+      "0E:C9:82:B7:88:2A:C2:2F:9F:C6:5F:EA:0F:61:03:2C:C3:52:7D:B4:29:BB:0A:B2:CF:C6:FB:42:C1:7E:13:25"
+    );
+  }
+}
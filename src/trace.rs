@@ -0,0 +1,57 @@
+/// Header carrying the W3C trace context, see https://www.w3.org/TR/trace-context/.
+pub const TRACEPARENT: &str = "traceparent";
+
+/// A `traceparent` value starting a new sampled trace, with random trace and parent ids.
+pub fn traceparent() -> String {
+  format!("00-{:032x}-{:016x}-01", fastrand::u128(1..), fastrand::u64(1..))
+}
+
+/// Trace id of a `traceparent` value, `None` when it's malformed or all zeros.
+pub fn trace_id(traceparent: &str) -> Option<String> {
+  let mut parts = traceparent.trim().split('-');
+  let version = parts.next()?;
+  let trace_id = parts.next()?;
+  let is_hex = |part: &str, len: usize| part.len() == len && part.bytes().all(|byte| byte.is_ascii_hexdigit());
+  (is_hex(version, 2) && is_hex(trace_id, 32) && trace_id.bytes().any(|byte| byte != b'0'))
+    .then(|| trace_id.to_lowercase())
+}
+
+/// Adds a fresh `traceparent` header, unless the request already carries one.
+pub fn apply(request: &mut reqwest::Request) {
+  if !request.headers().contains_key(TRACEPARENT) {
+    if let Ok(value) = reqwest::header::HeaderValue::from_str(traceparent().as_str()) {
+      request.headers_mut().insert(TRACEPARENT, value);
+    }
+  }
+}
+
+/// Url of a trace in a tracing ui like Jaeger or Tempo, `{trace_id}` in the template is replaced.
+pub fn link(template: &str, trace_id: &str) -> String {
+  template.replace("{trace_id}", trace_id)
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_traceparent() {
+    let traceparent = traceparent();
+    assert_eq!(traceparent.len(), 55);
+    assert_eq!(trace_id(traceparent.as_str()).as_deref(), traceparent.get(3..35));
+
+    assert_eq!(
+      trace_id("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01").as_deref(),
+      Some("4bf92f3577b34da6a3ce929d0e0e4736")
+    );
+    assert_eq!(trace_id("00-00000000000000000000000000000000-00f067aa0ba902b7-01"), None);
+    assert_eq!(trace_id("not a traceparent"), None);
+
+    let mut request = reqwest::Client::new().get("https://example.com").header(TRACEPARENT, "kept").build().unwrap();
+    apply(&mut request);
+    assert_eq!(request.headers()[TRACEPARENT], "kept");
+    assert_eq!(link("http://localhost:16686/trace/{trace_id}", "abc"), "http://localhost:16686/trace/abc");
+  }
+}
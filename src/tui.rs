@@ -3,14 +3,16 @@ use std::{
   time::Duration,
 };
 
+use base64::{prelude::BASE64_STANDARD, Engine};
 use color_eyre::eyre::Result;
 use crossterm::{
   cursor,
   event::{
-    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event as CrosstermEvent,
-    KeyEvent, KeyEventKind, MouseEvent,
+    DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste, EnableFocusChange,
+    EnableMouseCapture, Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent,
   },
-  terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+  style::Print,
+  terminal::{EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
 };
 use futures::{FutureExt, StreamExt};
 use ratatui::backend::CrosstermBackend as Backend;
@@ -174,7 +176,8 @@ impl Tui {
 
   pub fn enter(&mut self) -> Result<()> {
     crossterm::terminal::enable_raw_mode()?;
-    crossterm::execute!(io(), EnterAlternateScreen, cursor::Hide)?;
+    // save the current title on the terminal stack so it's restored on exit
+    crossterm::execute!(io(), Print("\x1b[22;0t"), EnterAlternateScreen, cursor::Hide, EnableFocusChange)?;
     if self.mouse {
       crossterm::execute!(io(), EnableMouseCapture)?;
     }
@@ -195,7 +198,7 @@ impl Tui {
       if self.mouse {
         crossterm::execute!(io(), DisableMouseCapture)?;
       }
-      crossterm::execute!(io(), LeaveAlternateScreen, cursor::Show)?;
+      crossterm::execute!(io(), DisableFocusChange, LeaveAlternateScreen, cursor::Show, Print("\x1b[23;0t"))?;
       crossterm::terminal::disable_raw_mode()?;
     }
     Ok(())
@@ -222,6 +225,49 @@ impl Tui {
   }
 }
 
+pub fn set_title(title: &str) -> Result<()> {
+  crossterm::execute!(io(), SetTitle(title))?;
+  Ok(())
+}
+
+/// Copies text to the system clipboard through OSC 52, which works over ssh as well.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+  let sequence = format!("\x1b]52;c;{}\x07", BASE64_STANDARD.encode(text));
+  crossterm::execute!(io(), Print(sequence))?;
+  Ok(())
+}
+
+/// Opens a url with the system handler, the default browser for web links.
+pub fn open_in_browser(url: &str) -> Result<()> {
+  let mut command = if cfg!(target_os = "macos") {
+    std::process::Command::new("open")
+  } else if cfg!(target_os = "windows") {
+    let mut command = std::process::Command::new("cmd");
+    command.args(["/C", "start", ""]);
+    command
+  } else {
+    std::process::Command::new("xdg-open")
+  };
+  let mut child = command
+    .arg(url)
+    .stdin(std::process::Stdio::null())
+    .stdout(std::process::Stdio::null())
+    .stderr(std::process::Stdio::null())
+    .spawn()?;
+  std::thread::spawn(move || child.wait());
+  Ok(())
+}
+
+/// Sends a desktop notification through the terminal. VTE based terminals and rxvt understand OSC 777, most others
+/// like iTerm2, WezTerm and Windows Terminal understand OSC 9.
+pub fn notify(title: &str, body: &str) -> Result<()> {
+  let osc777 = std::env::var("VTE_VERSION").is_ok() || std::env::var("TERM").is_ok_and(|term| term.contains("rxvt"));
+  let sequence =
+    if osc777 { format!("\x1b]777;notify;{title};{body}\x07") } else { format!("\x1b]9;{title}: {body}\x07") };
+  crossterm::execute!(io(), Print(sequence))?;
+  Ok(())
+}
+
 impl Deref for Tui {
   type Target = ratatui::Terminal<Backend<IO>>;
 
@@ -0,0 +1,130 @@
+use std::{
+  net::{Ipv4Addr, SocketAddr, TcpListener},
+  process::Stdio,
+  time::{Duration, Instant},
+};
+
+use color_eyre::eyre::{bail, eyre, Result};
+use tokio::{io::AsyncReadExt, net::TcpStream, process::Child};
+
+use crate::config::TunnelConfig;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An `ssh -L` local port forward, closed when dropped.
+pub struct Tunnel {
+  pub hosts: Vec<String>,
+  pub target: String,
+  pub local_address: SocketAddr,
+  _child: Child,
+}
+
+/// Host of a `host:port` target, without the brackets of an IPv6 address.
+fn target_host(target: &str) -> &str {
+  let host = target.rsplit_once(':').map_or(target, |(host, _)| host);
+  host.trim_start_matches('[').trim_end_matches(']')
+}
+
+/// Hosts routed through a tunnel, the target host when none are configured.
+fn hosts(config: &TunnelConfig) -> Vec<String> {
+  if config.hosts.is_empty() {
+    vec![target_host(config.target.as_str()).to_string()]
+  } else {
+    config.hosts.clone()
+  }
+}
+
+/// Arguments of the `ssh` command forwarding `local_port` to the target through the bastion, failing instead of
+/// prompting or running without the forward.
+fn ssh_args(config: &TunnelConfig, local_port: u16) -> Vec<String> {
+  let mut args = vec![
+    String::from("-N"),
+    String::from("-o"),
+    String::from("ExitOnForwardFailure=yes"),
+    String::from("-o"),
+    String::from("BatchMode=yes"),
+    String::from("-L"),
+    format!("{}:{local_port}:{}", Ipv4Addr::LOCALHOST, config.target),
+  ];
+  if let Some(key) = &config.key {
+    args.extend([String::from("-i"), key.clone()]);
+  }
+  args.push(config.ssh.clone());
+  args
+}
+
+fn free_port() -> Result<u16> {
+  Ok(TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?.local_addr()?.port())
+}
+
+/// Starts the forward of a tunnel and waits up to `timeout` for its local port to accept connections.
+pub async fn open(config: &TunnelConfig, timeout: Duration) -> Result<Tunnel> {
+  let local_port = if config.local_port == 0 { free_port()? } else { config.local_port };
+  let local_address = SocketAddr::from((Ipv4Addr::LOCALHOST, local_port));
+  let mut child = tokio::process::Command::new("ssh")
+    .args(ssh_args(config, local_port))
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+    .map_err(|error| eyre!("can't run ssh: {error}"))?;
+  let started_at = Instant::now();
+  loop {
+    if let Some(status) = child.try_wait()? {
+      let mut stderr = String::new();
+      if let Some(mut pipe) = child.stderr.take() {
+        pipe.read_to_string(&mut stderr).await.ok();
+      }
+      bail!("ssh exited with {status}: {}", stderr.trim());
+    }
+    if TcpStream::connect(local_address).await.is_ok() {
+      break;
+    }
+    if started_at.elapsed() > timeout {
+      bail!("{local_address} isn't forwarded after {}s", timeout.as_secs());
+    }
+    tokio::time::sleep(POLL_INTERVAL).await;
+  }
+  Ok(Tunnel { hosts: hosts(config), target: config.target.clone(), local_address, _child: child })
+}
+
+/// Hosts of the open tunnels with the port of their target, and the local address their connections are dialed to
+/// instead.
+pub fn routes(tunnels: &[Tunnel]) -> Vec<(String, u16, SocketAddr)> {
+  tunnels
+    .iter()
+    .flat_map(|tunnel| {
+      let port = tunnel.target.rsplit_once(':').and_then(|(_, port)| port.parse().ok()).unwrap_or(443);
+      tunnel.hosts.iter().map(move |host| (host.clone(), port, tunnel.local_address))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_ssh_args() {
+    let mut config = TunnelConfig {
+      hosts: vec![],
+      ssh: String::from("deploy@bastion.example.com"),
+      key: Some(String::from("~/.ssh/bastion")),
+      target: String::from("api.internal:8443"),
+      local_port: 0,
+    };
+    assert_eq!(
+      ssh_args(&config, 9000).join(" "),
+      "-N -o ExitOnForwardFailure=yes -o BatchMode=yes -L 127.0.0.1:9000:api.internal:8443 -i ~/.ssh/bastion \
+       deploy@bastion.example.com"
+    );
+    assert_eq!(hosts(&config), ["api.internal"]);
+    config.target = String::from("[fd00::1]:443");
+    assert_eq!(hosts(&config), ["fd00::1"]);
+    config.hosts = vec![String::from("a.internal"), String::from("b.internal")];
+    assert_eq!(hosts(&config), ["a.internal", "b.internal"]);
+  }
+}
@@ -0,0 +1,106 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{client::Client, utils::get_data_dir};
+
+const RELEASES_URL: &str = "https://api.github.com/repos/zaghaghi/openapi-tui/releases";
+const CACHE_FILE: &str = "update-check.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A published release and its notes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Release {
+  pub version: String,
+  pub notes: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Cache {
+  checked_at: u64,
+  releases: Vec<Release>,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+  tag_name: String,
+  #[serde(default)]
+  body: Option<String>,
+  #[serde(default)]
+  draft: bool,
+  #[serde(default)]
+  prerelease: bool,
+}
+
+/// Releases newer than the running version, newest first. GitHub is asked at most once a day, the answer is cached
+/// in the data directory.
+pub async fn newer_releases(client: &Client) -> Result<Vec<Release>> {
+  let path = get_data_dir().join(CACHE_FILE);
+  let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+  let cache = std::fs::read_to_string(&path)
+    .ok()
+    .and_then(|content| serde_json::from_str::<Cache>(content.as_str()).ok())
+    .filter(|cache| now.saturating_sub(cache.checked_at) < CHECK_INTERVAL.as_secs());
+  let releases = match cache {
+    Some(cache) => cache.releases,
+    None => {
+      let body = client
+        .get(RELEASES_URL)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+      let releases = serde_json::from_slice::<Vec<GithubRelease>>(&body)?
+        .into_iter()
+        .filter(|release| !release.draft && !release.prerelease)
+        .map(|release| Release { version: release.tag_name, notes: release.body.unwrap_or_default() })
+        .collect::<Vec<_>>();
+      std::fs::create_dir_all(get_data_dir())?;
+      std::fs::write(&path, serde_json::to_string(&Cache { checked_at: now, releases: releases.clone() })?)?;
+      releases
+    },
+  };
+  Ok(newer_than(releases, env!("CARGO_PKG_VERSION")))
+}
+
+fn newer_than(releases: Vec<Release>, current: &str) -> Vec<Release> {
+  let Some(current) = parse_version(current) else {
+    return vec![];
+  };
+  let mut releases = releases
+    .into_iter()
+    .filter_map(|release| {
+      parse_version(release.version.as_str()).filter(|version| *version > current).zip(Some(release))
+    })
+    .collect::<Vec<_>>();
+  releases.sort_by(|(a, _), (b, _)| b.cmp(a));
+  releases.into_iter().map(|(_, release)| release).collect()
+}
+
+/// Major, minor and patch of a version or tag like `v0.10.0`, pre-release and build suffixes are ignored.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+  let version = version.trim().trim_start_matches('v');
+  let version = version.split(['-', '+']).next()?;
+  let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+  Some((parts.next()??, parts.next().flatten().unwrap_or_default(), parts.next().flatten().unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_newer_than() {
+    assert_eq!(parse_version("v0.10.2-rc.1"), Some((0, 10, 2)));
+    assert_eq!(parse_version("nightly"), None);
+
+    let release = |version: &str| Release { version: version.into(), notes: String::default() };
+    let releases = vec![release("0.9.0"), release("v0.11.0"), release("0.10.1"), release("0.10.0"), release("nightly")];
+    assert_eq!(newer_than(releases, "0.10.0"), [release("v0.11.0"), release("0.10.1")]);
+  }
+}
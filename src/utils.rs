@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+  path::PathBuf,
+  time::{Duration, Instant},
+};
 
 use color_eyre::eyre::Result;
 use directories::ProjectDirs;
@@ -139,6 +142,35 @@ macro_rules! trace_dbg {
     };
 }
 
+/// 64-bit FNV-1a hash. Unlike `DefaultHasher` its output is stable across builds, so it can be used for identifiers
+/// that end up on disk.
+pub fn stable_hash(value: &str) -> u64 {
+  value.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// How long ago an instant was, e.g. `3m ago`.
+pub fn ago(at: Instant) -> String {
+  match at.elapsed().as_secs() {
+    seconds @ 0..=59 => format!("{seconds}s ago"),
+    seconds @ 60..=3599 => format!("{}m ago", seconds / 60),
+    seconds => format!("{}h ago", seconds / 3600),
+  }
+}
+
+/// Parses intervals like `500ms`, `2s` or `1m`, plain numbers are taken as seconds.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+  if let Some(millis) = value.strip_suffix("ms") {
+    millis.parse().ok().map(Duration::from_millis)
+  } else if let Some(seconds) = value.strip_suffix('s') {
+    seconds.parse().ok().map(Duration::from_secs_f64)
+  } else if let Some(minutes) = value.strip_suffix('m') {
+    minutes.parse::<f64>().ok().map(|minutes| Duration::from_secs_f64(minutes * 60.0))
+  } else {
+    value.parse().ok().map(Duration::from_secs_f64)
+  }
+  .filter(|duration| !duration.is_zero())
+}
+
 pub fn version() -> String {
   let author = clap::crate_authors!();
 
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use reqwest::header::HeaderValue;
+
+use crate::request;
+
+/// Random version 4 UUID.
+pub fn uuid() -> String {
+  let bits = fastrand::u128(..) & !(0xf << 76) & !(0x3 << 62) | (0x4 << 76) | (0x2 << 62);
+  format!(
+    "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+    bits >> 96,
+    (bits >> 80) & 0xffff,
+    (bits >> 64) & 0xffff,
+    (bits >> 48) & 0xffff,
+    bits & 0xffff_ffff_ffff
+  )
+}
+
+/// Value of a helper placeholder of a body: `uuid`, `now_iso8601`, `random_int <min> <max>` or `base64 <text>`,
+/// where `file:<path>` encodes the contents of a file. `None` for anything else.
+fn helper(expression: &str) -> Option<String> {
+  let (name, args) = expression.split_once(' ').unwrap_or((expression, ""));
+  let args = args.trim();
+  match name {
+    "uuid" if args.is_empty() => Some(uuid()),
+    "now_iso8601" if args.is_empty() => Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+    "random_int" => {
+      let (min, max) = args.split_once(' ')?;
+      let (min, max) = (min.trim().parse::<i64>().ok()?, max.trim().parse::<i64>().ok()?);
+      (min <= max).then(|| fastrand::i64(min..=max).to_string())
+    },
+    "base64" if !args.is_empty() => {
+      let bytes = match args.trim_matches('"').strip_prefix("file:") {
+        Some(path) => std::fs::read(path).ok()?,
+        None => args.trim_matches('"').as_bytes().to_vec(),
+      };
+      Some(BASE64_STANDARD.encode(bytes))
+    },
+    _ => None,
+  }
+}
+
+/// Replaces `{{name}}` placeholders with the captured variables, unknown placeholders are left as they are.
+pub fn substitute(text: &str, variables: &BTreeMap<String, String>) -> String {
+  replace(text, |name| variables.get(name).cloned())
+}
+
+/// Replaces `{{name}}` placeholders with the captured variables or, when no variable has the name, the value of a
+/// helper like `{{uuid}}` or `{{random_int 1 100}}`, evaluated for each placeholder.
+pub fn substitute_with_helpers(text: &str, variables: &BTreeMap<String, String>) -> String {
+  replace(text, |expression| variables.get(expression).cloned().or_else(|| helper(expression)))
+}
+
+fn replace(text: &str, resolve: impl Fn(&str) -> Option<String>) -> String {
+  let mut result = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find("{{") {
+    result.push_str(&rest[..start]);
+    let tail = &rest[start + 2..];
+    match tail.find("}}").and_then(|end| resolve(tail[..end].trim()).map(|value| (end, value))) {
+      Some((end, value)) => {
+        result.push_str(value.as_str());
+        rest = &tail[end + 2..];
+      },
+      None => {
+        result.push_str("{{");
+        rest = tail;
+      },
+    }
+  }
+  result.push_str(rest);
+  result
+}
+
+/// Substitutes variables in the url, headers and body of a request, values are percent encoded in the url. Bodies
+/// also get the values of helpers.
+pub fn apply(request: &mut reqwest::Request, variables: &BTreeMap<String, String>) {
+  let encoded = variables
+    .iter()
+    .map(|(name, value)| (name.clone(), utf8_percent_encode(value, NON_ALPHANUMERIC).to_string()))
+    .collect::<BTreeMap<_, _>>();
+  let url = substitute(&request::template_url(request), &encoded);
+  if let Ok(url) = reqwest::Url::parse(url.as_str()) {
+    *request.url_mut() = url;
+  }
+  for value in request.headers_mut().values_mut() {
+    if let Some(substituted) = value.to_str().ok().filter(|text| text.contains("{{")).and_then(|text| {
+      HeaderValue::from_str(request::encode_header_value(substitute(text, variables).as_str()).as_ref()).ok()
+    }) {
+      let sensitive = value.is_sensitive();
+      *value = substituted;
+      value.set_sensitive(sensitive);
+    }
+  }
+  let body = request
+    .body()
+    .and_then(|body| body.as_bytes())
+    .and_then(|bytes| std::str::from_utf8(bytes).ok())
+    .filter(|text| text.contains("{{"))
+    .map(|text| substitute_with_helpers(text, variables));
+  if let Some(body) = body {
+    *request.body_mut() = Some(body.into());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_apply() {
+    let variables = BTreeMap::from([("id".to_string(), "42".to_string()), ("name".to_string(), "rex cat".to_string())]);
+    assert_eq!(substitute("{{id}}-{{ name }}-{{unknown}}-{{", &variables), "42-rex cat-{{unknown}}-{{");
+
+    let mut request = reqwest::Client::new()
+      .post("https://example.com/pets/{{id}}?name={{name}}")
+      .header("x-pet", "{{id}}")
+      .body("{\"name\": \"{{name}}\"}")
+      .build()
+      .unwrap();
+    apply(&mut request, &variables);
+    assert_eq!(request.url().as_str(), "https://example.com/pets/42?name=rex%20cat");
+    assert_eq!(request.headers()["x-pet"], "42");
+    assert_eq!(request.body().and_then(|body| body.as_bytes()), Some("{\"name\": \"rex cat\"}".as_bytes()));
+  }
+
+  #[test]
+  fn test_helpers() {
+    let variables = BTreeMap::from([("uuid".to_string(), "fixed".to_string())]);
+    assert_eq!(substitute_with_helpers("{{uuid}} {{ base64 rex }} {{random_int 7 7}}", &variables), "fixed cmV4 7");
+    let uuid = substitute_with_helpers("{{uuid}}", &BTreeMap::new());
+    assert_eq!((uuid.len(), &uuid[14..15], uuid.matches('-').count()), (36, "4", 4));
+    assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+    assert_eq!(substitute_with_helpers("{{now_iso8601}}", &BTreeMap::new()).len(), "2024-01-01T00:00:00Z".len());
+    assert_eq!(
+      substitute_with_helpers("{{random_int 9 1}} {{base64 file:/nonexistent}} {{unknown}}", &BTreeMap::new()),
+      "{{random_int 9 1}} {{base64 file:/nonexistent}} {{unknown}}"
+    );
+  }
+}
@@ -0,0 +1,53 @@
+use std::{
+  error::Error,
+  time::{Duration, Instant},
+};
+
+use color_eyre::eyre::{eyre, Result};
+use reqwest::Method;
+
+use crate::client::Client;
+
+const WARM_UP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reachability of a server found by warming it up.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerHealth {
+  Resolving,
+  Ready(Duration),
+  Failed(String),
+}
+
+/// Sends a `HEAD` request to a server through the shared client, so resolution, connection and TLS failures show up
+/// before the first request and the connection it opened is pooled for it, returning how long it took. Any response
+/// counts, the server doesn't have to document `HEAD`.
+pub async fn warm_up(client: &Client, base_url: &str) -> Result<Duration> {
+  let started_at = Instant::now();
+  let url = reqwest::Url::parse(base_url)?;
+  let request = client.request(Method::HEAD, url).timeout(WARM_UP_TIMEOUT).build()?;
+  client.execute(request).await.map_err(|error| match error.is_timeout() {
+    true => eyre!("no response after {}s", WARM_UP_TIMEOUT.as_secs()),
+    false => eyre!("{}", error.source().map_or(error.to_string(), ToString::to_string)),
+  })?;
+  Ok(started_at.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::io::AsyncWriteExt;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_warm_up() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      stream.write_all(b"HTTP/1.1 405 Method Not Allowed\r\ncontent-length: 0\r\n\r\n").await.unwrap();
+    });
+    let client = Client::default();
+    assert!(warm_up(&client, format!("http://127.0.0.1:{port}/v1").as_str()).await.is_ok());
+    assert!(warm_up(&client, "/relative").await.is_err());
+  }
+}
@@ -0,0 +1,102 @@
+use std::net::SocketAddr;
+
+use color_eyre::eyre::{ContextCompat, Result};
+use serde::{Deserialize, Serialize};
+use tokio::{
+  io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+  net::TcpListener,
+  sync::mpsc::UnboundedSender,
+  task::JoinHandle,
+};
+
+use crate::action::Action;
+
+const MAX_BODY_LENGTH: usize = 16 * 1024 * 1024;
+
+/// A webhook call received by the local listener.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Delivery {
+  pub method: String,
+  pub path: String,
+  pub headers: Vec<(String, String)>,
+  pub body: Vec<u8>,
+}
+
+/// Listens for webhook calls of an operation, every delivery is sent back as `Action::WebhookDelivery` and answered
+/// with an empty `200 OK`.
+pub async fn listen(
+  address: &str,
+  operation_id: String,
+  action_tx: UnboundedSender<Action>,
+) -> Result<(SocketAddr, JoinHandle<()>)> {
+  let listener = TcpListener::bind(address).await?;
+  let local_address = listener.local_addr()?;
+  let handle = tokio::spawn(async move {
+    while let Ok((stream, _)) = listener.accept().await {
+      let (reader, mut writer) = stream.into_split();
+      match read_delivery(&mut BufReader::new(reader)).await {
+        Ok(delivery) => {
+          writer.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n").await.ok();
+          action_tx.send(Action::WebhookDelivery(operation_id.clone(), delivery)).ok();
+        },
+        Err(error) => {
+          log::error!("invalid webhook delivery: {error}");
+          writer.write_all(b"HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\nconnection: close\r\n\r\n").await.ok();
+        },
+      }
+    }
+  });
+  Ok((local_address, handle))
+}
+
+pub async fn read_delivery<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Delivery> {
+  let mut request_line = String::new();
+  reader.read_line(&mut request_line).await?;
+  let mut parts = request_line.split_whitespace();
+  let method = parts.next().context("missing method")?.to_string();
+  let path = parts.next().context("missing path")?.to_string();
+
+  let mut headers = vec![];
+  loop {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+      break;
+    }
+    let line = line.trim_end();
+    if line.is_empty() {
+      break;
+    }
+    if let Some((name, value)) = line.split_once(':') {
+      headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+    }
+  }
+
+  let content_length = headers
+    .iter()
+    .find(|(name, _)| name == "content-length")
+    .and_then(|(_, value)| value.parse::<usize>().ok())
+    .unwrap_or_default()
+    .min(MAX_BODY_LENGTH);
+  let mut body = vec![0; content_length];
+  reader.read_exact(&mut body).await?;
+  Ok(Delivery { method, path, headers, body })
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_read_delivery() {
+    let mut request =
+      "POST /hooks/pets HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: 11\r\n\r\n{\"id\": 12}\n"
+        .as_bytes();
+    let delivery = read_delivery(&mut request).await.unwrap();
+    assert_eq!(delivery.method, "POST");
+    assert_eq!(delivery.path, "/hooks/pets");
+    assert_eq!(delivery.headers[1], ("content-type".to_string(), "application/json".to_string()));
+    assert_eq!(delivery.body, b"{\"id\": 12}\n");
+  }
+}